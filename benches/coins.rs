@@ -0,0 +1,48 @@
+//! # Benchmark: coin values and state lookups
+//!
+//! Totals the value of a million random coins with `Coin2::cents`, the
+//! library's `value_in_cents`, and looks up 100k typed state names with
+//! `UsState::from_name`, along with the year of each state's quarter.
+//! Prints the best time over a few runs of each.
+//!
+//! Run with `cargo bench --bench coins`.
+use std::hint::black_box;
+
+use rust_enums::bench_support::{best_of, random_coins, state_names};
+use rust_enums::{Cents, Coin2, UsState};
+
+const COINS: usize = 1_000_000;
+const NAMES: usize = 100_000;
+const RUNS: usize = 20;
+
+fn main() {
+    let coins = random_coins(COINS, 1);
+    let names = state_names(NAMES, 2);
+
+    let total: Cents = coins.iter().map(Coin2::cents).sum();
+    let cents_time = best_of(RUNS, || {
+        black_box(black_box(&coins).iter().map(Coin2::cents).sum::<Cents>());
+    });
+    println!("{COINS} coins worth {total} cents: {cents_time:?}");
+
+    let lookup_time = best_of(RUNS, || {
+        for name in black_box(&names) {
+            black_box(UsState::from_name(name));
+        }
+    });
+    let states: Vec<UsState> = names.iter().filter_map(|n| UsState::from_name(n)).collect();
+    println!(
+        "{NAMES} from_name lookups, {} states: {lookup_time:?}",
+        states.len()
+    );
+
+    let year_time = best_of(RUNS, || {
+        for state in black_box(&states) {
+            black_box(state.quarter_release_year());
+        }
+    });
+    println!(
+        "{} quarter_release_year lookups: {year_time:?}",
+        states.len()
+    );
+}
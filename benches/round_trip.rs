@@ -0,0 +1,48 @@
+//! # Benchmark: `Message::encode` and `Message::decode`
+//!
+//! Encodes 100k random messages of every kind one at a time, then decodes
+//! the bytes back, checking once that the round trip gives the same
+//! messages. Prints the best time over a few runs of each half.
+//!
+//! Run with `cargo bench --bench round_trip`.
+use std::hint::black_box;
+
+use rust_enums::bench_support::{best_of, random_messages};
+use rust_enums::Message;
+
+const BATCH: usize = 100_000;
+const RUNS: usize = 20;
+
+fn encode(msgs: &[Message]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for msg in msgs {
+        msg.encode(&mut buf).unwrap();
+    }
+    buf
+}
+
+fn decode(mut bytes: &[u8]) -> Vec<Message> {
+    let mut msgs = Vec::new();
+    while !bytes.is_empty() {
+        let (msg, used) = Message::decode(bytes).expect("encoded by `encode`");
+        msgs.push(msg);
+        bytes = &bytes[used..];
+    }
+    msgs
+}
+
+fn main() {
+    let msgs = random_messages(BATCH, 3);
+    let bytes = encode(&msgs);
+    assert_eq!(decode(&bytes), msgs);
+
+    let encode_time = best_of(RUNS, || {
+        black_box(encode(black_box(&msgs)));
+    });
+    let decode_time = best_of(RUNS, || {
+        black_box(decode(black_box(&bytes)));
+    });
+    println!("{BATCH} messages, {} bytes", bytes.len());
+    println!("encode: {encode_time:?}");
+    println!("decode: {decode_time:?}");
+}
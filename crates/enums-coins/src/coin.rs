@@ -0,0 +1,557 @@
+//! # US coins, and the state quarters
+//!
+//! `Coin` has a variant per coin and nothing more. `Coin2` is the same coin
+//! with a `UsState` held inside its `Quarter` variant, for the 50 State
+//! Quarters minted from 1999 to 2008.
+//!
+//! All three display as the words a person would use, and parse back from
+//! them, ignoring case: `New York`, and `quarter from New York` for its
+//! quarter.
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "strum")]
+use strum_macros::EnumIter;
+
+/// A value in US cents
+///
+/// ```
+/// use enums_coins::{Cents, Coin2};
+///
+/// let total: Cents = [Coin2::Dime, Coin2::Nickel].iter().map(Coin2::cents).sum();
+/// assert_eq!(total, 15);
+/// ```
+pub use enums_core::Cents;
+
+/// # Enum to represent `Coin`s
+///
+/// An enum that has all variants of `Coin` types
+///
+/// ```
+/// use enums_coins::{Coin, Coin2, UsState};
+///
+/// assert_eq!(Coin2::Quarter(UsState::Ohio).kind(), Coin::Quarter);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "strum", derive(EnumIter))]
+pub enum Coin {
+    /// One cent
+    Penny,
+    /// Five cents
+    Nickel,
+    /// Ten cents
+    Dime,
+    /// Twenty-five cents
+    Quarter,
+}
+
+/// #  Enum to represent `UsState`s for all 50 US State Quarters
+///
+/// `Quarter` variant to include a `UsState` value stored inside it
+///
+/// ```
+/// use enums_coins::UsState;
+///
+/// assert_eq!(UsState::from_name("new york"), Some(UsState::NewYork));
+/// assert_eq!(UsState::NewYork.quarter_release_year(), 2001);
+/// ```
+#[allow(missing_docs)] // each variant is named for its state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "strum", derive(EnumIter))]
+pub enum UsState {
+    Alabama,
+    Alaska,
+    Arizona,
+    Arkansas,
+    California,
+    Colorado,
+    Connecticut,
+    Delaware,
+    Florida,
+    Georgia,
+    Hawaii,
+    Idaho,
+    Illinois,
+    Indiana,
+    Iowa,
+    Kansas,
+    Kentucky,
+    Louisiana,
+    Maine,
+    Maryland,
+    Massachusetts,
+    Michigan,
+    Minnesota,
+    Mississippi,
+    Missouri,
+    Montana,
+    Nebraska,
+    Nevada,
+    NewHampshire,
+    NewJersey,
+    NewMexico,
+    NewYork,
+    NorthCarolina,
+    NorthDakota,
+    Ohio,
+    Oklahoma,
+    Oregon,
+    Pennsylvania,
+    RhodeIsland,
+    SouthCarolina,
+    SouthDakota,
+    Tennessee,
+    Texas,
+    Utah,
+    Vermont,
+    Virginia,
+    Washington,
+    WestVirginia,
+    Wisconsin,
+    Wyoming,
+}
+
+impl Coin {
+    /// Every coin, in the order they are declared
+    ///
+    /// The same coins, in the same order, as `Coin::iter()` with the
+    /// `strum` feature on, but there without it too.
+    ///
+    /// ```
+    /// use enums_coins::Coin;
+    ///
+    /// assert_eq!(Coin::VARIANTS.len(), 4);
+    /// assert_eq!(Coin::VARIANTS[3], Coin::Quarter);
+    /// ```
+    pub const VARIANTS: &'static [Coin] = &[Coin::Penny, Coin::Nickel, Coin::Dime, Coin::Quarter];
+
+    /// The coin called `name`, ignoring case
+    ///
+    /// ```
+    /// use enums_coins::Coin;
+    ///
+    /// assert_eq!(Coin::from_name("Dime"), Some(Coin::Dime));
+    /// assert_eq!(Coin::from_name("button"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Coin> {
+        Coin::VARIANTS
+            .iter()
+            .copied()
+            .find(|coin| format!("{coin:?}").eq_ignore_ascii_case(name))
+    }
+}
+
+impl UsState {
+    /// Every state, in alphabetical order, as `UsState::iter()` gives them
+    /// with the `strum` feature on
+    pub const VARIANTS: &'static [UsState] = &[
+        UsState::Alabama,
+        UsState::Alaska,
+        UsState::Arizona,
+        UsState::Arkansas,
+        UsState::California,
+        UsState::Colorado,
+        UsState::Connecticut,
+        UsState::Delaware,
+        UsState::Florida,
+        UsState::Georgia,
+        UsState::Hawaii,
+        UsState::Idaho,
+        UsState::Illinois,
+        UsState::Indiana,
+        UsState::Iowa,
+        UsState::Kansas,
+        UsState::Kentucky,
+        UsState::Louisiana,
+        UsState::Maine,
+        UsState::Maryland,
+        UsState::Massachusetts,
+        UsState::Michigan,
+        UsState::Minnesota,
+        UsState::Mississippi,
+        UsState::Missouri,
+        UsState::Montana,
+        UsState::Nebraska,
+        UsState::Nevada,
+        UsState::NewHampshire,
+        UsState::NewJersey,
+        UsState::NewMexico,
+        UsState::NewYork,
+        UsState::NorthCarolina,
+        UsState::NorthDakota,
+        UsState::Ohio,
+        UsState::Oklahoma,
+        UsState::Oregon,
+        UsState::Pennsylvania,
+        UsState::RhodeIsland,
+        UsState::SouthCarolina,
+        UsState::SouthDakota,
+        UsState::Tennessee,
+        UsState::Texas,
+        UsState::Utah,
+        UsState::Vermont,
+        UsState::Virginia,
+        UsState::Washington,
+        UsState::WestVirginia,
+        UsState::Wisconsin,
+        UsState::Wyoming,
+    ];
+
+    /// The state called `name`, ignoring case and spaces, so `"new york"`
+    /// finds `NewYork`
+    ///
+    /// ```
+    /// use enums_coins::UsState;
+    ///
+    /// assert_eq!(UsState::from_name("rhode island"), Some(UsState::RhodeIsland));
+    /// assert_eq!(UsState::from_name("Atlantis"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<UsState> {
+        let name: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+        UsState::VARIANTS
+            .iter()
+            .copied()
+            .find(|state| format!("{state:?}").eq_ignore_ascii_case(&name))
+    }
+
+    /// The year the state's quarter came out
+    ///
+    /// The 50 State Quarters program ran from 1999 to 2008, five states a
+    /// year, in the order the states joined the Union.
+    ///
+    /// ```
+    /// use enums_coins::UsState;
+    ///
+    /// assert_eq!(UsState::Delaware.quarter_release_year(), 1999);
+    /// assert_eq!(UsState::Hawaii.quarter_release_year(), 2008);
+    /// ```
+    pub fn quarter_release_year(&self) -> u16 {
+        use UsState::*;
+        match self {
+            Delaware | Pennsylvania | NewJersey | Georgia | Connecticut => 1999,
+            Massachusetts | Maryland | SouthCarolina | NewHampshire | Virginia => 2000,
+            NewYork | NorthCarolina | RhodeIsland | Vermont | Kentucky => 2001,
+            Tennessee | Ohio | Louisiana | Indiana | Mississippi => 2002,
+            Illinois | Alabama | Maine | Missouri | Arkansas => 2003,
+            Michigan | Florida | Texas | Iowa | Wisconsin => 2004,
+            California | Minnesota | Oregon | Kansas | WestVirginia => 2005,
+            Nevada | Nebraska | Colorado | NorthDakota | SouthDakota => 2006,
+            Montana | Washington | Idaho | Wyoming | Utah => 2007,
+            Oklahoma | NewMexico | Arizona | Alaska | Hawaii => 2008,
+        }
+    }
+}
+
+/// The state's name in words, such as `New York`
+///
+/// ```
+/// use enums_coins::UsState;
+///
+/// assert_eq!(UsState::NorthDakota.to_string(), "North Dakota");
+/// assert_eq!("north dakota".parse(), Ok(UsState::NorthDakota));
+/// ```
+impl fmt::Display for UsState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, c) in format!("{self:?}").char_indices() {
+            if i > 0 && c.is_uppercase() {
+                write!(f, " ")?;
+            }
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The inverse of `Display`, by way of `UsState::from_name`
+impl FromStr for UsState {
+    type Err = UnknownState;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UsState::from_name(s).ok_or_else(|| UnknownState(s.to_string()))
+    }
+}
+
+/// Year the first state quarters came out, used when the state isn't known
+///
+/// ```
+/// use enums_coins::{coin::FIRST_QUARTER_YEAR, UsState};
+///
+/// assert_eq!(UsState::Delaware.quarter_release_year(), FIRST_QUARTER_YEAR);
+/// ```
+pub const FIRST_QUARTER_YEAR: u16 = 1999;
+
+/// # No state goes by the given name
+///
+/// ```
+/// use enums_coins::coin::UnknownState;
+///
+/// let e = UnknownState(String::from("Atlantis"));
+/// assert_eq!(e.to_string(), r#"no state named "Atlantis""#);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownState(pub String);
+
+impl fmt::Display for UnknownState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no state named {:?}", self.0)
+    }
+}
+
+impl core::error::Error for UnknownState {}
+
+/// The year the quarter for the state called `name` came out, or `None` if
+/// there is no such state
+///
+/// ```
+/// use enums_coins::coin::quarter_year_for;
+///
+/// assert_eq!(quarter_year_for("Ohio"), Some(2002));
+/// assert_eq!(quarter_year_for("Atlantis"), None);
+/// ```
+pub fn quarter_year_for(name: &str) -> Option<u16> {
+    UsState::from_name(name).map(|s| s.quarter_release_year())
+}
+
+/// `quarter_year_for`, falling back to `FIRST_QUARTER_YEAR`
+///
+/// ```
+/// use enums_coins::coin::{quarter_year_or_default, FIRST_QUARTER_YEAR};
+///
+/// assert_eq!(quarter_year_or_default("Atlantis"), FIRST_QUARTER_YEAR);
+/// ```
+pub fn quarter_year_or_default(name: &str) -> u16 {
+    quarter_year_for(name).unwrap_or(FIRST_QUARTER_YEAR)
+}
+
+/// `quarter_year_for`, with a missing state as an `UnknownState` error
+///
+/// ```
+/// use enums_coins::coin::{quarter_year_or_err, UnknownState};
+///
+/// assert_eq!(quarter_year_or_err("Utah"), Ok(2007));
+/// assert_eq!(
+///     quarter_year_or_err("Atlantis"),
+///     Err(UnknownState(String::from("Atlantis")))
+/// );
+/// ```
+pub fn quarter_year_or_err(name: &str) -> Result<u16, UnknownState> {
+    quarter_year_for(name).ok_or_else(|| UnknownState(name.to_string()))
+}
+
+/// # `Default` trait implementation for `UsState`
+///
+/// You have a better chance of finding `Virginia` quarters than any others.
+/// Mint records show nearly 1.6 billion were put into circulation.
+///
+/// Therefore, the `Coin2::Quarter(UsState::Virginia)` variant is the most
+/// probable to find and is set to be the `Default` variant
+impl Default for UsState {
+    fn default() -> Self {
+        UsState::Virginia
+    }
+}
+
+/// # Enum to represent `Coin`s and US State Quarters
+///
+/// A `Coin` enum in which the `Quarter` variant also holds a `UsState` value
+///
+/// ```
+/// use enums_coins::{Coin2, UsState};
+///
+/// let coin = Coin2::Quarter(UsState::Alaska);
+/// assert_eq!(coin.cents(), 25);
+/// if let Coin2::Quarter(state) = coin {
+///     assert_eq!(state, UsState::Alaska);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "strum", derive(EnumIter))]
+pub enum Coin2 {
+    /// One cent
+    Penny,
+    /// Five cents
+    Nickel,
+    /// Ten cents
+    Dime,
+    /// Twenty-five cents, with the state on its back
+    Quarter(UsState),
+}
+
+impl Coin2 {
+    /// One coin of each kind, the quarter from the default state, Virginia,
+    /// as `Coin2::iter()` gives them with the `strum` feature on
+    pub const VARIANTS: &'static [Coin2] = &[
+        Coin2::Penny,
+        Coin2::Nickel,
+        Coin2::Dime,
+        Coin2::Quarter(UsState::Virginia),
+    ];
+
+    /// What the coin is worth
+    ///
+    /// ```
+    /// use enums_coins::Coin2;
+    ///
+    /// assert_eq!(Coin2::Nickel.cents(), 5);
+    /// ```
+    pub fn cents(&self) -> Cents {
+        match self {
+            Coin2::Penny => 1,
+            Coin2::Nickel => 5,
+            Coin2::Dime => 10,
+            Coin2::Quarter(_) => 25,
+        }
+    }
+
+    /// Which coin it is, without a quarter's state
+    ///
+    /// ```
+    /// use enums_coins::{Coin, Coin2};
+    ///
+    /// assert_eq!(Coin2::Dime.kind(), Coin::Dime);
+    /// ```
+    pub fn kind(&self) -> Coin {
+        match self {
+            Coin2::Penny => Coin::Penny,
+            Coin2::Nickel => Coin::Nickel,
+            Coin2::Dime => Coin::Dime,
+            Coin2::Quarter(_) => Coin::Quarter,
+        }
+    }
+}
+
+/// `penny`, `nickel`, `dime`, or `quarter from <state>`
+///
+/// ```
+/// use enums_coins::{Coin2, UsState};
+///
+/// assert_eq!(Coin2::Dime.to_string(), "dime");
+/// assert_eq!(
+///     Coin2::Quarter(UsState::NewMexico).to_string(),
+///     "quarter from New Mexico"
+/// );
+/// ```
+impl fmt::Display for Coin2 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Coin2::Penny => write!(f, "penny"),
+            Coin2::Nickel => write!(f, "nickel"),
+            Coin2::Dime => write!(f, "dime"),
+            Coin2::Quarter(state) => write!(f, "quarter from {state}"),
+        }
+    }
+}
+
+/// The inverse of `Display`, ignoring case
+///
+/// A state quarter can also be written as one word, `quarter:<state>`, and
+/// a `quarter` with no state is from `UsState::default()`.
+///
+/// ```
+/// use enums_coins::{Coin2, UsState};
+///
+/// assert_eq!("Quarter from Ohio".parse(), Ok(Coin2::Quarter(UsState::Ohio)));
+/// assert_eq!("quarter:newyork".parse(), Ok(Coin2::Quarter(UsState::NewYork)));
+/// assert_eq!("quarter".parse(), Ok(Coin2::Quarter(UsState::Virginia)));
+/// assert!("button".parse::<Coin2>().is_err());
+/// ```
+impl FromStr for Coin2 {
+    type Err = UnknownCoin;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || UnknownCoin(s.to_string());
+        let lower = s.trim().to_ascii_lowercase();
+        let state = lower
+            .strip_prefix("quarter from ")
+            .or_else(|| lower.strip_prefix("quarter:"));
+        if let Some(state) = state {
+            return UsState::from_name(state)
+                .map(Coin2::Quarter)
+                .ok_or_else(unknown);
+        }
+        Ok(match Coin::from_name(&lower).ok_or_else(unknown)? {
+            Coin::Penny => Coin2::Penny,
+            Coin::Nickel => Coin2::Nickel,
+            Coin::Dime => Coin2::Dime,
+            Coin::Quarter => Coin2::Quarter(UsState::default()),
+        })
+    }
+}
+
+/// # No coin goes by the given name
+///
+/// ```
+/// use enums_coins::coin::UnknownCoin;
+///
+/// let e = UnknownCoin(String::from("button"));
+/// assert_eq!(e.to_string(), r#""button" is not a coin"#);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownCoin(pub String);
+
+impl fmt::Display for UnknownCoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} is not a coin", self.0)
+    }
+}
+
+impl core::error::Error for UnknownCoin {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarter_year_chain() {
+        assert_eq!(quarter_year_for("Ohio"), Some(2002));
+        assert_eq!(quarter_year_or_default("Ohio"), 2002);
+        assert_eq!(quarter_year_or_err("Ohio"), Ok(2002));
+    }
+
+    #[test]
+    fn quarter_year_for_an_unknown_state() {
+        assert_eq!(quarter_year_for("Atlantis"), None);
+        assert_eq!(quarter_year_or_default("Atlantis"), FIRST_QUARTER_YEAR);
+        assert_eq!(
+            quarter_year_or_err("Atlantis"),
+            Err(UnknownState(String::from("Atlantis")))
+        );
+    }
+
+    #[test]
+    fn state_names_ignore_case_and_spaces() {
+        assert_eq!(UsState::from_name("ohio"), Some(UsState::Ohio));
+        assert_eq!(UsState::from_name("New York"), Some(UsState::NewYork));
+        assert_eq!(UsState::from_name("NEWYORK"), Some(UsState::NewYork));
+        assert_eq!(UsState::from_name(""), None);
+        assert_eq!(quarter_year_for("west virginia"), Some(2005));
+    }
+
+    #[test]
+    fn five_quarters_a_year() {
+        for year in 1999..=2008 {
+            let states = UsState::VARIANTS
+                .iter()
+                .filter(|s| s.quarter_release_year() == year)
+                .count();
+            assert_eq!(states, 5, "{year}");
+        }
+    }
+
+    #[test]
+    fn coin_names() {
+        assert_eq!("penny".parse(), Ok(Coin2::Penny));
+        assert_eq!("Quarter".parse(), Ok(Coin2::Quarter(UsState::Virginia)));
+        assert_eq!(
+            "quarter:newmexico".parse(),
+            Ok(Coin2::Quarter(UsState::NewMexico))
+        );
+        assert_eq!(
+            "quarter:atlantis".parse::<Coin2>(),
+            Err(UnknownCoin(String::from("quarter:atlantis")))
+        );
+        assert!("dime:ohio".parse::<Coin2>().is_err());
+    }
+}
@@ -0,0 +1,33 @@
+//! # Enums and Pattern Matching: coins, state quarters and wallets
+//!
+//! `Coin`, the `Coin2` whose quarters carry a `UsState`, and the `Wallet`
+//! that holds them, with no dependency on the IP address or `Message`
+//! crates.
+//!
+//! ```
+//! use enums_coins::{Coin2, UsState, Wallet};
+//!
+//! let wallet = Wallet::new([Coin2::Quarter(UsState::Ohio), Coin2::Dime]);
+//! assert_eq!(wallet.total(), 35);
+//! ```
+//!
+//! The crate is `#![no_std]`, needing only `alloc`. The `rand` feature
+//! brings in `Wallet::random` and `strum` brings in `iter()` over `Coin`
+//! and `UsState`; each has a `VARIANTS` slice for when `strum` is off.
+#![cfg_attr(not(test), no_std)]
+#![deny(missing_docs)]
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::todo,
+    clippy::unimplemented
+)]
+#![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used))]
+
+extern crate alloc;
+
+pub mod coin;
+pub mod wallet;
+
+pub use coin::{Cents, Coin, Coin2, UsState};
+pub use wallet::Wallet;
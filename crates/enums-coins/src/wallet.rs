@@ -0,0 +1,232 @@
+//! # Comparing coins and wallets with `Ordering`
+//!
+//! `Ord::cmp` answers "less, equal or greater" with a `std::cmp::Ordering`,
+//! and matching on its three variants is the same idiom the guessing game
+//! in the book uses to say "too small", "too big" or "you win".
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{Cents, Coin2};
+
+/// How `a` compares to `b` by what each is worth
+///
+/// Quarters from different states are worth the same, so compare as equal.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use enums_coins::wallet::compare_value;
+/// use enums_coins::{Coin2, UsState};
+///
+/// assert_eq!(compare_value(&Coin2::Penny, &Coin2::Dime), Ordering::Less);
+/// assert_eq!(
+///     compare_value(&Coin2::Quarter(UsState::Ohio), &Coin2::Quarter(UsState::Utah)),
+///     Ordering::Equal
+/// );
+/// ```
+pub fn compare_value(a: &Coin2, b: &Coin2) -> Ordering {
+    a.cents().cmp(&b.cents())
+}
+
+/// # A handful of coins someone carries
+///
+/// ```
+/// use enums_coins::{Coin2, Wallet};
+///
+/// let wallet = Wallet::new([Coin2::Dime, Coin2::Penny]);
+/// assert_eq!(wallet.coins(), [Coin2::Dime, Coin2::Penny]);
+/// assert_eq!(wallet.total(), 11);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Wallet {
+    coins: Vec<Coin2>,
+}
+
+impl Wallet {
+    /// A wallet holding `coins`
+    ///
+    /// ```
+    /// use enums_coins::{Coin2, Wallet};
+    ///
+    /// assert_eq!(Wallet::new([Coin2::Nickel]).total(), 5);
+    /// ```
+    pub fn new(coins: impl IntoIterator<Item = Coin2>) -> Self {
+        Wallet {
+            coins: coins.into_iter().collect(),
+        }
+    }
+
+    /// `count` coins of any kind, quarters from any state, chosen from `seed`
+    ///
+    /// ```
+    /// use enums_coins::Wallet;
+    ///
+    /// assert_eq!(Wallet::random(6, 1), Wallet::random(6, 1));
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn random(count: usize, seed: u64) -> Self {
+        Wallet::random_with(count, &mut enums_core::rng::SeededRng::new(seed))
+    }
+
+    /// `count` coins of any kind, quarters from any state, picked by `rng`
+    ///
+    /// ```
+    /// use enums_coins::{Coin2, Wallet};
+    /// use enums_core::rng::{RecordingRng, ReplayRng, SeededRng};
+    ///
+    /// let mut rng = RecordingRng::new(SeededRng::new(1));
+    /// let wallet = Wallet::random_with(6, &mut rng);
+    /// let again = Wallet::random_with(6, &mut ReplayRng::new(rng.into_log()));
+    /// assert_eq!(wallet, again);
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn random_with(count: usize, rng: &mut impl enums_core::rng::CrateRng) -> Self {
+        use crate::UsState;
+
+        Wallet::new((0..count).map(|_| match rng.roll_range(0, 3) {
+            0 => Coin2::Penny,
+            1 => Coin2::Nickel,
+            2 => Coin2::Dime,
+            _ => Coin2::Quarter(rng.pick(UsState::VARIANTS).unwrap_or_default()),
+        }))
+    }
+
+    /// The fewest coins that add up to `cents`, biggest first
+    ///
+    /// Change doesn't say which state its quarters are from, so they are
+    /// all from the default one, Virginia.
+    ///
+    /// ```
+    /// use enums_coins::{Coin2, Wallet};
+    ///
+    /// let change = Wallet::change_for(41);
+    /// assert_eq!(change.total(), 41);
+    /// assert_eq!(change.coins()[1..], [Coin2::Dime, Coin2::Nickel, Coin2::Penny]);
+    /// ```
+    pub fn change_for(mut cents: Cents) -> Self {
+        let mut coins = Vec::new();
+        for coin in Coin2::VARIANTS.iter().rev() {
+            let count = cents / coin.cents();
+            coins.extend(core::iter::repeat_n(*coin, count as usize));
+            cents %= coin.cents();
+        }
+        Wallet { coins }
+    }
+
+    /// The coins, in the order they went in
+    ///
+    /// ```
+    /// use enums_coins::{Coin2, Wallet};
+    ///
+    /// assert_eq!(Wallet::new([Coin2::Dime]).coins(), [Coin2::Dime]);
+    /// ```
+    pub fn coins(&self) -> &[Coin2] {
+        &self.coins
+    }
+
+    /// What all the coins add up to
+    ///
+    /// ```
+    /// use enums_coins::Wallet;
+    ///
+    /// assert_eq!(Wallet::default().total(), 0);
+    /// ```
+    pub fn total(&self) -> Cents {
+        self.coins.iter().map(Coin2::cents).sum()
+    }
+}
+
+/// # Which of two wallets holds more money
+///
+/// Returned by `pick_richer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RicherOutcome<'a> {
+    /// The first wallet holds more
+    First(&'a Wallet),
+    /// The second wallet holds more
+    Second(&'a Wallet),
+    /// Both wallets hold `Cents`
+    Tie(Cents),
+}
+
+/// Whichever of `a` and `b` holds more money, or a tie
+///
+/// ```
+/// use enums_coins::wallet::{pick_richer, RicherOutcome};
+/// use enums_coins::{Coin2, Wallet};
+///
+/// let a = Wallet::new([Coin2::Dime]);
+/// let b = Wallet::new([Coin2::Nickel, Coin2::Nickel]);
+/// assert_eq!(pick_richer(&a, &b), RicherOutcome::Tie(10));
+/// ```
+pub fn pick_richer<'a>(a: &'a Wallet, b: &'a Wallet) -> RicherOutcome<'a> {
+    match a.total().cmp(&b.total()) {
+        Ordering::Less => RicherOutcome::Second(b),
+        Ordering::Equal => RicherOutcome::Tie(a.total()),
+        Ordering::Greater => RicherOutcome::First(a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UsState;
+
+    #[test]
+    fn coins_compare_by_value() {
+        assert_eq!(compare_value(&Coin2::Penny, &Coin2::Dime), Ordering::Less);
+        assert_eq!(
+            compare_value(&Coin2::Quarter(UsState::Ohio), &Coin2::Nickel),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_value(
+                &Coin2::Quarter(UsState::Ohio),
+                &Coin2::Quarter(UsState::Utah)
+            ),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn richer_first_wallet() {
+        let a = Wallet::new([Coin2::Quarter(UsState::Iowa), Coin2::Penny]);
+        let b = Wallet::new([Coin2::Dime, Coin2::Dime]);
+        assert_eq!(pick_richer(&a, &b), RicherOutcome::First(&a));
+    }
+
+    #[test]
+    fn richer_second_wallet() {
+        let a = Wallet::new([Coin2::Nickel]);
+        let b = Wallet::new([Coin2::Dime]);
+        match pick_richer(&a, &b) {
+            RicherOutcome::Second(richer) => assert!(std::ptr::eq(richer, &b)),
+            other => panic!("expected the second wallet, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn equal_totals_tie() {
+        // Different coins, same money
+        let a = Wallet::new([Coin2::Quarter(UsState::Utah)]);
+        let b = Wallet::new([Coin2::Dime, Coin2::Dime, Coin2::Nickel]);
+        assert_eq!(pick_richer(&a, &b), RicherOutcome::Tie(25));
+        let empty = Wallet::default();
+        assert_eq!(pick_richer(&empty, &empty), RicherOutcome::Tie(0));
+    }
+
+    #[test]
+    fn random_wallets_repeat_for_a_seed() {
+        assert_eq!(Wallet::random(8, 3), Wallet::random(8, 3));
+        assert_eq!(Wallet::random(8, 3).coins().len(), 8);
+    }
+
+    #[test]
+    fn change_uses_the_fewest_coins() {
+        assert_eq!(Wallet::change_for(0), Wallet::default());
+        assert_eq!(Wallet::change_for(30).coins().len(), 2);
+        assert_eq!(Wallet::change_for(99).coins().len(), 9);
+        for cents in 0..=200 {
+            assert_eq!(Wallet::change_for(cents).total(), cents);
+        }
+    }
+}
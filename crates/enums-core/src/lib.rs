@@ -0,0 +1,40 @@
+//! # Enums and Pattern Matching: the pieces every other crate shares
+//!
+//! The `Cents` that coins and wallets count in, the `noop!` macro, the
+//! helpers for `Option`s of references and for naming the type of a value,
+//! `Maybe`, the hand-written `Option`, and `classify_roll`'s range, guard
+//! and `@` patterns, and with the `rand` feature the `CrateRng` random
+//! picks are drawn from.
+//! Nothing here depends on anything else, so each of the other `enums-*`
+//! crates can use it without pulling in the rest.
+//!
+//! The crate is `#![no_std]`, needing only `alloc`.
+#![cfg_attr(not(test), no_std)]
+#![deny(missing_docs)]
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::todo,
+    clippy::unimplemented
+)]
+#![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used))]
+
+extern crate alloc;
+
+mod macros;
+pub mod maybe;
+pub mod option_ref;
+pub mod patterns_advanced;
+#[cfg(feature = "rand")]
+pub mod rng;
+pub mod type_names;
+
+/// A value in US cents
+///
+/// ```
+/// use enums_core::Cents;
+///
+/// let total: Cents = [10, 5].iter().sum();
+/// assert_eq!(total, 15);
+/// ```
+pub type Cents = u32;
@@ -0,0 +1,81 @@
+//! # Macros exported at the crate root
+
+/// # A `noop` macro to perform no operation
+///
+/// Stands in for a match arm, or any other block, that deliberately does
+/// nothing, and says so more plainly than `{}` or `()`. Whatever tokens it
+/// is given are thrown away unread, so they can say why nothing happens:
+///
+/// ```
+/// use enums_core::noop;
+///
+/// let cents = |coin| match coin {
+///     "quarter" => 25,
+///     _ => {
+///         noop!(smaller coins are not counted);
+///         0
+///     }
+/// };
+/// assert_eq!(cents("dime"), 0);
+/// ```
+///
+/// Given `do` in front of an expression, it evaluates the expression for
+/// its side effects and throws away its value, so the arm is still `()`:
+///
+/// ```
+/// use enums_core::noop;
+///
+/// let mut seen = Vec::new();
+/// let () = noop!(do seen.push(1));
+/// assert_eq!(seen, [1]);
+/// ```
+///
+/// Source: [Reddit: best no op macro? assert!(true)? What do you think of this
+/// error handling pattern?][1]
+///
+/// [1]:
+///     https://www.reddit.com/r/rust/comments/8powj8/comment/e0czkeu/?utm_source=reddit&utm_medium=web2x&context=3
+#[macro_export]
+macro_rules! noop {
+    (do $e:expr) => {{
+        let _ = $e;
+    }};
+    ($($ignored:tt)*) => {
+        ()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn discards_whatever_it_is_given() {
+        let () = noop!();
+        // Not even an expression, let alone one that would compile
+        noop!(this is { not [valid] Rust } at all, 1 + );
+        // Nothing is evaluated without `do`
+        noop!(panic!("evaluated"));
+    }
+
+    #[test]
+    fn evaluates_an_expression_after_do() {
+        let mut calls = 0;
+        noop!(do calls += 1);
+        noop!(do {
+            calls += 1;
+            calls
+        });
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn works_as_a_match_arm() {
+        let describe = |n: u8| match n {
+            0 => noop!(),
+            1 => noop!(one is fine too),
+            _ => noop!(do n.checked_mul(2)),
+        };
+        for n in 0..3 {
+            let () = describe(n);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! # `Option`, written out by hand
+//!
+//! `Option<T>` is no compiler magic: it is an ordinary enum from the
+//! standard library, and its combinators are ordinary `match`es. `Maybe<T>`
+//! is the same enum under other names, with a few of those combinators
+//! written out, to show there is nothing more to them.
+//!
+//! ```
+//! # use enums_core::maybe::{plus_one_maybe, Maybe};
+//! let six = plus_one_maybe(Maybe::Just(5));
+//! assert_eq!(six, Maybe::Just(6));
+//! assert_eq!(Option::from(six), Some(6));
+//! ```
+use core::fmt;
+
+/// # A value that may be missing, just like `Option<T>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Maybe<T> {
+    /// There is a value
+    Just(T),
+    /// There isn't
+    Nothing,
+}
+
+impl<T> Maybe<T> {
+    /// Whether there is a value, like `Option::is_some`
+    pub fn is_just(&self) -> bool {
+        matches!(self, Maybe::Just(_))
+    }
+
+    /// Apply `f` to the value, if there is one, like `Option::map`
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Maybe<U> {
+        match self {
+            Maybe::Just(x) => Maybe::Just(f(x)),
+            Maybe::Nothing => Maybe::Nothing,
+        }
+    }
+
+    /// Apply `f`, which may itself come up empty, to the value, like
+    /// `Option::and_then`
+    pub fn and_then<U>(self, f: impl FnOnce(T) -> Maybe<U>) -> Maybe<U> {
+        match self {
+            Maybe::Just(x) => f(x),
+            Maybe::Nothing => Maybe::Nothing,
+        }
+    }
+
+    /// The value, or `default` if there isn't one, like `Option::unwrap_or`
+    pub fn unwrap_or(self, default: T) -> T {
+        match self {
+            Maybe::Just(x) => x,
+            Maybe::Nothing => default,
+        }
+    }
+
+    /// The value, or `err` if there isn't one, like `Option::ok_or`
+    pub fn ok_or<E>(self, err: E) -> Result<T, E> {
+        match self {
+            Maybe::Just(x) => Ok(x),
+            Maybe::Nothing => Err(err),
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Maybe<T> {
+    fn from(option: Option<T>) -> Self {
+        match option {
+            Some(x) => Maybe::Just(x),
+            None => Maybe::Nothing,
+        }
+    }
+}
+
+impl<T> From<Maybe<T>> for Option<T> {
+    fn from(maybe: Maybe<T>) -> Self {
+        match maybe {
+            Maybe::Just(x) => Some(x),
+            Maybe::Nothing => None,
+        }
+    }
+}
+
+/// `Just(x)` shows as `x`, and `Nothing` as `nothing`
+impl<T: fmt::Display> fmt::Display for Maybe<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Maybe::Just(x) => write!(f, "{x}"),
+            Maybe::Nothing => write!(f, "nothing"),
+        }
+    }
+}
+
+/// `plus_one` from `main`, over `Maybe` instead of `Option`
+#[allow(clippy::manual_map)] // the explicit `match` is the point of the example
+pub fn plus_one_maybe(x: Maybe<i32>) -> Maybe<i32> {
+    match x {
+        Maybe::Nothing => Maybe::Nothing,
+        Maybe::Just(i) => Maybe::Just(i + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn half(x: i32) -> Maybe<i32> {
+        if x % 2 == 0 {
+            Maybe::Just(x / 2)
+        } else {
+            Maybe::Nothing
+        }
+    }
+
+    #[test]
+    fn plus_one_maybe_matches_plus_one() {
+        assert_eq!(plus_one_maybe(Maybe::Just(5)), Maybe::Just(6));
+        assert_eq!(plus_one_maybe(Maybe::Nothing), Maybe::Nothing);
+    }
+
+    #[test]
+    fn is_just() {
+        assert!(Maybe::Just(()).is_just());
+        assert!(!Maybe::<()>::Nothing.is_just());
+    }
+
+    #[test]
+    fn map() {
+        assert_eq!(
+            Maybe::Just(2).map(|x| x.to_string()),
+            Maybe::Just(String::from("2"))
+        );
+        assert_eq!(Maybe::<i32>::Nothing.map(|x| x + 1), Maybe::Nothing);
+    }
+
+    #[test]
+    fn and_then_chains() {
+        assert_eq!(
+            Maybe::Just(12).and_then(half).and_then(half),
+            Maybe::Just(3)
+        );
+        // The second step comes up empty
+        assert_eq!(Maybe::Just(6).and_then(half).and_then(half), Maybe::Nothing);
+        // Nothing to start with, so `half` is never called
+        assert_eq!(Maybe::Nothing.and_then(half).and_then(half), Maybe::Nothing);
+    }
+
+    #[test]
+    fn unwrap_or_and_ok_or() {
+        assert_eq!(Maybe::Just(1).unwrap_or(0), 1);
+        assert_eq!(Maybe::Nothing.unwrap_or(0), 0);
+        assert_eq!(Maybe::Just(1).ok_or("none"), Ok(1));
+        assert_eq!(Maybe::<i32>::Nothing.ok_or("none"), Err("none"));
+    }
+
+    #[test]
+    fn round_trips_with_option() {
+        for option in [Some(3), None] {
+            let maybe = Maybe::from(option);
+            assert_eq!(maybe.is_just(), option.is_some());
+            assert_eq!(Option::from(maybe), option);
+        }
+        for maybe in [Maybe::Just('a'), Maybe::Nothing] {
+            let option: Option<char> = maybe.into();
+            assert_eq!(Maybe::from(option), maybe);
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Maybe::Just(4).to_string(), "4");
+        assert_eq!(Maybe::<i32>::Nothing.to_string(), "nothing");
+    }
+}
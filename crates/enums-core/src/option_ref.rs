@@ -0,0 +1,87 @@
+//! # `Option`s of references
+//!
+//! Right after `Option<T>` comes `Option<&T>`: looking something up in a
+//! collection you don't own gives back a borrow of it, if it's there. These
+//! helpers take and return such options, and their examples show the
+//! adapters that move between the owned and borrowed forms: `as_ref`,
+//! `as_deref` and `copied`.
+use alloc::format;
+use alloc::string::String;
+
+/// One more than the borrowed value, if there is one
+///
+/// The result is a fresh `i32`, so it borrows nothing.
+///
+/// ```
+/// # use enums_core::option_ref::plus_one_ref;
+/// let numbers = vec![1, 2, 3];
+/// assert_eq!(plus_one_ref(numbers.first()), Some(2));
+/// assert_eq!(plus_one_ref(None), None);
+/// ```
+pub fn plus_one_ref(x: Option<&i32>) -> Option<i32> {
+    x.map(|i| i + 1)
+}
+
+/// The first value in `xs` above zero, borrowed from `xs`
+///
+/// `copied` turns the borrow into a value of its own when that's more
+/// convenient:
+///
+/// ```
+/// # use enums_core::option_ref::first_positive;
+/// let xs = [-2, 0, 5, 7];
+/// assert_eq!(first_positive(&xs), Some(&5));
+/// assert_eq!(first_positive(&xs).copied(), Some(5));
+/// assert_eq!(first_positive(&[-1, 0]), None);
+/// ```
+pub fn first_positive(xs: &[i32]) -> Option<&i32> {
+    xs.iter().find(|&&x| x > 0)
+}
+
+/// A greeting for `name`, or for the world if there is no name
+///
+/// An `Option<String>` can't hand out its `String` without giving it up, so
+/// callers pass `as_deref()` to lend out a `&str` instead:
+///
+/// ```
+/// # use enums_core::option_ref::greet;
+/// let name = Some(String::from("Ferris"));
+/// assert_eq!(greet(name.as_deref()), "Hello, Ferris!");
+/// // `name` is still ours
+/// assert_eq!(name.as_ref().map(String::len), Some(6));
+/// assert_eq!(greet(None), "Hello, world!");
+/// ```
+pub fn greet(name: Option<&str>) -> String {
+    format!("Hello, {}!", name.unwrap_or("world"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plus_one_ref_of_none() {
+        assert_eq!(plus_one_ref(None), None);
+        assert_eq!(plus_one_ref(Some(&-1)), Some(0));
+    }
+
+    #[test]
+    fn no_positive_values() {
+        assert_eq!(first_positive(&[]), None);
+        assert_eq!(first_positive(&[-3, -2, 0]), None);
+    }
+
+    #[test]
+    fn first_positive_borrows_from_the_slice() {
+        let xs = [0, 4, 4, 9];
+        let found = first_positive(&xs).unwrap();
+        // The first 4, not the second one or a copy
+        assert!(std::ptr::eq(found, &xs[1]));
+    }
+
+    #[test]
+    fn greet_with_and_without_a_name() {
+        assert_eq!(greet(Some("you")), "Hello, you!");
+        assert_eq!(greet(None), "Hello, world!");
+    }
+}
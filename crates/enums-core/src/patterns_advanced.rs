@@ -0,0 +1,74 @@
+//! # Ranges, guards and `@` bindings
+//!
+//! A `match` arm can do more than name a single value:
+//!
+//! - a range pattern such as `2..=3` matches every value in it
+//! - a guard, `n if n % 2 == 0`, adds a condition the pattern alone can't
+//!   express
+//! - an `@` binding, `big @ 10..=12`, tests against a pattern and keeps
+//!   the value it matched
+//!
+//! Here all three sort the sum of two six-sided dice into a `RollClass`:
+//!
+//! ```
+//! # use enums_core::patterns_advanced::{classify_roll, RollClass};
+//! assert_eq!(classify_roll(7), RollClass::Seven);
+//! assert_eq!(classify_roll(11), RollClass::Big(11));
+//! ```
+/// # What kind of two-dice roll a sum is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollClass {
+    /// 2 or 3
+    Low,
+    /// 7, the most likely sum
+    Seven,
+    /// An even sum from 4 to 8
+    Even(u8),
+    /// An odd sum from 5 to 9, other than 7
+    Odd(u8),
+    /// 10 to 12
+    Big(u8),
+    /// A sum two dice can't make: 0, 1 or more than 12
+    Impossible(u8),
+}
+
+/// Classify the sum of two dice
+///
+/// The arms are tried in order, so `Seven` comes before the guard that would
+/// otherwise call it `Odd`.
+pub fn classify_roll(roll: u8) -> RollClass {
+    match roll {
+        n @ (0..=1 | 13..) => RollClass::Impossible(n),
+        2..=3 => RollClass::Low,
+        7 => RollClass::Seven,
+        big @ 10..=12 => RollClass::Big(big),
+        n if n % 2 == 0 => RollClass::Even(n),
+        n => RollClass::Odd(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_boundaries() {
+        assert_eq!(classify_roll(2), RollClass::Low);
+        assert_eq!(classify_roll(3), RollClass::Low);
+        assert_eq!(classify_roll(4), RollClass::Even(4));
+        assert_eq!(classify_roll(5), RollClass::Odd(5));
+        assert_eq!(classify_roll(6), RollClass::Even(6));
+        assert_eq!(classify_roll(7), RollClass::Seven);
+        assert_eq!(classify_roll(8), RollClass::Even(8));
+        assert_eq!(classify_roll(9), RollClass::Odd(9));
+        assert_eq!(classify_roll(10), RollClass::Big(10));
+        assert_eq!(classify_roll(12), RollClass::Big(12));
+    }
+
+    #[test]
+    fn impossible_sums() {
+        for roll in [0, 1, 13, u8::MAX] {
+            assert_eq!(classify_roll(roll), RollClass::Impossible(roll));
+        }
+    }
+}
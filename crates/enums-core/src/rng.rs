@@ -0,0 +1,535 @@
+//! # Where the random picks come from
+//!
+//! Code that picks a coin, a state or a die face at random takes a
+//! `CrateRng` rather than a `rand::Rng`, so the picks can come from the
+//! thread's RNG (`DefaultRng`), from a seed (`SeededRng`), or from a log
+//! of an earlier run (`ReplayRng`). A `RecordingRng` wraps any of them and
+//! writes each decision to an `RngLog`, so a surprising run can be played
+//! again exactly, even one whose seed nobody noted.
+//!
+//! ```
+//! use enums_core::rng::{CrateRng, RecordingRng, ReplayRng, RngLog, SeededRng};
+//!
+//! let coins = ["penny", "nickel", "dime", "quarter"];
+//! let mut recording = RecordingRng::new(SeededRng::new(7));
+//! let picks: Vec<Option<&str>> = (0..5).map(|_| recording.pick(&coins)).collect();
+//!
+//! let log: RngLog = recording.into_log().to_string().parse().unwrap();
+//! let mut replay = ReplayRng::new(log);
+//! let again: Vec<Option<&str>> = (0..5).map(|_| replay.pick(&coins)).collect();
+//! assert_eq!(picks, again);
+//! ```
+//!
+//! A `SeededRng` draws numbers that fit in a `u32` the way `rand`'s
+//! `SliceRandom::choose` does, die faces the way `gen_range` on a `u8`
+//! does, and weighted picks with a `WeightedIndex`, so each comes out as it
+//! would from a seeded `StdRng` used directly. An `RngLog` is written as
+//! text with `Display`, one decision per line, and read back with
+//! `FromStr`.
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// # A source of random decisions
+///
+/// Only `roll_range` has to be written; the other decisions are made from
+/// it unless an RNG has a better way.
+pub trait CrateRng {
+    /// One of `items`, each as likely as the others, or `None` if there
+    /// are none
+    ///
+    /// This returns an `Option`, as `SliceRandom::choose` does, rather than
+    /// a bare `T`, so picking from an empty slice can't panic.
+    fn pick<T: Copy>(&mut self, items: &[T]) -> Option<T> {
+        let last = items.len().checked_sub(1)?;
+        items.get(self.roll_range(0, last as u64) as usize).copied()
+    }
+
+    /// A number from `lo` to `hi`, both included, each as likely as the
+    /// others; `lo` if `hi` is below it
+    fn roll_range(&mut self, lo: u64, hi: u64) -> u64;
+
+    /// A face from 1 to `sides` of a fair die; 1 if it has fewer than two
+    fn roll_face(&mut self, sides: u8) -> u8 {
+        self.roll_range(1, u64::from(sides)) as u8
+    }
+
+    /// The index of one of `weights`, each in proportion to its weight, or
+    /// `None` if a weight is negative, infinite or NaN, or none is above
+    /// zero
+    fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        let total = weights_total(weights)?;
+        // A point in `0.0..total`, in steps of `total / 2^32`
+        let step = self.roll_range(0, u64::from(u32::MAX)) as f64;
+        let point = step / (f64::from(u32::MAX) + 1.0) * total;
+        let mut below = 0.0;
+        weights
+            .iter()
+            .position(|w| {
+                below += w;
+                point < below
+            })
+            // Rounding can leave the point just past the last weight
+            .or_else(|| weights.iter().rposition(|w| *w > 0.0))
+    }
+}
+
+impl<R: CrateRng + ?Sized> CrateRng for &mut R {
+    fn pick<T: Copy>(&mut self, items: &[T]) -> Option<T> {
+        (**self).pick(items)
+    }
+
+    fn roll_range(&mut self, lo: u64, hi: u64) -> u64 {
+        (**self).roll_range(lo, hi)
+    }
+
+    fn roll_face(&mut self, sides: u8) -> u8 {
+        (**self).roll_face(sides)
+    }
+
+    fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        (**self).pick_weighted(weights)
+    }
+}
+
+/// The sum of `weights`, if they are fit to pick from
+fn weights_total(weights: &[f64]) -> Option<f64> {
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        return None;
+    }
+    let total: f64 = weights.iter().sum();
+    (total > 0.0).then_some(total)
+}
+
+/// A number from `lo` to `hi` drawn from `rng`, sampled as a `u32` when it
+/// fits in one, as `SliceRandom::choose` samples indexes
+fn draw(rng: &mut impl Rng, lo: u64, hi: u64) -> u64 {
+    if hi <= lo {
+        return lo;
+    }
+    match (u32::try_from(lo), u32::try_from(hi)) {
+        (Ok(lo), Ok(hi)) => u64::from(rng.gen_range(lo..=hi)),
+        _ => rng.gen_range(lo..=hi),
+    }
+}
+
+/// A face from 1 to `sides` drawn from `rng`, sampled as a `u8`, which
+/// rejects a different set of draws than a wider type would
+fn draw_face(rng: &mut impl Rng, sides: u8) -> u8 {
+    if sides < 2 {
+        return 1;
+    }
+    rng.gen_range(1..=sides)
+}
+
+/// The index of one of `weights` drawn from `rng` with a `WeightedIndex`
+fn draw_weighted(rng: &mut impl Rng, weights: &[f64]) -> Option<usize> {
+    weights_total(weights)?;
+    Some(WeightedIndex::new(weights).ok()?.sample(rng))
+}
+
+/// # Decisions drawn from the thread's RNG, different on every run
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRng;
+
+#[cfg(feature = "std")]
+impl CrateRng for DefaultRng {
+    fn roll_range(&mut self, lo: u64, hi: u64) -> u64 {
+        draw(&mut rand::thread_rng(), lo, hi)
+    }
+
+    fn roll_face(&mut self, sides: u8) -> u8 {
+        draw_face(&mut rand::thread_rng(), sides)
+    }
+
+    fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        draw_weighted(&mut rand::thread_rng(), weights)
+    }
+}
+
+/// # Decisions drawn from a `StdRng`, the same for the same seed
+#[derive(Debug, Clone)]
+pub struct SeededRng(StdRng);
+
+impl SeededRng {
+    /// An RNG whose decisions are decided by `seed`
+    pub fn new(seed: u64) -> Self {
+        SeededRng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl CrateRng for SeededRng {
+    fn roll_range(&mut self, lo: u64, hi: u64) -> u64 {
+        draw(&mut self.0, lo, hi)
+    }
+
+    fn roll_face(&mut self, sides: u8) -> u8 {
+        draw_face(&mut self.0, sides)
+    }
+
+    fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        draw_weighted(&mut self.0, weights)
+    }
+}
+
+/// # One decision an RNG made: `value`, out of `lo..=hi`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decision {
+    /// The lowest value it could have been
+    pub lo: u64,
+    /// The highest value it could have been
+    pub hi: u64,
+    /// The value it was
+    pub value: u64,
+}
+
+/// # Every decision a `RecordingRng` saw, in order
+///
+/// Written with `Display` as a line of `lo..=hi value` per decision, which
+/// `FromStr` reads back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RngLog {
+    decisions: Vec<Decision>,
+}
+
+impl RngLog {
+    /// The decisions, in the order they were made
+    pub fn decisions(&self) -> &[Decision] {
+        &self.decisions
+    }
+}
+
+impl fmt::Display for RngLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for Decision { lo, hi, value } in &self.decisions {
+            writeln!(f, "{lo}..={hi} {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// # A line of an `RngLog` that isn't a decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLogError {
+    /// The line, counting from 1
+    pub line: usize,
+}
+
+impl fmt::Display for ParseLogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {} is not a decision like `0..=3 2`, with the value in its range",
+            self.line
+        )
+    }
+}
+
+impl core::error::Error for ParseLogError {}
+
+impl FromStr for RngLog {
+    type Err = ParseLogError;
+
+    /// The log written by `Display`; blank lines are skipped
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let decision = |line: &str| {
+            let (range, value) = line.trim().split_once(' ')?;
+            let (lo, hi) = range.split_once("..=")?;
+            let decision = Decision {
+                lo: lo.parse().ok()?,
+                hi: hi.parse().ok()?,
+                value: value.trim().parse().ok()?,
+            };
+            let Decision { lo, hi, value } = decision;
+            (lo <= value && value <= hi.max(lo)).then_some(decision)
+        };
+        let decisions = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| decision(line).ok_or(ParseLogError { line: i + 1 }))
+            .collect::<Result<_, _>>()?;
+        Ok(RngLog { decisions })
+    }
+}
+
+/// # An RNG that writes down every decision another one makes
+#[derive(Debug, Clone)]
+pub struct RecordingRng<R> {
+    inner: R,
+    log: RngLog,
+}
+
+impl<R: CrateRng> RecordingRng<R> {
+    /// Record the decisions of `inner`
+    pub fn new(inner: R) -> Self {
+        RecordingRng {
+            inner,
+            log: RngLog::default(),
+        }
+    }
+
+    /// The decisions made so far
+    pub fn log(&self) -> &RngLog {
+        &self.log
+    }
+
+    /// The decisions made, once done
+    pub fn into_log(self) -> RngLog {
+        self.log
+    }
+}
+
+impl<R: CrateRng> RecordingRng<R> {
+    fn push(&mut self, lo: u64, hi: u64, value: u64) {
+        self.log.decisions.push(Decision { lo, hi, value });
+    }
+}
+
+/// A face is logged as a roll from 1 to `sides`, and a weighted pick as a
+/// roll over the indexes of the weights, so a `ReplayRng` can make either
+/// again with `roll_range`
+impl<R: CrateRng> CrateRng for RecordingRng<R> {
+    fn roll_range(&mut self, lo: u64, hi: u64) -> u64 {
+        let value = self.inner.roll_range(lo, hi);
+        self.push(lo, hi, value);
+        value
+    }
+
+    fn roll_face(&mut self, sides: u8) -> u8 {
+        let face = self.inner.roll_face(sides);
+        self.push(1, u64::from(sides), u64::from(face));
+        face
+    }
+
+    fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        let index = self.inner.pick_weighted(weights)?;
+        self.push(0, weights.len() as u64 - 1, index as u64);
+        Some(index)
+    }
+}
+
+/// # An RNG making the decisions in an `RngLog` again, in order
+///
+/// Asked for a decision the log doesn't hold next, because it has run out
+/// or the next one was out of another range, the replay has diverged: it
+/// answers `lo` from then on, and `diverged` says where it happened.
+#[derive(Debug, Clone)]
+pub struct ReplayRng {
+    log: RngLog,
+    next: usize,
+    diverged: Option<usize>,
+}
+
+impl ReplayRng {
+    /// Replay `log` from its first decision
+    pub fn new(log: RngLog) -> Self {
+        ReplayRng {
+            log,
+            next: 0,
+            diverged: None,
+        }
+    }
+
+    /// The index of the first decision asked for that the log didn't hold,
+    /// if there has been one
+    pub fn diverged(&self) -> Option<usize> {
+        self.diverged
+    }
+
+    /// Whether every decision in the log has been made again
+    pub fn is_done(&self) -> bool {
+        self.next == self.log.decisions.len()
+    }
+}
+
+impl CrateRng for ReplayRng {
+    fn roll_range(&mut self, lo: u64, hi: u64) -> u64 {
+        if self.diverged.is_some() {
+            return lo;
+        }
+        match self.log.decisions.get(self.next) {
+            Some(d) if d.lo == lo && d.hi == hi => {
+                self.next += 1;
+                d.value
+            }
+            _ => {
+                self.diverged = Some(self.next);
+                lo
+            }
+        }
+    }
+
+    /// The index a `RecordingRng` logged; weights it wouldn't pick from
+    /// make no decision, as they made none when recorded
+    fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        weights_total(weights)?;
+        Some(self.roll_range(0, weights.len() as u64 - 1) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn seeded_picks_match_choose() {
+        let items: Vec<u32> = (0..50).collect();
+        let mut ours = SeededRng::new(3);
+        let mut theirs = StdRng::seed_from_u64(3);
+        for _ in 0..100 {
+            assert_eq!(ours.pick(&items), items.choose(&mut theirs).copied());
+        }
+    }
+
+    #[test]
+    fn nothing_is_picked_from_nothing() {
+        let mut rng = SeededRng::new(3);
+        assert_eq!(rng.pick::<u8>(&[]), None);
+        assert_eq!(rng.pick_weighted(&[]), None);
+        assert_eq!(rng.pick_weighted(&[0.0, 0.0]), None);
+        assert_eq!(rng.pick_weighted(&[1.0, f64::NAN]), None);
+        assert_eq!(rng.pick_weighted(&[1.0, -1.0]), None);
+    }
+
+    #[test]
+    fn seeded_faces_match_gen_range_on_a_u8() {
+        let mut ours = SeededRng::new(4);
+        let mut theirs = StdRng::seed_from_u64(4);
+        for sides in [6, 12, 20].into_iter().cycle().take(300) {
+            assert_eq!(ours.roll_face(sides), theirs.gen_range(1..=sides));
+        }
+    }
+
+    #[test]
+    fn seeded_weighted_picks_match_weighted_index() {
+        let weights = [0.5, 0.0, 2.0, 1.0];
+        let index = WeightedIndex::new(weights).unwrap();
+        let mut ours = SeededRng::new(5);
+        let mut theirs = StdRng::seed_from_u64(5);
+        for _ in 0..300 {
+            assert_eq!(
+                ours.pick_weighted(&weights),
+                Some(index.sample(&mut theirs))
+            );
+        }
+    }
+
+    /// An RNG with only `roll_range`, to test the other decisions' defaults
+    struct RangesOnly(SeededRng);
+
+    impl CrateRng for RangesOnly {
+        fn roll_range(&mut self, lo: u64, hi: u64) -> u64 {
+            self.0.roll_range(lo, hi)
+        }
+    }
+
+    #[test]
+    fn default_decisions_are_made_from_ranges() {
+        let mut rng = RangesOnly(SeededRng::new(6));
+        assert!((0..1000).all(|_| (1..=6).contains(&rng.roll_face(6))));
+        assert_eq!(rng.roll_face(1), 1);
+
+        let n = 20_000;
+        let mut counts = [0; 3];
+        for _ in 0..n {
+            counts[rng.pick_weighted(&[1.0, 0.0, 3.0]).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        let share = f64::from(counts[2]) / f64::from(n);
+        assert!((share - 0.75).abs() < 0.02, "{share}");
+    }
+
+    #[test]
+    fn faces_and_weighted_picks_replay() {
+        let weights = [1.0, 4.0, 2.0];
+        let mut recording = RecordingRng::new(SeededRng::new(7));
+        let picks: Vec<(u8, Option<usize>)> = (0..20)
+            .map(|_| (recording.roll_face(6), recording.pick_weighted(&weights)))
+            .collect();
+        assert_eq!(recording.pick_weighted(&[0.0]), None);
+        assert_eq!(recording.log().decisions().len(), 40);
+
+        let mut replay = ReplayRng::new(recording.into_log());
+        let again: Vec<(u8, Option<usize>)> = (0..20)
+            .map(|_| (replay.roll_face(6), replay.pick_weighted(&weights)))
+            .collect();
+        assert_eq!(picks, again);
+        assert_eq!(replay.pick_weighted(&[0.0]), None);
+        assert!(replay.is_done());
+        assert_eq!(replay.diverged(), None);
+    }
+
+    #[test]
+    fn rolls_stay_in_range() {
+        let mut rng = SeededRng::new(1);
+        for _ in 0..1000 {
+            assert!((1..=6).contains(&rng.roll_range(1, 6)));
+        }
+        assert_eq!(rng.roll_range(4, 4), 4);
+        assert_eq!(rng.roll_range(9, 2), 9);
+        assert!(rng.roll_range(1 << 40, u64::MAX) >= 1 << 40);
+        assert!((1..=6).contains(&DefaultRng.roll_range(1, 6)));
+    }
+
+    #[test]
+    fn a_replay_makes_the_recorded_picks() {
+        let mut recording = RecordingRng::new(SeededRng::new(11));
+        let picks: Vec<u64> = (0..20).map(|n| recording.roll_range(0, n)).collect();
+        assert_eq!(recording.log().decisions().len(), 20);
+
+        let mut replay = ReplayRng::new(recording.into_log());
+        let again: Vec<u64> = (0..20).map(|n| replay.roll_range(0, n)).collect();
+        assert_eq!(picks, again);
+        assert!(replay.is_done());
+        assert_eq!(replay.diverged(), None);
+    }
+
+    #[test]
+    fn a_replay_asked_for_something_else_diverges() {
+        let mut recording = RecordingRng::new(SeededRng::new(2));
+        recording.roll_range(1, 6);
+        let mut replay = ReplayRng::new(recording.into_log());
+        assert_eq!(replay.roll_range(1, 20), 1);
+        assert_eq!(replay.roll_range(1, 6), 1);
+        assert_eq!(replay.diverged(), Some(0));
+    }
+
+    #[test]
+    fn logs_round_trip_as_text() {
+        let mut recording = RecordingRng::new(SeededRng::new(5));
+        for hi in [3, 49, u64::MAX] {
+            recording.roll_range(0, hi);
+        }
+        let log = recording.into_log();
+        let text = log.to_string();
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.starts_with("0..=3 "), "{text}");
+        assert_eq!(text.parse::<RngLog>(), Ok(log));
+    }
+
+    #[test]
+    fn malformed_logs_name_the_line() {
+        assert_eq!(
+            "0..=3 2\n\n1..=6 6\n"
+                .parse::<RngLog>()
+                .map(|l| l.decisions().len()),
+            Ok(2)
+        );
+        assert_eq!(
+            "0..=3 2\n0..=3 9\n".parse::<RngLog>(),
+            Err(ParseLogError { line: 2 })
+        );
+        assert_eq!(
+            "zero to three\n".parse::<RngLog>(),
+            Err(ParseLogError { line: 1 })
+        );
+    }
+}
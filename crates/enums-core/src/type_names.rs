@@ -0,0 +1,100 @@
+//! # The type of a value, as a string
+//!
+//! `std::any::type_name` spells out every path in full, which is exact but
+//! hard to read once generics nest: `Option<Vec<Coin2>>` comes out as
+//! `core::option::Option<alloc::vec::Vec<enums_coins::coin::Coin2>>`.
+//! `short_type_name_of` keeps only the last segment of each path.
+//!
+//! Neither name is guaranteed to stay the same between compiler versions,
+//! so they are for showing to people, not for comparing types.
+use alloc::string::String;
+use core::any::type_name;
+
+/// The full name of the type of the value given
+///
+/// ```
+/// # use enums_core::type_names::type_name_of;
+/// assert_eq!(type_name_of(&Some(5)), "core::option::Option<i32>");
+/// ```
+pub fn type_name_of<T: ?Sized>(_: &T) -> &'static str {
+    type_name::<T>()
+}
+
+/// The name of the type of the value given, without module paths
+///
+/// Every path in the name is shortened, including those of generic
+/// arguments, tuple members and referenced types:
+///
+/// ```
+/// # use enums_core::type_names::short_type_name_of;
+/// struct Coin2;
+/// let coins = vec![Coin2];
+/// assert_eq!(short_type_name_of(&Some(&coins)), "Option<&Vec<Coin2>>");
+/// assert_eq!(short_type_name_of(&(1u8, "two")), "(u8, &str)");
+/// ```
+pub fn short_type_name_of<T: ?Sized>(value: &T) -> String {
+    shorten(type_name_of(value))
+}
+
+/// `name` with each `a::b::C` path in it cut down to `C`
+fn shorten(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut path = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || matches!(c, '_' | ':' | '{' | '}') {
+            path.push(c);
+        } else {
+            short.push_str(last_segment(&path));
+            path.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(last_segment(&path));
+    short
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Named for the coin type the examples ask about
+    enum Coin2 {
+        Penny,
+        Nickel,
+        Dime,
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(short_type_name_of(&Some(1i32)), "Option<i32>");
+        assert_eq!(short_type_name_of(&Some(Some('c'))), "Option<Option<char>>");
+    }
+
+    #[test]
+    fn paths_inside_generics_are_shortened() {
+        let coins = vec![Coin2::Dime];
+        assert_eq!(
+            type_name_of(&coins),
+            "alloc::vec::Vec<enums_core::type_names::tests::Coin2>"
+        );
+        assert_eq!(short_type_name_of(&coins), "Vec<Coin2>");
+        let results: Vec<Result<Coin2, String>> = Vec::new();
+        assert_eq!(short_type_name_of(&results), "Vec<Result<Coin2, String>>");
+    }
+
+    #[test]
+    fn tuples_and_references() {
+        assert_eq!(
+            short_type_name_of(&(Coin2::Penny, Some(2u8), "s")),
+            "(Coin2, Option<u8>, &str)"
+        );
+        let coin = Coin2::Nickel;
+        assert_eq!(short_type_name_of(&&coin), "&Coin2");
+        assert_eq!(short_type_name_of(&[Some(&coin)]), "[Option<&Coin2>; 1]");
+        assert_eq!(short_type_name_of("unsized"), "str");
+    }
+}
@@ -0,0 +1,381 @@
+//! # Applying `Message`s on an async worker
+//!
+//! The async twin of [`worker`](crate::worker): messages are sent with
+//! `Sender::send(..).await` and the canvas comes back by awaiting the
+//! `JoinHandle`. Like the thread worker, it stops at a `Quit` or once every
+//! `Sender` is gone.
+//!
+//! The module carries its own small runtime: an unbounded channel whose
+//! receiving end is a future, and `block_on` to drive a future to
+//! completion on the current thread. The worker runs its async loop under
+//! `block_on` on a thread of its own, so there is no executor to share
+//! and no timers.
+use std::collections::VecDeque;
+use std::future::{poll_fn, Future};
+use std::io;
+use std::pin::{pin, Pin};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use crate::canvas::{self, Canvas};
+use crate::Message;
+
+/// Run `future` to completion on the current thread, parking between polls
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    /// Wakes the blocked thread by unparking it
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+/// State shared by both ends of a channel
+struct Shared<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    receiver_alive: bool,
+    waker: Option<Waker>,
+}
+
+fn lock<T>(shared: &Mutex<Shared<T>>) -> MutexGuard<'_, Shared<T>> {
+    // Nothing panics while holding the lock, so it is never poisoned
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// An unbounded channel whose `Receiver::recv` can be awaited
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Mutex::new(Shared {
+        queue: VecDeque::new(),
+        senders: 1,
+        receiver_alive: true,
+        waker: None,
+    }));
+    (
+        Sender {
+            shared: Arc::clone(&shared),
+        },
+        Receiver { shared },
+    )
+}
+
+/// # The sending half of a `channel`
+pub struct Sender<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+/// # A value sent after its `Receiver` was dropped
+#[derive(Debug, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> Sender<T> {
+    /// Queue `value` for the receiver, or hand it back if the receiver is
+    /// gone
+    ///
+    /// The channel is unbounded, so this never actually waits.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut shared = lock(&self.shared);
+        if !shared.receiver_alive {
+            return Err(SendError(value));
+        }
+        shared.queue.push_back(value);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        lock(&self.shared).senders += 1;
+        Sender {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = lock(&self.shared);
+        shared.senders -= 1;
+        if shared.senders == 0 {
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// # The receiving half of a `channel`
+pub struct Receiver<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// The next value, or `None` once the queue is empty and every `Sender`
+    /// has been dropped
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context) -> Poll<Option<T>> {
+        let mut shared = lock(&self.shared);
+        match shared.queue.pop_front() {
+            Some(value) => Poll::Ready(Some(value)),
+            None if shared.senders == 0 => Poll::Ready(None),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        lock(&self.shared).receiver_alive = false;
+    }
+}
+
+/// # Awaits the `Canvas` of a worker spawned by `spawn_async_worker`
+///
+/// Resolves to `Err` with the panic payload if the worker panicked, as
+/// `std::thread::JoinHandle::join` does.
+pub struct JoinHandle<T> {
+    result: Receiver<T>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = thread::Result<T>;
+
+    /// Polling again after completion stays pending, which the `Future`
+    /// contract allows, rather than panicking
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let Poll::Ready(result) = self.result.poll_recv(cx) else {
+            return Poll::Pending;
+        };
+        let Some(thread) = self.thread.take() else {
+            return Poll::Pending;
+        };
+        // The worker sends its result as its very last step
+        Poll::Ready(match (result, thread.join()) {
+            (Some(value), _) => Ok(value),
+            (None, Err(payload)) => Err(payload),
+            // Dropping the sender without a result or a panic would be a
+            // bug in the worker, reported as a panic would be
+            (None, Ok(())) => Err(Box::new("worker ended without a result")),
+        })
+    }
+}
+
+/// Spawn an async worker that applies received `Message`s to a fresh
+/// `Canvas`
+///
+/// The worker stops, resolving the `JoinHandle` to its canvas, once it has
+/// applied a `Quit` or once every `Sender` has been dropped. Messages queued
+/// behind the `Quit` are never applied.
+pub fn spawn_async_worker() -> (Sender<Message>, JoinHandle<Canvas>) {
+    let (sender, mut receiver) = channel();
+    let (done, result) = channel();
+    let thread = thread::spawn(move || {
+        block_on(async move {
+            let mut canvas = Canvas::new();
+            while let Some(msg) = receiver.recv().await {
+                canvas::apply(&mut canvas, &msg);
+                if !canvas.is_running() {
+                    break;
+                }
+            }
+            // The handle may already have been dropped, which is fine
+            let _ = done.send(canvas).await;
+        })
+    });
+    let handle = JoinHandle {
+        result,
+        thread: Some(thread),
+    };
+    (sender, handle)
+}
+
+/// # A source of lines that can be awaited
+///
+/// The async counterpart of `BufRead::lines`.
+pub trait AsyncLines {
+    /// The next line without its newline, or `None` at the end of input
+    fn poll_next_line(&mut self, cx: &mut Context) -> Poll<Option<io::Result<String>>>;
+}
+
+/// Lines arriving over a channel, from a producer on another task or thread
+impl AsyncLines for Receiver<String> {
+    fn poll_next_line(&mut self, cx: &mut Context) -> Poll<Option<io::Result<String>>> {
+        self.poll_recv(cx).map(|line| line.map(Ok))
+    }
+}
+
+/// Read one `Message` from each line of `lines`, like `json::read_jsonl`
+#[cfg(feature = "json")]
+pub fn read_jsonl_async<L: AsyncLines>(lines: L) -> AsyncJsonl<L> {
+    AsyncJsonl { lines, number: 0 }
+}
+
+/// # `Message`s read from `AsyncLines` of JSON
+///
+/// Returned by `read_jsonl_async`.
+#[cfg(feature = "json")]
+pub struct AsyncJsonl<L> {
+    lines: L,
+    number: usize,
+}
+
+#[cfg(feature = "json")]
+impl<L: AsyncLines> AsyncJsonl<L> {
+    /// The next message, or `None` at the end of input
+    ///
+    /// Blank lines are skipped, and a bad line yields an error without
+    /// ending the stream, as with `json::read_jsonl`.
+    pub async fn next(&mut self) -> Option<Result<Message, crate::json::JsonlError>> {
+        use crate::json::{message_from_json, JsonlError};
+
+        loop {
+            let line = poll_fn(|cx| self.lines.poll_next_line(cx)).await?;
+            self.number += 1;
+            match line {
+                Err(e) => return Some(Err(JsonlError::Io(e))),
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => {
+                    return Some(message_from_json(&line).map_err(|error| JsonlError::Json {
+                        line: self.number,
+                        error,
+                    }))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_when_senders_are_dropped() {
+        let canvas = block_on(async {
+            let (sender, handle) = spawn_async_worker();
+            for msg in [
+                Message::Move { x: 1, y: 2 },
+                Message::Write(String::from("a")),
+                Message::Draw(vec![(3, 3)]),
+            ] {
+                sender.send(msg).await.unwrap();
+            }
+            drop(sender);
+            handle.await.unwrap()
+        });
+        assert_eq!(canvas.cursor(), (1, 2));
+        assert_eq!(canvas.log(), ["a"]);
+        assert_eq!(canvas.points().len(), 1);
+        assert!(canvas.is_running());
+    }
+
+    #[test]
+    fn stops_at_quit_with_messages_queued_behind_it() {
+        let canvas = block_on(async {
+            let (sender, handle) = spawn_async_worker();
+            sender
+                .send(Message::Write(String::from("before")))
+                .await
+                .unwrap();
+            sender.send(Message::Quit { code: 1 }).await.unwrap();
+            for i in 0..100 {
+                // The worker may already have hung up, so these can fail
+                let _ = sender.send(Message::Write(format!("after {i}"))).await;
+            }
+            // Still holding the sender: only the Quit can stop the worker
+            let canvas = handle.await.unwrap();
+            drop(sender);
+            canvas
+        });
+        assert_eq!(canvas.log(), ["before"]);
+        assert_eq!(canvas.exit_code(), Some(1));
+    }
+
+    #[test]
+    fn polling_after_completion_stays_pending() {
+        let (sender, handle) = spawn_async_worker();
+        drop(sender);
+        let mut handle = std::pin::pin!(handle);
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        loop {
+            match handle.as_mut().poll(&mut cx) {
+                Poll::Ready(canvas) => break assert!(canvas.is_ok()),
+                Poll::Pending => thread::yield_now(),
+            }
+        }
+        assert!(handle.as_mut().poll(&mut cx).is_pending());
+    }
+
+    #[test]
+    fn send_after_the_receiver_is_gone_fails() {
+        let (sender, receiver) = channel();
+        drop(receiver);
+        assert_eq!(block_on(sender.send(5)), Err(SendError(5)));
+    }
+
+    #[test]
+    fn recv_waits_for_another_thread() {
+        let (sender, mut receiver) = channel();
+        let producer = thread::spawn(move || {
+            for i in 0..3 {
+                block_on(sender.send(i)).unwrap();
+            }
+        });
+        let received = block_on(async {
+            let mut received = Vec::new();
+            while let Some(i) = receiver.recv().await {
+                received.push(i);
+            }
+            received
+        });
+        producer.join().unwrap();
+        assert_eq!(received, [0, 1, 2]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn jsonl_over_a_channel_keeps_going_after_a_bad_line() {
+        use crate::json::JsonlError;
+
+        let (sender, receiver) = channel();
+        for line in [r#"{"Move":{"x":1,"y":2}}"#, r#"{"Move":"#, "", r#""Quit""#] {
+            block_on(sender.send(line.to_string())).unwrap();
+        }
+        drop(sender);
+        let results = block_on(async {
+            let mut jsonl = read_jsonl_async(receiver);
+            let mut results = Vec::new();
+            while let Some(result) = jsonl.next().await {
+                results.push(result);
+            }
+            results
+        });
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], Ok(Message::Move { x: 1, y: 2 })));
+        assert!(matches!(results[1], Err(JsonlError::Json { line: 2, .. })));
+        assert!(matches!(results[2], Ok(Message::Quit { code: 0 })));
+    }
+}
@@ -0,0 +1,171 @@
+//! # A fluent builder for sequences of `Message`s
+//!
+//! Instead of hand-writing `vec![Message::Move { .. }, ...]`, a sequence can
+//! be composed one call at a time:
+//!
+//! ```
+//! # use enums_msg::builder::MessageScript;
+//! let msgs = MessageScript::new()
+//!     .move_by(3, 4)
+//!     .write("hello")
+//!     .color(255, 0, 0)
+//!     .quit()
+//!     .build();
+//! assert_eq!(msgs.len(), 4);
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::color::Color;
+use crate::Message;
+
+/// # Builder for a `Vec<Message>`
+///
+/// A script holds at most one `Quit`: once one has been added, further calls
+/// to `quit()` do nothing. Messages added after the `Quit` are kept in order,
+/// even though a `run_queue` would never reach them.
+#[derive(Debug, Default)]
+pub struct MessageScript {
+    messages: Vec<Message>,
+    has_quit: bool,
+}
+
+impl MessageScript {
+    /// An empty script
+    pub fn new() -> Self {
+        MessageScript::default()
+    }
+
+    /// Add a `Move { x, y }`
+    pub fn move_by(mut self, x: i32, y: i32) -> Self {
+        self.messages.push(Message::Move { x, y });
+        self
+    }
+
+    /// Add a `Write` of `text`
+    pub fn write(mut self, text: impl Into<String>) -> Self {
+        self.messages.push(Message::Write(text.into()));
+        self
+    }
+
+    /// Add a `ChangeColor` to the color with components `r`, `g`, `b`
+    pub fn color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.messages
+            .push(Message::ChangeColor(Color::rgb(r, g, b)));
+        self
+    }
+
+    /// Add a `Resize` to a `width` by `height` board
+    pub fn resize(mut self, width: u32, height: u32) -> Self {
+        self.messages.push(Message::Resize { width, height });
+        self
+    }
+
+    /// Add a `Draw` of `points`
+    pub fn draw(mut self, points: impl Into<Vec<(i32, i32)>>) -> Self {
+        self.messages.push(Message::Draw(points.into()));
+        self
+    }
+
+    /// Add a `Quit`, unless the script already has one
+    pub fn quit(mut self) -> Self {
+        if !self.has_quit {
+            self.messages.push(Message::Quit { code: 0 });
+            self.has_quit = true;
+        }
+        self
+    }
+
+    /// Run `body` on the script `n` times, appending its messages each time
+    pub fn repeat(mut self, n: usize, body: impl Fn(Self) -> Self) -> Self {
+        for _ in 0..n {
+            self = body(self);
+        }
+        self
+    }
+
+    /// Number of messages added so far
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// `true` if no messages have been added
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Finish the script
+    pub fn build(self) -> Vec<Message> {
+        self.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_the_same_as_a_literal() {
+        let built = MessageScript::new()
+            .move_by(3, 4)
+            .write("hello")
+            .color(255, 0, 0)
+            .resize(40, 20)
+            .draw([(1, 2)])
+            .quit()
+            .build();
+        let literal = vec![
+            Message::Move { x: 3, y: 4 },
+            Message::Write(String::from("hello")),
+            Message::ChangeColor(Color::RED),
+            Message::Resize {
+                width: 40,
+                height: 20,
+            },
+            Message::Draw(vec![(1, 2)]),
+            Message::Quit { code: 0 },
+        ];
+        assert_eq!(built, literal);
+    }
+
+    #[test]
+    fn repeat_expands_the_body() {
+        let script = MessageScript::new()
+            .write("start")
+            .repeat(3, |b| b.move_by(1, 0).write("step"));
+        assert_eq!(script.len(), 7);
+        let text: Vec<String> = script.build().iter().map(Message::to_string).collect();
+        assert_eq!(
+            text,
+            [
+                r#"write "start""#,
+                "move 1 0",
+                r#"write "step""#,
+                "move 1 0",
+                r#"write "step""#,
+                "move 1 0",
+                r#"write "step""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn repeat_zero_times_adds_nothing() {
+        let script = MessageScript::new().repeat(0, |b| b.write("never"));
+        assert!(script.is_empty());
+    }
+
+    #[test]
+    fn quit_is_only_emitted_once() {
+        let msgs = MessageScript::new()
+            .quit()
+            .move_by(1, 1)
+            .quit()
+            .repeat(2, |b| b.quit())
+            .build();
+        assert_eq!(
+            msgs,
+            [Message::Quit { code: 0 }, Message::Move { x: 1, y: 1 }]
+        );
+    }
+}
@@ -0,0 +1,772 @@
+//! # Applying `Message`s to some state
+//!
+//! A `Canvas` is a small piece of state that a queue of `Message`s can act
+//! on: a cursor to move, a pen color to change, a log to write into, a set
+//! of plotted points to draw into, and a flag that `Quit` turns off.
+//!
+//! The cursor lives on a bounded `Board`. A `Move` that would leave the board
+//! is either clamped to the edge or rejected outright, depending on the
+//! canvas's `MovePolicy`. `Resize` replaces the board, pulling the cursor
+//! back onto it if the board shrank.
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::color::Color;
+use crate::handler::MessageHandler;
+use crate::Message;
+
+/// # The area the cursor may move around in
+///
+/// Valid cursor positions are `0..width` by `0..height`. A board has at
+/// most `MAX_CELLS` positions, so `Canvas::render` can always draw it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Board {
+    width: i32,
+    height: i32,
+}
+
+impl Board {
+    /// The most positions a board may have: a little over a million, so a
+    /// rendered board stays a few megabytes at most
+    pub const MAX_CELLS: i64 = 1 << 20;
+
+    /// A `width` by `height` board, or `None` unless both are positive and
+    /// the board has at most `MAX_CELLS` positions
+    pub fn new(width: i32, height: i32) -> Option<Board> {
+        let cells = i64::from(width) * i64::from(height);
+        (width > 0 && height > 0 && cells <= Board::MAX_CELLS).then_some(Board { width, height })
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Number of rows
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Whether `(x, y)` is a position on this board
+    pub fn contains(&self, (x, y): (i64, i64)) -> bool {
+        (0..i64::from(self.width)).contains(&x) && (0..i64::from(self.height)).contains(&y)
+    }
+
+    /// The position on this board closest to `(x, y)`
+    fn clamp(&self, (x, y): (i64, i64)) -> (i32, i32) {
+        // Both results are within `0..width` or `0..height`, so fit in `i32`
+        (
+            x.clamp(0, i64::from(self.width) - 1) as i32,
+            y.clamp(0, i64::from(self.height) - 1) as i32,
+        )
+    }
+}
+
+/// The default board is the classic 80×24 terminal
+impl Default for Board {
+    fn default() -> Self {
+        Board {
+            width: 80,
+            height: 24,
+        }
+    }
+}
+
+/// # What to do with a `Move` that would leave the `Board`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MovePolicy {
+    /// Stop the cursor at the edge of the board
+    #[default]
+    Clamp,
+    /// Refuse the whole move and leave the cursor where it is
+    Reject,
+}
+
+/// # The result of applying one `Message` to a `Canvas`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyOutcome {
+    /// The message took effect as written
+    Applied,
+    /// A `Move` went past the edge and the cursor stopped at `at`
+    Clamped {
+        /// Where the cursor stopped
+        at: (i32, i32),
+    },
+    /// A `Move` to `target`, which is off the board, was refused
+    Rejected {
+        /// Where the `Move` would have gone
+        target: (i64, i64),
+    },
+    /// A `Resize` to a size that can't make a `Board` was refused
+    InvalidSize {
+        /// The width asked for
+        width: u32,
+        /// The height asked for
+        height: u32,
+    },
+}
+
+/// # The state that `Message`s are applied to
+///
+/// ```
+/// use enums_msg::{canvas, Canvas, Message};
+///
+/// let mut canvas = Canvas::new();
+/// canvas::apply(&mut canvas, &Message::Write(String::from("hi")));
+/// canvas::apply(&mut canvas, &Message::Quit { code: 3 });
+/// assert_eq!(canvas.log(), ["hi"]);
+/// assert_eq!(canvas.exit_code(), Some(3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    cursor: (i32, i32),
+    color: Color,
+    log: Vec<String>,
+    points: HashSet<(i32, i32)>,
+    running: bool,
+    exit_code: Option<i32>,
+    board: Board,
+    policy: MovePolicy,
+}
+
+impl Canvas {
+    /// A running canvas on the default board that clamps moves, with the
+    /// cursor at the origin and a black pen
+    pub fn new() -> Self {
+        Canvas::with_board(Board::default(), MovePolicy::default())
+    }
+
+    /// A running canvas on `board`, handling out-of-bounds moves per `policy`
+    pub fn with_board(board: Board, policy: MovePolicy) -> Self {
+        Canvas {
+            cursor: (0, 0),
+            color: Color::BLACK,
+            log: Vec::new(),
+            points: HashSet::new(),
+            running: true,
+            exit_code: None,
+            board,
+            policy,
+        }
+    }
+
+    /// The board the cursor moves on
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    /// How out-of-bounds moves are handled
+    pub fn policy(&self) -> MovePolicy {
+        self.policy
+    }
+
+    /// Current cursor position
+    pub fn cursor(&self) -> (i32, i32) {
+        self.cursor
+    }
+
+    /// Current pen color
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Every `Write` payload applied so far, oldest first
+    pub fn log(&self) -> &[String] {
+        &self.log
+    }
+
+    /// Every point plotted by a `Draw` so far
+    pub fn points(&self) -> &HashSet<(i32, i32)> {
+        &self.points
+    }
+
+    /// `false` once a `Quit` has been applied
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// The `code` of the `Quit` that stopped the canvas, if one has
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Draw the board as a grid of characters, one line per row
+    ///
+    /// The cursor is `@`, plotted points are `*` and everything else is `.`.
+    /// Points off the board are left out. Every row, including the last,
+    /// ends in a newline.
+    pub fn render(&self) -> String {
+        let (width, height) = (self.board.width, self.board.height);
+        // Both are positive, so the casts are lossless
+        let mut out = String::with_capacity((width as usize + 1) * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                out.push(if (x, y) == self.cursor {
+                    '@'
+                } else if self.points.contains(&(x, y)) {
+                    '*'
+                } else {
+                    '.'
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// `render`, followed by a legend line giving the pen color
+    pub fn render_with_legend(&self) -> String {
+        format!("{}color {}\n", self.render(), self.color)
+    }
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Canvas::new()
+    }
+}
+
+/// `Display` summarizes the canvas on one line, for example
+/// `cursor (3, 4) on 80x24, color #ff0000, 1 line(s), 0 point(s), running`
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cursor ({}, {}) on {}x{}, color {}, {} line(s), {} point(s), {}",
+            self.cursor.0,
+            self.cursor.1,
+            self.board.width,
+            self.board.height,
+            self.color,
+            self.log.len(),
+            self.points.len(),
+            if self.running { "running" } else { "stopped" }
+        )
+    }
+}
+
+/// Apply a single `Message` to `canvas`
+///
+/// - `Move` translates the cursor by `x` and `y`, checked against the board
+///   according to the canvas's `MovePolicy`
+/// - `Write` appends its text to the log
+/// - `ChangeColor` sets the pen color
+/// - `Resize` replaces the board, clamping the cursor onto the new one
+/// - `Draw` plots its points, which are absolute positions rather than
+///   offsets from the cursor and are kept whether or not they are on the board
+/// - `Quit` stops the canvas
+pub fn apply(canvas: &mut Canvas, msg: &Message) -> ApplyOutcome {
+    let mut applier = Applier {
+        canvas,
+        outcome: ApplyOutcome::Applied,
+    };
+    msg.visit(&mut applier);
+    applier.outcome
+}
+
+/// Visiting a `Canvas` applies each message to it, as `apply` does, but
+/// without reporting whether a `Move` or `Resize` was clamped or refused
+impl MessageHandler for Canvas {
+    fn on_quit(&mut self, code: i32) {
+        self.running = false;
+        self.exit_code = Some(code);
+    }
+
+    fn on_move(&mut self, x: i32, y: i32) {
+        move_cursor(self, x, y);
+    }
+
+    fn on_write(&mut self, text: &str) {
+        self.log.push(text.to_string());
+    }
+
+    fn on_change_color(&mut self, c: &Color) {
+        self.color = *c;
+    }
+
+    fn on_resize(&mut self, width: u32, height: u32) {
+        resize(self, width, height);
+    }
+
+    fn on_draw(&mut self, points: &[(i32, i32)]) {
+        self.points.extend(points);
+    }
+}
+
+/// Forwards to the `Canvas` handler, keeping the `ApplyOutcome` that the
+/// canvas itself drops
+struct Applier<'a> {
+    canvas: &'a mut Canvas,
+    outcome: ApplyOutcome,
+}
+
+impl MessageHandler for Applier<'_> {
+    fn on_quit(&mut self, code: i32) {
+        self.canvas.on_quit(code);
+    }
+
+    fn on_move(&mut self, x: i32, y: i32) {
+        self.outcome = move_cursor(self.canvas, x, y);
+    }
+
+    fn on_write(&mut self, text: &str) {
+        self.canvas.on_write(text);
+    }
+
+    fn on_change_color(&mut self, c: &Color) {
+        self.canvas.on_change_color(c);
+    }
+
+    fn on_resize(&mut self, width: u32, height: u32) {
+        self.outcome = resize(self.canvas, width, height);
+    }
+
+    fn on_draw(&mut self, points: &[(i32, i32)]) {
+        self.canvas.on_draw(points);
+    }
+}
+
+fn resize(canvas: &mut Canvas, width: u32, height: u32) -> ApplyOutcome {
+    let board = i32::try_from(width)
+        .ok()
+        .zip(i32::try_from(height).ok())
+        .and_then(|(w, h)| Board::new(w, h));
+    let Some(board) = board else {
+        return ApplyOutcome::InvalidSize { width, height };
+    };
+    canvas.board = board;
+    let (x, y) = canvas.cursor;
+    canvas.cursor = board.clamp((i64::from(x), i64::from(y)));
+    ApplyOutcome::Applied
+}
+
+fn move_cursor(canvas: &mut Canvas, x: i32, y: i32) -> ApplyOutcome {
+    // Widen so that a move can't overflow before it is checked
+    let target = (
+        i64::from(canvas.cursor.0) + i64::from(x),
+        i64::from(canvas.cursor.1) + i64::from(y),
+    );
+    if canvas.board.contains(target) {
+        canvas.cursor = canvas.board.clamp(target);
+        return ApplyOutcome::Applied;
+    }
+    match canvas.policy {
+        MovePolicy::Clamp => {
+            canvas.cursor = canvas.board.clamp(target);
+            ApplyOutcome::Clamped { at: canvas.cursor }
+        }
+        MovePolicy::Reject => ApplyOutcome::Rejected { target },
+    }
+}
+
+/// Apply `msgs` in order until a `Quit` stops the canvas
+///
+/// Returns how many messages were applied, counting the `Quit` itself.
+/// Messages after the `Quit` are left alone, and a canvas that has already
+/// stopped applies nothing.
+pub fn run_queue(canvas: &mut Canvas, msgs: &[Message]) -> usize {
+    let mut applied = 0;
+    for msg in msgs {
+        if !canvas.running {
+            break;
+        }
+        apply(canvas, msg);
+        applied += 1;
+    }
+    applied
+}
+
+/// # Why `apply_all` refused a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyError {
+    /// A `Move` to `target` was off the board under `MovePolicy::Reject`
+    RejectedMove {
+        /// Where the `Move` would have gone
+        target: (i64, i64),
+    },
+    /// A `Resize` asked for a size that can't make a `Board`
+    InvalidSize {
+        /// The width asked for
+        width: u32,
+        /// The height asked for
+        height: u32,
+    },
+    /// The canvas had already been stopped by a `Quit`
+    NotRunning,
+}
+
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApplyError::RejectedMove { target } => {
+                write!(f, "move to {:?} is off the board", target)
+            }
+            ApplyError::InvalidSize { width, height } => {
+                write!(f, "{width}x{height} is not a valid board size")
+            }
+            ApplyError::NotRunning => write!(f, "canvas is not running"),
+        }
+    }
+}
+
+impl std::error::Error for ApplyError {}
+
+/// # The result of `apply_all`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BatchResult {
+    /// Number of messages that took effect, including clamped moves
+    pub applied: usize,
+    /// Index into the batch and error of every message that failed
+    pub errors: Vec<(usize, ApplyError)>,
+}
+
+/// Apply every message in `msgs`, collecting errors instead of stopping
+///
+/// Unlike `run_queue`, a `Quit` doesn't end the batch: the messages after it
+/// are each reported as `ApplyError::NotRunning`. With `fail_fast` set,
+/// processing instead stops at the first error, so `errors` holds at most
+/// one entry and nothing after it is applied.
+pub fn apply_all(canvas: &mut Canvas, msgs: &[Message], fail_fast: bool) -> BatchResult {
+    let mut result = BatchResult::default();
+    for (index, msg) in msgs.iter().enumerate() {
+        let error = if !canvas.running {
+            ApplyError::NotRunning
+        } else {
+            match apply(canvas, msg) {
+                ApplyOutcome::Applied | ApplyOutcome::Clamped { .. } => {
+                    result.applied += 1;
+                    continue;
+                }
+                ApplyOutcome::Rejected { target } => ApplyError::RejectedMove { target },
+                ApplyOutcome::InvalidSize { width, height } => {
+                    ApplyError::InvalidSize { width, height }
+                }
+            }
+        };
+        result.errors.push((index, error));
+        if fail_fast {
+            break;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script() -> Vec<Message> {
+        vec![
+            Message::Move { x: 3, y: 4 },
+            Message::Write(String::from("hello")),
+            Message::ChangeColor(Color::rgb(255, 128, 0)),
+            Message::Move { x: -1, y: 2 },
+            Message::Write(String::from("world")),
+        ]
+    }
+
+    #[test]
+    fn new_canvas_is_running_at_origin() {
+        let canvas = Canvas::new();
+        assert_eq!(canvas.cursor(), (0, 0));
+        assert_eq!(canvas.color(), Color::BLACK);
+        assert!(canvas.log().is_empty());
+        assert!(canvas.is_running());
+    }
+
+    #[test]
+    fn display_summarizes_the_canvas() {
+        let mut canvas = Canvas::new();
+        assert_eq!(
+            canvas.to_string(),
+            "cursor (0, 0) on 80x24, color #000000, 0 line(s), 0 point(s), running"
+        );
+        run_queue(&mut canvas, &script());
+        apply(&mut canvas, &Message::Quit { code: 0 });
+        assert_eq!(
+            canvas.to_string(),
+            "cursor (2, 6) on 80x24, color #ff8000, 2 line(s), 0 point(s), stopped"
+        );
+    }
+
+    #[test]
+    fn run_queue_applies_everything_without_quit() {
+        let mut canvas = Canvas::new();
+        assert_eq!(run_queue(&mut canvas, &script()), 5);
+        assert_eq!(canvas.cursor(), (2, 6));
+        assert_eq!(canvas.color(), Color::rgb(255, 128, 0));
+        assert_eq!(canvas.log(), ["hello", "world"]);
+        assert!(canvas.is_running());
+        assert_eq!(canvas.exit_code(), None);
+    }
+
+    #[test]
+    fn run_queue_stops_at_quit() {
+        let mut msgs = script();
+        msgs.insert(2, Message::Quit { code: 4 });
+        let mut canvas = Canvas::new();
+        assert_eq!(run_queue(&mut canvas, &msgs), 3);
+        assert_eq!(canvas.cursor(), (3, 4));
+        assert_eq!(canvas.color(), Color::BLACK);
+        assert_eq!(canvas.log(), ["hello"]);
+        assert!(!canvas.is_running());
+        assert_eq!(canvas.exit_code(), Some(4));
+
+        // A stopped canvas applies nothing more
+        assert_eq!(run_queue(&mut canvas, &msgs), 0);
+        assert_eq!(canvas.log(), ["hello"]);
+    }
+
+    #[test]
+    fn resize_and_draw_end_to_end() {
+        let msgs: Vec<Message> = [
+            "move 30 20",
+            "resize 10 5",
+            "draw 1,1 2,2 1,1",
+            "draw 50,50",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+        let mut canvas = Canvas::new();
+        for m in &msgs {
+            assert_eq!(apply(&mut canvas, m), ApplyOutcome::Applied, "{m}");
+        }
+        assert_eq!(canvas.board(), Board::new(10, 5).unwrap());
+        // Shrinking the board pulled the cursor back onto it
+        assert_eq!(canvas.cursor(), (9, 4));
+        assert_eq!(canvas.points(), &HashSet::from([(1, 1), (2, 2), (50, 50)]));
+        let shown: Vec<String> = msgs.iter().map(Message::to_string).collect();
+        assert_eq!(
+            shown,
+            [
+                "move 30 20",
+                "resize 10 5",
+                "draw 1,1 2,2 1,1",
+                "draw 50,50"
+            ]
+        );
+    }
+
+    #[test]
+    fn resize_to_an_invalid_size_is_refused() {
+        let mut canvas = Canvas::new();
+        for (width, height) in [(0, 5), (5, 0), (u32::MAX, 5), (100_000, 100_000)] {
+            assert_eq!(
+                apply(&mut canvas, &Message::Resize { width, height }),
+                ApplyOutcome::InvalidSize { width, height }
+            );
+        }
+        assert_eq!(canvas.board(), Board::default());
+
+        let result = apply_all(&mut canvas, &["resize 0 0".parse().unwrap()], false);
+        assert_eq!(
+            result.errors,
+            [(
+                0,
+                ApplyError::InvalidSize {
+                    width: 0,
+                    height: 0
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn visiting_a_canvas_matches_apply() {
+        let mut msgs = script();
+        msgs.push(Message::Draw(vec![(1, 1)]));
+        msgs.push(Message::Resize {
+            width: 2,
+            height: 2,
+        });
+        msgs.push(Message::Quit { code: 0 });
+        let mut applied = Canvas::new();
+        let mut visited = Canvas::new();
+        for m in &msgs {
+            apply(&mut applied, m);
+            m.visit(&mut visited);
+        }
+        assert_eq!(visited, applied);
+        assert_eq!(visited.cursor(), (1, 1));
+        assert!(!visited.is_running());
+    }
+
+    #[test]
+    fn board_requires_positive_dimensions() {
+        assert!(Board::new(0, 5).is_none());
+        assert!(Board::new(5, -1).is_none());
+        assert!(Board::new(1, 1).is_some());
+    }
+
+    #[test]
+    fn board_has_at_most_max_cells() {
+        assert!(Board::new(1024, 1024).is_some());
+        assert!(Board::new(1025, 1024).is_none());
+        assert!(Board::new(i32::MAX, 1).is_none());
+    }
+
+    /// Walk off the right, bottom, left and top edges of a 5×4 board, then
+    /// make one in-bounds move and one huge move from the far corner
+    fn walk() -> Vec<Message> {
+        vec![
+            Message::Move { x: 7, y: 0 },
+            Message::Move { x: 0, y: 9 },
+            Message::Move { x: 2, y: 1 },
+            Message::Move { x: -20, y: 0 },
+            Message::Move { x: 0, y: -5 },
+            Message::Move {
+                x: i32::MAX,
+                y: i32::MAX,
+            },
+        ]
+    }
+
+    fn drive(policy: MovePolicy) -> Vec<((i32, i32), ApplyOutcome)> {
+        let mut canvas = Canvas::with_board(Board::new(5, 4).unwrap(), policy);
+        walk()
+            .iter()
+            .map(|m| {
+                let outcome = apply(&mut canvas, m);
+                (canvas.cursor(), outcome)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clamp_policy_stops_at_every_edge() {
+        assert_eq!(
+            drive(MovePolicy::Clamp),
+            [
+                ((4, 0), ApplyOutcome::Clamped { at: (4, 0) }),
+                ((4, 3), ApplyOutcome::Clamped { at: (4, 3) }),
+                ((4, 3), ApplyOutcome::Clamped { at: (4, 3) }),
+                ((0, 3), ApplyOutcome::Clamped { at: (0, 3) }),
+                ((0, 0), ApplyOutcome::Clamped { at: (0, 0) }),
+                ((4, 3), ApplyOutcome::Clamped { at: (4, 3) }),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_policy_refuses_off_board_moves() {
+        let max = i64::from(i32::MAX);
+        assert_eq!(
+            drive(MovePolicy::Reject),
+            [
+                ((0, 0), ApplyOutcome::Rejected { target: (7, 0) }),
+                ((0, 0), ApplyOutcome::Rejected { target: (0, 9) }),
+                ((2, 1), ApplyOutcome::Applied),
+                ((2, 1), ApplyOutcome::Rejected { target: (-18, 1) }),
+                ((2, 1), ApplyOutcome::Rejected { target: (2, -4) }),
+                (
+                    (2, 1),
+                    ApplyOutcome::Rejected {
+                        target: (2 + max, 1 + max)
+                    }
+                ),
+            ]
+        );
+    }
+
+    /// A batch whose messages at index 1 and 4 fail on a 5×5 rejecting board
+    fn batch_with_two_bad_messages() -> Vec<Message> {
+        vec![
+            Message::Move { x: 1, y: 1 },
+            Message::Move { x: 10, y: 0 },
+            Message::Write(String::from("still applied")),
+            Message::Quit { code: 0 },
+            Message::Write(String::from("after quit")),
+        ]
+    }
+
+    fn rejecting_canvas() -> Canvas {
+        Canvas::with_board(Board::new(5, 5).unwrap(), MovePolicy::Reject)
+    }
+
+    #[test]
+    fn apply_all_continues_past_errors() {
+        let mut canvas = rejecting_canvas();
+        let result = apply_all(&mut canvas, &batch_with_two_bad_messages(), false);
+        assert_eq!(result.applied, 3);
+        assert_eq!(
+            result.errors,
+            [
+                (1, ApplyError::RejectedMove { target: (11, 1) }),
+                (4, ApplyError::NotRunning),
+            ]
+        );
+        assert_eq!(canvas.cursor(), (1, 1));
+        assert_eq!(canvas.log(), ["still applied"]);
+        assert!(!canvas.is_running());
+    }
+
+    #[test]
+    fn apply_all_fail_fast_stops_at_first_error() {
+        let mut canvas = rejecting_canvas();
+        let result = apply_all(&mut canvas, &batch_with_two_bad_messages(), true);
+        assert_eq!(result.applied, 1);
+        assert_eq!(
+            result.errors,
+            [(1, ApplyError::RejectedMove { target: (11, 1) })]
+        );
+        assert_eq!(canvas.cursor(), (1, 1));
+        assert!(canvas.log().is_empty());
+        assert!(canvas.is_running());
+    }
+
+    #[test]
+    fn apply_all_counts_clamped_moves_as_applied() {
+        let mut canvas = Canvas::with_board(Board::new(5, 5).unwrap(), MovePolicy::Clamp);
+        let result = apply_all(&mut canvas, &batch_with_two_bad_messages(), false);
+        assert_eq!(result.applied, 4);
+        assert_eq!(result.errors, [(4, ApplyError::NotRunning)]);
+    }
+
+    #[test]
+    fn moves_within_the_board_apply_from_a_corner() {
+        let mut canvas = Canvas::with_board(Board::new(3, 3).unwrap(), MovePolicy::Reject);
+        assert_eq!(
+            apply(&mut canvas, &Message::Move { x: 2, y: 2 }),
+            ApplyOutcome::Applied
+        );
+        assert_eq!(
+            apply(&mut canvas, &Message::Move { x: -2, y: -2 }),
+            ApplyOutcome::Applied
+        );
+        assert_eq!(canvas.cursor(), (0, 0));
+    }
+
+    #[test]
+    fn render_small_scene() {
+        let mut canvas = Canvas::with_board(Board::new(5, 3).unwrap(), MovePolicy::Clamp);
+        for msg in [
+            // One point on each border, one in the middle and two off the board
+            Message::Draw(vec![(2, 0), (4, 1), (1, 2), (0, 1), (2, 1)]),
+            Message::Draw(vec![(5, 1), (-1, -1)]),
+            Message::ChangeColor(Color::rgb(255, 136, 0)),
+            Message::Move { x: 3, y: 2 },
+        ] {
+            apply(&mut canvas, &msg);
+        }
+        assert_eq!(
+            canvas.render(),
+            "\
+..*..
+*.*.*
+.*.@.
+"
+        );
+        assert_eq!(
+            canvas.render_with_legend(),
+            format!("{}color #ff8800\n", canvas.render())
+        );
+    }
+
+    #[test]
+    fn cursor_is_drawn_over_a_point() {
+        let mut canvas = Canvas::with_board(Board::new(2, 1).unwrap(), MovePolicy::Clamp);
+        apply(&mut canvas, &Message::Draw(vec![(0, 0), (1, 0)]));
+        assert_eq!(canvas.render(), "@*\n");
+    }
+}
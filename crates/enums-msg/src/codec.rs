@@ -0,0 +1,551 @@
+//! # Compact binary encoding for `Message`
+//!
+//! Every message starts with a 1-byte tag naming the variant, followed by its
+//! payload:
+//!
+//! | Tag | Variant       | Payload                                      |
+//! |-----|---------------|----------------------------------------------|
+//! | `0` | `Quit`        | `code` as little-endian `i32`                |
+//! | `1` | `Move`        | `x`, `y` as little-endian `i32`              |
+//! | `2` | `Write`       | `u32` little-endian byte length, UTF-8 bytes |
+//! | `3` | `ChangeColor` | `r`, `g`, `b` as little-endian `i32`         |
+//! | `4` | `Resize`      | `width`, `height` as little-endian `u32`     |
+//! | `5` | `Draw`        | `u32` little-endian point count, then each   |
+//! |     |               | point's `x`, `y` as little-endian `i32`      |
+//!
+//! Color components are range-checked on decode, so bytes that would make an
+//! invalid `Color` are an error rather than being clamped.
+//!
+//! `decode` reports how many bytes it consumed, so several messages can be
+//! packed back to back in a single buffer, as `encode_batch` does.
+//!
+//! This is version 2 of the format. Data that has to outlive format changes
+//! should be wrapped in an `Envelope`, which leads with a version byte.
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::color::{Color, ColorError};
+use crate::Message;
+
+pub(crate) const TAG_QUIT: u8 = 0;
+pub(crate) const TAG_MOVE: u8 = 1;
+pub(crate) const TAG_WRITE: u8 = 2;
+pub(crate) const TAG_CHANGE_COLOR: u8 = 3;
+const TAG_RESIZE: u8 = 4;
+const TAG_DRAW: u8 = 5;
+
+impl Message {
+    /// Number of bytes `encode` appends for this `Message`
+    pub fn encoded_len(&self) -> usize {
+        1 + match self {
+            Message::Quit { .. } => 4,
+            Message::Move { .. } => 2 * 4,
+            Message::Write(text) => 4 + text.len(),
+            Message::ChangeColor(_) => 3 * 4,
+            Message::Resize { .. } => 2 * 4,
+            Message::Draw(points) => 4 + points.len() * 2 * 4,
+        }
+    }
+
+    /// Append the binary encoding of this `Message` to `buf`
+    ///
+    /// A `Write` payload longer than `u32::MAX` bytes, or a `Draw` of more
+    /// than `u32::MAX` points, is too long for its length prefix to say, and
+    /// is an error, leaving `buf` as it was. That is why this returns a
+    /// `Result` rather than nothing: cutting the payload short or wrapping
+    /// the prefix would write bytes that `decode` reads back as another
+    /// message.
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        let start = buf.len();
+        match self {
+            Message::Quit { code } => {
+                buf.push(TAG_QUIT);
+                buf.extend_from_slice(&code.to_le_bytes());
+            }
+            Message::Move { x, y } => {
+                buf.push(TAG_MOVE);
+                buf.extend_from_slice(&x.to_le_bytes());
+                buf.extend_from_slice(&y.to_le_bytes());
+            }
+            Message::Write(text) => {
+                let len = length_prefix(text.len(), EncodeError::WriteTooLong)?;
+                buf.push(TAG_WRITE);
+                buf.extend_from_slice(&len.to_le_bytes());
+                buf.extend_from_slice(text.as_bytes());
+            }
+            Message::ChangeColor(color) => {
+                buf.push(TAG_CHANGE_COLOR);
+                for component in [color.r(), color.g(), color.b()] {
+                    buf.extend_from_slice(&i32::from(component).to_le_bytes());
+                }
+            }
+            Message::Resize { width, height } => {
+                buf.push(TAG_RESIZE);
+                buf.extend_from_slice(&width.to_le_bytes());
+                buf.extend_from_slice(&height.to_le_bytes());
+            }
+            Message::Draw(points) => {
+                let count = length_prefix(points.len(), EncodeError::TooManyPoints)?;
+                buf.push(TAG_DRAW);
+                buf.extend_from_slice(&count.to_le_bytes());
+                for (x, y) in points {
+                    buf.extend_from_slice(&x.to_le_bytes());
+                    buf.extend_from_slice(&y.to_le_bytes());
+                }
+            }
+        }
+        debug_assert_eq!(buf.len() - start, self.encoded_len());
+        Ok(())
+    }
+
+    /// Decode one `Message` from the front of `bytes`
+    ///
+    /// Returns the message along with the number of bytes it occupied; any
+    /// bytes after that are left for the next call.
+    pub fn decode(bytes: &[u8]) -> Result<(Message, usize), DecodeError> {
+        let mut reader = Reader::new(bytes);
+        let message = match reader.u8()? {
+            TAG_QUIT => Message::Quit {
+                code: reader.i32()?,
+            },
+            TAG_MOVE => Message::Move {
+                x: reader.i32()?,
+                y: reader.i32()?,
+            },
+            TAG_WRITE => {
+                let len = reader.u32()?;
+                let len = usize::try_from(len).map_err(|_| DecodeError::UnexpectedEof)?;
+                let text = core::str::from_utf8(reader.take(len)?)
+                    .map_err(|_| DecodeError::InvalidUtf8)?;
+                Message::Write(text.to_string())
+            }
+            TAG_CHANGE_COLOR => {
+                let color = Color::new(reader.i32()?, reader.i32()?, reader.i32()?)
+                    .map_err(DecodeError::InvalidColor)?;
+                Message::ChangeColor(color)
+            }
+            TAG_RESIZE => Message::Resize {
+                width: reader.u32()?,
+                height: reader.u32()?,
+            },
+            TAG_DRAW => {
+                // Points are read one at a time rather than reserved up front,
+                // so a bogus count runs out of input instead of memory
+                let count = reader.u32()?;
+                let mut points = Vec::new();
+                for _ in 0..count {
+                    points.push((reader.i32()?, reader.i32()?));
+                }
+                Message::Draw(points)
+            }
+            tag => return Err(DecodeError::UnknownTag(tag)),
+        };
+        Ok((message, reader.pos))
+    }
+}
+
+/// Encode every message in `msgs` back to back into one buffer
+///
+/// The buffer is sized up front from each message's `encoded_len`, so it is
+/// allocated once instead of growing as messages are appended. A message
+/// `encode` refuses fails the whole batch, so this returns a `Result`
+/// rather than a buffer that is missing a message.
+pub fn encode_batch(msgs: &[Message]) -> Result<Vec<u8>, EncodeError> {
+    let len = msgs.iter().map(Message::encoded_len).sum();
+    let mut buf = Vec::with_capacity(len);
+    for msg in msgs {
+        msg.encode(&mut buf)?;
+    }
+    Ok(buf)
+}
+
+/// `len` as the `u32` that prefixes a payload, or `too_long(len)` if it
+/// doesn't fit
+pub(crate) fn length_prefix(
+    len: usize,
+    too_long: fn(usize) -> EncodeError,
+) -> Result<u32, EncodeError> {
+    u32::try_from(len).map_err(|_| too_long(len))
+}
+
+/// # Errors from encoding a binary `Message`
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    /// A `Write` payload of this many bytes, more than a `u32` length can say
+    WriteTooLong(usize),
+    /// A `Draw` of this many points, more than a `u32` count can say
+    TooManyPoints(usize),
+    /// A message this many bytes long, more than a `u32` frame length can say
+    FrameTooLong(usize),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::WriteTooLong(len) => {
+                write!(f, "Write payload of {len} bytes is over 4 GiB")
+            }
+            EncodeError::TooManyPoints(count) => {
+                write!(f, "Draw of {count} points is over u32::MAX points")
+            }
+            EncodeError::FrameTooLong(len) => write!(f, "frame of {len} bytes is over 4 GiB"),
+        }
+    }
+}
+
+impl core::error::Error for EncodeError {}
+
+/// # Errors from decoding a binary `Message`
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer ended before the message was complete
+    UnexpectedEof,
+    /// The tag byte does not name a `Message` variant
+    UnknownTag(u8),
+    /// The version byte of an `Envelope` is not a known format version
+    UnknownVersion(u8),
+    /// A `Write` payload was not valid UTF-8
+    InvalidUtf8,
+    /// A `ChangeColor` payload had a component outside `0..=255`
+    InvalidColor(ColorError),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEof => write!(f, "unexpected end of input"),
+            DecodeError::UnknownTag(tag) => write!(f, "unknown message tag {tag}"),
+            DecodeError::UnknownVersion(version) => {
+                write!(f, "unknown envelope version {version}")
+            }
+            DecodeError::InvalidUtf8 => write!(f, "Write payload is not valid UTF-8"),
+            DecodeError::InvalidColor(e) => write!(f, "invalid ChangeColor payload: {e}"),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            DecodeError::InvalidColor(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Cursor over the bytes being decoded that never reads out of bounds
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes, pos: 0 }
+    }
+
+    /// Number of bytes read so far
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self.pos.checked_add(n).ok_or(DecodeError::UnexpectedEof)?;
+        let taken = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or(DecodeError::UnexpectedEof)?;
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn i32(&mut self) -> Result<i32, DecodeError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn encoded(message: &Message) -> Vec<u8> {
+        let mut buf = Vec::new();
+        message.encode(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn encoded_len_predicts_every_variant() {
+        let messages = [
+            Message::Quit { code: 0 },
+            Message::Move {
+                x: i32::MIN,
+                y: i32::MAX,
+            },
+            Message::Write(String::new()),
+            Message::Write(String::from("hello")),
+            // 1 + 2 + 3 + 4 bytes of UTF-8 for 4 chars
+            Message::Write(String::from("aé€🦀")),
+            Message::ChangeColor(Color::WHITE),
+            Message::Resize {
+                width: 80,
+                height: 24,
+            },
+            Message::Draw(Vec::new()),
+            Message::Draw(vec![(1, 2), (-3, 4)]),
+        ];
+        for m in &messages {
+            assert_eq!(m.encoded_len(), encoded(m).len(), "{m}");
+        }
+        assert_eq!(messages[4].encoded_len(), 1 + 4 + 10);
+    }
+
+    #[test]
+    fn encode_batch_allocates_exactly_once() {
+        let msgs = [
+            Message::Write(String::from("héllo")),
+            Message::Move { x: 1, y: 2 },
+            Message::ChangeColor(Color::RED),
+            Message::Quit { code: 0 },
+        ];
+        let buf = encode_batch(&msgs).unwrap();
+        let expected: usize = msgs.iter().map(Message::encoded_len).sum();
+        assert_eq!(buf.len(), expected);
+        assert_eq!(buf.capacity(), expected);
+
+        let mut naive = Vec::new();
+        for m in &msgs {
+            m.encode(&mut naive).unwrap();
+        }
+        assert_eq!(buf, naive);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn lengths_over_u32_max_are_errors() {
+        assert_eq!(length_prefix(5, EncodeError::WriteTooLong), Ok(5));
+        let too_long = u32::MAX as usize + 1;
+        let e = length_prefix(too_long, EncodeError::TooManyPoints).unwrap_err();
+        assert_eq!(e, EncodeError::TooManyPoints(too_long));
+        assert_eq!(
+            EncodeError::WriteTooLong(too_long).to_string(),
+            "Write payload of 4294967296 bytes is over 4 GiB"
+        );
+    }
+
+    #[test]
+    fn encode_batch_of_nothing_is_empty() {
+        assert!(encode_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn quit_round_trip() {
+        let buf = encoded(&Message::Quit { code: -2 });
+        assert_eq!(buf, [0, 0xfe, 0xff, 0xff, 0xff]);
+        assert!(matches!(
+            Message::decode(&buf),
+            Ok((Message::Quit { code: -2 }, 5))
+        ));
+    }
+
+    #[test]
+    fn move_round_trip() {
+        let buf = encoded(&Message::Move { x: 3, y: -1 });
+        assert_eq!(buf, [1, 3, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        assert!(matches!(
+            Message::decode(&buf),
+            Ok((Message::Move { x: 3, y: -1 }, 9))
+        ));
+    }
+
+    #[test]
+    fn write_round_trip() {
+        let buf = encoded(&Message::Write(String::from("hé")));
+        assert_eq!(buf, [2, 3, 0, 0, 0, b'h', 0xc3, 0xa9]);
+        let (m, used) = Message::decode(&buf).unwrap();
+        assert!(matches!(m, Message::Write(text) if text == "hé"));
+        assert_eq!(used, 8);
+    }
+
+    #[test]
+    fn change_color_round_trip() {
+        let buf = encoded(&Message::ChangeColor(Color::rgb(255, 0, 1)));
+        assert_eq!(buf, [3, 255, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        assert!(matches!(
+            Message::decode(&buf),
+            Ok((Message::ChangeColor(c), 13)) if c == Color::rgb(255, 0, 1)
+        ));
+    }
+
+    #[test]
+    fn resize_round_trip() {
+        let buf = encoded(&Message::Resize {
+            width: 80,
+            height: 0x0100,
+        });
+        assert_eq!(buf, [4, 80, 0, 0, 0, 0, 1, 0, 0]);
+        assert!(matches!(
+            Message::decode(&buf),
+            Ok((
+                Message::Resize {
+                    width: 80,
+                    height: 256
+                },
+                9
+            ))
+        ));
+    }
+
+    #[test]
+    fn draw_round_trip() {
+        let buf = encoded(&Message::Draw(vec![(1, -1)]));
+        assert_eq!(buf, [5, 1, 0, 0, 0, 1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        let (m, used) = Message::decode(&buf).unwrap();
+        assert!(matches!(m, Message::Draw(points) if points == [(1, -1)]));
+        assert_eq!(used, 13);
+    }
+
+    #[test]
+    fn huge_draw_count_is_unexpected_eof() {
+        let buf = [5, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0];
+        assert_eq!(
+            Message::decode(&buf).unwrap_err(),
+            DecodeError::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn packed_messages_decode_back_to_back() {
+        let mut buf = Vec::new();
+        Message::Write(String::from("hi")).encode(&mut buf).unwrap();
+        Message::Move { x: 1, y: 2 }.encode(&mut buf).unwrap();
+        Message::Quit { code: 0 }.encode(&mut buf).unwrap();
+
+        let (first, used) = Message::decode(&buf).unwrap();
+        assert!(matches!(first, Message::Write(text) if text == "hi"));
+        let rest = &buf[used..];
+        let (second, used) = Message::decode(rest).unwrap();
+        assert!(matches!(second, Message::Move { x: 1, y: 2 }));
+        let (third, used) = Message::decode(&rest[used..]).unwrap();
+        assert!(matches!(third, Message::Quit { code: 0 }));
+        assert_eq!(used, 5);
+    }
+
+    #[test]
+    fn truncated_buffer_is_unexpected_eof() {
+        let buf = encoded(&Message::ChangeColor(Color::rgb(1, 2, 3)));
+        for len in 0..buf.len() {
+            assert_eq!(
+                Message::decode(&buf[..len]).unwrap_err(),
+                DecodeError::UnexpectedEof
+            );
+        }
+        let buf = encoded(&Message::Write(String::from("hello")));
+        assert_eq!(
+            Message::decode(&buf[..buf.len() - 1]).unwrap_err(),
+            DecodeError::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn huge_write_length_is_unexpected_eof() {
+        let buf = [2, 0xff, 0xff, 0xff, 0xff, b'a'];
+        assert_eq!(
+            Message::decode(&buf).unwrap_err(),
+            DecodeError::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn unknown_tag_reports_value() {
+        assert_eq!(
+            Message::decode(&[42, 0, 0]).unwrap_err(),
+            DecodeError::UnknownTag(42)
+        );
+    }
+
+    #[test]
+    fn out_of_range_color_is_an_error() {
+        let buf = [3, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(
+            Message::decode(&buf).unwrap_err(),
+            DecodeError::InvalidColor(ColorError::OutOfRange {
+                component: 'r',
+                value: 256
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_is_an_error() {
+        let buf = [2, 1, 0, 0, 0, 0xff];
+        assert_eq!(Message::decode(&buf).unwrap_err(), DecodeError::InvalidUtf8);
+    }
+
+    /// The property checked by the `fuzz_message_decode` target: anything
+    /// that decodes re-encodes to exactly the bytes it was decoded from
+    fn assert_decode_reencodes(bytes: &[u8]) {
+        if let Ok((message, used)) = Message::decode(bytes) {
+            assert!(used <= bytes.len());
+            assert_eq!(encoded(&message), bytes[..used], "{:?}", bytes);
+        }
+    }
+
+    #[test]
+    fn random_bytes_decode_without_panicking() {
+        let mut rng = StdRng::seed_from_u64(369);
+        for _ in 0..10_000 {
+            let len = rng.gen_range(0..32);
+            let mut bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            // Keep most tags valid so the payload decoders get exercised
+            if let Some(tag) = bytes.first_mut() {
+                *tag %= 7;
+            }
+            assert_decode_reencodes(&bytes);
+        }
+    }
+
+    #[test]
+    fn corrupted_encodings_decode_without_panicking() {
+        let mut rng = StdRng::seed_from_u64(370);
+        let messages = [
+            Message::Quit { code: 0 },
+            Message::Move { x: 3, y: -7 },
+            Message::Write(String::from("hé")),
+            Message::ChangeColor(Color::rgb(255, 136, 0)),
+            Message::Resize {
+                width: 80,
+                height: 24,
+            },
+            Message::Draw(vec![(1, 2), (-3, 4)]),
+        ];
+        for message in &messages {
+            let original = encoded(message);
+            assert_decode_reencodes(&original);
+            for _ in 0..1_000 {
+                let mut bytes = original.clone();
+                let i = rng.gen_range(0..bytes.len());
+                bytes[i] = rng.gen();
+                bytes.truncate(rng.gen_range(0..=bytes.len()));
+                assert_decode_reencodes(&bytes);
+            }
+        }
+    }
+}
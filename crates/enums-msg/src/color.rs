@@ -0,0 +1,196 @@
+//! # A validated RGB color
+//!
+//! `Message::ChangeColor` used to carry three bare `i32`s, which let nonsense
+//! like `ChangeColor(-5, 9999, 0)` through. A `Color` can only hold
+//! components in `0..=255`: out-of-range values are rejected when the color
+//! is built, rather than clamped later on.
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// # An RGB color with 8 bits per component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+impl Color {
+    /// `#000000`
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    /// `#ffffff`
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    /// `#ff0000`
+    pub const RED: Color = Color::rgb(255, 0, 0);
+    /// `#00ff00`
+    pub const GREEN: Color = Color::rgb(0, 255, 0);
+    /// `#0000ff`
+    pub const BLUE: Color = Color::rgb(0, 0, 255);
+
+    /// Build a `Color`, rejecting any component outside `0..=255`
+    pub fn new(r: i32, g: i32, b: i32) -> Result<Color, ColorError> {
+        let component = |name, value: i32| {
+            u8::try_from(value).map_err(|_| ColorError::OutOfRange {
+                component: name,
+                value,
+            })
+        };
+        Ok(Color {
+            r: component('r', r)?,
+            g: component('g', g)?,
+            b: component('b', b)?,
+        })
+    }
+
+    /// Build a `Color` from components that are already in range
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b }
+    }
+
+    /// Parse `#rrggbb` or the `#rgb` shorthand, with or without the `#`
+    pub fn from_hex(hex: &str) -> Result<Color, ColorError> {
+        let invalid = || ColorError::InvalidHex(hex.to_string());
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let parse = |s: &str| u8::from_str_radix(s, 16).map_err(|_| invalid());
+        match digits.len() {
+            6 => Ok(Color::rgb(
+                parse(&digits[0..2])?,
+                parse(&digits[2..4])?,
+                parse(&digits[4..6])?,
+            )),
+            // Each shorthand digit is doubled: `#f80` is `#ff8800`
+            3 => Ok(Color::rgb(
+                parse(&digits[0..1])? * 0x11,
+                parse(&digits[1..2])? * 0x11,
+                parse(&digits[2..3])? * 0x11,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Red component
+    pub fn r(&self) -> u8 {
+        self.r
+    }
+
+    /// Green component
+    pub fn g(&self) -> u8 {
+        self.g
+    }
+
+    /// Blue component
+    pub fn b(&self) -> u8 {
+        self.b
+    }
+}
+
+/// `Display` shows the color in `#rrggbb` hex notation
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// # Errors from building a `Color`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+    /// A component was outside `0..=255`
+    OutOfRange {
+        /// `'r'`, `'g'` or `'b'`
+        component: char,
+        /// The value it was given
+        value: i32,
+    },
+    /// The text was not `#rrggbb` or `#rgb` hex notation
+    InvalidHex(String),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorError::OutOfRange { component, value } => {
+                write!(
+                    f,
+                    "color component `{component}` = {value} is outside 0..=255"
+                )
+            }
+            ColorError::InvalidHex(hex) => write!(f, "`{hex}` is not a hex color"),
+        }
+    }
+}
+
+impl core::error::Error for ColorError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_accepts_full_range() {
+        let c = Color::new(0, 128, 255).unwrap();
+        assert_eq!((c.r(), c.g(), c.b()), (0, 128, 255));
+    }
+
+    #[test]
+    fn new_rejects_out_of_range_instead_of_clamping() {
+        assert_eq!(
+            Color::new(-5, 0, 0),
+            Err(ColorError::OutOfRange {
+                component: 'r',
+                value: -5
+            })
+        );
+        assert_eq!(
+            Color::new(0, 9999, 0),
+            Err(ColorError::OutOfRange {
+                component: 'g',
+                value: 9999
+            })
+        );
+        assert_eq!(
+            Color::new(0, 0, 256),
+            Err(ColorError::OutOfRange {
+                component: 'b',
+                value: 256
+            })
+        );
+    }
+
+    #[test]
+    fn named_constants() {
+        assert_eq!(Color::RED, Color::new(255, 0, 0).unwrap());
+        assert_eq!(Color::default(), Color::BLACK);
+    }
+
+    #[test]
+    fn from_hex_with_and_without_hash() {
+        assert_eq!(Color::from_hex("#ff8800"), Ok(Color::rgb(255, 136, 0)));
+        assert_eq!(Color::from_hex("FF8800"), Ok(Color::rgb(255, 136, 0)));
+    }
+
+    #[test]
+    fn from_hex_shorthand() {
+        assert_eq!(Color::from_hex("#f80"), Ok(Color::rgb(255, 136, 0)));
+        assert_eq!(Color::from_hex("fff"), Ok(Color::WHITE));
+    }
+
+    #[test]
+    fn from_hex_invalid() {
+        for hex in ["", "#", "#ff88", "#ff88000", "#gg8800", "+f80", "#+f+f+f"] {
+            assert_eq!(
+                Color::from_hex(hex),
+                Err(ColorError::InvalidHex(hex.to_string())),
+                "{hex}"
+            );
+        }
+    }
+
+    #[test]
+    fn display_as_hex() {
+        assert_eq!(Color::rgb(255, 136, 0).to_string(), "#ff8800");
+        assert_eq!(Color::BLACK.to_string(), "#000000");
+    }
+}
@@ -0,0 +1,283 @@
+//! # Versioned binary messages
+//!
+//! `ChangeColor` used to hold three bare `i32`s. Recordings made back then
+//! still have to load now that it holds a validated `Color`, so the binary
+//! form is wrapped in an `Envelope` that starts with a version byte:
+//!
+//! | Version | Payload                                                  |
+//! |---------|----------------------------------------------------------|
+//! | `1`     | a `MessageV1`, `ChangeColor` components unchecked `i32`s  |
+//! | `2`     | a `Message`, in the [`codec`](crate::codec) format        |
+//!
+//! Both versions use the same tags, and the same layout for every variant
+//! they share except `Quit`, which only gained its `code` in version 2.
+//! Version 1 has no `Resize` or `Draw`, and doesn't range-check colors.
+//! A version 1 `Quit` upgrades to `Quit { code: 0 }`. `Envelope::upgrade`
+//! turns either into a current `Message`.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::codec::{
+    length_prefix, DecodeError, EncodeError, Reader, TAG_CHANGE_COLOR, TAG_MOVE, TAG_QUIT,
+    TAG_WRITE,
+};
+use crate::color::Color;
+use crate::Message;
+
+const VERSION_1: u8 = 1;
+const VERSION_2: u8 = 2;
+
+/// # The shape `Message` had in format version 1
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageV1 {
+    /// Stop, with no exit code
+    Quit,
+    /// Move the cursor by `x`, `y`
+    Move {
+        /// The columns to move by
+        x: i32,
+        /// The rows to move by
+        y: i32,
+    },
+    /// Log some text
+    Write(String),
+    /// Change to an unchecked red, green and blue
+    ChangeColor(i32, i32, i32),
+}
+
+/// # A `Message` of either format version
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Envelope {
+    /// A message in the version 1 format
+    V1(MessageV1),
+    /// A message in the current, version 2 format
+    V2(Message),
+}
+
+impl From<Message> for Envelope {
+    fn from(msg: Message) -> Self {
+        Envelope::V2(msg)
+    }
+}
+
+impl Envelope {
+    /// The current `Message` equivalent to this envelope's contents
+    ///
+    /// Version 1 colors had no range check, so each component is clamped
+    /// into `0..=255` on the way through `Color`: `ChangeColor(-5, 300, 7)`
+    /// becomes `#00ff07`.
+    pub fn upgrade(self) -> Message {
+        match self {
+            Envelope::V2(msg) => msg,
+            Envelope::V1(MessageV1::Quit) => Message::Quit { code: 0 },
+            Envelope::V1(MessageV1::Move { x, y }) => Message::Move { x, y },
+            Envelope::V1(MessageV1::Write(text)) => Message::Write(text),
+            Envelope::V1(MessageV1::ChangeColor(r, g, b)) => {
+                let clamp = |c: i32| c.clamp(0, 255) as u8;
+                Message::ChangeColor(Color::rgb(clamp(r), clamp(g), clamp(b)))
+            }
+        }
+    }
+
+    /// Append the version byte and then the message to `buf`
+    ///
+    /// A message too long to encode is an error, as for `Message::encode`,
+    /// leaving `buf` as it was.
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        let start = buf.len();
+        let encoded = match self {
+            Envelope::V1(msg) => {
+                buf.push(VERSION_1);
+                msg.encode(buf)
+            }
+            Envelope::V2(msg) => {
+                buf.push(VERSION_2);
+                msg.encode(buf)
+            }
+        };
+        if encoded.is_err() {
+            buf.truncate(start);
+        }
+        encoded
+    }
+
+    /// Decode one envelope of either version from the front of `bytes`
+    ///
+    /// Returns the envelope along with the number of bytes it occupied,
+    /// version byte included.
+    pub fn decode(bytes: &[u8]) -> Result<(Envelope, usize), DecodeError> {
+        let (&version, rest) = bytes.split_first().ok_or(DecodeError::UnexpectedEof)?;
+        let (envelope, used) = match version {
+            VERSION_1 => {
+                let (msg, used) = MessageV1::decode(rest)?;
+                (Envelope::V1(msg), used)
+            }
+            VERSION_2 => {
+                let (msg, used) = Message::decode(rest)?;
+                (Envelope::V2(msg), used)
+            }
+            version => return Err(DecodeError::UnknownVersion(version)),
+        };
+        Ok((envelope, 1 + used))
+    }
+}
+
+impl MessageV1 {
+    /// Append the version 1 encoding of this message, without a version byte
+    ///
+    /// A `Write` payload longer than `u32::MAX` bytes is an error, leaving
+    /// `buf` as it was.
+    pub fn encode(&self, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        match self {
+            MessageV1::Quit => buf.push(TAG_QUIT),
+            MessageV1::Move { x, y } => {
+                buf.push(TAG_MOVE);
+                buf.extend_from_slice(&x.to_le_bytes());
+                buf.extend_from_slice(&y.to_le_bytes());
+            }
+            MessageV1::Write(text) => {
+                let len = length_prefix(text.len(), EncodeError::WriteTooLong)?;
+                buf.push(TAG_WRITE);
+                buf.extend_from_slice(&len.to_le_bytes());
+                buf.extend_from_slice(text.as_bytes());
+            }
+            MessageV1::ChangeColor(r, g, b) => {
+                buf.push(TAG_CHANGE_COLOR);
+                for component in [r, g, b] {
+                    buf.extend_from_slice(&component.to_le_bytes());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode one version 1 message, without a version byte, from `bytes`
+    pub fn decode(bytes: &[u8]) -> Result<(MessageV1, usize), DecodeError> {
+        let mut reader = Reader::new(bytes);
+        let message = match reader.u8()? {
+            TAG_QUIT => MessageV1::Quit,
+            TAG_MOVE => MessageV1::Move {
+                x: reader.i32()?,
+                y: reader.i32()?,
+            },
+            TAG_WRITE => {
+                let len = reader.u32()?;
+                let len = usize::try_from(len).map_err(|_| DecodeError::UnexpectedEof)?;
+                let text = core::str::from_utf8(reader.take(len)?)
+                    .map_err(|_| DecodeError::InvalidUtf8)?;
+                MessageV1::Write(text.to_string())
+            }
+            TAG_CHANGE_COLOR => MessageV1::ChangeColor(reader.i32()?, reader.i32()?, reader.i32()?),
+            tag => return Err(DecodeError::UnknownTag(tag)),
+        };
+        Ok((message, reader.pos()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A version 1 recording, as written by the version 1 encoder: a `Move`,
+    /// a `Write` and an out-of-range `ChangeColor(-5, 300, 7)`
+    const V1_FIXTURE: &[u8] = &[
+        1, 1, 3, 0, 0, 0, 0xf9, 0xff, 0xff, 0xff, // Move { x: 3, y: -7 }
+        1, 2, 2, 0, 0, 0, b'h', b'i', // Write("hi")
+        1, 3, 0xfb, 0xff, 0xff, 0xff, 0x2c, 0x01, 0, 0, 7, 0, 0, 0, // ChangeColor
+    ];
+
+    fn decode_all(mut bytes: &[u8]) -> Vec<Envelope> {
+        let mut envelopes = Vec::new();
+        while !bytes.is_empty() {
+            let (envelope, used) = Envelope::decode(bytes).unwrap();
+            envelopes.push(envelope);
+            bytes = &bytes[used..];
+        }
+        envelopes
+    }
+
+    #[test]
+    fn v1_fixture_decodes_and_upgrades() {
+        let envelopes = decode_all(V1_FIXTURE);
+        assert_eq!(
+            envelopes,
+            [
+                Envelope::V1(MessageV1::Move { x: 3, y: -7 }),
+                Envelope::V1(MessageV1::Write(String::from("hi"))),
+                Envelope::V1(MessageV1::ChangeColor(-5, 300, 7)),
+            ]
+        );
+        let upgraded: Vec<Message> = envelopes.into_iter().map(Envelope::upgrade).collect();
+        assert_eq!(
+            upgraded,
+            [
+                Message::Move { x: 3, y: -7 },
+                Message::Write(String::from("hi")),
+                Message::ChangeColor(Color::rgb(0, 255, 7)),
+            ]
+        );
+    }
+
+    #[test]
+    fn v1_and_v2_forms_are_equivalent() {
+        let v1 = [
+            MessageV1::Quit,
+            MessageV1::Move { x: 1, y: 2 },
+            MessageV1::Write(String::from("hé")),
+            MessageV1::ChangeColor(255, 136, 0),
+        ];
+        let v2 = [
+            Message::Quit { code: 0 },
+            Message::Move { x: 1, y: 2 },
+            Message::Write(String::from("hé")),
+            Message::ChangeColor(Color::rgb(255, 136, 0)),
+        ];
+        for (old, new) in v1.into_iter().zip(v2) {
+            let mut old_bytes = Vec::new();
+            Envelope::V1(old).encode(&mut old_bytes).unwrap();
+            let mut new_bytes = Vec::new();
+            Envelope::from(new.clone()).encode(&mut new_bytes).unwrap();
+            // Only the version byte differs, apart from Quit's code
+            if !new.is_quit() {
+                assert_eq!(old_bytes[1..], new_bytes[1..]);
+            }
+
+            let (old, _) = Envelope::decode(&old_bytes).unwrap();
+            let (same, used) = Envelope::decode(&new_bytes).unwrap();
+            assert_eq!(used, new_bytes.len());
+            assert_eq!(old.upgrade(), new);
+            assert_eq!(same.upgrade(), new);
+        }
+    }
+
+    #[test]
+    fn v2_only_variants_round_trip() {
+        let msg = Message::Draw(vec![(1, 2)]);
+        let mut buf = Vec::new();
+        Envelope::from(msg.clone()).encode(&mut buf).unwrap();
+        assert_eq!(buf[0], 2);
+        assert_eq!(decode_all(&buf), [Envelope::V2(msg)]);
+        // Version 1 has no Draw tag
+        buf[0] = 1;
+        assert_eq!(
+            Envelope::decode(&buf).unwrap_err(),
+            DecodeError::UnknownTag(5)
+        );
+    }
+
+    #[test]
+    fn unknown_or_missing_version() {
+        assert_eq!(
+            Envelope::decode(&[9, 0]).unwrap_err(),
+            DecodeError::UnknownVersion(9)
+        );
+        assert_eq!(
+            Envelope::decode(&[]).unwrap_err(),
+            DecodeError::UnexpectedEof
+        );
+        assert_eq!(
+            Envelope::decode(&[2]).unwrap_err(),
+            DecodeError::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! # Executing a `Message` for a result
+//!
+//! `call` only prints, so there is no way for a caller to find out what a
+//! message did or whether it made sense. `execute` does the same work but
+//! returns a `Response` describing it, or a `MessageError` for messages that
+//! would do nothing at all.
+use core::fmt;
+
+use crate::Message;
+
+/// # What executing a `Message` did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Response {
+    /// A `Quit` was acknowledged
+    Ack,
+    /// A `Write` of this many bytes
+    Wrote(usize),
+    /// A `Move` by this `(x, y)` delta
+    MovedTo(i32, i32),
+    /// A `ChangeColor` was applied
+    ColorSet,
+    /// A `Resize` to this `width` and `height`
+    Resized {
+        /// The new width
+        width: u32,
+        /// The new height
+        height: u32,
+    },
+    /// A `Draw` of this many points
+    Drew(usize),
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Response::Ack => write!(f, "ack"),
+            Response::Wrote(len) => write!(f, "wrote {len} byte(s)"),
+            Response::MovedTo(x, y) => write!(f, "moved to ({x}, {y})"),
+            Response::ColorSet => write!(f, "color set"),
+            Response::Resized { width, height } => write!(f, "resized to {width}x{height}"),
+            Response::Drew(count) => write!(f, "drew {count} point(s)"),
+        }
+    }
+}
+
+/// # Why a `Message` was rejected by `execute`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageError {
+    /// A `Write` with no text
+    EmptyWrite,
+    /// A `Move` by `(0, 0)`, which would go nowhere
+    NoOpMove,
+    /// A `Resize` with a zero width or height
+    ZeroSize,
+    /// A `Draw` with no points
+    EmptyDraw,
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessageError::EmptyWrite => write!(f, "nothing to write"),
+            MessageError::NoOpMove => write!(f, "move by (0, 0) does nothing"),
+            MessageError::ZeroSize => write!(f, "cannot resize to a zero width or height"),
+            MessageError::EmptyDraw => write!(f, "nothing to draw"),
+        }
+    }
+}
+
+impl core::error::Error for MessageError {}
+
+impl Message {
+    /// Execute this `Message`, reporting what it did
+    ///
+    /// Unlike `call`, nothing is printed; the caller gets the `Response` and
+    /// decides what to do with it. Messages that would have no effect are
+    /// rejected with a `MessageError` instead.
+    pub fn execute(&self) -> Result<Response, MessageError> {
+        match self {
+            Message::Quit { .. } => Ok(Response::Ack),
+            Message::Move { x: 0, y: 0 } => Err(MessageError::NoOpMove),
+            Message::Move { x, y } => Ok(Response::MovedTo(*x, *y)),
+            Message::Write(text) if text.is_empty() => Err(MessageError::EmptyWrite),
+            Message::Write(text) => Ok(Response::Wrote(text.len())),
+            Message::ChangeColor(_) => Ok(Response::ColorSet),
+            Message::Resize { width, height } if *width == 0 || *height == 0 => {
+                Err(MessageError::ZeroSize)
+            }
+            Message::Resize { width, height } => Ok(Response::Resized {
+                width: *width,
+                height: *height,
+            }),
+            Message::Draw(points) if points.is_empty() => Err(MessageError::EmptyDraw),
+            Message::Draw(points) => Ok(Response::Drew(points.len())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    #[test]
+    fn success_responses() {
+        assert_eq!(Message::Quit { code: 0 }.execute(), Ok(Response::Ack));
+        assert_eq!(
+            Message::Write(String::from("hé")).execute(),
+            Ok(Response::Wrote(3))
+        );
+        assert_eq!(
+            Message::Move { x: 0, y: -2 }.execute(),
+            Ok(Response::MovedTo(0, -2))
+        );
+        assert_eq!(
+            Message::ChangeColor(Color::RED).execute(),
+            Ok(Response::ColorSet)
+        );
+        assert_eq!(
+            Message::Resize {
+                width: 80,
+                height: 24
+            }
+            .execute(),
+            Ok(Response::Resized {
+                width: 80,
+                height: 24
+            })
+        );
+        assert_eq!(
+            Message::Draw(vec![(1, 1), (2, 2)]).execute(),
+            Ok(Response::Drew(2))
+        );
+    }
+
+    #[test]
+    fn empty_write_is_an_error() {
+        assert_eq!(
+            Message::Write(String::new()).execute(),
+            Err(MessageError::EmptyWrite)
+        );
+    }
+
+    #[test]
+    fn move_by_nothing_is_an_error() {
+        assert_eq!(
+            Message::Move { x: 0, y: 0 }.execute(),
+            Err(MessageError::NoOpMove)
+        );
+    }
+
+    #[test]
+    fn zero_sized_resize_is_an_error() {
+        for (width, height) in [(0, 24), (80, 0), (0, 0)] {
+            assert_eq!(
+                Message::Resize { width, height }.execute(),
+                Err(MessageError::ZeroSize)
+            );
+        }
+    }
+
+    #[test]
+    fn empty_draw_is_an_error() {
+        assert_eq!(
+            Message::Draw(Vec::new()).execute(),
+            Err(MessageError::EmptyDraw)
+        );
+    }
+
+    #[test]
+    fn responses_and_errors_display() {
+        assert_eq!(Response::MovedTo(1, -1).to_string(), "moved to (1, -1)");
+        assert_eq!(Response::ColorSet.to_string(), "color set");
+        assert_eq!(
+            MessageError::NoOpMove.to_string(),
+            "move by (0, 0) does nothing"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! # `Message`s that go stale
+//!
+//! A `Timed` wraps a payload with the time it expires at, in milliseconds on
+//! whatever clock the caller uses. `drain_live` empties a queue of timed
+//! messages, keeping the ones still worth applying.
+//!
+//! A `Quit` never expires, however old it is: dropping one would leave
+//! whoever is applying the queue running forever.
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::Message;
+
+/// # A payload that stops being valid at `expires_at_ms`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Timed<T> {
+    payload: T,
+    expires_at_ms: u64,
+}
+
+impl<T> Timed<T> {
+    /// `payload`, valid until `expires_at_ms`
+    pub fn new(payload: T, expires_at_ms: u64) -> Self {
+        Timed {
+            payload,
+            expires_at_ms,
+        }
+    }
+
+    /// The wrapped payload
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    /// When the payload expires
+    pub fn expires_at_ms(&self) -> u64 {
+        self.expires_at_ms
+    }
+
+    /// Unwrap the payload, whether or not it has expired
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+}
+
+impl Timed<Message> {
+    /// Whether the message should be dropped at `now_ms`
+    ///
+    /// A message expires at the very millisecond `expires_at_ms` is reached,
+    /// not just after it. A `Quit` is never expired.
+    pub fn is_expired(&self, now_ms: u64) -> bool {
+        !self.payload.is_quit() && self.expires_at_ms <= now_ms
+    }
+}
+
+/// Empty `queue`, returning the messages still live at `now_ms`, in queue
+/// order, and how many expired ones were dropped
+pub fn drain_live(queue: &mut VecDeque<Timed<Message>>, now_ms: u64) -> (Vec<Message>, usize) {
+    let mut live = Vec::with_capacity(queue.len());
+    let mut expired = 0;
+    for timed in queue.drain(..) {
+        if timed.is_expired(now_ms) {
+            expired += 1;
+        } else {
+            live.push(timed.into_payload());
+        }
+    }
+    (live, expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_queue_keeps_only_live_messages() {
+        let mut queue = VecDeque::from([
+            Timed::new(Message::Write(String::from("stale")), 50),
+            Timed::new(Message::Move { x: 1, y: 1 }, 150),
+            Timed::new(Message::Write(String::from("also stale")), 99),
+            Timed::new(Message::Write(String::from("fresh")), 200),
+        ]);
+        let (live, expired) = drain_live(&mut queue, 100);
+        assert_eq!(
+            live,
+            [
+                Message::Move { x: 1, y: 1 },
+                Message::Write(String::from("fresh")),
+            ]
+        );
+        assert_eq!(expired, 2);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn quit_never_expires() {
+        let mut queue = VecDeque::from([
+            Timed::new(Message::Move { x: 1, y: 1 }, 0),
+            Timed::new(Message::Quit { code: 4 }, 0),
+        ]);
+        let (live, expired) = drain_live(&mut queue, u64::MAX);
+        assert_eq!(live, [Message::Quit { code: 4 }]);
+        assert_eq!(expired, 1);
+    }
+
+    #[test]
+    fn expiry_time_itself_counts_as_expired() {
+        let timed = Timed::new(Message::Move { x: 1, y: 0 }, 100);
+        assert!(!timed.is_expired(99));
+        assert!(timed.is_expired(100));
+        assert!(timed.is_expired(101));
+    }
+
+    #[test]
+    fn draining_an_empty_queue() {
+        assert_eq!(drain_live(&mut VecDeque::new(), 0), (Vec::new(), 0));
+    }
+}
@@ -0,0 +1,155 @@
+//! # Handling `Message`s with a visitor
+//!
+//! Rather than matching on `Message` everywhere it is consumed, a type can
+//! implement `MessageHandler` and let `Message::visit` do the one `match`,
+//! calling back into the handler method for the variant it found. Every
+//! method has an empty default, so a handler only overrides the variants it
+//! cares about.
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::color::Color;
+use crate::Message;
+
+/// # Callbacks for each `Message` variant
+pub trait MessageHandler {
+    /// Called for `Message::Quit`
+    fn on_quit(&mut self, _code: i32) {}
+
+    /// Called for `Message::Move`
+    fn on_move(&mut self, _x: i32, _y: i32) {}
+
+    /// Called for `Message::Write`
+    fn on_write(&mut self, _text: &str) {}
+
+    /// Called for `Message::ChangeColor`
+    fn on_change_color(&mut self, _c: &Color) {}
+
+    /// Called for `Message::Resize`
+    fn on_resize(&mut self, _width: u32, _height: u32) {}
+
+    /// Called for `Message::Draw`
+    fn on_draw(&mut self, _points: &[(i32, i32)]) {}
+}
+
+impl Message {
+    /// Call the `handler` method matching this `Message`'s variant
+    pub fn visit(&self, handler: &mut dyn MessageHandler) {
+        match self {
+            Message::Quit { code } => handler.on_quit(*code),
+            Message::Move { x, y } => handler.on_move(*x, *y),
+            Message::Write(text) => handler.on_write(text),
+            Message::ChangeColor(color) => handler.on_change_color(color),
+            Message::Resize { width, height } => handler.on_resize(*width, *height),
+            Message::Draw(points) => handler.on_draw(points),
+        }
+    }
+}
+
+/// # A `MessageHandler` that records every call it receives
+///
+/// Each call is logged as the method name and its arguments, for example
+/// `on_move(3, 4)`, in the order they arrived.
+#[derive(Debug, Default)]
+pub struct MessageLogger {
+    calls: Vec<String>,
+}
+
+impl MessageLogger {
+    /// A logger that hasn't seen any calls
+    pub fn new() -> Self {
+        MessageLogger::default()
+    }
+
+    /// Every call received so far, oldest first
+    pub fn calls(&self) -> &[String] {
+        &self.calls
+    }
+}
+
+impl MessageHandler for MessageLogger {
+    fn on_quit(&mut self, code: i32) {
+        self.calls.push(format!("on_quit({code})"));
+    }
+
+    fn on_move(&mut self, x: i32, y: i32) {
+        self.calls.push(format!("on_move({x}, {y})"));
+    }
+
+    fn on_write(&mut self, text: &str) {
+        self.calls.push(format!("on_write({text:?})"));
+    }
+
+    fn on_change_color(&mut self, c: &Color) {
+        self.calls.push(format!("on_change_color({c})"));
+    }
+
+    fn on_resize(&mut self, width: u32, height: u32) {
+        self.calls.push(format!("on_resize({width}, {height})"));
+    }
+
+    fn on_draw(&mut self, points: &[(i32, i32)]) {
+        self.calls.push(format!("on_draw({points:?})"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logger_records_dispatch_order() {
+        let batch = [
+            Message::Write(String::from("hello")),
+            Message::Move { x: 3, y: -4 },
+            Message::ChangeColor(Color::RED),
+            Message::Draw(vec![(1, 2)]),
+            Message::Move { x: 0, y: 1 },
+            Message::Resize {
+                width: 8,
+                height: 6,
+            },
+            Message::Quit { code: 0 },
+        ];
+        let mut logger = MessageLogger::new();
+        for msg in &batch {
+            msg.visit(&mut logger);
+        }
+        assert_eq!(
+            logger.calls(),
+            [
+                r#"on_write("hello")"#,
+                "on_move(3, -4)",
+                "on_change_color(#ff0000)",
+                "on_draw([(1, 2)])",
+                "on_move(0, 1)",
+                "on_resize(8, 6)",
+                "on_quit(0)",
+            ]
+        );
+    }
+
+    #[test]
+    fn default_methods_do_nothing() {
+        /// Only counts moves, ignoring every other variant
+        struct MoveCounter(usize);
+
+        impl MessageHandler for MoveCounter {
+            fn on_move(&mut self, _x: i32, _y: i32) {
+                self.0 += 1;
+            }
+        }
+
+        let mut counter = MoveCounter(0);
+        for msg in [
+            Message::Quit { code: 0 },
+            Message::Move { x: 1, y: 1 },
+            Message::Write(String::from("ignored")),
+            Message::Move { x: 2, y: 2 },
+        ] {
+            msg.visit(&mut counter);
+        }
+        assert_eq!(counter.0, 2);
+    }
+}
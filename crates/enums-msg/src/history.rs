@@ -0,0 +1,220 @@
+//! # Undo support via inverse messages
+//!
+//! Most `Message`s can be undone by applying another `Message`: a `Move`
+//! is undone by moving back, a `ChangeColor` by changing back to the color
+//! the canvas had before, and a `Resize` by resizing back to the old board.
+//! `History` applies messages to a `Canvas` while recording those inverses,
+//! so they can be popped off again by `undo`.
+use std::fmt;
+
+use crate::canvas::{self, ApplyOutcome, Canvas};
+use crate::Message;
+
+impl Message {
+    /// The `Message` that undoes this one, given the canvas it applies to
+    ///
+    /// - `Move { x, y }` inverts to `Move { -x, -y }`
+    /// - `ChangeColor` inverts to a `ChangeColor` back to `prior`'s color
+    /// - `Resize` inverts to a `Resize` back to `prior`'s board size
+    /// - `Write`, `Draw` and `Quit` can't be undone, so return `None`
+    ///
+    /// Undoing a `Resize` that shrank the board restores the size, but not a
+    /// cursor position that the shrink clamped.
+    ///
+    /// A `Move` by `i32::MIN` in either direction has no inverse either,
+    /// because `-i32::MIN` doesn't fit in an `i32`.
+    pub fn inverse(&self, prior: &Canvas) -> Option<Message> {
+        match self {
+            Message::Move { x, y } => Some(Message::Move {
+                x: x.checked_neg()?,
+                y: y.checked_neg()?,
+            }),
+            Message::ChangeColor(_) => Some(Message::ChangeColor(prior.color())),
+            Message::Resize { .. } => Some(Message::Resize {
+                width: u32::try_from(prior.board().width()).ok()?,
+                height: u32::try_from(prior.board().height()).ok()?,
+            }),
+            Message::Write(_) | Message::Draw(_) | Message::Quit { .. } => None,
+        }
+    }
+}
+
+/// # A stack of inverse messages for undoing changes to a `Canvas`
+///
+/// Messages without an inverse (`Write`, `Draw` and `Quit`) are still
+/// applied, but nothing is recorded for them: `undo` skips straight past
+/// them to the previous change that can be undone.
+#[derive(Debug, Default)]
+pub struct History {
+    undo_stack: Vec<Message>,
+}
+
+impl History {
+    /// An empty history
+    pub fn new() -> Self {
+        History::default()
+    }
+
+    /// Number of changes that can currently be undone
+    pub fn len(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// `true` if there is nothing to undo
+    pub fn is_empty(&self) -> bool {
+        self.undo_stack.is_empty()
+    }
+
+    /// Apply `msg` to `canvas`, recording how to undo it
+    ///
+    /// A rejected `Move` or `Resize` changes nothing, so it records
+    /// nothing. A clamped `Move` records the inverse of the distance the
+    /// cursor really moved, rather than of the distance asked for.
+    pub fn apply(&mut self, canvas: &mut Canvas, msg: Message) -> ApplyOutcome {
+        let prior_cursor = canvas.cursor();
+        let inverse = msg.inverse(canvas);
+        let outcome = canvas::apply(canvas, &msg);
+        let inverse = match outcome {
+            ApplyOutcome::Applied => inverse,
+            ApplyOutcome::Clamped { at } => Some(Message::Move {
+                x: prior_cursor.0 - at.0,
+                y: prior_cursor.1 - at.1,
+            }),
+            ApplyOutcome::Rejected { .. } | ApplyOutcome::InvalidSize { .. } => None,
+        };
+        if let Some(inverse) = inverse {
+            self.undo_stack.push(inverse);
+        }
+        outcome
+    }
+
+    /// Undo the most recent change recorded in this history
+    pub fn undo(&mut self, canvas: &mut Canvas) -> Result<(), UndoError> {
+        let inverse = self.undo_stack.pop().ok_or(UndoError::NothingToUndo)?;
+        canvas::apply(canvas, &inverse);
+        Ok(())
+    }
+}
+
+/// # Errors from `History::undo`
+#[derive(Debug, PartialEq, Eq)]
+pub enum UndoError {
+    /// Every recorded change has already been undone
+    NothingToUndo,
+}
+
+impl fmt::Display for UndoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UndoError::NothingToUndo => write!(f, "nothing to undo"),
+        }
+    }
+}
+
+impl std::error::Error for UndoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::{Board, MovePolicy};
+    use crate::color::Color;
+
+    #[test]
+    fn inverse_of_each_variant() {
+        let mut prior = Canvas::new();
+        canvas::apply(&mut prior, &Message::ChangeColor(Color::BLUE));
+
+        assert!(matches!(
+            Message::Move { x: 3, y: -4 }.inverse(&prior),
+            Some(Message::Move { x: -3, y: 4 })
+        ));
+        assert!(matches!(
+            Message::ChangeColor(Color::RED).inverse(&prior),
+            Some(Message::ChangeColor(Color::BLUE))
+        ));
+        assert!(Message::Write(String::from("hi")).inverse(&prior).is_none());
+        assert!(Message::Quit { code: 0 }.inverse(&prior).is_none());
+        assert!(Message::Move { x: i32::MIN, y: 0 }
+            .inverse(&prior)
+            .is_none());
+    }
+
+    #[test]
+    fn undo_everything_restores_the_initial_canvas() {
+        let mut canvas = Canvas::new();
+        let mut history = History::new();
+        history.apply(&mut canvas, Message::Move { x: 3, y: 4 });
+        history.apply(&mut canvas, Message::ChangeColor(Color::RED));
+        history.apply(&mut canvas, Message::Move { x: 10, y: 0 });
+        history.apply(&mut canvas, Message::Move { x: -2, y: 5 });
+        assert_eq!(canvas.cursor(), (11, 9));
+        assert_eq!(history.len(), 4);
+
+        for _ in 0..4 {
+            history.undo(&mut canvas).unwrap();
+        }
+        assert_eq!(canvas, Canvas::new());
+        assert_eq!(history.undo(&mut canvas), Err(UndoError::NothingToUndo));
+    }
+
+    #[test]
+    fn undo_skips_writes() {
+        let mut canvas = Canvas::new();
+        let mut history = History::new();
+        history.apply(&mut canvas, Message::Move { x: 1, y: 1 });
+        history.apply(&mut canvas, Message::Write(String::from("kept")));
+        history.apply(&mut canvas, Message::ChangeColor(Color::GREEN));
+        assert_eq!(history.len(), 2);
+
+        history.undo(&mut canvas).unwrap();
+        assert_eq!(canvas.color(), Color::BLACK);
+        // The Write has no inverse, so this undoes the Move before it
+        history.undo(&mut canvas).unwrap();
+        assert_eq!(canvas.cursor(), (0, 0));
+        assert_eq!(canvas.log(), ["kept"]);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn resize_undoes_back_to_the_old_board() {
+        let mut canvas = Canvas::new();
+        let mut history = History::new();
+        history.apply(
+            &mut canvas,
+            Message::Resize {
+                width: 10,
+                height: 10,
+            },
+        );
+        history.apply(&mut canvas, Message::Draw(vec![(1, 1)]));
+        history.apply(
+            &mut canvas,
+            Message::Resize {
+                width: 0,
+                height: 10,
+            },
+        );
+        assert_eq!(history.len(), 1);
+        history.undo(&mut canvas).unwrap();
+        assert_eq!(canvas.board(), Board::default());
+        // Draw has no inverse, so its point stays plotted
+        assert!(canvas.points().contains(&(1, 1)));
+    }
+
+    #[test]
+    fn clamped_and_rejected_moves_undo_exactly() {
+        let board = Board::new(5, 5).unwrap();
+        let mut canvas = Canvas::with_board(board, MovePolicy::Clamp);
+        let mut history = History::new();
+        history.apply(&mut canvas, Message::Move { x: 2, y: 2 });
+        history.apply(&mut canvas, Message::Move { x: 10, y: 0 });
+        assert_eq!(canvas.cursor(), (4, 2));
+        history.undo(&mut canvas).unwrap();
+        assert_eq!(canvas.cursor(), (2, 2));
+
+        let mut canvas = Canvas::with_board(board, MovePolicy::Reject);
+        let mut history = History::new();
+        history.apply(&mut canvas, Message::Move { x: 10, y: 0 });
+        assert!(history.is_empty());
+    }
+}
@@ -0,0 +1,835 @@
+//! # JSON round trip for `Message`
+//!
+//! `Message` is written using the same externally-tagged representation that
+//! serde uses by default: an object with a single key naming the variant.
+//!
+//! ```json
+//! {"Quit":{"code":0}}
+//! {"Move":{"x":3,"y":7}}
+//! {"Write":"hello"}
+//! {"ChangeColor":[255,0,0]}
+//! {"Resize":{"width":80,"height":24}}
+//! {"Draw":[[1,2],[3,4]]}
+//! ```
+//!
+//! `Quit` used to be a unit variant written as the bare string `"Quit"`; that
+//! form is still read, as a `Quit` with code 0.
+//!
+//! `read_jsonl` and `write_jsonl` stream `Message`s as JSON Lines, one
+//! message per line. `MessageStats` can be written out too, see
+//! `stats_to_json`.
+//!
+//! The reader is a small recursive-descent JSON parser. It is strict: unknown
+//! variant names, missing or unknown fields, and out-of-range numbers are all
+//! errors rather than silently falling back to defaults. `parse_value`
+//! exposes the parser on its own, for JSON that isn't a `Message`.
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::color::{Color, ColorError};
+use crate::message::MessageKind;
+use crate::stats::MessageStats;
+use crate::Message;
+
+/// Serialize a `Message` to a compact JSON string
+pub fn message_to_json(message: &Message) -> String {
+    let mut out = String::new();
+    write_message(&mut out, message);
+    out
+}
+
+/// Deserialize a `Message` from a JSON string
+pub fn message_from_json(json: &str) -> Result<Message, JsonError> {
+    let value = parse_value(json)?;
+    message_from_value(&value)
+}
+
+/// Serialize a slice of `Message`s to a JSON array
+pub fn messages_to_json(messages: &[Message]) -> String {
+    let mut out = String::from("[");
+    for (i, message) in messages.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_message(&mut out, message);
+    }
+    out.push(']');
+    out
+}
+
+/// Deserialize a JSON array of `Message`s
+pub fn messages_from_json(json: &str) -> Result<Vec<Message>, JsonError> {
+    match parse_value(json)? {
+        Value::Array(items) => items.iter().map(message_from_value).collect(),
+        _ => Err(JsonError::InvalidType("an array of messages")),
+    }
+}
+
+/// Write each of `messages` to `writer` as JSON on a line of its own
+pub fn write_jsonl<W: Write>(mut writer: W, messages: &[Message]) -> io::Result<()> {
+    for message in messages {
+        writeln!(writer, "{}", message_to_json(message))?;
+    }
+    writer.flush()
+}
+
+/// Read one `Message` from each line of `reader`, as written by `write_jsonl`
+///
+/// Blank lines are skipped. A line that isn't a valid message yields an
+/// error naming it, and reading carries on with the next line, so it is up
+/// to the caller whether one bad line should stop the rest.
+pub fn read_jsonl<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Message, JsonlError>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| match line {
+            Err(e) => Some(Err(JsonlError::Io(e))),
+            Ok(line) if line.trim().is_empty() => None,
+            Ok(line) => Some(message_from_json(&line).map_err(|error| JsonlError::Json {
+                line: index + 1,
+                error,
+            })),
+        })
+}
+
+/// Serialize `MessageStats` to a compact JSON object
+///
+/// Every kind is listed under `counts`, including those never seen.
+/// `move_bounds` is `null` when there were no moves, and `colors` is sorted
+/// so the output doesn't depend on hashing order.
+///
+/// ```json
+/// {"counts":{"Quit":1,"Move":2,...},"bytes_written":5,
+///  "move_bounds":{"min":[-1,0],"max":[3,4]},"colors":["#ff0000"]}
+/// ```
+pub fn stats_to_json(stats: &MessageStats) -> String {
+    let counts: Vec<String> = MessageKind::VARIANTS
+        .iter()
+        .map(|kind| format!("\"{:?}\":{}", kind, stats.count(*kind)))
+        .collect();
+    let bounds = match stats.move_bounds() {
+        Some(((min_x, min_y), (max_x, max_y))) => {
+            format!("{{\"min\":[{min_x},{min_y}],\"max\":[{max_x},{max_y}]}}")
+        }
+        None => String::from("null"),
+    };
+    let mut colors: Vec<Color> = stats.colors().iter().copied().collect();
+    colors.sort_by_key(|c| (c.r(), c.g(), c.b()));
+    let colors: Vec<String> = colors.iter().map(|c| format!("\"{c}\"")).collect();
+    format!(
+        "{{\"counts\":{{{}}},\"bytes_written\":{},\"move_bounds\":{},\"colors\":[{}]}}",
+        counts.join(","),
+        stats.bytes_written(),
+        bounds,
+        colors.join(",")
+    )
+}
+
+/// # Errors from reading a `Message` out of JSON
+#[derive(Debug, PartialEq, Eq)]
+pub enum JsonError {
+    /// The input is not valid JSON; `offset` is the byte where it went wrong
+    Syntax {
+        /// The byte the error was found at
+        offset: usize,
+        /// What was wrong there
+        reason: &'static str,
+    },
+    /// A value had the wrong JSON type
+    InvalidType(&'static str),
+    /// The variant name does not belong to `Message`
+    UnknownVariant(String),
+    /// A required field was not present
+    MissingField(&'static str),
+    /// A field that `Message` does not have was present
+    UnknownField(String),
+    /// A number did not fit in the payload's integer type
+    OutOfRange(String),
+    /// The `ChangeColor` components do not make a valid `Color`
+    InvalidColor(ColorError),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::Syntax { offset, reason } => {
+                write!(f, "invalid JSON at byte {offset}: {reason}")
+            }
+            JsonError::InvalidType(expected) => write!(f, "invalid type, expected {expected}"),
+            JsonError::UnknownVariant(name) => write!(f, "unknown variant `{name}`"),
+            JsonError::MissingField(name) => write!(f, "missing field `{name}`"),
+            JsonError::UnknownField(name) => write!(f, "unknown field `{name}`"),
+            JsonError::OutOfRange(number) => write!(f, "number `{number}` is out of range"),
+            JsonError::InvalidColor(e) => write!(f, "invalid color: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JsonError::InvalidColor(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// # Errors from `read_jsonl`
+#[derive(Debug)]
+pub enum JsonlError {
+    /// The input could not be read
+    Io(io::Error),
+    /// Line number `line`, counting from 1, was not a valid message
+    Json {
+        /// The line number
+        line: usize,
+        /// What was wrong with it
+        error: JsonError,
+    },
+}
+
+impl fmt::Display for JsonlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonlError::Io(e) => write!(f, "could not read JSON lines: {e}"),
+            JsonlError::Json { line, error } => write!(f, "line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JsonlError::Io(e) => Some(e),
+            JsonlError::Json { error, .. } => Some(error),
+        }
+    }
+}
+
+fn write_message(out: &mut String, message: &Message) {
+    match message {
+        Message::Quit { code } => out.push_str(&format!("{{\"Quit\":{{\"code\":{code}}}}}")),
+        Message::Move { x, y } => out.push_str(&format!("{{\"Move\":{{\"x\":{x},\"y\":{y}}}}}")),
+        Message::Write(text) => {
+            out.push_str("{\"Write\":");
+            write_string(out, text);
+            out.push('}');
+        }
+        Message::ChangeColor(color) => out.push_str(&format!(
+            "{{\"ChangeColor\":[{},{},{}]}}",
+            color.r(),
+            color.g(),
+            color.b()
+        )),
+        Message::Resize { width, height } => out.push_str(&format!(
+            "{{\"Resize\":{{\"width\":{width},\"height\":{height}}}}}"
+        )),
+        Message::Draw(points) => {
+            out.push_str("{\"Draw\":[");
+            for (i, (x, y)) in points.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&format!("[{x},{y}]"));
+            }
+            out.push_str("]}");
+        }
+    }
+}
+
+/// Write `text` as a JSON string literal
+pub(crate) fn write_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn message_from_value(value: &Value) -> Result<Message, JsonError> {
+    let (variant, payload) = match value {
+        Value::String(name) => (name.as_str(), None),
+        Value::Object(fields) => match fields.as_slice() {
+            [(name, payload)] => (name.as_str(), Some(payload)),
+            _ => {
+                return Err(JsonError::InvalidType(
+                    "an object with a single variant key",
+                ))
+            }
+        },
+        _ => {
+            return Err(JsonError::InvalidType(
+                "a string or object naming a variant",
+            ))
+        }
+    };
+
+    match (variant, payload) {
+        ("Quit", None) => Ok(Message::Quit { code: 0 }),
+        ("Quit", Some(Value::Object(fields))) => {
+            let [code] = struct_fields(fields, ["code"])?;
+            Ok(Message::Quit {
+                code: to_integer(code)?,
+            })
+        }
+        ("Quit", _) => Err(JsonError::InvalidType("struct variant `Quit`")),
+        ("Move", Some(Value::Object(fields))) => {
+            let [x, y] = struct_fields(fields, ["x", "y"])?;
+            Ok(Message::Move {
+                x: to_integer(x)?,
+                y: to_integer(y)?,
+            })
+        }
+        ("Move", _) => Err(JsonError::InvalidType("struct variant `Move`")),
+        ("Write", Some(Value::String(text))) => Ok(Message::Write(text.clone())),
+        ("Write", _) => Err(JsonError::InvalidType(
+            "newtype variant `Write` holding a string",
+        )),
+        ("ChangeColor", Some(Value::Array(items))) => match items.as_slice() {
+            [r, g, b] => Color::new(to_integer(r)?, to_integer(g)?, to_integer(b)?)
+                .map(Message::ChangeColor)
+                .map_err(JsonError::InvalidColor),
+            _ => Err(JsonError::InvalidType("an array of 3 color components")),
+        },
+        ("ChangeColor", _) => Err(JsonError::InvalidType("tuple variant `ChangeColor`")),
+        ("Resize", Some(Value::Object(fields))) => {
+            let [width, height] = struct_fields(fields, ["width", "height"])?;
+            Ok(Message::Resize {
+                width: to_integer(width)?,
+                height: to_integer(height)?,
+            })
+        }
+        ("Resize", _) => Err(JsonError::InvalidType("struct variant `Resize`")),
+        ("Draw", Some(Value::Array(items))) => items
+            .iter()
+            .map(|item| match item {
+                Value::Array(point) => match point.as_slice() {
+                    [x, y] => Ok((to_integer(x)?, to_integer(y)?)),
+                    _ => Err(JsonError::InvalidType("an `[x, y]` point")),
+                },
+                _ => Err(JsonError::InvalidType("an `[x, y]` point")),
+            })
+            .collect::<Result<_, _>>()
+            .map(Message::Draw),
+        ("Draw", _) => Err(JsonError::InvalidType(
+            "newtype variant `Draw` holding an array of points",
+        )),
+        (name, _) => Err(JsonError::UnknownVariant(name.to_string())),
+    }
+}
+
+/// The values of exactly the fields `names` of a struct variant, in order
+fn struct_fields<'a, const N: usize>(
+    fields: &'a [(String, Value)],
+    names: [&'static str; N],
+) -> Result<[&'a Value; N], JsonError> {
+    for (name, _) in fields {
+        if !names.contains(&name.as_str()) {
+            return Err(JsonError::UnknownField(name.clone()));
+        }
+    }
+    let mut values = [&Value::Null; N];
+    for (value, key) in values.iter_mut().zip(names) {
+        *value = fields
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+            .ok_or(JsonError::MissingField(key))?;
+    }
+    Ok(values)
+}
+
+/// Convert a JSON number to whichever integer type the payload needs
+fn to_integer<T: std::str::FromStr>(value: &Value) -> Result<T, JsonError> {
+    match value {
+        Value::Number(number) => number
+            .parse()
+            .map_err(|_| JsonError::OutOfRange(number.clone())),
+        _ => Err(JsonError::InvalidType("an integer")),
+    }
+}
+
+/// # A parsed JSON value
+///
+/// Numbers keep their original text so that integers can be range-checked
+/// exactly when they are converted to a payload type.
+///
+/// ```
+/// use enums_msg::json::{parse_value, Value};
+///
+/// let value = parse_value(r#"{"coins":[{"cents":25}]}"#).unwrap();
+/// let coins = value.get("coins").unwrap();
+/// assert!(matches!(coins, Value::Array(items) if items.len() == 1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// `null`
+    Null,
+    /// `true` or `false`
+    Bool(bool),
+    /// A number, exactly as written
+    Number(String),
+    /// A string, with its escapes resolved
+    String(String),
+    /// `[...]`
+    Array(Vec<Value>),
+    /// `{...}`, members in the order they were written
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// The member called `key`, if this is an object that has one
+    ///
+    /// ```
+    /// use enums_msg::json::{parse_value, Value};
+    ///
+    /// let value = parse_value(r#"{"kind":"penny"}"#).unwrap();
+    /// assert_eq!(value.get("kind"), Some(&Value::String(String::from("penny"))));
+    /// assert_eq!(value.get("cents"), None);
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a complete JSON document into a `Value`
+///
+/// ```
+/// use enums_msg::json::{parse_value, Value};
+///
+/// let value = parse_value("[true, null]").unwrap();
+/// assert_eq!(value, Value::Array(vec![Value::Bool(true), Value::Null]));
+/// assert!(parse_value("[true,").is_err());
+/// ```
+pub fn parse_value(json: &str) -> Result<Value, JsonError> {
+    let mut parser = Parser { json, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos != json.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    json: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: &'static str) -> JsonError {
+        JsonError::Syntax {
+            offset: self.pos,
+            reason,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.json.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, reason: &'static str) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(reason))
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Value) -> Result<Value, JsonError> {
+        if self.json[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("expected a value"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some(b'n') => self.keyword("null", Value::Null),
+            Some(b't') => self.keyword("true", Value::Bool(true)),
+            Some(b'f') => self.keyword("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => self.array(),
+            Some(b'{') => self.object(),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, JsonError> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let digits = self.pos;
+        while let Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') = self.peek() {
+            self.pos += 1;
+        }
+        let text = &self.json[start..self.pos];
+        if self.pos == digits || text.parse::<f64>().is_err() {
+            return Err(self.error("invalid number"));
+        }
+        Ok(Value::Number(text.to_string()))
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect(b'"', "expected a string")?;
+        let mut text = String::new();
+        loop {
+            let Some(c) = self.json[self.pos..].chars().next() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(text),
+                '\\' => {
+                    let escape = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match escape {
+                        b'"' => text.push('"'),
+                        b'\\' => text.push('\\'),
+                        b'/' => text.push('/'),
+                        b'b' => text.push('\u{8}'),
+                        b'f' => text.push('\u{c}'),
+                        b'n' => text.push('\n'),
+                        b'r' => text.push('\r'),
+                        b't' => text.push('\t'),
+                        b'u' => text.push(self.unicode_escape()?),
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                c if (c as u32) < 0x20 => return Err(self.error("control character in string")),
+                c => text.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let hex = self
+            .json
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        let code =
+            u32::from_str_radix(hex, 16).map_err(|_| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if !self.json[self.pos..].starts_with("\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn array(&mut self) -> Result<Value, JsonError> {
+        self.expect(b'[', "expected an array")?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, JsonError> {
+        self.expect(b'{', "expected an object")?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':', "expected `:`")?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(fields));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(message: &Message) -> Message {
+        message_from_json(&message_to_json(message)).unwrap()
+    }
+
+    #[test]
+    fn quit_round_trip() {
+        let quit = Message::Quit { code: 3 };
+        assert_eq!(message_to_json(&quit), r#"{"Quit":{"code":3}}"#);
+        assert_eq!(round_trip(&quit), quit);
+        // The unit-variant form from before `Quit` had a code
+        assert_eq!(
+            message_from_json(r#""Quit""#),
+            Ok(Message::Quit { code: 0 })
+        );
+    }
+
+    #[test]
+    fn move_round_trip() {
+        let m = Message::Move { x: 3, y: -7 };
+        assert_eq!(message_to_json(&m), r#"{"Move":{"x":3,"y":-7}}"#);
+        assert!(matches!(round_trip(&m), Message::Move { x: 3, y: -7 }));
+    }
+
+    #[test]
+    fn write_round_trip() {
+        let m = Message::Write(String::from("say \"hi\"\n\u{1}é"));
+        assert_eq!(message_to_json(&m), r#"{"Write":"say \"hi\"\n\u0001é"}"#);
+        assert!(matches!(round_trip(&m), Message::Write(text) if text == "say \"hi\"\n\u{1}é"));
+    }
+
+    #[test]
+    fn change_color_round_trip() {
+        let m = Message::ChangeColor(Color::RED);
+        assert_eq!(message_to_json(&m), r#"{"ChangeColor":[255,0,0]}"#);
+        assert!(matches!(round_trip(&m), Message::ChangeColor(Color::RED)));
+    }
+
+    #[test]
+    fn resize_round_trip() {
+        let m = Message::Resize {
+            width: 80,
+            height: 24,
+        };
+        assert_eq!(
+            message_to_json(&m),
+            r#"{"Resize":{"width":80,"height":24}}"#
+        );
+        assert!(matches!(
+            round_trip(&m),
+            Message::Resize {
+                width: 80,
+                height: 24
+            }
+        ));
+    }
+
+    #[test]
+    fn draw_round_trip() {
+        let m = Message::Draw(vec![(1, 2), (-3, 4)]);
+        assert_eq!(message_to_json(&m), r#"{"Draw":[[1,2],[-3,4]]}"#);
+        assert!(matches!(round_trip(&m), Message::Draw(points) if points == [(1, 2), (-3, 4)]));
+        assert_eq!(
+            message_to_json(&Message::Draw(Vec::new())),
+            r#"{"Draw":[]}"#
+        );
+    }
+
+    #[test]
+    fn resize_and_draw_payload_errors() {
+        assert_eq!(
+            message_from_json(r#"{"Resize":{"width":-1,"height":2}}"#).unwrap_err(),
+            JsonError::OutOfRange(String::from("-1"))
+        );
+        assert_eq!(
+            message_from_json(r#"{"Resize":{"width":1}}"#).unwrap_err(),
+            JsonError::MissingField("height")
+        );
+        assert_eq!(
+            message_from_json(r#"{"Draw":[[1,2,3]]}"#).unwrap_err(),
+            JsonError::InvalidType("an `[x, y]` point")
+        );
+    }
+
+    #[test]
+    fn reads_whitespace_and_field_order() {
+        let m = message_from_json(r#" { "Move" : { "y" : 2 , "x" : 1 } } "#).unwrap();
+        assert!(matches!(m, Message::Move { x: 1, y: 2 }));
+    }
+
+    #[test]
+    fn vec_round_trip() {
+        let messages = vec![
+            Message::Quit { code: 0 },
+            Message::Move { x: 1, y: 2 },
+            Message::Write(String::from("hello")),
+            Message::ChangeColor(Color::rgb(1, 2, 3)),
+        ];
+        let json = messages_to_json(&messages);
+        let back = messages_from_json(&json).unwrap();
+        assert_eq!(messages_to_json(&back), json);
+    }
+
+    #[test]
+    fn stats_as_json() {
+        let stats = MessageStats::from_messages(&[
+            Message::Move { x: 3, y: 4 },
+            Message::ChangeColor(Color::RED),
+            Message::Write(String::from("hello")),
+            Message::Move { x: -1, y: 0 },
+            Message::ChangeColor(Color::BLUE),
+        ]);
+        let json = stats_to_json(&stats);
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"counts":{"Quit":0,"Move":2,"Write":1,"ChangeColor":2,"Resize":0,"Draw":0},"#,
+                r#""bytes_written":5,"move_bounds":{"min":[-1,0],"max":[3,4]},"#,
+                r##""colors":["#0000ff","#ff0000"]}"##
+            )
+        );
+        // The output is valid JSON
+        assert!(parse_value(&json).is_ok());
+        assert!(stats_to_json(&MessageStats::new()).contains(r#""move_bounds":null"#));
+    }
+
+    #[test]
+    fn unknown_variant_is_an_error() {
+        assert_eq!(
+            message_from_json(r#""Jump""#).unwrap_err(),
+            JsonError::UnknownVariant(String::from("Jump"))
+        );
+        assert_eq!(
+            message_from_json(r#"{"Jump":{"x":1}}"#).unwrap_err(),
+            JsonError::UnknownVariant(String::from("Jump"))
+        );
+    }
+
+    #[test]
+    fn missing_field_is_an_error() {
+        assert_eq!(
+            message_from_json(r#"{"Move":{"x":1}}"#).unwrap_err(),
+            JsonError::MissingField("y")
+        );
+    }
+
+    #[test]
+    fn unknown_field_is_an_error() {
+        assert_eq!(
+            message_from_json(r#"{"Move":{"x":1,"y":2,"z":3}}"#).unwrap_err(),
+            JsonError::UnknownField(String::from("z"))
+        );
+    }
+
+    #[test]
+    fn wrong_payload_shapes_are_errors() {
+        assert!(message_from_json(r#"{"ChangeColor":[1,2]}"#).is_err());
+        assert_eq!(
+            message_from_json(r#"{"ChangeColor":[1,2,300]}"#).unwrap_err(),
+            JsonError::InvalidColor(ColorError::OutOfRange {
+                component: 'b',
+                value: 300
+            })
+        );
+        assert!(message_from_json(r#"{"Write":5}"#).is_err());
+        assert!(message_from_json(r#"{"Quit":null}"#).is_err());
+        assert!(message_from_json(r#"{"Move":{"x":1.5,"y":2}}"#).is_err());
+        assert_eq!(
+            message_from_json(r#"{"Move":{"x":3000000000,"y":2}}"#).unwrap_err(),
+            JsonError::OutOfRange(String::from("3000000000"))
+        );
+    }
+
+    #[test]
+    fn syntax_errors_report_offset() {
+        assert_eq!(
+            message_from_json(r#"{"Move":{"x":1,"y":2}"#).unwrap_err(),
+            JsonError::Syntax {
+                offset: 21,
+                reason: "expected `,` or `}`"
+            }
+        );
+        assert!(message_from_json(r#""Quit" extra"#).is_err());
+        assert!(message_from_json("").is_err());
+    }
+
+    #[test]
+    fn jsonl_round_trip() {
+        let batch = [
+            Message::Write(String::from("line\nbreak")),
+            Message::Move { x: 1, y: -2 },
+            Message::Draw(vec![(1, 2)]),
+            Message::Quit { code: 3 },
+        ];
+        let mut buf = Vec::new();
+        write_jsonl(&mut buf, &batch).unwrap();
+        // Newlines inside strings are escaped, so each message is one line
+        assert_eq!(buf.iter().filter(|&&b| b == b'\n').count(), batch.len());
+        let read: Vec<Message> = read_jsonl(buf.as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, batch);
+    }
+
+    #[test]
+    fn jsonl_keeps_going_after_a_bad_line() {
+        let input = "{\"Move\":{\"x\":1,\"y\":2}}\n{\"Move\":\n\n\"Quit\"\n";
+        let results: Vec<_> = read_jsonl(input.as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], Ok(Message::Move { x: 1, y: 2 })));
+        match &results[1] {
+            Err(JsonlError::Json { line, error }) => {
+                assert_eq!(*line, 2);
+                assert!(matches!(error, JsonError::Syntax { .. }));
+            }
+            other => panic!("expected an error on line 2, got {other:?}"),
+        }
+        assert_eq!(
+            results[1].as_ref().unwrap_err().to_string(),
+            "line 2: invalid JSON at byte 8: unexpected end of input"
+        );
+        assert!(matches!(results[2], Ok(Message::Quit { code: 0 })));
+    }
+}
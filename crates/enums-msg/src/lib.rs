@@ -0,0 +1,63 @@
+//! # Enums and Pattern Matching: `Message` and the modules built on it
+//!
+//! The `Message` enum, the `Color`s it carries, and the modules that build,
+//! encode, apply and schedule `Message`s, with the `Canvas` they draw on.
+//!
+//! ```
+//! use enums_msg::Message;
+//!
+//! assert_eq!(Message::Quit { code: 0 }.describe(), "Quit: shutting down");
+//! ```
+//!
+//! With the default `std` feature off the crate is `#![no_std]`: `Message`,
+//! `Color`, the codec and the other modules that only need `alloc` are
+//! still there, while the `Canvas` and everything needing printing, I/O,
+//! threads, clocks or `HashMap`s are left out.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::todo,
+    clippy::unimplemented
+)]
+#![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used))]
+
+extern crate alloc;
+
+#[cfg(feature = "async")]
+pub mod async_worker;
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod canvas;
+pub mod codec;
+pub mod color;
+pub mod envelope;
+pub mod execute;
+pub mod expiry;
+pub mod handler;
+#[cfg(feature = "std")]
+pub mod history;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod message;
+#[cfg(feature = "std")]
+pub mod priority;
+#[cfg(feature = "std")]
+pub mod repl;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod router;
+#[cfg(feature = "std")]
+pub mod script;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod worker;
+
+#[cfg(feature = "std")]
+pub use canvas::Canvas;
+pub use message::Message;
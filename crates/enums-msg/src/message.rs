@@ -0,0 +1,962 @@
+//! # The `Message` enum
+//!
+//! `Message` and everything that works on its variants directly: describing,
+//! classifying, printing and parsing. The other modules in this crate build
+//! on these to encode, apply and schedule messages.
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "strum")]
+use strum_macros::EnumIter;
+
+use crate::color::{Color, ColorError};
+
+/// # Enum with wide variety of types embedded in its variants.
+///
+/// `Message` enum whose variants each store different amounts and types of
+/// values
+/// This enum has six variants with different types:
+///
+/// - `Quit` has a named `code` field for the process exit status.
+/// - `Move` has named fields, like a struct does.
+/// - `Write` includes a single `String`.
+/// - `ChangeColor` includes a `Color`, which holds three validated `u8` values.
+/// - `Resize` has named `u32` fields for the new board size.
+/// - `Draw` includes a `Vec` of `(x, y)` points.
+///
+/// If we used the different structs, each of which has its own type, we
+/// couldn’t as easily define a function to take any of these kinds of messages
+/// as we could with the `Message` enum defined below, which is a single type.
+///
+/// ```
+/// use enums_msg::Message;
+///
+/// let msg = Message::Move { x: 3, y: 4 };
+/// assert_eq!(msg.describe(), "Move: by x = 3, y = 4");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Message {
+    /// Stop, exiting with `code`
+    Quit {
+        /// The process exit status
+        code: i32,
+    },
+    /// Move the cursor by `x`, `y`
+    Move {
+        /// The columns to move by
+        x: i32,
+        /// The rows to move by
+        y: i32,
+    },
+    /// Log some text
+    Write(String),
+    /// Draw in a new color from now on
+    ChangeColor(Color),
+    /// Resize the board to `width` by `height`
+    Resize {
+        /// The new width
+        width: u32,
+        /// The new height
+        height: u32,
+    },
+    /// Plot some `(x, y)` points
+    Draw(Vec<(i32, i32)>),
+}
+
+/// # Enum with methods example
+///
+/// An `impl` for `call` method on `Message` enum
+impl Message {
+    /// Print the `describe()` text for this `Message`
+    #[cfg(feature = "std")]
+    pub fn call(&self) {
+        println!("{}", self.describe());
+    }
+
+    /// Describe what handling this `Message` does, one `match` arm per variant
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Write(string) => format!("Message: {string}"),
+            Message::Quit { code: 0 } => String::from("Quit: shutting down"),
+            Message::Quit { code } => format!("Quit: shutting down with code {code}"),
+            Message::Move { x, y } => format!("Move: by x = {x}, y = {y}"),
+            Message::ChangeColor(color) => format!("ChangeColor: {color}"),
+            Message::Resize { width, height } => format!("Resize: to {width}x{height}"),
+            Message::Draw(points) => format!("Draw: {} point(s)", points.len()),
+        }
+    }
+}
+
+/// # A fieldless companion to `Message`
+///
+/// `MessageKind` names a `Message` variant without its payload, so code can
+/// group, count, or route messages without matching on their data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "strum", derive(EnumIter))]
+pub enum MessageKind {
+    /// A `Message::Quit`
+    Quit,
+    /// A `Message::Move`
+    Move,
+    /// A `Message::Write`
+    Write,
+    /// A `Message::ChangeColor`
+    ChangeColor,
+    /// A `Message::Resize`
+    Resize,
+    /// A `Message::Draw`
+    Draw,
+}
+
+impl MessageKind {
+    /// Every kind, in the order `Message` declares its variants, as
+    /// `MessageKind::iter()` gives them with the `strum` feature on
+    pub const VARIANTS: &'static [MessageKind] = &[
+        MessageKind::Quit,
+        MessageKind::Move,
+        MessageKind::Write,
+        MessageKind::ChangeColor,
+        MessageKind::Resize,
+        MessageKind::Draw,
+    ];
+}
+
+impl Message {
+    /// The `MessageKind` of this `Message`
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Message::Quit { .. } => MessageKind::Quit,
+            Message::Move { .. } => MessageKind::Move,
+            Message::Write(_) => MessageKind::Write,
+            Message::ChangeColor(_) => MessageKind::ChangeColor,
+            Message::Resize { .. } => MessageKind::Resize,
+            Message::Draw(_) => MessageKind::Draw,
+        }
+    }
+}
+
+/// # Predicates and accessors with `matches!`
+///
+/// The `matches!` macro turns a pattern into a `bool`, which is handy when
+/// all we want to know is which variant we have. The `as_*` accessors borrow
+/// the payload out of the matching variant, and return `None` for the rest.
+impl Message {
+    /// `true` for `Message::Quit`
+    pub fn is_quit(&self) -> bool {
+        matches!(self, Message::Quit { .. })
+    }
+
+    /// `true` for `Message::Move`
+    pub fn is_move(&self) -> bool {
+        matches!(self, Message::Move { .. })
+    }
+
+    /// `true` for `Message::Write`
+    pub fn is_write(&self) -> bool {
+        matches!(self, Message::Write(_))
+    }
+
+    /// `true` for `Message::ChangeColor`
+    pub fn is_change_color(&self) -> bool {
+        matches!(self, Message::ChangeColor(_))
+    }
+
+    /// `true` for `Message::Resize`
+    pub fn is_resize(&self) -> bool {
+        matches!(self, Message::Resize { .. })
+    }
+
+    /// `true` for `Message::Draw`
+    pub fn is_draw(&self) -> bool {
+        matches!(self, Message::Draw(_))
+    }
+
+    /// The text of a `Write`, borrowed rather than cloned
+    pub fn as_write(&self) -> Option<&str> {
+        match self {
+            Message::Write(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The `(x, y)` deltas of a `Move`
+    pub fn as_move(&self) -> Option<(i32, i32)> {
+        match self {
+            Message::Move { x, y } => Some((*x, *y)),
+            _ => None,
+        }
+    }
+
+    /// The `Color` of a `ChangeColor`
+    pub fn as_color(&self) -> Option<Color> {
+        match self {
+            Message::ChangeColor(color) => Some(*color),
+            _ => None,
+        }
+    }
+
+    /// The `(width, height)` of a `Resize`
+    pub fn as_resize(&self) -> Option<(u32, u32)> {
+        match self {
+            Message::Resize { width, height } => Some((*width, *height)),
+            _ => None,
+        }
+    }
+
+    /// The points of a `Draw`, borrowed rather than cloned
+    pub fn as_draw(&self) -> Option<&[(i32, i32)]> {
+        match self {
+            Message::Draw(points) => Some(points),
+            _ => None,
+        }
+    }
+}
+
+/// Count how many of `msgs` there are of each `MessageKind`
+///
+/// Kinds that don't appear in `msgs` are left out of the map, which
+/// iterates in the order `MessageKind`'s variants are declared.
+pub fn count_by_kind(msgs: &[Message]) -> BTreeMap<MessageKind, usize> {
+    let mut counts = BTreeMap::new();
+    for msg in msgs {
+        *counts.entry(msg.kind()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Remove adjacent duplicates from `msgs`, keeping the first of each run
+///
+/// Handy for noisy input that repeats the same `ChangeColor` over and over.
+/// Equal messages that aren't next to each other are all kept.
+pub fn dedup_consecutive(mut msgs: Vec<Message>) -> Vec<Message> {
+    msgs.dedup();
+    msgs
+}
+
+/// Merge each run of consecutive `Move`s into a single `Move` by the same
+/// total distance
+///
+/// A run whose deltas sum to zero is dropped altogether. Everything other
+/// than a `Move` is kept in place, and breaks up any run it falls in. If a
+/// run's total would overflow `i32`, the `Move` so far is kept and a new
+/// one started from there.
+pub fn coalesce_moves(msgs: Vec<Message>) -> Vec<Message> {
+    let mut out = Vec::with_capacity(msgs.len());
+    let mut run: Option<(i32, i32)> = None;
+    for msg in msgs {
+        match (msg, run) {
+            (Message::Move { x, y }, None) => run = Some((x, y)),
+            (Message::Move { x, y }, Some((sum_x, sum_y))) => {
+                run = match (sum_x.checked_add(x), sum_y.checked_add(y)) {
+                    (Some(sum_x), Some(sum_y)) => Some((sum_x, sum_y)),
+                    _ => {
+                        push_move(&mut out, sum_x, sum_y);
+                        Some((x, y))
+                    }
+                }
+            }
+            (other, _) => {
+                if let Some((sum_x, sum_y)) = run.take() {
+                    push_move(&mut out, sum_x, sum_y);
+                }
+                out.push(other);
+            }
+        }
+    }
+    if let Some((sum_x, sum_y)) = run {
+        push_move(&mut out, sum_x, sum_y);
+    }
+    out
+}
+
+/// Push a coalesced `Move`, unless it goes nowhere
+fn push_move(out: &mut Vec<Message>, x: i32, y: i32) {
+    if (x, y) != (0, 0) {
+        out.push(Message::Move { x, y });
+    }
+}
+
+/// # `Display` for `Message`
+///
+/// A compact, human-readable form of each variant, one line per `Message`:
+///
+/// - `quit`, or `quit 2` when the code isn't 0
+/// - `move 3 7`
+/// - `write "hello"`, with `"`, `\\` and control characters such as newlines
+///   escaped so the output never spans more than one line
+/// - `color 255 0 0`
+/// - `resize 80 24`
+/// - `draw 1,2 3,4`, or just `draw` with no points
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Quit { code: 0 } => write!(f, "quit"),
+            Message::Quit { code } => write!(f, "quit {code}"),
+            Message::Move { x, y } => write!(f, "move {x} {y}"),
+            Message::Write(text) => {
+                write!(f, "write \"")?;
+                for c in text.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+                        c => write!(f, "{c}")?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Message::ChangeColor(color) => {
+                write!(f, "color {} {} {}", color.r(), color.g(), color.b())
+            }
+            Message::Resize { width, height } => write!(f, "resize {width} {height}"),
+            Message::Draw(points) => {
+                write!(f, "draw")?;
+                for (x, y) in points {
+                    write!(f, " {x},{y}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// # Parsing `Message`s from command text
+///
+/// The inverse of `Display`: accepts one command per line in the form
+///
+/// - `quit`
+/// - `move <x> <y>`
+/// - `write <rest of line>`
+/// - `color <r> <g> <b>`
+/// - `resize <width> <height>`
+/// - `draw <x>,<y> <x>,<y> ...`
+///
+/// The keyword is case-insensitive and arguments are separated by whitespace.
+/// A `write` payload is taken verbatim, unless it is a double-quoted string
+/// like the one `Display` produces, in which case the quotes are removed and
+/// its escapes are decoded.
+///
+/// `color` components must each fit in `0..=255`, so `color 256 0 0` is
+/// rejected with the `ColorError` explaining why. `resize` sizes must not be
+/// negative, and each `draw` point is two `i32`s joined by a comma.
+impl FromStr for Message {
+    type Err = MessageParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = s.trim();
+        let (command, rest) = match line.split_once(char::is_whitespace) {
+            Some((command, rest)) => (command, rest.trim_start()),
+            None => (line, ""),
+        };
+
+        match command.to_ascii_lowercase().as_str() {
+            "" => Err(MessageParseError::Empty),
+            "quit" => {
+                let code = match rest {
+                    "" => 0,
+                    _ => {
+                        let [code] = parse_args("quit", rest)?;
+                        code
+                    }
+                };
+                Ok(Message::Quit { code })
+            }
+            "move" => {
+                let [x, y] = parse_args("move", rest)?;
+                Ok(Message::Move { x, y })
+            }
+            "write" => Ok(Message::Write(
+                unquote(rest).unwrap_or_else(|| rest.to_string()),
+            )),
+            "color" => {
+                let [r, g, b] = parse_args("color", rest)?;
+                let color = Color::new(r, g, b).map_err(MessageParseError::InvalidColor)?;
+                Ok(Message::ChangeColor(color))
+            }
+            "resize" => {
+                let [width, height] = parse_args("resize", rest)?;
+                Ok(Message::Resize { width, height })
+            }
+            "draw" => rest
+                .split_whitespace()
+                .map(parse_point)
+                .collect::<Result<_, _>>()
+                .map(Message::Draw),
+            _ => Err(MessageParseError::UnknownCommand(command.to_string())),
+        }
+    }
+}
+
+/// Parse exactly `N` whitespace-separated integer arguments for `command`
+fn parse_args<T: FromStr + Copy + Default, const N: usize>(
+    command: &'static str,
+    rest: &str,
+) -> Result<[T; N], MessageParseError> {
+    let tokens: Vec<&str> = rest.split_whitespace().collect();
+    if tokens.len() != N {
+        return Err(MessageParseError::WrongArgCount {
+            command,
+            expected: N,
+            found: tokens.len(),
+        });
+    }
+    let mut args = [T::default(); N];
+    for (arg, token) in args.iter_mut().zip(tokens) {
+        *arg = token
+            .parse()
+            .map_err(|_| MessageParseError::NotANumber(token.to_string()))?;
+    }
+    Ok(args)
+}
+
+/// Parse one `<x>,<y>` point of a `draw` command
+fn parse_point(token: &str) -> Result<(i32, i32), MessageParseError> {
+    let invalid = || MessageParseError::InvalidPoint(token.to_string());
+    let (x, y) = token.split_once(',').ok_or_else(invalid)?;
+    Ok((
+        x.parse().map_err(|_| invalid())?,
+        y.parse().map_err(|_| invalid())?,
+    ))
+}
+
+/// Decode a double-quoted string written by `Message`'s `Display`
+///
+/// Returns `None` if `quoted` is not a well-formed quoted string, so the
+/// caller can fall back to the raw text.
+fn unquote(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return None,
+            '\\' => match chars.next()? {
+                '"' => text.push('"'),
+                '\\' => text.push('\\'),
+                'n' => text.push('\n'),
+                'r' => text.push('\r'),
+                't' => text.push('\t'),
+                'u' => {
+                    let hex = chars.as_str().strip_prefix('{')?;
+                    let (digits, after) = hex.split_once('}')?;
+                    text.push(char::from_u32(u32::from_str_radix(digits, 16).ok()?)?);
+                    chars = after.chars();
+                }
+                _ => return None,
+            },
+            c => text.push(c),
+        }
+    }
+    Some(text)
+}
+
+/// # Errors from parsing a `Message` out of command text
+#[derive(Debug, PartialEq, Eq)]
+pub enum MessageParseError {
+    /// The line was empty or only whitespace
+    Empty,
+    /// The first word was not one of `quit`, `move`, `write`, `color`,
+    /// `resize` or `draw`
+    UnknownCommand(String),
+    /// The command was given the wrong number of arguments
+    WrongArgCount {
+        /// The command's name
+        command: &'static str,
+        /// How many arguments it takes
+        expected: usize,
+        /// How many it was given
+        found: usize,
+    },
+    /// An argument that must be an integer was not one, or was out of range
+    NotANumber(String),
+    /// A `draw` point was not two `i32`s joined by a comma
+    InvalidPoint(String),
+    /// The `color` components do not make a valid `Color`
+    InvalidColor(ColorError),
+}
+
+impl fmt::Display for MessageParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessageParseError::Empty => write!(f, "empty command"),
+            MessageParseError::UnknownCommand(command) => {
+                write!(f, "unknown command `{command}`")
+            }
+            MessageParseError::WrongArgCount {
+                command,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{command}` takes {expected} argument(s) but {found} were given"
+            ),
+            MessageParseError::NotANumber(token) => write!(f, "`{token}` is not a number"),
+            MessageParseError::InvalidColor(e) => write!(f, "invalid color: {e}"),
+            MessageParseError::InvalidPoint(token) => {
+                write!(f, "`{token}` is not an `x,y` point")
+            }
+        }
+    }
+}
+
+impl core::error::Error for MessageParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            MessageParseError::InvalidColor(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn describe_write() {
+        let m = Message::Write(String::from("hello"));
+        assert_eq!(m.describe(), "Message: hello");
+    }
+
+    #[test]
+    fn describe_quit() {
+        assert_eq!(Message::Quit { code: 0 }.describe(), "Quit: shutting down");
+        assert_eq!(
+            Message::Quit { code: 1 }.describe(),
+            "Quit: shutting down with code 1"
+        );
+    }
+
+    #[test]
+    fn describe_move() {
+        let m = Message::Move { x: 3, y: -7 };
+        assert_eq!(m.describe(), "Move: by x = 3, y = -7");
+    }
+
+    #[test]
+    fn describe_change_color() {
+        let m = Message::ChangeColor(Color::rgb(255, 136, 0));
+        assert_eq!(m.describe(), "ChangeColor: #ff8800");
+    }
+
+    /// One `Message` of every kind
+    fn one_of_each() -> Vec<Message> {
+        vec![
+            Message::Quit { code: 0 },
+            Message::Move { x: 1, y: 2 },
+            Message::Write(String::from("hello")),
+            Message::ChangeColor(Color::RED),
+            Message::Resize {
+                width: 80,
+                height: 24,
+            },
+            Message::Draw(vec![(1, 2)]),
+        ]
+    }
+
+    #[test]
+    fn every_kind_is_constructible() {
+        let kinds: Vec<MessageKind> = one_of_each().iter().map(Message::kind).collect();
+        for kind in MessageKind::iter() {
+            assert!(kinds.contains(&kind), "no Message of kind {:?}", kind);
+        }
+    }
+
+    #[test]
+    fn predicates_match_only_their_variant() {
+        let predicates: [fn(&Message) -> bool; 6] = [
+            Message::is_quit,
+            Message::is_move,
+            Message::is_write,
+            Message::is_change_color,
+            Message::is_resize,
+            Message::is_draw,
+        ];
+        for (i, m) in one_of_each().iter().enumerate() {
+            for (j, predicate) in predicates.iter().enumerate() {
+                assert_eq!(predicate(m), i == j, "{:?} predicate {j}", m);
+            }
+        }
+    }
+
+    #[test]
+    fn as_write_borrows_the_payload() {
+        let m = Message::Write(String::from("hello"));
+        let text = m.as_write().unwrap();
+        assert_eq!(text, "hello");
+        match &m {
+            Message::Write(owned) => assert!(std::ptr::eq(text, owned.as_str())),
+            _ => unreachable!(),
+        }
+        assert_eq!(Message::Quit { code: 0 }.as_write(), None);
+    }
+
+    #[test]
+    fn as_move_and_as_color() {
+        assert_eq!(Message::Move { x: -1, y: 4 }.as_move(), Some((-1, 4)));
+        assert_eq!(Message::Write(String::new()).as_move(), None);
+        assert_eq!(
+            Message::ChangeColor(Color::GREEN).as_color(),
+            Some(Color::GREEN)
+        );
+        assert_eq!(Message::Move { x: 0, y: 0 }.as_color(), None);
+    }
+
+    #[test]
+    fn as_resize_and_as_draw() {
+        let resize = Message::Resize {
+            width: 8,
+            height: 6,
+        };
+        assert_eq!(resize.as_resize(), Some((8, 6)));
+        assert_eq!(resize.as_draw(), None);
+        let draw = Message::Draw(vec![(1, 2), (3, 4)]);
+        assert_eq!(draw.as_draw(), Some(&[(1, 2), (3, 4)][..]));
+        assert_eq!(draw.as_resize(), None);
+    }
+
+    #[test]
+    fn count_by_kind_on_mixed_slice() {
+        let mut msgs = one_of_each();
+        msgs.push(Message::Move { x: -1, y: 0 });
+        msgs.push(Message::Move { x: 0, y: 0 });
+        msgs.push(Message::Write(String::new()));
+        let counts = count_by_kind(&msgs);
+        assert_eq!(counts[&MessageKind::Quit], 1);
+        assert_eq!(counts[&MessageKind::Move], 3);
+        assert_eq!(counts[&MessageKind::Write], 2);
+        assert_eq!(counts[&MessageKind::ChangeColor], 1);
+        assert_eq!(counts.values().sum::<usize>(), msgs.len());
+    }
+
+    #[test]
+    fn count_by_kind_skips_absent_kinds() {
+        let counts = count_by_kind(&[Message::Quit { code: 0 }]);
+        assert_eq!(counts.len(), 1);
+        assert!(!counts.contains_key(&MessageKind::Move));
+    }
+
+    #[test]
+    fn count_by_kind_iterates_in_declaration_order() {
+        let mut msgs = one_of_each();
+        msgs.reverse();
+        let kinds: Vec<MessageKind> = count_by_kind(&msgs).into_keys().collect();
+        assert_eq!(kinds, MessageKind::iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn describe_resize_and_draw() {
+        let resize = Message::Resize {
+            width: 80,
+            height: 24,
+        };
+        assert_eq!(resize.describe(), "Resize: to 80x24");
+        let draw = Message::Draw(vec![(1, 2), (3, 4)]);
+        assert_eq!(draw.describe(), "Draw: 2 point(s)");
+    }
+
+    #[test]
+    fn dedup_collapses_a_run_of_identical_writes() {
+        let hello = Message::Write(String::from("hello"));
+        let msgs = vec![
+            hello.clone(),
+            hello.clone(),
+            hello.clone(),
+            Message::Quit { code: 0 },
+        ];
+        assert_eq!(dedup_consecutive(msgs), [hello, Message::Quit { code: 0 }]);
+    }
+
+    #[test]
+    fn dedup_keeps_non_adjacent_duplicates() {
+        let red = Message::ChangeColor(Color::RED);
+        let msgs = vec![
+            red.clone(),
+            red.clone(),
+            Message::Move { x: 1, y: 0 },
+            red.clone(),
+            Message::ChangeColor(Color::BLUE),
+            Message::ChangeColor(Color::BLUE),
+        ];
+        assert_eq!(
+            dedup_consecutive(msgs),
+            [
+                red.clone(),
+                Message::Move { x: 1, y: 0 },
+                red,
+                Message::ChangeColor(Color::BLUE),
+            ]
+        );
+        assert!(dedup_consecutive(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn coalesce_sums_a_run_of_moves() {
+        let msgs = vec![
+            Message::Move { x: 1, y: 0 },
+            Message::Move { x: 2, y: -1 },
+            Message::Move { x: -1, y: 5 },
+            Message::Move { x: 0, y: 0 },
+            Message::Move { x: 4, y: 2 },
+        ];
+        assert_eq!(coalesce_moves(msgs), [Message::Move { x: 6, y: 6 }]);
+        assert!(coalesce_moves(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn coalesce_does_not_merge_across_other_messages() {
+        let write = Message::Write(String::from("between"));
+        let msgs = vec![
+            Message::Move { x: 1, y: 1 },
+            Message::Move { x: 1, y: 1 },
+            write.clone(),
+            Message::Move { x: 3, y: 0 },
+            Message::Quit { code: 0 },
+        ];
+        assert_eq!(
+            coalesce_moves(msgs),
+            [
+                Message::Move { x: 2, y: 2 },
+                write,
+                Message::Move { x: 3, y: 0 },
+                Message::Quit { code: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn coalesce_drops_a_net_zero_run() {
+        let msgs = vec![
+            Message::ChangeColor(Color::RED),
+            Message::Move { x: 3, y: -2 },
+            Message::Move { x: -1, y: 1 },
+            Message::Move { x: -2, y: 1 },
+            Message::ChangeColor(Color::BLUE),
+        ];
+        assert_eq!(
+            coalesce_moves(msgs),
+            [
+                Message::ChangeColor(Color::RED),
+                Message::ChangeColor(Color::BLUE),
+            ]
+        );
+    }
+
+    #[test]
+    fn coalesce_splits_a_run_that_would_overflow() {
+        let msgs = vec![
+            Message::Move { x: i32::MAX, y: 0 },
+            Message::Move { x: 1, y: 0 },
+            Message::Move { x: 1, y: 0 },
+        ];
+        assert_eq!(
+            coalesce_moves(msgs),
+            [
+                Message::Move { x: i32::MAX, y: 0 },
+                Message::Move { x: 2, y: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn messages_can_be_looked_up_in_a_hash_set() {
+        let seen: HashSet<Message> = one_of_each().into_iter().collect();
+        assert_eq!(seen.len(), one_of_each().len());
+        assert!(seen.contains(&Message::Write(String::from("hello"))));
+        assert!(!seen.contains(&Message::Write(String::from("goodbye"))));
+        assert!(!seen.contains(&Message::Move { x: 2, y: 1 }));
+    }
+
+    #[test]
+    fn display_quit() {
+        assert_eq!(Message::Quit { code: 0 }.to_string(), "quit");
+        assert_eq!(Message::Quit { code: 2 }.to_string(), "quit 2");
+    }
+
+    #[test]
+    fn display_move() {
+        assert_eq!(Message::Move { x: 3, y: 7 }.to_string(), "move 3 7");
+        assert_eq!(Message::Move { x: -3, y: 0 }.to_string(), "move -3 0");
+    }
+
+    #[test]
+    fn display_write() {
+        let m = Message::Write(String::from("hello"));
+        assert_eq!(m.to_string(), r#"write "hello""#);
+    }
+
+    #[test]
+    fn display_write_escapes_quotes_and_newlines() {
+        let m = Message::Write(String::from("say \"hi\"\nback\\slash"));
+        assert_eq!(m.to_string(), r#"write "say \"hi\"\nback\\slash""#);
+        assert_eq!(m.to_string().lines().count(), 1);
+    }
+
+    #[test]
+    fn display_change_color() {
+        assert_eq!(
+            Message::ChangeColor(Color::RED).to_string(),
+            "color 255 0 0"
+        );
+    }
+
+    #[test]
+    fn display_resize_and_draw() {
+        let resize = Message::Resize {
+            width: 80,
+            height: 24,
+        };
+        assert_eq!(resize.to_string(), "resize 80 24");
+        let draw = Message::Draw(vec![(1, 2), (-3, 4)]);
+        assert_eq!(draw.to_string(), "draw 1,2 -3,4");
+        assert_eq!(Message::Draw(Vec::new()).to_string(), "draw");
+    }
+
+    #[test]
+    fn parse_quit() {
+        assert!(matches!("quit".parse(), Ok(Message::Quit { code: 0 })));
+        assert!(matches!("  QuIt  ".parse(), Ok(Message::Quit { code: 0 })));
+        assert!(matches!("quit -2".parse(), Ok(Message::Quit { code: -2 })));
+        assert_eq!(
+            "quit now".parse::<Message>().unwrap_err(),
+            MessageParseError::NotANumber(String::from("now"))
+        );
+    }
+
+    #[test]
+    fn parse_move_negative_coordinates() {
+        assert!(matches!(
+            "move -3   -7".parse(),
+            Ok(Message::Move { x: -3, y: -7 })
+        ));
+    }
+
+    #[test]
+    fn parse_write_keeps_spaces() {
+        let m: Message = "write hello  big world".parse().unwrap();
+        assert!(matches!(&m, Message::Write(text) if text == "hello  big world"));
+    }
+
+    #[test]
+    fn parse_write_unquotes_display_form() {
+        let original = Message::Write(String::from("say \"hi\"\n\u{7}"));
+        let parsed: Message = original.to_string().parse().unwrap();
+        assert!(matches!(&parsed, Message::Write(text) if text == "say \"hi\"\n\u{7}"));
+    }
+
+    #[test]
+    fn parse_write_malformed_quotes_taken_verbatim() {
+        let m: Message = r#"write "unterminated"#.parse().unwrap();
+        assert!(matches!(&m, Message::Write(text) if text == "\"unterminated"));
+    }
+
+    #[test]
+    fn parse_color() {
+        let m: Message = "color 0 128 255".parse().unwrap();
+        assert!(matches!(m, Message::ChangeColor(c) if c == Color::rgb(0, 128, 255)));
+    }
+
+    #[test]
+    fn parse_color_rejects_out_of_range() {
+        assert_eq!(
+            "color 256 0 0".parse::<Message>().unwrap_err(),
+            MessageParseError::InvalidColor(ColorError::OutOfRange {
+                component: 'r',
+                value: 256
+            })
+        );
+        assert!("color 0 0 -1".parse::<Message>().is_err());
+    }
+
+    #[test]
+    fn parse_resize() {
+        let m: Message = "resize 80 24".parse().unwrap();
+        assert!(matches!(
+            m,
+            Message::Resize {
+                width: 80,
+                height: 24
+            }
+        ));
+        assert_eq!(
+            "resize -1 24".parse::<Message>().unwrap_err(),
+            MessageParseError::NotANumber(String::from("-1"))
+        );
+    }
+
+    #[test]
+    fn parse_draw() {
+        let m: Message = "draw 1,2  -3,4".parse().unwrap();
+        assert_eq!(m.as_draw(), Some(&[(1, 2), (-3, 4)][..]));
+        let m: Message = "DRAW".parse().unwrap();
+        assert_eq!(m.as_draw(), Some(&[][..]));
+        for bad in ["1", "1,", ",2", "1,2,3", "a,b"] {
+            assert_eq!(
+                format!("draw 0,0 {bad}").parse::<Message>().unwrap_err(),
+                MessageParseError::InvalidPoint(bad.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn parse_display_round_trip_for_every_variant() {
+        for m in one_of_each() {
+            let text = m.to_string();
+            assert_eq!(text.parse::<Message>().unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn parse_empty_line() {
+        assert_eq!("".parse::<Message>().unwrap_err(), MessageParseError::Empty);
+        assert_eq!(
+            "   ".parse::<Message>().unwrap_err(),
+            MessageParseError::Empty
+        );
+    }
+
+    #[test]
+    fn parse_unknown_command() {
+        assert_eq!(
+            "jump 1 2".parse::<Message>().unwrap_err(),
+            MessageParseError::UnknownCommand(String::from("jump"))
+        );
+    }
+
+    #[test]
+    fn parse_wrong_arg_count() {
+        assert_eq!(
+            "move 1".parse::<Message>().unwrap_err(),
+            MessageParseError::WrongArgCount {
+                command: "move",
+                expected: 2,
+                found: 1
+            }
+        );
+        assert_eq!(
+            "quit 1 2".parse::<Message>().unwrap_err(),
+            MessageParseError::WrongArgCount {
+                command: "quit",
+                expected: 1,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn parse_not_a_number() {
+        assert_eq!(
+            "color 1 two 3".parse::<Message>().unwrap_err(),
+            MessageParseError::NotANumber(String::from("two"))
+        );
+    }
+}
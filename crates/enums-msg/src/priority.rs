@@ -0,0 +1,209 @@
+//! # Scheduling `Message`s by priority
+//!
+//! Some messages are more urgent than others: a `Quit` should jump the queue,
+//! while a `Write` can wait. `PriorityQueue` pops the most urgent message
+//! first, and messages of equal priority in the order they were pushed.
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::canvas::{self, Canvas};
+use crate::Message;
+
+/// # How urgently a `Message` should be handled
+///
+/// Variants are declared from least to most urgent, so the derived `Ord`
+/// sorts `Highest` above everything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Whenever there is time
+    Low,
+    /// Ordinary traffic
+    Normal,
+    /// Ahead of ordinary traffic
+    High,
+    /// Before anything else
+    Highest,
+}
+
+impl Message {
+    /// The `Priority` this `Message` is scheduled with
+    pub fn priority(&self) -> Priority {
+        match self {
+            Message::Quit { .. } => Priority::Highest,
+            Message::ChangeColor(_) | Message::Resize { .. } => Priority::High,
+            Message::Move { .. } | Message::Draw(_) => Priority::Normal,
+            Message::Write(_) => Priority::Low,
+        }
+    }
+}
+
+/// A queued message, ordered by priority and then by insertion order
+#[derive(Debug)]
+struct Entry {
+    priority: Priority,
+    seq: u64,
+    message: Message,
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `BinaryHeap` pops the greatest entry, so an earlier `seq` must
+        // compare greater for equal priorities to come out first in, first out
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+/// # A queue that pops the highest-`Priority` `Message` first
+#[derive(Debug, Default)]
+pub struct PriorityQueue {
+    heap: BinaryHeap<Entry>,
+    next_seq: u64,
+}
+
+impl PriorityQueue {
+    /// An empty queue
+    pub fn new() -> Self {
+        PriorityQueue::default()
+    }
+
+    /// Number of queued messages
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// `true` if no messages are queued
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Queue `message` behind everything of the same or higher priority
+    pub fn push(&mut self, message: Message) {
+        self.heap.push(Entry {
+            priority: message.priority(),
+            seq: self.next_seq,
+            message,
+        });
+        self.next_seq += 1;
+    }
+
+    /// Remove the most urgent message, oldest first among equals
+    pub fn pop(&mut self) -> Option<Message> {
+        self.heap.pop().map(|entry| entry.message)
+    }
+
+    /// Empty the queue into `canvas` in priority order
+    ///
+    /// Like `run_queue`, messages stop being applied once the canvas has
+    /// stopped, but the queue is emptied either way. Returns how many
+    /// messages were applied.
+    pub fn drain_to(&mut self, canvas: &mut Canvas) -> usize {
+        let mut applied = 0;
+        while let Some(message) = self.pop() {
+            if canvas.is_running() {
+                canvas::apply(canvas, &message);
+                applied += 1;
+            }
+        }
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    fn pop_all(queue: &mut PriorityQueue) -> Vec<String> {
+        std::iter::from_fn(|| queue.pop())
+            .map(|m| m.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn priorities_are_ordered() {
+        assert!(Priority::Highest > Priority::High);
+        assert!(Priority::High > Priority::Normal);
+        assert!(Priority::Normal > Priority::Low);
+    }
+
+    #[test]
+    fn pop_order_for_mixed_batch() {
+        let mut queue = PriorityQueue::new();
+        queue.push(Message::Write(String::from("first write")));
+        queue.push(Message::Move { x: 1, y: 0 });
+        queue.push(Message::ChangeColor(Color::RED));
+        queue.push(Message::Write(String::from("second write")));
+        queue.push(Message::Quit { code: 0 });
+        queue.push(Message::ChangeColor(Color::BLUE));
+        queue.push(Message::Draw(vec![(0, 0)]));
+        queue.push(Message::Resize {
+            width: 8,
+            height: 8,
+        });
+        assert_eq!(queue.len(), 8);
+        assert_eq!(
+            pop_all(&mut queue),
+            [
+                "quit",
+                "color 255 0 0",
+                "color 0 0 255",
+                "resize 8 8",
+                "move 1 0",
+                "draw 0,0",
+                r#"write "first write""#,
+                r#"write "second write""#,
+            ]
+        );
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn equal_priorities_are_fifo() {
+        let mut queue = PriorityQueue::new();
+        queue.push(Message::Move { x: 1, y: 0 });
+        queue.push(Message::Move { x: 2, y: 0 });
+        queue.push(Message::Move { x: 3, y: 0 });
+        assert_eq!(pop_all(&mut queue), ["move 1 0", "move 2 0", "move 3 0"]);
+    }
+
+    #[test]
+    fn drain_to_applies_quit_first() {
+        let mut queue = PriorityQueue::new();
+        queue.push(Message::Move { x: 1, y: 1 });
+        queue.push(Message::Quit { code: 0 });
+        let mut canvas = Canvas::new();
+        assert_eq!(queue.drain_to(&mut canvas), 1);
+        assert!(queue.is_empty());
+        assert_eq!(canvas.cursor(), (0, 0));
+        assert!(!canvas.is_running());
+    }
+
+    #[test]
+    fn drain_to_applies_everything_without_quit() {
+        let mut queue = PriorityQueue::new();
+        queue.push(Message::Write(String::from("a")));
+        queue.push(Message::Move { x: 1, y: 1 });
+        queue.push(Message::ChangeColor(Color::GREEN));
+        let mut canvas = Canvas::new();
+        assert_eq!(queue.drain_to(&mut canvas), 3);
+        assert_eq!(canvas.cursor(), (1, 1));
+        assert_eq!(canvas.color(), Color::GREEN);
+        assert_eq!(canvas.log(), ["a"]);
+    }
+}
@@ -0,0 +1,154 @@
+//! # An interactive loop over `Message` commands
+//!
+//! Each input line is parsed with `Message`'s `FromStr` and applied to a
+//! `Canvas`, after which the canvas is echoed so the effect can be seen.
+//! Lines that fail to parse, and messages the canvas refuses, are reported
+//! on a separate error stream without ending the session.
+//!
+//! The loop only needs `BufRead` and `Write`, so it runs just as well over
+//! in-memory buffers as over stdin and stdout.
+use std::io::{self, BufRead, Write};
+
+use crate::canvas::{self, ApplyError, ApplyOutcome, Canvas};
+use crate::Message;
+
+/// Run commands from `input` against a fresh `Canvas` until `quit` or EOF
+///
+/// After every applied command the canvas summary is written to `output`,
+/// followed by `Canvas::render_with_legend` when `render` is set.
+/// Parse errors and refused messages go to `errors`, prefixed with the line
+/// number they came from. Blank lines are skipped. Returns the final canvas.
+pub fn run_repl(
+    input: impl BufRead,
+    mut output: impl Write,
+    mut errors: impl Write,
+    render: bool,
+) -> io::Result<Canvas> {
+    let mut canvas = Canvas::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let msg = match line.parse::<Message>() {
+            Ok(msg) => msg,
+            Err(e) => {
+                writeln!(errors, "line {number}: {e}")?;
+                continue;
+            }
+        };
+        let refused = match canvas::apply(&mut canvas, &msg) {
+            ApplyOutcome::Applied | ApplyOutcome::Clamped { .. } => None,
+            ApplyOutcome::Rejected { target } => Some(ApplyError::RejectedMove { target }),
+            ApplyOutcome::InvalidSize { width, height } => {
+                Some(ApplyError::InvalidSize { width, height })
+            }
+        };
+        if let Some(e) = refused {
+            writeln!(errors, "line {number}: {e}")?;
+        }
+        writeln!(output, "{canvas}")?;
+        if render {
+            write!(output, "{}", canvas.render_with_legend())?;
+        }
+        if !canvas.is_running() {
+            break;
+        }
+    }
+    output.flush()?;
+    Ok(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    /// Run `session` through the REPL, returning the canvas, output and errors
+    fn session(input: &str) -> (Canvas, String, String) {
+        session_with(input, false)
+    }
+
+    fn session_with(input: &str, render: bool) -> (Canvas, String, String) {
+        let mut output = Vec::new();
+        let mut errors = Vec::new();
+        let canvas = run_repl(input.as_bytes(), &mut output, &mut errors, render).unwrap();
+        (
+            canvas,
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(errors).unwrap(),
+        )
+    }
+
+    #[test]
+    fn scripted_session_with_a_bad_line() {
+        let (canvas, output, errors) = session(
+            "move 3 4\n\
+             jump 1 2\n\
+             \n\
+             write hello\n\
+             color 255 0 0\n\
+             quit\n\
+             write never applied\n",
+        );
+        assert_eq!(canvas.cursor(), (3, 4));
+        assert_eq!(canvas.log(), ["hello"]);
+        assert_eq!(canvas.color(), Color::RED);
+        assert!(!canvas.is_running());
+        assert_eq!(errors, "line 2: unknown command `jump`\n");
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            [
+                "cursor (3, 4) on 80x24, color #000000, 0 line(s), 0 point(s), running",
+                "cursor (3, 4) on 80x24, color #000000, 1 line(s), 0 point(s), running",
+                "cursor (3, 4) on 80x24, color #ff0000, 1 line(s), 0 point(s), running",
+                "cursor (3, 4) on 80x24, color #ff0000, 1 line(s), 0 point(s), stopped",
+            ]
+        );
+    }
+
+    #[test]
+    fn eof_ends_the_session_while_running() {
+        let (canvas, output, errors) = session("draw 1,1 2,2\nmove 1 1");
+        assert!(canvas.is_running());
+        assert_eq!(canvas.cursor(), (1, 1));
+        assert_eq!(canvas.points().len(), 2);
+        assert_eq!(output.lines().count(), 2);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn refused_messages_are_reported_but_echoed() {
+        let (canvas, output, errors) = session("resize 0 5\ncolor 1 2 300\n");
+        assert_eq!(canvas, Canvas::new());
+        assert_eq!(output.lines().count(), 1);
+        assert_eq!(
+            errors,
+            "line 1: 0x5 is not a valid board size\n\
+             line 2: invalid color: color component `b` = 300 is outside 0..=255\n"
+        );
+    }
+
+    #[test]
+    fn render_flag_draws_the_board_after_each_command() {
+        let (_, output, _) = session_with("resize 3 2\ndraw 2,1\nquit\n", true);
+        assert_eq!(
+            output,
+            "\
+cursor (0, 0) on 3x2, color #000000, 0 line(s), 0 point(s), running
+@..
+...
+color #000000
+cursor (0, 0) on 3x2, color #000000, 0 line(s), 1 point(s), running
+@..
+..*
+color #000000
+cursor (0, 0) on 3x2, color #000000, 0 line(s), 1 point(s), stopped
+@..
+..*
+color #000000
+"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! # Recording and replaying timed sessions
+//!
+//! A `RecordedMessage` is a `Message` stamped with when it happened, in
+//! milliseconds since the session began. `replay` applies a recording to a
+//! `Canvas` with the same pauses between messages, sped up or slowed down.
+//!
+//! `replay` doesn't sleep by itself: it hands every pause to a `clock`
+//! closure. A real caller passes `std::thread::sleep`, while a test can pass
+//! a closure that just writes the pauses down.
+use std::time::{Duration, Instant};
+
+use crate::canvas::{self, Canvas};
+use crate::Message;
+
+/// # A `Message` and when it was sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedMessage {
+    /// Milliseconds since the start of the recording
+    pub at_ms: u64,
+    /// The message that was sent
+    pub msg: Message,
+}
+
+/// Append `msg` to `recording`, stamped with the time elapsed since `origin`
+pub fn record(recording: &mut Vec<RecordedMessage>, origin: Instant, msg: Message) {
+    let at_ms = u64::try_from(origin.elapsed().as_millis()).unwrap_or(u64::MAX);
+    recording.push(RecordedMessage { at_ms, msg });
+}
+
+/// Apply `recording` to `canvas` in timestamp order, pausing between messages
+///
+/// Messages are sorted by `at_ms`, keeping the recorded order for equal
+/// stamps, and the recording is taken to start at `0`. Before each message,
+/// `clock` is called with the time since the previous one divided by
+/// `speed`, so `2.0` replays twice as fast. A `speed` of zero (or one that
+/// is negative or not a number) replays as fast as possible, never calling
+/// `clock`; gaps of zero are skipped either way.
+///
+/// Like `run_queue`, replay stops once a `Quit` has stopped the canvas.
+/// Returns how many messages were applied.
+pub fn replay(
+    recording: &[RecordedMessage],
+    canvas: &mut Canvas,
+    speed: f64,
+    clock: &mut impl FnMut(Duration),
+) -> usize {
+    let mut ordered: Vec<&RecordedMessage> = recording.iter().collect();
+    ordered.sort_by_key(|recorded| recorded.at_ms);
+
+    let mut applied = 0;
+    let mut previous_ms = 0;
+    for recorded in ordered {
+        if !canvas.is_running() {
+            break;
+        }
+        let gap_ms = recorded.at_ms - previous_ms;
+        previous_ms = recorded.at_ms;
+        if speed > 0.0 && gap_ms > 0 {
+            clock(Duration::from_secs_f64(gap_ms as f64 / 1000.0 / speed));
+        }
+        canvas::apply(canvas, &recorded.msg);
+        applied += 1;
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(at_ms: u64, msg: Message) -> RecordedMessage {
+        RecordedMessage { at_ms, msg }
+    }
+
+    /// Recorded out of order: sorted, it writes "a" at 100, "b" at 250,
+    /// "c" at 250 and "d" at 1000
+    fn unsorted() -> Vec<RecordedMessage> {
+        vec![
+            at(250, Message::Write(String::from("b"))),
+            at(1000, Message::Write(String::from("d"))),
+            at(100, Message::Write(String::from("a"))),
+            at(250, Message::Write(String::from("c"))),
+        ]
+    }
+
+    fn replay_collecting(recording: &[RecordedMessage], speed: f64) -> (Canvas, Vec<Duration>) {
+        let mut canvas = Canvas::new();
+        let mut pauses = Vec::new();
+        replay(recording, &mut canvas, speed, &mut |pause| {
+            pauses.push(pause)
+        });
+        (canvas, pauses)
+    }
+
+    #[test]
+    fn applies_in_timestamp_order() {
+        let (canvas, _) = replay_collecting(&unsorted(), 1.0);
+        assert_eq!(canvas.log(), ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn pauses_are_the_gaps_scaled_by_speed() {
+        let (_, pauses) = replay_collecting(&unsorted(), 1.0);
+        assert_eq!(
+            pauses,
+            [
+                Duration::from_millis(100),
+                Duration::from_millis(150),
+                Duration::from_millis(750),
+            ]
+        );
+        let (_, pauses) = replay_collecting(&unsorted(), 2.0);
+        assert_eq!(
+            pauses,
+            [
+                Duration::from_millis(50),
+                Duration::from_millis(75),
+                Duration::from_millis(375),
+            ]
+        );
+    }
+
+    #[test]
+    fn speed_zero_is_as_fast_as_possible() {
+        for speed in [0.0, -1.0, f64::NAN] {
+            let (canvas, pauses) = replay_collecting(&unsorted(), speed);
+            assert!(pauses.is_empty());
+            assert_eq!(canvas.log().len(), 4);
+        }
+    }
+
+    #[test]
+    fn stops_at_quit() {
+        let mut recording = unsorted();
+        recording.push(at(500, Message::Quit { code: 0 }));
+        let mut canvas = Canvas::new();
+        let applied = replay(&recording, &mut canvas, 0.0, &mut |_| {});
+        assert_eq!(applied, 4);
+        assert_eq!(canvas.log(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn record_stamps_relative_to_the_origin() {
+        let origin = Instant::now() - Duration::from_millis(40);
+        let mut recording = Vec::new();
+        record(&mut recording, origin, Message::Quit { code: 0 });
+        assert_eq!(recording.len(), 1);
+        assert!(recording[0].at_ms >= 40);
+        assert_eq!(recording[0].msg, Message::Quit { code: 0 });
+    }
+}
@@ -0,0 +1,159 @@
+//! # Routing `Message`s to handlers registered by kind
+//!
+//! `MessageHandler` suits a handler whose variants are known when it is
+//! written. A `Router` is for handlers that come and go at runtime, plugins
+//! or test probes say: each is a closure registered for one `MessageKind`,
+//! with an optional fallback for kinds nobody registered.
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::message::MessageKind;
+use crate::Message;
+
+/// A handler closure a `Router` can call
+type Handler = Box<dyn FnMut(&Message) + Send>;
+
+/// # What `Router::route` did with a `Message`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteOutcome {
+    /// The handler for the message's kind, or the fallback, was called
+    Handled,
+    /// No handler was registered for the kind and there is no fallback
+    NoHandler,
+}
+
+/// # Handlers for `Message`s, looked up by `MessageKind`
+#[derive(Default)]
+pub struct Router {
+    handlers: HashMap<MessageKind, Handler>,
+    fallback: Option<Handler>,
+}
+
+impl Router {
+    /// A router with no handlers and no fallback
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    /// Call `handler` for every message of `kind`, replacing any handler
+    /// already registered for it
+    pub fn register(&mut self, kind: MessageKind, handler: impl FnMut(&Message) + Send + 'static) {
+        self.handlers.insert(kind, Box::new(handler));
+    }
+
+    /// Call `handler` for messages whose kind has no handler of its own
+    pub fn set_fallback(&mut self, handler: impl FnMut(&Message) + Send + 'static) {
+        self.fallback = Some(Box::new(handler));
+    }
+
+    /// Pass `msg` to the handler for its kind, or else to the fallback
+    pub fn route(&mut self, msg: &Message) -> RouteOutcome {
+        match self
+            .handlers
+            .get_mut(&msg.kind())
+            .or(self.fallback.as_mut())
+        {
+            Some(handler) => {
+                handler(msg);
+                RouteOutcome::Handled
+            }
+            None => RouteOutcome::NoHandler,
+        }
+    }
+}
+
+/// Handlers are closures, so `Debug` lists only the kinds they handle
+impl fmt::Debug for Router {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Router")
+            .field("kinds", &self.handlers.keys().collect::<Vec<_>>())
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A handler that counts its calls in the returned counter
+    fn counting() -> (Arc<AtomicUsize>, impl FnMut(&Message) + Send + 'static) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let handler = {
+            let count = Arc::clone(&count);
+            move |_: &Message| {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        };
+        (count, handler)
+    }
+
+    fn messages() -> [Message; 4] {
+        [
+            Message::Write(String::from("one")),
+            Message::Move { x: 1, y: 1 },
+            Message::Write(String::from("two")),
+            Message::Quit { code: 0 },
+        ]
+    }
+
+    #[test]
+    fn routes_by_kind() {
+        let (writes, on_write) = counting();
+        let (moves, on_move) = counting();
+        let mut router = Router::new();
+        router.register(MessageKind::Write, on_write);
+        router.register(MessageKind::Move, on_move);
+        let outcomes: Vec<_> = messages().iter().map(|m| router.route(m)).collect();
+        assert_eq!(
+            outcomes,
+            [
+                RouteOutcome::Handled,
+                RouteOutcome::Handled,
+                RouteOutcome::Handled,
+                RouteOutcome::NoHandler,
+            ]
+        );
+        assert_eq!(writes.load(Ordering::Relaxed), 2);
+        assert_eq!(moves.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn unregistered_kinds_go_to_the_fallback() {
+        let (writes, on_write) = counting();
+        let (others, fallback) = counting();
+        let mut router = Router::new();
+        router.register(MessageKind::Write, on_write);
+        router.set_fallback(fallback);
+        for m in &messages() {
+            assert_eq!(router.route(m), RouteOutcome::Handled);
+        }
+        assert_eq!(writes.load(Ordering::Relaxed), 2);
+        assert_eq!(others.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn registering_again_replaces_the_handler() {
+        let (old, first) = counting();
+        let (new, second) = counting();
+        let mut router = Router::new();
+        router.register(MessageKind::Write, first);
+        router.register(MessageKind::Write, second);
+        for m in &messages() {
+            router.route(m);
+        }
+        assert_eq!(old.load(Ordering::Relaxed), 0);
+        assert_eq!(new.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn empty_router_handles_nothing() {
+        let mut router = Router::new();
+        assert_eq!(
+            router.route(&Message::Quit { code: 0 }),
+            RouteOutcome::NoHandler
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! # Running a file of `Message` commands
+//!
+//! A script is plain text with one command per line, in the same grammar
+//! that `Message`'s `FromStr` accepts. Blank lines and lines starting with
+//! `#` are skipped:
+//!
+//! ```text
+//! # Draw a little square in red
+//! color 255 0 0
+//! draw 1,1 1,2 2,1 2,2
+//! move 3 0
+//! quit
+//! ```
+//!
+//! Unlike the REPL, a script is not forgiving: the first line that fails to
+//! parse stops it with an error naming that line.
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::canvas::{self, Canvas};
+use crate::message::MessageParseError;
+use crate::Message;
+
+/// # What running a script did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptReport {
+    /// Number of commands applied, including the `quit`
+    pub executed: usize,
+    /// One warning for each command left over after the `quit`
+    pub warnings: Vec<String>,
+}
+
+/// # Why a script stopped early
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The script could not be read
+    Io(io::Error),
+    /// Line number `line`, counting from 1, was not a valid command
+    Parse {
+        /// The line number
+        line: usize,
+        /// What was wrong with it
+        error: MessageParseError,
+    },
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::Io(e) => write!(f, "could not read script: {e}"),
+            ScriptError::Parse { line, error } => write!(f, "line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScriptError::Io(e) => Some(e),
+            ScriptError::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ScriptError {
+    fn from(e: io::Error) -> Self {
+        ScriptError::Io(e)
+    }
+}
+
+/// Apply every command read from `reader` to `canvas`, stopping at `quit`
+///
+/// Commands after the `quit` are not parsed or applied; each is reported as
+/// a warning instead. Commands the canvas clamps or refuses, such as a
+/// `Move` off the board, still count as executed.
+pub fn run_script<R: BufRead>(reader: R, canvas: &mut Canvas) -> Result<ScriptReport, ScriptError> {
+    let mut report = ScriptReport::default();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+        let command = line.trim();
+        if command.is_empty() || command.starts_with('#') {
+            continue;
+        }
+        if !canvas.is_running() {
+            report
+                .warnings
+                .push(format!("line {number}: `{command}` ignored after quit"));
+            continue;
+        }
+        let msg: Message = command.parse().map_err(|error| ScriptError::Parse {
+            line: number,
+            error,
+        })?;
+        canvas::apply(canvas, &msg);
+        report.executed += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    const FIXTURE: &str = "\
+# A fixture script
+move 3 4
+
+write hello
+   # an indented comment
+color 0 0 255
+quit
+move 100 100
+write never
+";
+
+    #[test]
+    fn fixture_stops_at_quit_and_warns_about_the_rest() {
+        let mut canvas = Canvas::new();
+        let report = run_script(FIXTURE.as_bytes(), &mut canvas).unwrap();
+        assert_eq!(report.executed, 4);
+        assert_eq!(
+            report.warnings,
+            [
+                "line 8: `move 100 100` ignored after quit",
+                "line 9: `write never` ignored after quit",
+            ]
+        );
+        assert_eq!(canvas.cursor(), (3, 4));
+        assert_eq!(canvas.log(), ["hello"]);
+        assert_eq!(canvas.color(), Color::BLUE);
+        assert!(!canvas.is_running());
+        assert_eq!(canvas.exit_code(), Some(0));
+    }
+
+    #[test]
+    fn quit_code_is_recorded() {
+        let mut canvas = Canvas::new();
+        let report = run_script("move 1 1\nquit 3\n".as_bytes(), &mut canvas).unwrap();
+        assert_eq!(report.executed, 2);
+        assert_eq!(canvas.exit_code(), Some(3));
+    }
+
+    #[test]
+    fn parse_error_names_its_line() {
+        let script = FIXTURE.replace("quit", "colour 1 2 3");
+        let mut canvas = Canvas::new();
+        let error = run_script(script.as_bytes(), &mut canvas).unwrap_err();
+        match &error {
+            ScriptError::Parse { line, error } => {
+                assert_eq!(*line, 7);
+                assert_eq!(
+                    *error,
+                    MessageParseError::UnknownCommand(String::from("colour"))
+                );
+            }
+            ScriptError::Io(e) => panic!("unexpected I/O error {e}"),
+        }
+        assert_eq!(error.to_string(), "line 7: unknown command `colour`");
+        // Everything before the bad line was applied
+        assert_eq!(canvas.color(), Color::BLUE);
+    }
+
+    #[test]
+    fn script_without_quit_runs_to_the_end() {
+        let mut canvas = Canvas::new();
+        let report = run_script("move 1 1\nmove 1 1\n".as_bytes(), &mut canvas).unwrap();
+        assert_eq!(report.executed, 2);
+        assert!(report.warnings.is_empty());
+        assert!(canvas.is_running());
+    }
+}
@@ -0,0 +1,178 @@
+//! # Statistics over a stream of `Message`s
+//!
+//! `MessageStats` is a `MessageHandler` that only looks: visiting it with
+//! each message of a stream tallies how many of each kind went by, how much
+//! text was written, how far the moves reached and which colors came up.
+use std::collections::{HashMap, HashSet};
+
+use crate::color::Color;
+use crate::handler::MessageHandler;
+use crate::message::MessageKind;
+use crate::Message;
+
+/// # Per-kind counts and payload statistics for a stream of `Message`s
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageStats {
+    counts: HashMap<MessageKind, usize>,
+    bytes_written: usize,
+    move_bounds: Option<((i32, i32), (i32, i32))>,
+    colors: HashSet<Color>,
+}
+
+impl MessageStats {
+    /// Statistics for an empty stream
+    pub fn new() -> Self {
+        MessageStats::default()
+    }
+
+    /// Statistics for every message in `msgs`
+    pub fn from_messages(msgs: &[Message]) -> Self {
+        let mut stats = MessageStats::new();
+        for msg in msgs {
+            msg.visit(&mut stats);
+        }
+        stats
+    }
+
+    /// How many messages of `kind` were seen
+    pub fn count(&self, kind: MessageKind) -> usize {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// How many messages were seen in total
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Total length in bytes of every `Write` payload
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+
+    /// The smallest box holding every `Move` delta, as its `(min, max)`
+    /// corners, or `None` if there were no moves
+    pub fn move_bounds(&self) -> Option<((i32, i32), (i32, i32))> {
+        self.move_bounds
+    }
+
+    /// Every distinct color a `ChangeColor` switched to
+    pub fn colors(&self) -> &HashSet<Color> {
+        &self.colors
+    }
+
+    /// A one-line, human-readable summary of the statistics
+    pub fn summary(&self) -> String {
+        let counts: Vec<String> = MessageKind::VARIANTS
+            .iter()
+            .map(|kind| format!("{:?} {}", kind, self.count(*kind)))
+            .collect();
+        let bounds = match self.move_bounds {
+            Some(((min_x, min_y), (max_x, max_y))) => {
+                format!("moves within ({min_x}, {min_y})..=({max_x}, {max_y})")
+            }
+            None => String::from("no moves"),
+        };
+        format!(
+            "{} message(s): {}; {} byte(s) written; {}; {} distinct color(s)",
+            self.total(),
+            counts.join(", "),
+            self.bytes_written,
+            bounds,
+            self.colors.len()
+        )
+    }
+
+    fn tally(&mut self, kind: MessageKind) {
+        *self.counts.entry(kind).or_insert(0) += 1;
+    }
+}
+
+impl MessageHandler for MessageStats {
+    fn on_quit(&mut self, _code: i32) {
+        self.tally(MessageKind::Quit);
+    }
+
+    fn on_move(&mut self, x: i32, y: i32) {
+        self.tally(MessageKind::Move);
+        self.move_bounds = Some(match self.move_bounds {
+            Some(((min_x, min_y), (max_x, max_y))) => {
+                ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+            }
+            None => ((x, y), (x, y)),
+        });
+    }
+
+    fn on_write(&mut self, text: &str) {
+        self.tally(MessageKind::Write);
+        self.bytes_written += text.len();
+    }
+
+    fn on_change_color(&mut self, c: &Color) {
+        self.tally(MessageKind::ChangeColor);
+        self.colors.insert(*c);
+    }
+
+    fn on_resize(&mut self, _width: u32, _height: u32) {
+        self.tally(MessageKind::Resize);
+    }
+
+    fn on_draw(&mut self, _points: &[(i32, i32)]) {
+        self.tally(MessageKind::Draw);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch() -> Vec<Message> {
+        vec![
+            Message::Write(String::from("hé")),
+            Message::Move { x: 3, y: -4 },
+            Message::ChangeColor(Color::RED),
+            Message::Move { x: -1, y: 2 },
+            Message::Write(String::from("hello")),
+            Message::ChangeColor(Color::BLUE),
+            Message::ChangeColor(Color::RED),
+            Message::Move { x: 0, y: 7 },
+            Message::Draw(vec![(1, 1)]),
+            Message::Quit { code: 0 },
+        ]
+    }
+
+    #[test]
+    fn every_field_for_a_mixed_batch() {
+        let stats = MessageStats::from_messages(&batch());
+        assert_eq!(stats.count(MessageKind::Quit), 1);
+        assert_eq!(stats.count(MessageKind::Move), 3);
+        assert_eq!(stats.count(MessageKind::Write), 2);
+        assert_eq!(stats.count(MessageKind::ChangeColor), 3);
+        assert_eq!(stats.count(MessageKind::Resize), 0);
+        assert_eq!(stats.count(MessageKind::Draw), 1);
+        assert_eq!(stats.total(), 10);
+        assert_eq!(stats.bytes_written(), 3 + 5);
+        assert_eq!(stats.move_bounds(), Some(((-1, -4), (3, 7))));
+        assert_eq!(stats.colors(), &HashSet::from([Color::RED, Color::BLUE]));
+        assert_eq!(
+            stats.summary(),
+            "10 message(s): Quit 1, Move 3, Write 2, ChangeColor 3, Resize 0, Draw 1; \
+             8 byte(s) written; moves within (-1, -4)..=(3, 7); 2 distinct color(s)"
+        );
+    }
+
+    #[test]
+    fn empty_stream() {
+        let stats = MessageStats::new();
+        assert_eq!(stats.total(), 0);
+        assert_eq!(stats.bytes_written(), 0);
+        assert_eq!(stats.move_bounds(), None);
+        assert!(stats.colors().is_empty());
+        assert!(stats.summary().contains("no moves"));
+    }
+
+    #[test]
+    fn single_move_is_a_point_box() {
+        let stats = MessageStats::from_messages(&[Message::Move { x: 5, y: -5 }]);
+        assert_eq!(stats.move_bounds(), Some(((5, -5), (5, -5))));
+    }
+}
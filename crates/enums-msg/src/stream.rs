@@ -0,0 +1,321 @@
+//! # Framed `Message`s over `Read` and `Write`
+//!
+//! The [`codec`](crate::codec) format only works on whole buffers. To send
+//! messages over a pipe or socket, each one is framed with its length as a
+//! little-endian `u32`, followed by that many bytes of encoded message.
+//!
+//! A `MessageReader` buffers until it has a whole frame, however the bytes
+//! arrive, and refuses frames longer than its maximum rather than
+//! allocating whatever size a corrupt or hostile prefix asks for.
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::codec::{DecodeError, EncodeError};
+use crate::Message;
+
+/// Largest frame a `MessageReader` accepts unless told otherwise: 1 MiB
+pub const DEFAULT_MAX_FRAME: usize = 1 << 20;
+
+/// Size of the length prefix in front of every frame
+const PREFIX: usize = 4;
+
+/// # Writes length-prefixed `Message`s to a `Write`
+#[derive(Debug)]
+pub struct MessageWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> MessageWriter<W> {
+    /// A writer framing messages onto `inner`
+    pub fn new(inner: W) -> Self {
+        MessageWriter { inner }
+    }
+
+    /// Write `msg` as one frame
+    ///
+    /// A message too long to encode, or longer encoded than a `u32` frame
+    /// length can say, is an `InvalidInput` error wrapping the
+    /// `EncodeError`, and nothing is written.
+    pub fn write_message(&mut self, msg: &Message) -> io::Result<()> {
+        let invalid = |e: EncodeError| io::Error::new(io::ErrorKind::InvalidInput, e);
+        let len = u32::try_from(msg.encoded_len())
+            .map_err(|_| invalid(EncodeError::FrameTooLong(msg.encoded_len())))?;
+        let mut frame = Vec::with_capacity(PREFIX + msg.encoded_len());
+        frame.extend_from_slice(&len.to_le_bytes());
+        msg.encode(&mut frame).map_err(invalid)?;
+        self.inner.write_all(&frame)
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// The underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// # Reads length-prefixed `Message`s from a `Read`
+#[derive(Debug)]
+pub struct MessageReader<R: Read> {
+    inner: R,
+    max_frame: usize,
+    /// The part of the current frame read so far, prefix included
+    buf: Vec<u8>,
+}
+
+impl<R: Read> MessageReader<R> {
+    /// A reader of frames from `inner`, up to `DEFAULT_MAX_FRAME` bytes each
+    pub fn new(inner: R) -> Self {
+        MessageReader::with_max_frame(inner, DEFAULT_MAX_FRAME)
+    }
+
+    /// A reader of frames from `inner`, up to `max_frame` bytes each
+    pub fn with_max_frame(inner: R, max_frame: usize) -> Self {
+        MessageReader {
+            inner,
+            max_frame,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Read the next message, or `None` if the input ended between frames
+    ///
+    /// A partly read frame is kept across calls, so if `inner` fails with an
+    /// error such as `WouldBlock`, calling again carries on where it left
+    /// off. After a `FrameTooLarge` error the reader can't find the next
+    /// frame boundary and should be dropped.
+    pub fn read_message(&mut self) -> Result<Option<Message>, StreamError> {
+        if !self.fill(PREFIX)? {
+            return match self.buf.len() {
+                0 => Ok(None),
+                buffered => Err(StreamError::UnexpectedEof { buffered }),
+            };
+        }
+        let mut prefix = [0; PREFIX];
+        prefix.copy_from_slice(&self.buf[..PREFIX]);
+        let len = u32::from_le_bytes(prefix) as usize;
+        if len > self.max_frame {
+            return Err(StreamError::FrameTooLarge {
+                len,
+                max: self.max_frame,
+            });
+        }
+        if !self.fill(PREFIX + len)? {
+            return Err(StreamError::UnexpectedEof {
+                buffered: self.buf.len(),
+            });
+        }
+        let decoded = Message::decode(&self.buf[PREFIX..]);
+        self.buf.clear();
+        match decoded? {
+            (msg, used) if used == len => Ok(Some(msg)),
+            (_, used) => Err(StreamError::TrailingBytes(len - used)),
+        }
+    }
+
+    /// Read until `buf` holds `n` bytes, returning `false` if the input ends
+    /// first
+    ///
+    /// Never reads past `n`, so the bytes of the next frame stay in `inner`.
+    fn fill(&mut self, n: usize) -> io::Result<bool> {
+        let mut chunk = [0; 4096];
+        while self.buf.len() < n {
+            let want = (n - self.buf.len()).min(chunk.len());
+            match self.inner.read(&mut chunk[..want]) {
+                Ok(0) => return Ok(false),
+                Ok(read) => self.buf.extend_from_slice(&chunk[..read]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// # Errors from reading framed `Message`s
+#[derive(Debug)]
+pub enum StreamError {
+    /// The input could not be read
+    Io(io::Error),
+    /// The input ended partway through a frame, after `buffered` bytes of it
+    UnexpectedEof {
+        /// How much of the frame had been read
+        buffered: usize,
+    },
+    /// A frame's prefix gave a length `len` over the reader's `max`
+    FrameTooLarge {
+        /// The length the prefix gave
+        len: usize,
+        /// The reader's limit
+        max: usize,
+    },
+    /// The frame's bytes are not a valid message
+    Decode(DecodeError),
+    /// The message ended this many bytes before its frame did
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "could not read frame: {e}"),
+            StreamError::UnexpectedEof { buffered } => {
+                write!(f, "input ended {buffered} byte(s) into a frame")
+            }
+            StreamError::FrameTooLarge { len, max } => {
+                write!(f, "frame of {len} bytes is over the {max} byte limit")
+            }
+            StreamError::Decode(e) => write!(f, "invalid frame: {e}"),
+            StreamError::TrailingBytes(n) => write!(f, "{n} byte(s) left over in frame"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            StreamError::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+impl From<DecodeError> for StreamError {
+    fn from(e: DecodeError) -> Self {
+        StreamError::Decode(e)
+    }
+}
+
+/// # A `Read` that hands out at most one byte per call
+///
+/// Stands in for a slow pipe or socket, to show that `MessageReader`
+/// doesn't rely on `read` filling its buffer.
+#[derive(Debug)]
+pub struct OneByteAtATime<'a>(pub &'a [u8]);
+
+impl Read for OneByteAtATime<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((&byte, rest)), Some(slot)) => {
+                *slot = byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    fn messages() -> Vec<Message> {
+        vec![
+            Message::Write(String::from("héllo")),
+            Message::Move { x: 3, y: -4 },
+            Message::ChangeColor(Color::RED),
+            Message::Draw(vec![(1, 2), (3, 4)]),
+            Message::Quit { code: 2 },
+        ]
+    }
+
+    fn framed(msgs: &[Message]) -> Vec<u8> {
+        let mut writer = MessageWriter::new(Vec::new());
+        for msg in msgs {
+            writer.write_message(msg).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    fn read_all<R: Read>(reader: &mut MessageReader<R>) -> Vec<Message> {
+        let mut msgs = Vec::new();
+        while let Some(msg) = reader.read_message().unwrap() {
+            msgs.push(msg);
+        }
+        msgs
+    }
+
+    #[test]
+    fn frames_are_prefixed_with_their_length() {
+        let bytes = framed(&[Message::Move { x: 1, y: 2 }]);
+        assert_eq!(bytes, [9, 0, 0, 0, 1, 1, 0, 0, 0, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn round_trip_in_one_read() {
+        let bytes = framed(&messages());
+        let mut reader = MessageReader::new(bytes.as_slice());
+        assert_eq!(read_all(&mut reader), messages());
+        // The end stays the end
+        assert!(reader.read_message().unwrap().is_none());
+    }
+
+    #[test]
+    fn round_trip_one_byte_at_a_time() {
+        let bytes = framed(&messages());
+        let mut reader = MessageReader::new(OneByteAtATime(&bytes));
+        assert_eq!(read_all(&mut reader), messages());
+    }
+
+    #[test]
+    fn oversized_frame_is_rejected_before_reading_it() {
+        let bytes = framed(&[Message::Write("x".repeat(100))]);
+        let mut reader = MessageReader::with_max_frame(bytes.as_slice(), 64);
+        match reader.read_message() {
+            Err(StreamError::FrameTooLarge { len: 105, max: 64 }) => {}
+            other => panic!("expected FrameTooLarge, got {other:?}"),
+        }
+
+        // A huge prefix with nothing behind it is refused, not allocated
+        let mut reader = MessageReader::new(&[0xff, 0xff, 0xff, 0xff][..]);
+        assert_eq!(
+            reader.read_message().unwrap_err().to_string(),
+            "frame of 4294967295 bytes is over the 1048576 byte limit"
+        );
+    }
+
+    #[test]
+    fn eof_mid_frame_is_an_error() {
+        let bytes = framed(&[Message::Move { x: 1, y: 2 }]);
+        for len in 1..bytes.len() {
+            let mut reader = MessageReader::new(OneByteAtATime(&bytes[..len]));
+            match reader.read_message() {
+                Err(StreamError::UnexpectedEof { buffered }) => assert_eq!(buffered, len),
+                other => panic!("expected UnexpectedEof at {len}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn frame_longer_than_its_message_is_an_error() {
+        let mut bytes = framed(&[Message::Quit { code: 0 }]);
+        bytes[0] += 2;
+        bytes.extend_from_slice(&[0, 0]);
+        let mut reader = MessageReader::new(bytes.as_slice());
+        assert!(matches!(
+            reader.read_message(),
+            Err(StreamError::TrailingBytes(2))
+        ));
+    }
+
+    #[test]
+    fn bad_message_in_a_frame_is_a_decode_error() {
+        let mut reader = MessageReader::new(&[1, 0, 0, 0, 9][..]);
+        assert!(matches!(
+            reader.read_message(),
+            Err(StreamError::Decode(DecodeError::UnknownTag(9)))
+        ));
+    }
+}
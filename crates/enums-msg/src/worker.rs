@@ -0,0 +1,82 @@
+//! # Applying `Message`s on a worker thread
+//!
+//! `Message` owns all of its data, so it is `Send` and can be handed to
+//! another thread over a channel. The worker applies every message it
+//! receives to its own `Canvas`, and hands that canvas back when it is done.
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+use crate::canvas::{self, Canvas};
+use crate::Message;
+
+/// Spawn a thread that applies received `Message`s to a fresh `Canvas`
+///
+/// The worker stops, returning its canvas through the `JoinHandle`, once it
+/// has applied a `Quit` or once every `Sender` has been dropped. Messages
+/// sent after the `Quit` are never applied.
+pub fn spawn_worker() -> (Sender<Message>, JoinHandle<Canvas>) {
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        let mut canvas = Canvas::new();
+        for msg in receiver {
+            canvas::apply(&mut canvas, &msg);
+            if !canvas.is_running() {
+                break;
+            }
+        }
+        canvas
+    });
+    (sender, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_when_senders_are_dropped() {
+        let (sender, handle) = spawn_worker();
+        sender.send(Message::Move { x: 1, y: 2 }).unwrap();
+        sender.send(Message::Write(String::from("a"))).unwrap();
+        drop(sender);
+        let canvas = handle.join().unwrap();
+        assert_eq!(canvas.cursor(), (1, 2));
+        assert_eq!(canvas.log(), ["a"]);
+        assert!(canvas.is_running());
+    }
+
+    #[test]
+    fn stops_at_quit() {
+        let (sender, handle) = spawn_worker();
+        sender.send(Message::Write(String::from("before"))).unwrap();
+        sender.send(Message::Quit { code: 0 }).unwrap();
+        // The worker may already have hung up, so this send can fail
+        let _ = sender.send(Message::Write(String::from("after")));
+        let canvas = handle.join().unwrap();
+        assert_eq!(canvas.log(), ["before"]);
+        assert!(!canvas.is_running());
+    }
+
+    #[test]
+    fn two_producers() {
+        const WRITES_PER_PRODUCER: usize = 50;
+        let (sender, handle) = spawn_worker();
+        let producers: Vec<_> = (0..2)
+            .map(|id| {
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    for i in 0..WRITES_PER_PRODUCER {
+                        sender.send(Message::Write(format!("{id}:{i}"))).unwrap();
+                        sender.send(Message::Move { x: 0, y: 0 }).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(sender);
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let canvas = handle.join().unwrap();
+        assert_eq!(canvas.log().len(), 2 * WRITES_PER_PRODUCER);
+    }
+}
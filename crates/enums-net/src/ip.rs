@@ -0,0 +1,89 @@
+//! # IP addresses, with data of a different type in each variant
+//!
+//! A version four address is four numbers, a version six one is written as
+//! a string, and one enum holds either.
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+
+/// # Implementation of IpAddr with different associated types
+///
+/// An example alternative implementation of `IpAddr` using different
+/// associated types for `V4` and `V6` variants.
+///
+/// ```
+/// use enums_net::IpAddrTypes;
+///
+/// let home = IpAddrTypes::V4(127, 0, 0, 1);
+/// assert!(matches!(home, IpAddrTypes::V4(127, ..)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpAddrTypes {
+    /// The four numbers of a version four address
+    V4(u8, u8, u8, u8),
+    /// A version six address, as written
+    V6(String),
+}
+
+/// The address as usually written: `127.0.0.1`, or the `V6` string as is
+///
+/// ```
+/// use enums_net::IpAddrTypes;
+///
+/// assert_eq!(IpAddrTypes::V4(10, 0, 0, 1).to_string(), "10.0.0.1");
+/// ```
+impl fmt::Display for IpAddrTypes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpAddrTypes::V4(a, b, c, d) => write!(f, "{a}.{b}.{c}.{d}"),
+            IpAddrTypes::V6(address) => write!(f, "{address}"),
+        }
+    }
+}
+
+/// The inverse of `Display`
+///
+/// An address that `std::net` reads as version four is a `V4`, and one it
+/// reads as version six is a `V6` holding the text as written.
+///
+/// ```
+/// use enums_net::IpAddrTypes;
+///
+/// assert_eq!("127.0.0.1".parse(), Ok(IpAddrTypes::V4(127, 0, 0, 1)));
+/// assert_eq!("::1".parse(), Ok(IpAddrTypes::V6(String::from("::1"))));
+/// assert!("localhost".parse::<IpAddrTypes>().is_err());
+/// ```
+impl FromStr for IpAddrTypes {
+    type Err = InvalidAddress;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(v4) = s.parse::<Ipv4Addr>() {
+            let [a, b, c, d] = v4.octets();
+            return Ok(IpAddrTypes::V4(a, b, c, d));
+        }
+        match s.parse::<Ipv6Addr>() {
+            Ok(_) => Ok(IpAddrTypes::V6(s.to_string())),
+            Err(_) => Err(InvalidAddress(s.to_string())),
+        }
+    }
+}
+
+/// # Text that is not an IP address
+///
+/// ```
+/// use enums_net::ip::InvalidAddress;
+///
+/// let e = InvalidAddress(String::from("localhost"));
+/// assert_eq!(e.to_string(), r#""localhost" is not an IP address"#);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidAddress(pub String);
+
+impl fmt::Display for InvalidAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} is not an IP address", self.0)
+    }
+}
+
+impl core::error::Error for InvalidAddress {}
@@ -0,0 +1,28 @@
+//! # Enums and Pattern Matching: IP addresses
+//!
+//! `IpAddrTypes`, the chapter's enum whose variants hold data of different
+//! types, with parsing and display to and from the standard notation.
+//!
+//! ```
+//! use enums_net::IpAddrTypes;
+//!
+//! let home: IpAddrTypes = "127.0.0.1".parse().unwrap();
+//! assert_eq!(home, IpAddrTypes::V4(127, 0, 0, 1));
+//! ```
+//!
+//! The crate is `#![no_std]`, needing only `alloc`.
+#![cfg_attr(not(test), no_std)]
+#![deny(missing_docs)]
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::todo,
+    clippy::unimplemented
+)]
+#![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used))]
+
+extern crate alloc;
+
+pub mod ip;
+
+pub use ip::IpAddrTypes;
@@ -0,0 +1,77 @@
+//! # Wallets of coins, and making change
+//!
+//! Fills two wallets with random coins, shows what each holds, says which
+//! is richer, and makes change for the difference with the fewest coins:
+//!
+//! ```text
+//! cargo run --example coins -- --seed 1
+//! ```
+//!
+//! Without `--seed` the seed is random, and printed to stderr so the run
+//! can be repeated.
+use std::cmp::Ordering;
+use std::env;
+use std::process::ExitCode;
+
+use rust_enums::wallet::{compare_value, pick_richer, RicherOutcome};
+use rust_enums::{Coin2, Wallet};
+
+/// The `N` of `--seed N` in `args`, or a random seed if there isn't one
+fn seed(mut args: impl Iterator<Item = String>) -> Result<u64, String> {
+    match (args.next().as_deref(), args.next()) {
+        (None, _) => {
+            let seed = rand::random();
+            eprintln!("seed: {seed}");
+            Ok(seed)
+        }
+        (Some("--seed"), Some(n)) => n.parse().map_err(|_| format!("{n:?} is not a seed")),
+        _ => Err(String::from("usage: coins [--seed N]")),
+    }
+}
+
+fn show(name: &str, wallet: &Wallet) {
+    let coins: Vec<String> = wallet.coins().iter().map(Coin2::to_string).collect();
+    println!("{name}: {} ({} cents)", coins.join(", "), wallet.total());
+}
+
+fn main() -> ExitCode {
+    let seed = match seed(env::args().skip(1)) {
+        Ok(seed) => seed,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::from(2);
+        }
+    };
+    let alice = Wallet::random(4, seed);
+    let bob = Wallet::random(5, seed.wrapping_add(1));
+    show("Alice", &alice);
+    show("Bob", &bob);
+
+    let (first, second) = (alice.coins()[0], bob.coins()[0]);
+    let verb = match compare_value(&first, &second) {
+        Ordering::Less => "is worth less than",
+        Ordering::Equal => "is worth the same as",
+        Ordering::Greater => "is worth more than",
+    };
+    println!("Alice's first coin, a {first}, {verb} Bob's, a {second}");
+
+    let difference = alice.total().abs_diff(bob.total());
+    match pick_richer(&alice, &bob) {
+        RicherOutcome::First(_) => println!("Alice is richer by {difference} cents"),
+        RicherOutcome::Second(_) => println!("Bob is richer by {difference} cents"),
+        RicherOutcome::Tie(cents) => println!("They both hold {cents} cents"),
+    }
+
+    let change = Wallet::change_for(difference);
+    let coins: Vec<String> = change
+        .coins()
+        .iter()
+        .map(|coin| format!("{:?}", coin.kind()))
+        .collect();
+    println!(
+        "Evening up takes {} coin(s): {}",
+        coins.len(),
+        coins.join(" ")
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,58 @@
+//! # Parsing and classifying IP addresses
+//!
+//! Parses each address given on the command line, or a few samples if
+//! there are none, and says what kind of address each one is:
+//!
+//! ```text
+//! cargo run --example ip -- 192.168.1.20 ::1 example.com
+//! ```
+use std::env;
+use std::process::ExitCode;
+
+use rust_enums::IpAddrTypes;
+
+/// What `address` is for, matched on the parts of each variant
+fn classify(address: &IpAddrTypes) -> &'static str {
+    match address {
+        IpAddrTypes::V4(127, ..) => "a version four loopback address",
+        IpAddrTypes::V4(10, ..) | IpAddrTypes::V4(192, 168, ..) => "a private version four address",
+        IpAddrTypes::V4(172, second, ..) if (16..=31).contains(second) => {
+            "a private version four address"
+        }
+        IpAddrTypes::V4(169, 254, ..) => "a link-local version four address",
+        IpAddrTypes::V4(..) => "a public version four address",
+        IpAddrTypes::V6(text) if text == "::1" => "the version six loopback address",
+        IpAddrTypes::V6(text) if text.to_ascii_lowercase().starts_with("fe80:") => {
+            "a link-local version six address"
+        }
+        IpAddrTypes::V6(_) => "a version six address",
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        args = [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.20.0.1",
+            "8.8.8.8",
+            "::1",
+            "fe80::1",
+            "2001:db8::",
+        ]
+        .map(String::from)
+        .to_vec();
+    }
+    let mut code = ExitCode::SUCCESS;
+    for arg in &args {
+        match arg.parse::<IpAddrTypes>() {
+            Ok(address) => println!("{:<12} is {}", address.to_string(), classify(&address)),
+            Err(e) => {
+                eprintln!("{e}");
+                code = ExitCode::from(2);
+            }
+        }
+    }
+    code
+}
@@ -0,0 +1,65 @@
+//! # From `Message`s to a drawing on a `Canvas`
+//!
+//! Builds a script of `Message`s, some of them random moves, applies each
+//! one to a small `Canvas` and says how it went, then draws the board:
+//!
+//! ```text
+//! cargo run --example messages -- --seed 1
+//! ```
+//!
+//! Without `--seed` the seed is random, and printed to stderr so the run
+//! can be repeated.
+use std::env;
+use std::process::ExitCode;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_enums::builder::MessageScript;
+use rust_enums::canvas::{self, ApplyOutcome, Board, MovePolicy};
+use rust_enums::Canvas;
+
+/// The `N` of `--seed N` in `args`, or a random seed if there isn't one
+fn seed(mut args: impl Iterator<Item = String>) -> Result<u64, String> {
+    match (args.next().as_deref(), args.next()) {
+        (None, _) => {
+            let seed = rand::random();
+            eprintln!("seed: {seed}");
+            Ok(seed)
+        }
+        (Some("--seed"), Some(n)) => n.parse().map_err(|_| format!("{n:?} is not a seed")),
+        _ => Err(String::from("usage: messages [--seed N]")),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut rng = match seed(env::args().skip(1)) {
+        Ok(seed) => StdRng::seed_from_u64(seed),
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::from(2);
+        }
+    };
+    let mut script = MessageScript::new().write("hello").color(255, 136, 0);
+    for _ in 0..4 {
+        script = script
+            .move_by(rng.gen_range(-4..=6), rng.gen_range(-2..=3))
+            .draw(vec![(rng.gen_range(0..16), rng.gen_range(0..6))]);
+    }
+    let messages = script.resize(0, 6).quit().build();
+
+    let board = Board::new(16, 6).expect("16x6 is a board");
+    let mut canvas = Canvas::with_board(board, MovePolicy::Clamp);
+    for message in &messages {
+        let outcome = match canvas::apply(&mut canvas, message) {
+            ApplyOutcome::Applied => String::from("applied"),
+            ApplyOutcome::Clamped { at } => format!("clamped at {at:?}"),
+            ApplyOutcome::Rejected { target } => format!("rejected, {target:?} is off the board"),
+            ApplyOutcome::InvalidSize { width, height } => {
+                format!("refused, {width}x{height} isn't a board")
+            }
+        };
+        println!("{:<18} {outcome}", message.to_string());
+    }
+    print!("{}", canvas.render());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,58 @@
+//! # The core types from a `#![no_std]` crate
+//!
+//! A library that has no standard library, only `alloc`, and still counts
+//! coins, parses addresses and sends `Message`s through the codec. Building
+//! it with the crate's `std` feature off shows those types don't lean on
+//! `std` anywhere:
+//!
+//! ```text
+//! cargo build --example no_std --no-default-features --features alloc
+//! ```
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use rust_enums::color::Color;
+use rust_enums::{Cents, Coin2, IpAddrTypes, Message, UsState, Wallet};
+
+/// What the coins named in `names` are worth, or the first name that isn't
+/// a coin
+pub fn total(names: &[&str]) -> Result<Cents, String> {
+    let coins = names
+        .iter()
+        .map(|name| name.parse::<Coin2>().map_err(|e| e.0))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Wallet::new(coins).total())
+}
+
+/// Whether `address` is a version six address
+pub fn is_v6(address: &str) -> bool {
+    matches!(address.parse(), Ok(IpAddrTypes::V6(_)))
+}
+
+/// `messages` encoded and decoded again, as a peer would receive them
+pub fn round_trip(messages: &[Message]) -> Vec<Message> {
+    let mut received = Vec::with_capacity(messages.len());
+    for message in messages {
+        let mut buf = Vec::new();
+        if message.encode(&mut buf).is_err() {
+            continue;
+        }
+        if let Ok((message, _)) = Message::decode(&buf) {
+            received.push(message);
+        }
+    }
+    received
+}
+
+/// The messages a coin-operated sign sends when `coin` goes in
+pub fn on_coin(coin: Coin2) -> Vec<Message> {
+    let mut messages = Vec::from([Message::ChangeColor(Color::GREEN)]);
+    if let Coin2::Quarter(UsState::Alaska) = coin {
+        messages.push(Message::Write(String::from("a quarter from Alaska!")));
+    }
+    messages
+}
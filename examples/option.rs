@@ -0,0 +1,25 @@
+//! # `Option`s of borrowed values
+//!
+//! Runs the `option_ref` helpers on a few inputs, some that have a value to
+//! find and some that don't:
+//!
+//! ```text
+//! cargo run --example option
+//! ```
+use rust_enums::option_ref::{first_positive, greet, plus_one_ref};
+
+fn main() {
+    let lists: [&[i32]; 3] = [&[-3, 0, 4, 9], &[-1, -2], &[]];
+    for xs in lists {
+        let found = first_positive(xs);
+        match found {
+            Some(x) => println!("{xs:?}: first positive is {x}"),
+            None => println!("{xs:?}: nothing positive"),
+        }
+        println!("  plus one: {:?}", plus_one_ref(found));
+    }
+
+    let names = ["Ferris".to_string()];
+    println!("{}", greet(names.first().map(String::as_str)));
+    println!("{}", greet(None));
+}
@@ -0,0 +1,22 @@
+//! # Fuzzing `Message::decode`
+//!
+//! Feeds arbitrary bytes to the binary decoder. It must never panic or read
+//! out of bounds, and whatever it does decode must re-encode to exactly the
+//! bytes it consumed.
+//!
+//! Run with `cargo fuzz run fuzz_message_decode` from the repository root;
+//! `corpus/fuzz_message_decode` is seeded with one encoding of each variant.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_enums::Message;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((message, used)) = Message::decode(data) {
+        assert!(used <= data.len());
+        assert_eq!(message.encoded_len(), used);
+        let mut buf = Vec::new();
+        message.encode(&mut buf).unwrap();
+        assert_eq!(buf, data[..used]);
+    }
+});
@@ -0,0 +1,232 @@
+//! # Structured values from raw bytes
+//!
+//! A fuzzer hands its target a slice of bytes. `Unstructured` reads values
+//! out of the slice, and `Arbitrary` says how to read each of the crate's
+//! enums, so a fuzz target can ask for a `Message` rather than parsing one
+//! itself. Every value read is valid: colors are in range, and a `V6`
+//! address is always a well-formed one.
+//!
+//! `Unstructured` and `Arbitrary` follow the `arbitrary` crate's API, minus
+//! the fallibility: once the bytes run out, numbers read as zero and
+//! collections as empty, so reading never fails.
+//!
+//! ```
+//! use rust_enums::arbitrary::Unstructured;
+//! use rust_enums::{Coin2, UsState};
+//!
+//! let mut u = Unstructured::new(&[3, 0, 0, 0, 1, 0, 0, 0]);
+//! let coin: Coin2 = u.arbitrary();
+//! assert_eq!(coin, Coin2::Quarter(UsState::Alaska));
+//! ```
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::net::Ipv6Addr;
+
+use crate::color::Color;
+use crate::{Coin, Coin2, IpAddrTypes, Message, UsState};
+
+/// # A reader of values from a slice of raw bytes
+#[derive(Debug, Clone)]
+pub struct Unstructured<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Unstructured<'a> {
+    /// A reader of `data`, from the start
+    pub fn new(data: &'a [u8]) -> Self {
+        Unstructured { data }
+    }
+
+    /// How many bytes are left
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether every byte has been read
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The next value of type `T`
+    pub fn arbitrary<T: Arbitrary>(&mut self) -> T {
+        T::arbitrary(self)
+    }
+
+    /// The next `N` bytes, padded with zeros once the data runs out
+    pub fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0; N];
+        let n = N.min(self.data.len());
+        bytes[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        bytes
+    }
+
+    /// An index into a collection of `len` items, `0` if `len` is `0`
+    pub fn choose_index(&mut self, len: usize) -> usize {
+        match len {
+            0 => 0,
+            _ => u32::from_le_bytes(self.bytes()) as usize % len,
+        }
+    }
+
+    /// A length for a collection, under 256 and never more than the bytes
+    /// left, so reading a collection can't run on long after the data ends
+    pub fn arbitrary_len(&mut self) -> usize {
+        let [len] = self.bytes();
+        usize::from(len).min(self.data.len())
+    }
+}
+
+/// # A type that can be read from an `Unstructured`
+pub trait Arbitrary: Sized {
+    /// Read a value from `u`
+    fn arbitrary(u: &mut Unstructured) -> Self;
+}
+
+macro_rules! arbitrary_int {
+    ($($int:ty),*) => {
+        $(
+            impl Arbitrary for $int {
+                fn arbitrary(u: &mut Unstructured) -> Self {
+                    <$int>::from_le_bytes(u.bytes())
+                }
+            }
+        )*
+    };
+}
+
+arbitrary_int!(u8, u16, u32, u64, u128, i32);
+
+impl Arbitrary for bool {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        u8::arbitrary(u) & 1 == 1
+    }
+}
+
+/// Up to `arbitrary_len` bytes, cut back to their longest valid UTF-8
+/// prefix
+impl Arbitrary for String {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        let len = u.arbitrary_len();
+        let (bytes, rest) = u.data.split_at(len);
+        u.data = rest;
+        match core::str::from_utf8(bytes) {
+            Ok(s) => s.to_string(),
+            Err(e) => String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned(),
+        }
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary> Arbitrary for (A, B) {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        (u.arbitrary(), u.arbitrary())
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        let len = u.arbitrary_len();
+        (0..len).map(|_| u.arbitrary()).collect()
+    }
+}
+
+/// The variant of `variants` at an arbitrary index
+fn arbitrary_variant<E: Copy>(u: &mut Unstructured, variants: &[E]) -> E {
+    variants[u.choose_index(variants.len())]
+}
+
+impl Arbitrary for Coin {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        arbitrary_variant(u, Coin::VARIANTS)
+    }
+}
+
+impl Arbitrary for UsState {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        arbitrary_variant(u, UsState::VARIANTS)
+    }
+}
+
+impl Arbitrary for Coin2 {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        match u.arbitrary::<Coin>() {
+            Coin::Penny => Coin2::Penny,
+            Coin::Nickel => Coin2::Nickel,
+            Coin::Dime => Coin2::Dime,
+            Coin::Quarter => Coin2::Quarter(u.arbitrary()),
+        }
+    }
+}
+
+/// Three components, each a `u8`, so always in range
+impl Arbitrary for Color {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        Color::rgb(u.arbitrary(), u.arbitrary(), u.arbitrary())
+    }
+}
+
+/// A `V6` holds a `u128` written as `std::net::Ipv6Addr` writes it
+impl Arbitrary for IpAddrTypes {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        if u.arbitrary() {
+            IpAddrTypes::V6(Ipv6Addr::from(u.arbitrary::<u128>()).to_string())
+        } else {
+            let [a, b, c, d] = u.bytes();
+            IpAddrTypes::V4(a, b, c, d)
+        }
+    }
+}
+
+impl Arbitrary for Message {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        match u.choose_index(6) {
+            0 => Message::Quit {
+                code: u.arbitrary(),
+            },
+            1 => Message::Move {
+                x: u.arbitrary(),
+                y: u.arbitrary(),
+            },
+            2 => Message::Write(u.arbitrary()),
+            3 => Message::ChangeColor(u.arbitrary()),
+            4 => Message::Resize {
+                width: u.arbitrary(),
+                height: u.arbitrary(),
+            },
+            _ => Message::Draw(u.arbitrary()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_out_of_data_reads_zeros() {
+        let mut u = Unstructured::new(&[7, 1]);
+        assert_eq!(u.arbitrary::<u32>(), 0x107);
+        assert!(u.is_empty());
+        assert_eq!(u.arbitrary::<i32>(), 0);
+        assert_eq!(u.arbitrary::<String>(), "");
+        assert_eq!(u.arbitrary::<Message>(), Message::Quit { code: 0 });
+    }
+
+    #[test]
+    fn strings_keep_their_valid_prefix() {
+        let mut u = Unstructured::new(&[4, b'o', b'k', 0xff, b'!', 9]);
+        assert_eq!(u.arbitrary::<String>(), "ok");
+        assert_eq!(u.len(), 1);
+    }
+
+    #[test]
+    fn v6_addresses_are_well_formed() {
+        let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..100 {
+            if let IpAddrTypes::V6(address) = u.arbitrary() {
+                assert!(address.parse::<Ipv6Addr>().is_ok(), "{address}");
+            }
+        }
+    }
+}
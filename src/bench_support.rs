@@ -0,0 +1,111 @@
+//! # Inputs and timing for the benchmarks in `benches/`
+//!
+//! Every generator takes a seed, so a benchmark measures the same inputs on
+//! every run. The benchmarks build their inputs once, before timing starts,
+//! and time only the code under test with `best_of`.
+//!
+//! Not part of the crate's API proper, so hidden from its docs.
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::color::Color;
+use crate::{Coin2, Message, UsState, Wallet};
+
+/// `count` coins of any kind, quarters from any state, chosen from `seed`
+///
+/// The same coins as `Wallet::random(count, seed)` holds.
+pub fn random_coins(count: usize, seed: u64) -> Vec<Coin2> {
+    Wallet::random(count, seed).coins().to_vec()
+}
+
+/// `count` messages of every kind, chosen from `seed`
+///
+/// Payloads vary in size: `Write`s of up to 32 characters, some of them
+/// not ASCII, and `Draw`s of up to 8 points.
+pub fn random_messages(count: usize, seed: u64) -> Vec<Message> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| match rng.gen_range(0..6) {
+            0 => Message::Quit {
+                code: rng.gen_range(0..4),
+            },
+            1 => Message::Move {
+                x: rng.gen(),
+                y: rng.gen(),
+            },
+            2 => Message::Write("é".repeat(rng.gen_range(0..=32))),
+            3 => Message::ChangeColor(Color::rgb(rng.gen(), rng.gen(), rng.gen())),
+            4 => Message::Resize {
+                width: rng.gen_range(1..=1000),
+                height: rng.gen_range(1..=1000),
+            },
+            _ => {
+                let points = rng.gen_range(0..=8);
+                Message::Draw((0..points).map(|_| (rng.gen(), rng.gen())).collect())
+            }
+        })
+        .collect()
+}
+
+/// `count` names to look up with `UsState::from_name`, chosen from `seed`
+///
+/// Mostly states, written in lower case with spaces as a person would type
+/// them, and one in ten a name that isn't a state.
+pub fn state_names(count: usize, seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let states = UsState::VARIANTS;
+    (0..count)
+        .map(|_| {
+            if rng.gen_ratio(1, 10) {
+                return String::from("atlantis");
+            }
+            let state = format!("{:?}", states.choose(&mut rng).copied().unwrap_or_default());
+            let mut name = String::with_capacity(state.len() + 1);
+            for (i, c) in state.char_indices() {
+                if i > 0 && c.is_uppercase() {
+                    name.push(' ');
+                }
+                name.push(c.to_ascii_lowercase());
+            }
+            name
+        })
+        .collect()
+}
+
+/// The best time of `runs` calls to `f`
+pub fn best_of(runs: usize, mut f: impl FnMut()) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generators_are_seeded() {
+        assert_eq!(random_coins(50, 3), random_coins(50, 3));
+        assert_eq!(random_messages(50, 3), random_messages(50, 3));
+        assert_eq!(state_names(50, 3), state_names(50, 3));
+    }
+
+    #[test]
+    fn state_names_are_mostly_states() {
+        let names = state_names(1000, 1);
+        let found = names
+            .iter()
+            .filter(|name| UsState::from_name(name).is_some())
+            .count();
+        assert!((800..1000).contains(&found), "{found} of 1000");
+        assert!(names.iter().any(|name| name == "new york"));
+    }
+}
@@ -0,0 +1,638 @@
+//! # Command-line entry points
+//!
+//! With no arguments the binary runs every example in `main`, as does `all`.
+//! One of the other `Part` names runs just that part's examples, and
+//! `--only a,b,c` just the `Section`s named, in that order; `--list` lists
+//! the parts and the sections in each. `--format json` writes each
+//! example's output as a JSON record rather than prose, and `--color
+//! auto|always|never` says whether to color prose; `auto`, the default,
+//! colors a terminal unless `NO_COLOR` is set. `--timings` ends the run
+//! with a table of how long each section took, as JSON does always.
+//! `--seed N` seeds the one random number generator every example draws
+//! from; without it the seed is random, and printed to stderr so a
+//! surprising run can be repeated. `-q` (or `--quiet`) cuts each example
+//! down to the one line it computes. Each `-v` (or `--verbose`) adds the
+//! type names and intermediate states behind the results, and logs more
+//! diagnostics to stderr, starting from the level `RUST_LOG` names.
+//!
+//! The seed, sections, format and color can also be set in `enums.toml` in
+//! the working directory, or the file `--config <path>` names, with the
+//! `config` feature; a flag beats the file. `config show` prints the
+//! settings a run would use. Otherwise the arguments pick a tool:
+//!
+//! - `message repl [--render]`: read commands from stdin, applying each to a
+//!   `Canvas`, and with `--render` drawing the board after every command
+//! - `message run <path>`: apply the script at `path` to a `Canvas`, exiting
+//!   with the code of the script's `quit`
+//! - `dice play [--seed N] [--turns N]`: play the fancy-hat `DiceGame`,
+//!   asking on stdin after every turn whether to go on, or with `--turns`
+//!   playing that many turns on its own and printing where the player ended
+//!   up
+//! - `coins count`: total up the coins named on stdin, such as `penny` or
+//!   `quarter:ohio`, separated by whitespace
+//! - `tui [--seed N]`: browse the sections in a menu in the terminal,
+//!   running each one chosen; only with the `tui` feature
+//! - `completions <shell>`: write a completion script for `bash`, `zsh`,
+//!   `fish` or `powershell` to stdout
+//!
+//! Errors go to stderr. The exit code is 1 when applying or undoing a
+//! `Message` fails, such as a `Canvas` refusing a command, 2 for input that
+//! doesn't parse, 3 when reading or writing fails, and 70 for a bug in the
+//! binary itself.
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+use rust_enums::canvas::Canvas;
+use rust_enums::error::EnumsError;
+use rust_enums::rng::{CrateRng, DefaultRng};
+use rust_enums::script::ScriptError;
+use rust_enums::stream::StreamError;
+use rust_enums::{repl, script, Cents, Coin2};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::completions::{self, Shell};
+use crate::config::Config;
+use crate::dice::RandomRoller;
+use crate::dice_game::DiceGame;
+use crate::logging;
+use crate::report::{ColorChoice, OutputFormat, OutputLevel, Reporter};
+use crate::section::{RunContext, Section};
+
+const USAGE: &str = "usage: enums [-q | -v] [--config <path>] [--format text|json]
+             [--color auto|always|never] [--seed N] [--timings]
+             [<part> | --only <section>,... | --list | config show]
+       enums message repl [--render]
+       enums message run <path>
+       enums dice play [--seed N] [--turns N]
+       enums coins count
+       enums tui [--seed N]
+       enums completions bash|zsh|fish|powershell";
+
+/// # A part of the examples in `main`, a run of related `Section`s
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum Part {
+    All,
+    Ip,
+    Messages,
+    Coins,
+    Patterns,
+    Dice,
+}
+
+impl Part {
+    /// The name that runs the part
+    pub fn name(self) -> &'static str {
+        match self {
+            Part::All => "all",
+            Part::Ip => "ip",
+            Part::Messages => "messages",
+            Part::Coins => "coins",
+            Part::Patterns => "patterns",
+            Part::Dice => "dice",
+        }
+    }
+
+    /// What the part's examples show, for `--list`
+    pub fn about(self) -> &'static str {
+        match self {
+            Part::All => "every section below, in order",
+            Part::Ip => "defining enums, using IP addresses",
+            Part::Messages => "enums with methods: `Message` and the tools built on it",
+            Part::Coins => "`match` on coins and state quarters",
+            Part::Patterns => "`Option`, `if let`, and patterns of every kind",
+            Part::Dice => "a dice game and its rules",
+        }
+    }
+
+    /// The part called `name`
+    fn from_name(name: &str) -> Option<Part> {
+        Part::iter().find(|s| s.name() == name)
+    }
+
+    /// The sections in the part, in the order they run
+    pub fn sections(self) -> Vec<Section> {
+        Section::iter()
+            .filter(|s| self == Part::All || s.part() == self)
+            .collect()
+    }
+
+    /// The part whose name `name` is most likely a typo of
+    fn closest_to(name: &str) -> Option<Part> {
+        Part::iter()
+            .map(|s| (edit_distance(name, s.name()), s))
+            .filter(|&(distance, s)| distance <= s.name().len() / 3 + 1)
+            .min_by_key(|&(distance, _)| distance)
+            .map(|(_, s)| s)
+    }
+}
+
+/// How many single-character insertions, deletions and substitutions turn
+/// `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // `row[j]` is the distance from the part of `a` seen so far to `b[..j]`
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substituted = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substituted.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Spaces on the board `dice play` plays on
+const BOARD_SIZE: u8 = 20;
+
+/// The exit code for input that doesn't make sense: an unknown subcommand
+/// or flag, or a coin, script and so on that doesn't parse
+const EXIT_USAGE: u8 = 2;
+
+/// The exit code for failing to read or write
+const EXIT_IO: u8 = 3;
+
+/// The exit code for a broken invariant, meaning a bug in the binary, as
+/// `EX_SOFTWARE` in BSD's `sysexits.h`
+pub const EXIT_SOFTWARE: u8 = 70;
+
+/// Run the tool named by `args`, returning the process exit code
+///
+/// Every failure is reported on stderr: bad input exits with `EXIT_USAGE`,
+/// I/O errors with `EXIT_IO`, and the rest, such as a `Canvas` refusing a
+/// command, with `1`.
+pub fn run(args: &[String]) -> u8 {
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let verbosity = take_verbosity(&mut args);
+    logging::init(verbosity);
+    let quiet = take_flag(&mut args, "-q") | take_flag(&mut args, "--quiet");
+    let level = match (quiet, verbosity) {
+        (false, 0) => OutputLevel::Normal,
+        (false, _) => OutputLevel::Verbose,
+        (true, 0) => OutputLevel::Quiet,
+        (true, _) => {
+            eprintln!("--quiet and --verbose don't go together\n{USAGE}");
+            return EXIT_USAGE;
+        }
+    };
+    let timings = take_flag(&mut args, "--timings");
+    let path = match take_option(&mut args, "--config") {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return EXIT_USAGE;
+        }
+    };
+    let mut config = match load_config(path) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+    let only = match take_config_flags(&mut args, &mut config) {
+        Ok(only) => only,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return EXIT_USAGE;
+        }
+    };
+    if only && !args.is_empty() {
+        eprintln!("--only runs examples, so takes no other arguments\n{USAGE}");
+        return EXIT_USAGE;
+    }
+    let seed = config.seed;
+    match args.as_slice() {
+        [] => match configured_sections(&config) {
+            Ok(sections) => exit_code(
+                "error",
+                run_examples(&sections, &config, level, timings).map(|()| 0),
+            ),
+            Err(e) => {
+                eprintln!("sections: {e}");
+                EXIT_USAGE
+            }
+        },
+        ["--list"] => {
+            list();
+            0
+        }
+        ["config", "show"] => {
+            print!("{config}");
+            0
+        }
+        ["completions", shell] => match shell.parse::<Shell>() {
+            Ok(shell) => exit_code(
+                "completions",
+                completions::write(shell, &mut io::stdout().lock())
+                    .map(|()| 0)
+                    .map_err(EnumsError::from),
+            ),
+            Err(_) => {
+                let shells: Vec<&str> = Shell::iter().map(<&str>::from).collect();
+                eprintln!(
+                    "completions: unknown shell {shell:?}, expected one of {}",
+                    shells.join(", ")
+                );
+                EXIT_USAGE
+            }
+        },
+        ["message", "repl"] => exit_code("repl", repl(false).map(|()| 0)),
+        ["message", "repl", "--render"] => exit_code("repl", repl(true).map(|()| 0)),
+        ["message", "run", path] => exit_code(path, run_script(path)),
+        ["coins", "count"] => exit_code("coins count", count_coins().map(|()| 0)),
+        #[cfg(feature = "tui")]
+        ["tui"] => exit_code("tui", tui(seed).map(|()| 0)),
+        #[cfg(not(feature = "tui"))]
+        ["tui"] => {
+            eprintln!("tui: built without the `tui` feature");
+            EXIT_USAGE
+        }
+        ["dice", "play", flags @ ..] => match parse_dice_flags(flags) {
+            Ok(turns) => exit_code("dice play", dice_play(seed, turns).map(|()| 0)),
+            Err(e) => {
+                eprintln!("dice play: {e}\n{USAGE}");
+                EXIT_USAGE
+            }
+        },
+        [name] if !name.starts_with('-') => match Part::from_name(name) {
+            Some(part) => exit_code(
+                "error",
+                run_examples(&part.sections(), &config, level, timings).map(|()| 0),
+            ),
+            None => {
+                eprintln!("error: unrecognized subcommand '{name}'\n");
+                if let Some(section) = Part::closest_to(name) {
+                    eprintln!("  tip: a similar subcommand exists: '{}'\n", section.name());
+                }
+                eprintln!("{USAGE}");
+                EXIT_USAGE
+            }
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            EXIT_USAGE
+        }
+    }
+}
+
+/// The exit code for how a subcommand went: the code it succeeded with,
+/// or, after printing its error prefixed with `context`, the code for that
+fn exit_code(context: &str, result: Result<u8, EnumsError>) -> u8 {
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{context}: {e}");
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// `EXIT_IO` for errors reading or writing, `1` for errors in what a valid
+/// command asked for, and `EXIT_USAGE` for the rest, which all come from
+/// input that doesn't parse
+fn exit_code_for(e: &EnumsError) -> u8 {
+    match e {
+        EnumsError::Io(_)
+        | EnumsError::Script(ScriptError::Io(_))
+        | EnumsError::Stream(StreamError::Io(_)) => EXIT_IO,
+        EnumsError::Apply(_) | EnumsError::Undo(_) | EnumsError::Message(_) => 1,
+        _ => EXIT_USAGE,
+    }
+}
+
+/// Remove every `-v`, `-vv` and so on, and `--verbose`, from `args`,
+/// returning how much more verbose they ask for
+fn take_verbosity(args: &mut Vec<&str>) -> usize {
+    let mut verbosity = 0;
+    args.retain(|&arg| {
+        let vs = arg
+            .strip_prefix('-')
+            .filter(|vs| !vs.is_empty() && vs.bytes().all(|b| b == b'v'));
+        match (arg, vs) {
+            ("--verbose", _) => verbosity += 1,
+            (_, Some(vs)) => verbosity += vs.len(),
+            _ => return true,
+        }
+        false
+    });
+    verbosity
+}
+
+/// Remove every `flag` from `args`, returning whether there were any
+fn take_flag(args: &mut Vec<&str>, flag: &str) -> bool {
+    let before = args.len();
+    args.retain(|&arg| arg != flag);
+    args.len() != before
+}
+
+/// Remove `flag` and the value after it from `args`, returning the value
+fn take_option<'a>(args: &mut Vec<&'a str>, flag: &str) -> Result<Option<&'a str>, String> {
+    let Some(at) = args.iter().position(|&arg| arg == flag) else {
+        return Ok(None);
+    };
+    let value = *args.get(at + 1).ok_or(format!("{flag} needs a value"))?;
+    args.drain(at..at + 2);
+    Ok(Some(value))
+}
+
+/// Remove the flags a `Config` can also set from `args`, overriding
+/// `config` with each one given, and returning whether `--only` was
+fn take_config_flags(args: &mut Vec<&str>, config: &mut Config) -> Result<bool, String> {
+    if let Some(format) = take_format(args)? {
+        config.format = format;
+    }
+    if let Some(color) = take_color(args)? {
+        config.color = color;
+    }
+    if let Some(seed) = take_seed(args)? {
+        config.seed = Some(seed);
+    }
+    let only = take_only(args)?;
+    let given = only.is_some();
+    if let Some(sections) = only {
+        config.sections = Some(sections.iter().map(Section::to_string).collect());
+    }
+    Ok(given)
+}
+
+/// Remove `--format <name>` from `args`, returning the format it names
+fn take_format(args: &mut Vec<&str>) -> Result<Option<OutputFormat>, String> {
+    take_option(args, "--format")?
+        .map(|name| {
+            OutputFormat::from_name(name).ok_or(format!("--format: unknown format {name:?}"))
+        })
+        .transpose()
+}
+
+/// Remove `--color <choice>` from `args`, returning the choice it names
+fn take_color(args: &mut Vec<&str>) -> Result<Option<ColorChoice>, String> {
+    take_option(args, "--color")?
+        .map(|name| ColorChoice::from_name(name).ok_or(format!("--color: unknown choice {name:?}")))
+        .transpose()
+}
+
+/// Remove `--only <a,b,c>` from `args`, returning the sections it names,
+/// in the order it names them
+fn take_only(args: &mut Vec<&str>) -> Result<Option<Vec<Section>>, String> {
+    take_option(args, "--only")?
+        .map(|names| sections_named(names.split(',')).map_err(|e| format!("--only: {e}")))
+        .transpose()
+}
+
+/// The sections called `names`, in order
+fn sections_named<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<Vec<Section>, String> {
+    names
+        .into_iter()
+        .map(|name| {
+            name.parse()
+                .map_err(|_| format!("unknown section {name:?}"))
+        })
+        .collect()
+}
+
+/// The sections `config` names, or every one if it names none
+fn configured_sections(config: &Config) -> Result<Vec<Section>, String> {
+    match &config.sections {
+        None => Ok(Part::All.sections()),
+        Some(names) => sections_named(names.iter().map(String::as_str)),
+    }
+}
+
+/// The config in the file at `path`, or `enums.toml` if there is one,
+/// printing a warning for each key ignored, or on failure the exit code
+#[cfg(feature = "config")]
+fn load_config(path: Option<&str>) -> Result<Config, u8> {
+    use crate::config::{self, ConfigError};
+
+    let (path, required) = match path {
+        Some(path) => (path, true),
+        None => (config::FILE_NAME, false),
+    };
+    match config::load(path) {
+        Ok((config, warnings)) => {
+            for warning in warnings {
+                eprintln!("{path}: warning: {warning}");
+            }
+            Ok(config)
+        }
+        Err(ConfigError::Io(e)) if !required && e.kind() == io::ErrorKind::NotFound => {
+            Ok(Config::default())
+        }
+        Err(e) => {
+            eprintln!("{path}: {e}");
+            Err(match e {
+                ConfigError::Io(_) => EXIT_IO,
+                ConfigError::Parse(_) => EXIT_USAGE,
+            })
+        }
+    }
+}
+
+/// The built-in defaults, as without the `config` feature there is no
+/// file to read
+#[cfg(not(feature = "config"))]
+fn load_config(path: Option<&str>) -> Result<Config, u8> {
+    match path {
+        None => Ok(Config::default()),
+        Some(_) => {
+            eprintln!("--config: built without the `config` feature");
+            Err(EXIT_USAGE)
+        }
+    }
+}
+
+/// Remove `--seed <N>` from `args`, returning the seed
+fn take_seed(args: &mut Vec<&str>) -> Result<Option<u64>, String> {
+    take_option(args, "--seed")?
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("--seed: {value:?} is not a number"))
+        })
+        .transpose()
+}
+
+/// `seed`, or a random one, which is then printed to stderr so the run can
+/// be repeated
+fn seed_or_random(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| {
+        let seed = DefaultRng.roll_range(0, u64::MAX);
+        eprintln!("seed: {seed}");
+        seed
+    })
+}
+
+/// `--list`: print each part, with the name and description of each of
+/// its sections under it
+fn list() {
+    println!("{:<10}{}", Part::All.name(), Part::All.about());
+    for part in Part::iter().filter(|&part| part != Part::All) {
+        println!("{:<10}{}", part.name(), part.about());
+        for section in part.sections() {
+            println!("  {:<44}{}", section.to_string(), section.description());
+        }
+    }
+}
+
+/// `<part>` or `--only`: run `sections` in order in the format `config`
+/// gives, colored as it says, writing the lines `level` lets through and
+/// drawing at random from one RNG seeded with its seed, then with
+/// `timings`, or always in JSON, write how long each one took
+fn run_examples(
+    sections: &[Section],
+    config: &Config,
+    level: OutputLevel,
+    timings: bool,
+) -> Result<(), EnumsError> {
+    let reporter = Reporter::stdout()
+        .with_format(config.format)
+        .with_color(config.color.for_stdout());
+    let mut ctx = RunContext::new(reporter, seed_or_random(config.seed)).with_level(level);
+    for section in sections {
+        section.run(&mut ctx)?;
+    }
+    if timings || config.format == OutputFormat::Json {
+        ctx.write_timings()?;
+    }
+    Ok(())
+}
+
+/// `tui`: the menu of sections, on the terminal, until the user quits
+#[cfg(feature = "tui")]
+fn tui(seed: Option<u64>) -> Result<(), EnumsError> {
+    use crate::tui::{run_menu, KeyEvents, Menu, RawMode};
+
+    let mut menu = Menu::new(seed_or_random(seed));
+    let _raw = RawMode::enter()?;
+    run_menu(
+        &mut menu,
+        &mut KeyEvents::new(io::stdin().lock()),
+        &mut io::stdout().lock(),
+    )?;
+    Ok(())
+}
+
+/// `message repl`: run the REPL over stdin and stdout
+fn repl(render: bool) -> Result<(), EnumsError> {
+    repl::run_repl(io::stdin().lock(), io::stdout(), io::stderr(), render)?;
+    Ok(())
+}
+
+/// `message run <path>`: print the final canvas, and the code of the
+/// script's `quit`, if it had one, for the process to exit with
+///
+/// Only the low byte of the code is kept, as the OS keeps only that much of
+/// an exit status.
+fn run_script(path: &str) -> Result<u8, EnumsError> {
+    let file = File::open(path)?;
+    let mut canvas = Canvas::new();
+    let report = script::run_script(BufReader::new(file), &mut canvas)?;
+    for warning in &report.warnings {
+        eprintln!("{path}: warning: {warning}");
+    }
+    println!("{} command(s) executed", report.executed);
+    println!("{canvas}");
+    Ok(canvas.exit_code().unwrap_or(0) as u8)
+}
+
+/// The `--turns` given to `dice play`
+///
+/// Its `--seed` is the same one the examples take, so `run` has already
+/// taken it.
+fn parse_dice_flags(flags: &[&str]) -> Result<Option<usize>, String> {
+    let mut turns = None;
+    let mut flags = flags.iter();
+    while let Some(&flag) = flags.next() {
+        let value = flags.next().ok_or(format!("{flag} needs a value"))?;
+        match flag {
+            "--turns" => {
+                turns = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("{flag}: {value:?} is not a number"))?,
+                )
+            }
+            _ => return Err(format!("unknown flag {flag:?}")),
+        }
+    }
+    Ok(turns)
+}
+
+/// `dice play`: play interactively over stdin and stdout, or `turns` turns
+/// on its own, then print where the player ended up and the longest run of
+/// one roll
+///
+/// Without a `seed`, every game is different.
+fn dice_play(seed: Option<u64>, turns: Option<usize>) -> Result<(), EnumsError> {
+    let mut roller = RandomRoller::from_seed(seed_or_random(seed));
+    let mut game = DiceGame::new(BOARD_SIZE).expect("BOARD_SIZE isn't 0");
+    let played = match turns {
+        Some(turns) => {
+            game.play(&mut roller, turns);
+            turns
+        }
+        None => game.play_interactive(&mut roller, io::stdin().lock(), io::stdout())?,
+    };
+    print!("after {played} turn(s): {}", game.player());
+    match game.history().best_streak() {
+        Some((roll, streak)) => println!(", longest streak {streak} x {roll}"),
+        None => println!(),
+    }
+    Ok(())
+}
+
+/// `coins count`: print how many coins stdin names and what they add up to
+///
+/// Stops at the first token that isn't a coin, without printing a total.
+fn count_coins() -> Result<(), EnumsError> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let (mut count, mut total): (usize, Cents) = (0, 0);
+    for token in input.split_whitespace() {
+        total += token.parse::<Coin2>()?.cents();
+        count += 1;
+    }
+    println!("{count} coin(s), {total} cent(s)");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("coins", "coins"), 0);
+        assert_eq!(edit_distance("coin", "coins"), 1);
+        assert_eq!(edit_distance("dcie", "dice"), 2);
+        assert_eq!(edit_distance("", "ip"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn typos_find_their_section() {
+        assert_eq!(Part::closest_to("message"), Some(Part::Messages));
+        assert_eq!(Part::closest_to("patern"), Some(Part::Patterns));
+        assert_eq!(Part::closest_to("al"), Some(Part::All));
+        assert_eq!(Part::closest_to("xyzzy"), None);
+    }
+
+    #[test]
+    fn errors_map_to_exit_codes() {
+        let io = || io::Error::other("gone");
+        assert_eq!(exit_code_for(&io().into()), EXIT_IO);
+        assert_eq!(exit_code_for(&ScriptError::Io(io()).into()), EXIT_IO);
+        let coin = "button".parse::<Coin2>().unwrap_err();
+        assert_eq!(exit_code_for(&coin.into()), EXIT_USAGE);
+        let undo = rust_enums::history::UndoError::NothingToUndo;
+        assert_eq!(exit_code_for(&undo.into()), 1);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for section in Part::iter() {
+            assert_eq!(Part::from_name(section.name()), Some(section));
+        }
+    }
+}
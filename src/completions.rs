@@ -0,0 +1,451 @@
+//! # Shell completion scripts for the binary
+//!
+//! `enums completions <shell>` writes a script that teaches the shell to
+//! complete the binary's flags, subcommands and their values. The section
+//! names `--only` takes come from the `Section` registry and the part names
+//! from `Part`, so a new section completes as soon as it is registered.
+//!
+//! `cli` parses its arguments by hand rather than from a declared command,
+//! so the scripts are written here from `FLAGS` and `commands`, which
+//! follow its `USAGE`, and a new flag has to be added to both.
+use std::io::{self, Write};
+
+use strum::IntoEnumIterator;
+use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+
+use crate::cli::Part;
+use crate::section::Section;
+
+/// # A shell `completions` writes a script for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+/// What a flag takes after it
+#[derive(Clone, Copy)]
+enum Takes {
+    Nothing,
+    Number,
+    Path,
+    OneOf(&'static [&'static str]),
+    /// Section names, separated by commas
+    Sections,
+}
+
+/// A flag for running the examples
+struct Flag {
+    long: &'static str,
+    short: Option<&'static str>,
+    about: &'static str,
+    takes: Takes,
+}
+
+/// The flags `cli::run` takes before a subcommand
+const FLAGS: &[Flag] = &[
+    Flag {
+        long: "--quiet",
+        short: Some("-q"),
+        about: "write only the line each section computes",
+        takes: Takes::Nothing,
+    },
+    Flag {
+        long: "--verbose",
+        short: Some("-v"),
+        about: "show the details behind results and log more to stderr",
+        takes: Takes::Nothing,
+    },
+    Flag {
+        long: "--config",
+        short: None,
+        about: "read settings from this file rather than enums.toml",
+        takes: Takes::Path,
+    },
+    Flag {
+        long: "--format",
+        short: None,
+        about: "write prose or JSON records",
+        takes: Takes::OneOf(&["text", "json"]),
+    },
+    Flag {
+        long: "--color",
+        short: None,
+        about: "whether to color prose",
+        takes: Takes::OneOf(&["auto", "always", "never"]),
+    },
+    Flag {
+        long: "--seed",
+        short: None,
+        about: "seed the random number generator",
+        takes: Takes::Number,
+    },
+    Flag {
+        long: "--timings",
+        short: None,
+        about: "end with how long each section took",
+        takes: Takes::Nothing,
+    },
+    Flag {
+        long: "--only",
+        short: None,
+        about: "run just these sections, in order",
+        takes: Takes::Sections,
+    },
+    Flag {
+        long: "--list",
+        short: None,
+        about: "list the parts and their sections",
+        takes: Takes::Nothing,
+    },
+];
+
+/// A subcommand, and the words that can follow it
+struct Command {
+    name: &'static str,
+    about: &'static str,
+    then: Vec<&'static str>,
+}
+
+/// Every subcommand: the parts, then the tools
+///
+/// `dice` and `coins` alone run a part, and are tools with a word after
+/// them, so each is one command offering both.
+fn commands() -> Vec<Command> {
+    let mut commands: Vec<Command> = Part::iter()
+        .map(|part| Command {
+            name: part.name(),
+            about: part.about(),
+            then: Vec::new(),
+        })
+        .collect();
+    let tools = [
+        (
+            "message",
+            "the message REPL, or run a script",
+            vec!["repl", "run"],
+        ),
+        ("dice", "play the fancy-hat dice game", vec!["play"]),
+        ("coins", "total up the coins named on stdin", vec!["count"]),
+        ("config", "print the settings a run would use", vec!["show"]),
+        ("tui", "browse and run the sections in a menu", Vec::new()),
+        (
+            "completions",
+            "write a shell completion script",
+            Shell::iter().map(<&str>::from).collect(),
+        ),
+    ];
+    for (name, about, then) in tools {
+        match commands.iter_mut().find(|command| command.name == name) {
+            Some(part) => part.then = then,
+            None => commands.push(Command { name, about, then }),
+        }
+    }
+    commands
+}
+
+/// Every section's name and description
+fn sections() -> Vec<(String, &'static str)> {
+    Section::iter()
+        .map(|section| (section.to_string(), section.description()))
+        .collect()
+}
+
+/// `text` in single quotes for a POSIX shell, zsh or fish
+fn quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Write the completion script for `shell` to `out`
+pub fn write(shell: Shell, out: &mut impl Write) -> io::Result<()> {
+    match shell {
+        Shell::Bash => bash(out),
+        Shell::Zsh => zsh(out),
+        Shell::Fish => fish(out),
+        Shell::Powershell => powershell(out),
+    }
+}
+
+fn bash(out: &mut impl Write) -> io::Result<()> {
+    let words = |words: Vec<&str>| words.join(" ");
+    let sections: Vec<String> = sections().into_iter().map(|(name, _)| name).collect();
+    writeln!(out, "# bash completion for enums")?;
+    writeln!(out, "_enums() {{")?;
+    writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(out, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+    writeln!(out, "    case \"$prev\" in")?;
+    for flag in FLAGS {
+        let reply = match flag.takes {
+            Takes::Nothing => continue,
+            Takes::Number => String::from("COMPREPLY=()"),
+            Takes::Path => String::from("COMPREPLY=($(compgen -f -- \"$cur\"))"),
+            Takes::OneOf(values) => format!(
+                "COMPREPLY=($(compgen -W {} -- \"$cur\"))",
+                quoted(&values.join(" "))
+            ),
+            // Complete the name after the last comma
+            Takes::Sections => format!(
+                "COMPREPLY=($(compgen -P \"${{cur%\"${{cur##*,}}\"}}\" -W {} -- \"${{cur##*,}}\"))",
+                quoted(&sections.join(" "))
+            ),
+        };
+        writeln!(
+            out,
+            "        {})\n            {reply}\n            return ;;",
+            flag.long
+        )?;
+    }
+    for command in commands().iter().filter(|c| !c.then.is_empty()) {
+        writeln!(
+            out,
+            "        {})\n            COMPREPLY=($(compgen -W {} -- \"$cur\"))\n            return ;;",
+            command.name,
+            quoted(&command.then.join(" "))
+        )?;
+    }
+    writeln!(out, "    esac")?;
+    let mut top: Vec<&str> = commands().iter().map(|c| c.name).collect();
+    top.extend(
+        FLAGS
+            .iter()
+            .flat_map(|flag| flag.short.into_iter().chain([flag.long])),
+    );
+    writeln!(
+        out,
+        "    COMPREPLY=($(compgen -W {} -- \"$cur\"))",
+        quoted(&words(top))
+    )?;
+    writeln!(out, "}}")?;
+    writeln!(out, "complete -F _enums enums")
+}
+
+fn zsh(out: &mut impl Write) -> io::Result<()> {
+    // `_describe` takes `name:description`; a colon in a name needs escaping
+    let described =
+        |name: &str, about: &str| quoted(&format!("{}:{about}", name.replace(':', r"\:")));
+    writeln!(out, "#compdef enums\n")?;
+    writeln!(out, "_enums_sections() {{")?;
+    writeln!(out, "    local -a sections\n    sections=(")?;
+    for (name, description) in sections() {
+        writeln!(out, "        {}", described(&name, description))?;
+    }
+    writeln!(
+        out,
+        "    )\n    _describe -t sections section sections\n}}\n"
+    )?;
+    writeln!(out, "_enums() {{")?;
+    writeln!(out, "    local -a commands\n    commands=(")?;
+    for command in commands() {
+        writeln!(out, "        {}", described(command.name, command.about))?;
+    }
+    writeln!(out, "    )")?;
+    writeln!(out, "    local curcontext=$curcontext state line")?;
+    writeln!(out, "    _arguments -C \\")?;
+    for flag in FLAGS {
+        let action = match flag.takes {
+            Takes::Nothing => String::new(),
+            Takes::Number => String::from(":number: "),
+            Takes::Path => String::from(":path:_files"),
+            Takes::OneOf(values) => format!(":value:({})", values.join(" ")),
+            Takes::Sections => String::from(":sections:_sequence _enums_sections"),
+        };
+        let names = match flag.short {
+            // Both spellings can be given any number of times
+            Some(short) => format!("'*'{{{short},{}}}", flag.long),
+            None => flag.long.to_string(),
+        };
+        writeln!(
+            out,
+            "        {names}{} \\",
+            quoted(&format!("[{}]{action}", flag.about))
+        )?;
+    }
+    writeln!(out, "        '1: :->command' \\\n        '*:: :->args'")?;
+    writeln!(out, "    case $state in")?;
+    writeln!(
+        out,
+        "        command) _describe -t commands command commands ;;"
+    )?;
+    writeln!(out, "        args)\n            case $line[1] in")?;
+    for command in commands().iter().filter(|c| !c.then.is_empty()) {
+        writeln!(
+            out,
+            "                {}) (( CURRENT == 2 )) && compadd {} ;;",
+            command.name,
+            command.then.join(" ")
+        )?;
+    }
+    writeln!(out, "            esac ;;\n    esac\n}}\n")?;
+    writeln!(out, "_enums \"$@\"")
+}
+
+fn fish(out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "# fish completion for enums")?;
+    writeln!(out, "complete -c enums -f")?;
+    for flag in FLAGS {
+        let mut line = format!("complete -c enums -l {}", &flag.long[2..]);
+        if let Some(short) = flag.short {
+            line += &format!(" -s {}", &short[1..]);
+        }
+        match flag.takes {
+            Takes::Nothing => {}
+            Takes::Number => line += " -x",
+            Takes::Path => line += " -r -F",
+            Takes::OneOf(values) => line += &format!(" -x -a {}", quoted(&values.join(" "))),
+            Takes::Sections => line += " -x",
+        }
+        writeln!(out, "{line} -d {}", quoted(flag.about))?;
+    }
+    for (name, description) in sections() {
+        writeln!(
+            out,
+            "complete -c enums -l only -x -a {name} -d {}",
+            quoted(description)
+        )?;
+    }
+    for command in commands() {
+        writeln!(
+            out,
+            "complete -c enums -n __fish_use_subcommand -a {} -d {}",
+            command.name,
+            quoted(command.about)
+        )?;
+        if !command.then.is_empty() {
+            writeln!(
+                out,
+                "complete -c enums -n '__fish_seen_subcommand_from {}' -a {}",
+                command.name,
+                quoted(&command.then.join(" "))
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn powershell(out: &mut impl Write) -> io::Result<()> {
+    // In single quotes PowerShell escapes a quote by doubling it
+    let list = |words: &[&str]| {
+        words
+            .iter()
+            .map(|word| format!("'{}'", word.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let sections: Vec<String> = sections().into_iter().map(|(name, _)| name).collect();
+    let sections: Vec<&str> = sections.iter().map(String::as_str).collect();
+    writeln!(out, "# PowerShell completion for enums")?;
+    writeln!(
+        out,
+        "Register-ArgumentCompleter -Native -CommandName enums -ScriptBlock {{"
+    )?;
+    writeln!(
+        out,
+        "    param($wordToComplete, $commandAst, $cursorPosition)"
+    )?;
+    writeln!(out, "    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object {{ $_.ToString() }})")?;
+    writeln!(
+        out,
+        "    if ($wordToComplete) {{ $words = @($words | Select-Object -SkipLast 1) }}"
+    )?;
+    writeln!(
+        out,
+        "    $previous = if ($words.Count) {{ $words[-1] }} else {{ '' }}"
+    )?;
+    writeln!(out, "    $candidates = switch ($previous) {{")?;
+    for flag in FLAGS {
+        match flag.takes {
+            Takes::Nothing | Takes::Number | Takes::Path => {}
+            Takes::OneOf(values) => {
+                writeln!(out, "        '{}' {{ @({}) }}", flag.long, list(values))?
+            }
+            Takes::Sections => {
+                writeln!(out, "        '{}' {{ @({}) }}", flag.long, list(&sections))?
+            }
+        }
+    }
+    for command in commands().iter().filter(|c| !c.then.is_empty()) {
+        writeln!(
+            out,
+            "        '{}' {{ @({}) }}",
+            command.name,
+            list(&command.then)
+        )?;
+    }
+    let mut top: Vec<&str> = commands().iter().map(|c| c.name).collect();
+    top.extend(
+        FLAGS
+            .iter()
+            .flat_map(|flag| flag.short.into_iter().chain([flag.long])),
+    );
+    writeln!(out, "        default {{ @({}) }}", list(&top))?;
+    writeln!(out, "    }}")?;
+    writeln!(
+        out,
+        "    $candidates | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{"
+    )?;
+    writeln!(out, "        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)")?;
+    writeln!(out, "    }}\n}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(shell: Shell) -> String {
+        let mut out = Vec::new();
+        write(shell, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn shells_are_named_as_given_on_the_command_line() {
+        let names: Vec<&str> = Shell::iter().map(<&str>::from).collect();
+        assert_eq!(names, ["bash", "zsh", "fish", "powershell"]);
+        assert_eq!("powershell".parse(), Ok(Shell::Powershell));
+        assert!("tcsh".parse::<Shell>().is_err());
+    }
+
+    #[test]
+    fn bash_completes_subcommands_flags_and_sections() {
+        let bash = script(Shell::Bash);
+        for word in [
+            "message",
+            "dice",
+            "coins",
+            "completions",
+            "--only",
+            "--seed",
+        ] {
+            assert!(bash.contains(word), "no {word} in\n{bash}");
+        }
+        assert!(bash.contains("-W 'defining_an_enum shape_examples "));
+        assert!(bash.contains("'repl run'"));
+        assert!(bash.ends_with("complete -F _enums enums\n"));
+    }
+
+    #[test]
+    fn zsh_completes_subcommands_flags_and_sections() {
+        let zsh = script(Shell::Zsh);
+        assert!(zsh.starts_with("#compdef enums\n"));
+        assert!(zsh.contains("'message:the message REPL, or run a script'"));
+        assert!(zsh.contains("'defining_an_enum:an enum of the kinds of IP address'"));
+        assert!(zsh.contains(
+            "'*'{-v,--verbose}'[show the details behind results and log more to stderr]'"
+        ));
+        assert!(zsh.contains("--format'[write prose or JSON records]:value:(text json)'"));
+    }
+
+    #[test]
+    fn every_section_is_offered() {
+        for shell in Shell::iter() {
+            let script = script(shell);
+            for section in Section::iter() {
+                let name = section.to_string();
+                assert!(script.contains(&name), "{shell:?} lacks {name}");
+            }
+        }
+    }
+}
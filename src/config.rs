@@ -0,0 +1,385 @@
+//! # Defaults for a run, from `enums.toml`
+//!
+//! A `Config` holds what the flags for running the examples can say: the
+//! seed, which sections to run, the output format and whether to color.
+//! The binary starts from the built-in defaults, takes whatever
+//! `enums.toml` in the working directory sets (or the file `--config`
+//! names), and then whatever the flags set, so a flag beats the file and
+//! the file beats the defaults. `enums config show` prints the result.
+//!
+//! ```toml
+//! # Every run the same, and just the coin examples
+//! seed = 42
+//! sections = ["match_control_flow", "match_control_flow_patterns"]
+//! format = "text"
+//! color = "never"
+//! ```
+//!
+//! With the `config` feature `parse` reads the part of TOML a `Config`
+//! needs: comments, and `key = value` lines whose values are whole
+//! numbers, strings and arrays of strings on one line. Tables, dates and
+//! arrays over several lines aren't read. A key it doesn't know is a
+//! warning, so a file written for a newer binary still loads; anything
+//! else it can't read is an error naming the line.
+use std::fmt::{self, Display};
+
+use crate::report::{ColorChoice, OutputFormat};
+
+/// # The settings for a run of the examples
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Config {
+    /// What to seed the RNG with, or `None` for a random seed
+    pub seed: Option<u64>,
+    /// The names of the sections to run, in order, or `None` for all
+    pub sections: Option<Vec<String>>,
+    /// How to write the output
+    pub format: OutputFormat,
+    /// Whether to color prose
+    pub color: ColorChoice,
+}
+
+/// The settings as `enums.toml` would set them; a setting left unset is a
+/// comment, so the text reads back as the same `Config`
+impl Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.seed {
+            Some(seed) => writeln!(f, "seed = {seed}")?,
+            None => writeln!(f, "# seed = a random one, printed to stderr")?,
+        }
+        match &self.sections {
+            Some(names) => writeln!(f, "sections = {names:?}")?,
+            None => writeln!(f, "# sections = every one, in order")?,
+        }
+        writeln!(f, "format = {:?}", self.format.name())?;
+        writeln!(f, "color = {:?}", self.color.name())
+    }
+}
+
+#[cfg(feature = "config")]
+pub use file::{load, ConfigError, FILE_NAME};
+
+#[cfg(feature = "config")]
+mod file {
+    use std::fmt::{self, Display};
+    use std::{fs, io};
+
+    use super::Config;
+    use crate::report::{ColorChoice, OutputFormat};
+    use crate::section::Section;
+
+    /// The file read from the working directory when `--config` isn't given
+    pub const FILE_NAME: &str = "enums.toml";
+
+    /// # Something wrong on one line of a config file
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Problem {
+        /// The line, counting from 1
+        pub line: usize,
+        /// What is wrong with it
+        pub message: String,
+    }
+
+    impl Display for Problem {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+
+    /// # Why a config file couldn't be loaded
+    #[derive(Debug)]
+    pub enum ConfigError {
+        /// The file couldn't be read
+        Io(io::Error),
+        /// The file isn't a config the binary can use
+        Parse(Problem),
+    }
+
+    impl Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                ConfigError::Io(e) => e.fmt(f),
+                ConfigError::Parse(problem) => problem.fmt(f),
+            }
+        }
+    }
+
+    /// Read the config file at `path`, returning the config and a warning
+    /// for every key in it that was ignored
+    pub fn load(path: &str) -> Result<(Config, Vec<Problem>), ConfigError> {
+        let text = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        parse(&text).map_err(ConfigError::Parse)
+    }
+
+    /// A value on the right of `=`
+    #[derive(Debug)]
+    enum Value {
+        Integer(i128),
+        String(String),
+        Array(Vec<Value>),
+        /// `true` or `false`, which no key takes
+        Boolean,
+    }
+
+    /// The config `text` sets, and a warning for every key in it that was
+    /// ignored
+    pub fn parse(text: &str) -> Result<(Config, Vec<Problem>), Problem> {
+        let mut config = Config::default();
+        let mut warnings = Vec::new();
+        // The line each known key was set on, to catch one set twice
+        let mut seen: Vec<(&str, usize)> = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let at = |message: String| Problem {
+                line: i + 1,
+                message,
+            };
+            let mut cursor = Cursor(line);
+            if cursor.at_end() {
+                continue;
+            }
+            if cursor.0.starts_with('[') {
+                return Err(at(String::from("tables aren't supported here")));
+            }
+            let key = cursor.key().map_err(at)?;
+            if !cursor.eat('=') {
+                return Err(at(format!("expected `=` after `{key}`")));
+            }
+            let value = cursor.value().map_err(at)?;
+            if !cursor.at_end() {
+                return Err(at(format!("unexpected `{}` after the value", cursor.0)));
+            }
+            if !matches!(key, "seed" | "sections" | "format" | "color") {
+                warnings.push(at(format!("unknown key `{key}`, ignored")));
+                continue;
+            }
+            if let Some(&(_, first)) = seen.iter().find(|&&(seen, _)| seen == key) {
+                return Err(at(format!("`{key}` is already set on line {first}")));
+            }
+            seen.push((key, i + 1));
+            set(&mut config, key, value).map_err(at)?;
+        }
+        Ok((config, warnings))
+    }
+
+    /// Set `key` of `config` to `value`
+    fn set(config: &mut Config, key: &str, value: Value) -> Result<(), String> {
+        match (key, value) {
+            ("seed", Value::Integer(seed)) => {
+                let seed = u64::try_from(seed)
+                    .map_err(|_| format!("`seed` must be from 0 to {}", u64::MAX))?;
+                config.seed = Some(seed);
+            }
+            ("sections", Value::Array(values)) => {
+                let names = values
+                    .into_iter()
+                    .map(|value| match value {
+                        Value::String(name) if name.parse::<Section>().is_ok() => Ok(name),
+                        Value::String(name) => Err(format!("unknown section {name:?}")),
+                        _ => Err(String::from("`sections` must be an array of strings")),
+                    })
+                    .collect::<Result<_, _>>()?;
+                config.sections = Some(names);
+            }
+            ("format", Value::String(name)) => {
+                config.format =
+                    OutputFormat::from_name(&name).ok_or(format!("unknown format {name:?}"))?;
+            }
+            ("color", Value::String(name)) => {
+                config.color =
+                    ColorChoice::from_name(&name).ok_or(format!("unknown color {name:?}"))?;
+            }
+            ("seed", _) => return Err(String::from("`seed` must be a whole number")),
+            ("sections", _) => return Err(String::from("`sections` must be an array")),
+            (key, _) => return Err(format!("`{key}` must be a string")),
+        }
+        Ok(())
+    }
+
+    /// # The rest of a line still to be read
+    struct Cursor<'a>(&'a str);
+
+    impl<'a> Cursor<'a> {
+        fn skip_space(&mut self) {
+            self.0 = self.0.trim_start_matches([' ', '\t']);
+        }
+
+        /// Whether only spaces and a comment are left
+        fn at_end(&mut self) -> bool {
+            self.skip_space();
+            self.0.is_empty() || self.0.starts_with('#')
+        }
+
+        /// Skip spaces and `c`, if `c` is next
+        fn eat(&mut self, c: char) -> bool {
+            self.skip_space();
+            match self.0.strip_prefix(c) {
+                Some(rest) => {
+                    self.0 = rest;
+                    true
+                }
+                None => false,
+            }
+        }
+
+        /// A bare key: letters, digits, `_` and `-`
+        fn key(&mut self) -> Result<&'a str, String> {
+            self.skip_space();
+            let end = self
+                .0
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(self.0.len());
+            let (key, rest) = self.0.split_at(end);
+            if key.is_empty() {
+                return Err(String::from("expected a key"));
+            }
+            self.0 = rest;
+            Ok(key)
+        }
+
+        fn value(&mut self) -> Result<Value, String> {
+            self.skip_space();
+            if self.eat('"') {
+                return self.string().map(Value::String);
+            }
+            if self.eat('[') {
+                let mut values = Vec::new();
+                // Elements are separated by commas, with one allowed at the end
+                while !self.eat(']') {
+                    if self.at_end() {
+                        return Err(String::from("expected `]` to end the array"));
+                    }
+                    values.push(self.value()?);
+                    if !self.eat(',') && !self.at_end() && !self.0.starts_with(']') {
+                        return Err(String::from("expected `,` or `]` after an element"));
+                    }
+                }
+                return Ok(Value::Array(values));
+            }
+            let end = self
+                .0
+                .find([' ', '\t', ',', ']', '#'])
+                .unwrap_or(self.0.len());
+            let (word, rest) = self.0.split_at(end);
+            let value = match word {
+                "" => return Err(String::from("expected a value")),
+                "true" | "false" => Value::Boolean,
+                _ => Value::Integer(
+                    word.replace('_', "")
+                        .parse()
+                        .map_err(|_| format!("`{word}` isn't a value; a string needs quotes"))?,
+                ),
+            };
+            self.0 = rest;
+            Ok(value)
+        }
+
+        /// The rest of a string whose opening `"` has been read
+        fn string(&mut self) -> Result<String, String> {
+            let mut string = String::new();
+            let mut chars = self.0.char_indices();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.0 = &self.0[i + 1..];
+                        return Ok(string);
+                    }
+                    '\\' => string.push(match chars.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'r')) => '\r',
+                        _ => return Err(String::from("unknown escape in a string")),
+                    }),
+                    c => string.push(c),
+                }
+            }
+            Err(String::from("expected `\"` to end the string"))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "config"))]
+mod tests {
+    use super::file::{parse, Problem};
+    use super::*;
+
+    #[test]
+    fn every_key_is_read() {
+        let text = "# defaults\nseed = 1_000\n\nsections = [\"defining_an_enum\", \"defining_an_enum\",]  # two\nformat = \"json\"\ncolor = 'x'\n";
+        assert_eq!(
+            parse(text).unwrap_err(),
+            Problem {
+                line: 6,
+                message: String::from("`'x'` isn't a value; a string needs quotes"),
+            }
+        );
+        let (config, warnings) = parse(&text.replace("'x'", "\"never\"")).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                seed: Some(1000),
+                sections: Some(vec![
+                    String::from("defining_an_enum"),
+                    String::from("defining_an_enum"),
+                ]),
+                format: OutputFormat::Json,
+                color: ColorChoice::Never,
+            }
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn an_unknown_key_is_a_warning() {
+        let (config, warnings) = parse("sedd = 4\nseed = 5\n").unwrap();
+        assert_eq!(config.seed, Some(5));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "line 1: unknown key `sedd`, ignored"
+        );
+    }
+
+    #[test]
+    fn errors_name_their_line() {
+        let cases = [
+            ("seed 4", "line 1: expected `=` after `seed`"),
+            (
+                "\nseed = -1",
+                "line 2: `seed` must be from 0 to 18446744073709551615",
+            ),
+            ("format = \"yaml\"", "line 1: unknown format \"yaml\""),
+            ("sections = [\"nope\"]", "line 1: unknown section \"nope\""),
+            (
+                "sections = [\"defining_an_enum\"",
+                "line 1: expected `]` to end the array",
+            ),
+            ("color = \"never", "line 1: expected `\"` to end the string"),
+            (
+                "seed = 1\n\n\nseed = 2",
+                "line 4: `seed` is already set on line 1",
+            ),
+            ("seed = 1 2", "line 1: unexpected `2` after the value"),
+            ("[run]", "line 1: tables aren't supported here"),
+            ("color = true", "line 1: `color` must be a string"),
+        ];
+        for (text, error) in cases {
+            assert_eq!(parse(text).unwrap_err().to_string(), error, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn shown_config_reads_back() {
+        for config in [
+            Config::default(),
+            Config {
+                seed: Some(42),
+                sections: Some(vec![String::from("defining_an_enum")]),
+                format: OutputFormat::Json,
+                color: ColorChoice::Always,
+            },
+        ] {
+            assert_eq!(parse(&config.to_string()).unwrap(), (config, Vec::new()));
+        }
+    }
+}
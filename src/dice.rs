@@ -0,0 +1,729 @@
+//! # Rolling dice
+//!
+//! A `Die` has its number of sides in its type: a `D6`, a `D12` or a `D20`,
+//! and is rolled with any `CrateRng`.
+//!
+//! A `DieRoller` hands out the faces of a die, six-sided unless it says
+//! otherwise. Code that rolls dice takes one instead of calling `thread_rng`
+//! itself, so a game can be played with real randomness, with a seed that
+//! replays the same game, or with a script of exactly the rolls a test
+//! needs. A `LoadedDie` is a six-sided die that cheats, drawing from a
+//! seeded RNG or any other `CrateRng`.
+//!
+//! `roll_stats` rolls a die many times and sums up how it behaved, and
+//! `compare_to_theory` checks sums of two dice against the odds
+//! `sum_probabilities` says they should have.
+//!
+//! A `RollHistory` keeps every roll of a game, and answers questions about
+//! streaks and droughts as it goes.
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
+
+use rust_enums::rng::{CrateRng, SeededRng};
+
+use crate::report::Reporter;
+
+/// # A fair die with faces numbered 1 to `SIDES`
+///
+/// A die needs at least two sides. Making a `Die<0>` or `Die<1>` fails to
+/// build, as soon as the compiler generates code for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Die<const SIDES: u8> {
+    _sides: (),
+}
+
+/// A six-sided die
+pub type D6 = Die<6>;
+/// A twelve-sided die
+pub type D12 = Die<12>;
+/// A twenty-sided die
+pub type D20 = Die<20>;
+
+impl<const SIDES: u8> Die<SIDES> {
+    /// Evaluated for every `SIDES` a die is made with
+    const AT_LEAST_TWO_SIDES: () = assert!(SIDES >= 2, "a die needs at least two sides");
+
+    /// A die with `SIDES` sides
+    pub const fn new() -> Self {
+        let () = Self::AT_LEAST_TWO_SIDES;
+        Die { _sides: () }
+    }
+
+    /// A face from 1 to `SIDES`
+    pub fn roll(&self, rng: &mut impl CrateRng) -> u8 {
+        rng.roll_face(SIDES)
+    }
+}
+
+impl<const SIDES: u8> Default for Die<SIDES> {
+    fn default() -> Self {
+        Die::new()
+    }
+}
+
+/// # A source of die rolls
+pub trait DieRoller {
+    /// The face that came up
+    fn roll(&mut self) -> u8;
+}
+
+/// # A fair die, six-sided unless given another, rolled with a seeded
+/// random number generator or any other `CrateRng`
+#[derive(Debug, Clone)]
+pub struct RandomRoller<const SIDES: u8 = 6, R = SeededRng> {
+    rng: R,
+    die: Die<SIDES>,
+}
+
+impl RandomRoller {
+    /// A six-sided die whose rolls are decided by `seed`
+    pub fn from_seed(seed: u64) -> Self {
+        RandomRoller::with_die(D6::new(), seed)
+    }
+}
+
+impl<const SIDES: u8> RandomRoller<SIDES> {
+    /// Roll `die`, with rolls decided by `seed`
+    pub fn with_die(die: Die<SIDES>, seed: u64) -> Self {
+        RandomRoller::with_rng(die, SeededRng::new(seed))
+    }
+}
+
+impl<const SIDES: u8, R: CrateRng> RandomRoller<SIDES, R> {
+    /// Roll `die`, with rolls picked by `rng`
+    pub fn with_rng(die: Die<SIDES>, rng: R) -> Self {
+        RandomRoller { rng, die }
+    }
+}
+
+impl<const SIDES: u8, R: CrateRng> DieRoller for RandomRoller<SIDES, R> {
+    /// A face from 1 to `SIDES`
+    fn roll(&mut self) -> u8 {
+        self.die.roll(&mut self.rng)
+    }
+}
+
+/// # A six-sided die that comes up on some faces more than others
+///
+/// Face `n` comes up in proportion to `weights[n - 1]`, so weights of
+/// `[1.0; 6]` make a fair die and `[0.0, 0.0, 1.0, 0.0, 0.0, 0.0]` one that
+/// always rolls 3.
+#[derive(Debug, Clone)]
+pub struct LoadedDie<R = SeededRng> {
+    weights: [f64; 6],
+    rng: R,
+}
+
+/// # Weights a `LoadedDie` can't be made with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadedDieError {
+    /// The weight for `face` is negative, infinite or NaN
+    InvalidWeight { face: u8 },
+    /// Every weight is zero, so no face could come up
+    AllZero,
+    /// `favoring` was asked for a face other than 1 to 6
+    NoSuchFace(u8),
+}
+
+impl fmt::Display for LoadedDieError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadedDieError::InvalidWeight { face } => {
+                write!(f, "weight for face {face} must be finite and not negative")
+            }
+            LoadedDieError::AllZero => write!(f, "at least one weight must be above zero"),
+            LoadedDieError::NoSuchFace(face) => write!(f, "a six-sided die has no face {face}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadedDieError {}
+
+impl<R: CrateRng> LoadedDie<R> {
+    /// A die rolling each face in proportion to its weight, with rolls
+    /// picked by `rng`
+    pub fn new(weights: [f64; 6], rng: R) -> Result<Self, LoadedDieError> {
+        if let Some(i) = weights.iter().position(|w| !w.is_finite() || *w < 0.0) {
+            return Err(LoadedDieError::InvalidWeight { face: i as u8 + 1 });
+        }
+        // Only an all-zero set of weights is left to reject
+        if weights.iter().all(|w| *w == 0.0) {
+            return Err(LoadedDieError::AllZero);
+        }
+        Ok(LoadedDie { weights, rng })
+    }
+
+    /// A die whose `face` is `factor` times as likely as each of the others,
+    /// with rolls picked by `rng`
+    pub fn favoring(face: u8, factor: f64, rng: R) -> Result<Self, LoadedDieError> {
+        if !(1..=6).contains(&face) {
+            return Err(LoadedDieError::NoSuchFace(face));
+        }
+        let mut weights = [1.0; 6];
+        weights[usize::from(face - 1)] = factor;
+        LoadedDie::new(weights, rng)
+    }
+
+    /// The weight of each face, from 1 to 6
+    pub fn weights(&self) -> [f64; 6] {
+        self.weights
+    }
+}
+
+impl<R: CrateRng> DieRoller for LoadedDie<R> {
+    fn roll(&mut self) -> u8 {
+        // An index into six weights, so 0 to 5; the weights were checked
+        // when the die was made, so there always is one
+        self.rng
+            .pick_weighted(&self.weights)
+            .map_or(1, |face| face as u8 + 1)
+    }
+}
+
+/// # A die that rolls a fixed sequence
+///
+/// Once the script runs out it starts again from the beginning, so a short
+/// script can drive a long game.
+///
+/// The values aren't checked, so a script can roll a 9 or a 0 where a real
+/// die couldn't.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptedRoller(pub VecDeque<u8>);
+
+impl ScriptedRoller {
+    /// A die that rolls `faces` in order, over and over
+    pub fn new(faces: impl IntoIterator<Item = u8>) -> Self {
+        ScriptedRoller(faces.into_iter().collect())
+    }
+}
+
+impl DieRoller for ScriptedRoller {
+    /// # Panics
+    ///
+    /// If the script is empty.
+    fn roll(&mut self) -> u8 {
+        let face = self.0.pop_front().expect("ScriptedRoller has no rolls");
+        self.0.push_back(face);
+        face
+    }
+}
+
+/// # How a run of die rolls came out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RollStats {
+    counts: BTreeMap<u8, usize>,
+    rolls: usize,
+    mean: Option<f64>,
+    longest_run: usize,
+}
+
+impl RollStats {
+    /// How many times `face` came up
+    pub fn count(&self, face: u8) -> usize {
+        self.counts.get(&face).copied().unwrap_or(0)
+    }
+
+    /// How many rolls were made
+    pub fn rolls(&self) -> usize {
+        self.rolls
+    }
+
+    /// The average face, or `None` if there were no rolls
+    pub fn mean(&self) -> Option<f64> {
+        self.mean
+    }
+
+    /// The most times in a row the same face came up
+    pub fn longest_run(&self) -> usize {
+        self.longest_run
+    }
+}
+
+/// Width of the longest bar in the histogram
+const BAR_WIDTH: usize = 40;
+
+/// A histogram with one line per face that came up, its bar scaled so the
+/// most common face fills `BAR_WIDTH`
+impl fmt::Display for RollStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let most = self.counts.values().copied().max().unwrap_or(0);
+        for (face, &count) in &self.counts {
+            let bar = "#".repeat(count * BAR_WIDTH / most);
+            writeln!(f, "{face:>3} | {bar:<BAR_WIDTH$} {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Roll `roller` `n` times, tallying each face, the mean and the longest
+/// run
+pub fn roll_stats(roller: &mut dyn DieRoller, n: usize) -> RollStats {
+    let mut stats = RollStats {
+        rolls: n,
+        ..RollStats::default()
+    };
+    let mut sum = 0;
+    let mut run = (0, 0);
+    for _ in 0..n {
+        let face = roller.roll();
+        *stats.counts.entry(face).or_insert(0) += 1;
+        sum += u64::from(face);
+        run = match run {
+            (last, length) if last == face && length > 0 => (face, length + 1),
+            _ => (face, 1),
+        };
+        stats.longest_run = stats.longest_run.max(run.1);
+    }
+    if n > 0 {
+        stats.mean = Some(sum as f64 / n as f64);
+    }
+    stats
+}
+
+/// # Every roll so far, in order
+///
+/// Besides the rolls themselves it keeps, for every possible value, where
+/// it was last rolled and its longest streak, so questions about one value
+/// don't rescan the whole history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollHistory {
+    rolls: Vec<u8>,
+    /// Index in `rolls` of the latest roll of each value
+    last_seen: [Option<usize>; 256],
+    /// Longest run of each value rolled back to back
+    longest_streak: [usize; 256],
+    /// How many rolls in a row, up to the latest, were the same
+    current_streak: usize,
+}
+
+impl RollHistory {
+    /// A history with no rolls
+    pub fn new() -> Self {
+        RollHistory {
+            rolls: Vec::new(),
+            last_seen: [None; 256],
+            longest_streak: [0; 256],
+            current_streak: 0,
+        }
+    }
+
+    /// Add `roll` to the end of the history
+    pub fn record(&mut self, roll: u8) {
+        self.current_streak = match self.rolls.last() {
+            Some(&last) if last == roll => self.current_streak + 1,
+            _ => 1,
+        };
+        let value = usize::from(roll);
+        self.longest_streak[value] = self.longest_streak[value].max(self.current_streak);
+        self.last_seen[value] = Some(self.rolls.len());
+        self.rolls.push(roll);
+    }
+
+    /// The most times `face` was rolled back to back, 0 if never
+    pub fn longest_streak(&self, face: u8) -> usize {
+        self.longest_streak[usize::from(face)]
+    }
+
+    /// The value with the longest streak, and how long it was
+    ///
+    /// On a tie, the lowest value wins. `None` if nothing was rolled.
+    pub fn best_streak(&self) -> Option<(u8, usize)> {
+        (0..=u8::MAX)
+            .map(|face| (face, self.longest_streak(face)))
+            .filter(|&(_, streak)| streak > 0)
+            .max_by_key(|&(face, streak)| (streak, std::cmp::Reverse(face)))
+    }
+
+    /// How many rolls since `face` last came up
+    ///
+    /// 0 if the latest roll was `face`. If `face` never came up, the whole
+    /// history is a drought.
+    pub fn drought(&self, face: u8) -> usize {
+        match self.last_seen[usize::from(face)] {
+            Some(index) => self.rolls.len() - 1 - index,
+            None => self.rolls.len(),
+        }
+    }
+
+    /// The latest `n` rolls, oldest first, or all of them if there are fewer
+    pub fn last_n(&self, n: usize) -> &[u8] {
+        &self.rolls[self.rolls.len().saturating_sub(n)..]
+    }
+}
+
+impl Default for RollHistory {
+    fn default() -> Self {
+        RollHistory::new()
+    }
+}
+
+/// Roll two dice from `roller`
+pub fn roll_two(roller: &mut dyn DieRoller) -> (u8, u8) {
+    (roller.roll(), roller.roll())
+}
+
+/// The exact chance of each sum of two fair six-sided dice, 2 to 12
+///
+/// A sum can be made by `6 - |sum - 7|` of the 36 pairs of faces.
+pub fn sum_probabilities() -> BTreeMap<u8, f64> {
+    (2..=12u8)
+        .map(|sum| (sum, f64::from(6 - sum.abs_diff(7)) / 36.0))
+        .collect()
+}
+
+/// How far `observed` counts of `n` two-dice sums are from
+/// `sum_probabilities`, as a chi-square statistic
+///
+/// Each sum adds `(observed - expected)² / expected`, so `0.0` is a perfect
+/// match. With 10 degrees of freedom, fair dice land above about 23.2 only
+/// one time in a hundred. A sum outside 2..=12 was never expected, so it
+/// makes the statistic infinite.
+pub fn compare_to_theory(observed: &BTreeMap<u8, usize>, n: usize) -> f64 {
+    let theory = sum_probabilities();
+    if observed
+        .iter()
+        .any(|(sum, &count)| count > 0 && !theory.contains_key(sum))
+    {
+        return f64::INFINITY;
+    }
+    theory
+        .iter()
+        .map(|(sum, p)| {
+            let expected = p * n as f64;
+            let seen = observed.get(sum).copied().unwrap_or(0) as f64;
+            (seen - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// # What many rolls of a die look like, alone and in pairs, every roll
+/// picked by `rng`
+pub fn dice_examples(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let stats = roll_stats(&mut RandomRoller::with_rng(D6::new(), &mut *rng), 10_000);
+    write!(r, "{stats}")?;
+    r.result(format_args!(
+        "{} rolls, {} sixes, mean {:.3}, longest run {}",
+        stats.rolls(),
+        stats.count(6),
+        stats.mean().unwrap_or_default(),
+        stats.longest_run()
+    ))?;
+
+    let mut loaded = LoadedDie::favoring(3, 3.0, &mut *rng).expect("valid weights");
+    let loaded_stats = roll_stats(&mut loaded, 10_000);
+    writeln!(
+        r,
+        "A die with weights {:?} rolls {} threes in 10000, a fair one {}",
+        loaded.weights(),
+        loaded_stats.count(3),
+        stats.count(3)
+    )?;
+
+    writeln!(
+        r,
+        "A d6 rolls {}, a d12 rolls {}, a d20 rolls {}",
+        D6::new().roll(rng),
+        D12::new().roll(rng),
+        D20::new().roll(rng)
+    )?;
+
+    let n = 5_000;
+    let mut roller = RandomRoller::with_rng(D6::new(), &mut *rng);
+    let mut observed = BTreeMap::new();
+    for _ in 0..n {
+        let (a, b) = roll_two(&mut roller);
+        *observed.entry(a + b).or_insert(0) += 1;
+    }
+    r.header("sum  observed  expected")?;
+    for (sum, p) in sum_probabilities() {
+        let seen = observed.get(&sum).copied().unwrap_or(0);
+        writeln!(r, "{sum:>3}  {seen:>8}  {:>8.1}", p * n as f64)?;
+    }
+    writeln!(
+        r,
+        "chi-square against theory: {:.2}",
+        compare_to_theory(&observed, n)
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::distributions::{Distribution, WeightedIndex};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rust_enums::rng::{RecordingRng, ReplayRng};
+
+    use super::*;
+
+    #[test]
+    fn scripted_roller_cycles() {
+        let mut roller = ScriptedRoller::new([1, 2, 3]);
+        let rolls: Vec<u8> = (0..7).map(|_| roller.roll()).collect();
+        assert_eq!(rolls, [1, 2, 3, 1, 2, 3, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "no rolls")]
+    fn empty_script_panics() {
+        ScriptedRoller::new([]).roll();
+    }
+
+    #[test]
+    fn same_seed_same_rolls() {
+        let mut a = RandomRoller::from_seed(3);
+        let mut b = RandomRoller::from_seed(3);
+        for _ in 0..100 {
+            let face = a.roll();
+            assert_eq!(face, b.roll());
+            assert!((1..=6).contains(&face));
+        }
+    }
+
+    #[test]
+    fn scripted_stats_are_exact() {
+        let mut roller = ScriptedRoller::new([2, 2, 2, 5, 1, 1, 6]);
+        let stats = roll_stats(&mut roller, 7);
+        assert_eq!(stats.rolls(), 7);
+        assert_eq!(
+            [1, 2, 3, 4, 5, 6].map(|face| stats.count(face)),
+            [2, 3, 0, 0, 1, 1]
+        );
+        assert_eq!(stats.mean(), Some(19.0 / 7.0));
+        assert_eq!(stats.longest_run(), 3);
+    }
+
+    #[test]
+    fn a_run_can_span_the_script_wrapping_around() {
+        let mut roller = ScriptedRoller::new([4, 3, 4]);
+        assert_eq!(roll_stats(&mut roller, 5).longest_run(), 2);
+    }
+
+    #[test]
+    fn no_rolls_no_mean() {
+        let stats = roll_stats(&mut RandomRoller::from_seed(0), 0);
+        assert_eq!(stats, RollStats::default());
+        assert_eq!(stats.to_string(), "");
+    }
+
+    #[test]
+    fn histogram_scales_to_the_most_common_face() {
+        let stats = roll_stats(&mut ScriptedRoller::new([1, 1, 1, 1, 3, 3]), 6);
+        let bar = "#".repeat(BAR_WIDTH);
+        let half = format!("{:<BAR_WIDTH$}", "#".repeat(BAR_WIDTH / 2));
+        assert_eq!(
+            stats.to_string(),
+            format!("  1 | {bar} 4\n  3 | {half} 2\n")
+        );
+    }
+
+    #[test]
+    fn random_roller_is_a_fair_six_sided_die() {
+        let n = 60_000;
+        let stats = roll_stats(&mut RandomRoller::from_seed(1), n);
+        // Nothing outside 1..=6, and each face close to a sixth of the rolls
+        assert_eq!(stats.count(0) + stats.count(7), 0);
+        for face in 1..=6 {
+            let share = stats.count(face) as f64 / n as f64;
+            assert!((share - 1.0 / 6.0).abs() < 0.01, "{face}: {share}");
+        }
+        let mean = stats.mean().unwrap();
+        assert!((mean - 3.5).abs() < 0.05, "{mean}");
+    }
+
+    #[test]
+    fn sum_probabilities_add_up_to_one() {
+        let theory = sum_probabilities();
+        assert_eq!(theory.len(), 11);
+        assert_eq!(theory[&2], 1.0 / 36.0);
+        assert_eq!(theory[&7], 6.0 / 36.0);
+        assert_eq!(theory[&12], 1.0 / 36.0);
+        assert!((theory.values().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn scripted_pairs_land_in_their_buckets() {
+        let mut roller = ScriptedRoller::new([1, 1, 6, 1, 3, 4, 6, 6]);
+        let mut observed = BTreeMap::new();
+        for _ in 0..4 {
+            let (a, b) = roll_two(&mut roller);
+            *observed.entry(a + b).or_insert(0) += 1;
+        }
+        assert_eq!(observed, BTreeMap::from([(2, 1), (7, 2), (12, 1)]));
+    }
+
+    #[test]
+    fn exact_counts_match_theory_perfectly() {
+        // 36 rolls, one per pair of faces
+        let observed: BTreeMap<u8, usize> = sum_probabilities()
+            .into_iter()
+            .map(|(sum, p)| (sum, (p * 36.0).round() as usize))
+            .collect();
+        assert!(compare_to_theory(&observed, 36).abs() < 1e-12);
+    }
+
+    #[test]
+    fn impossible_or_lopsided_sums_are_far_from_theory() {
+        assert_eq!(
+            compare_to_theory(&BTreeMap::from([(7, 6), (13, 1)]), 7),
+            f64::INFINITY
+        );
+        // Always rolling 7 is nothing like fair dice
+        assert!(compare_to_theory(&BTreeMap::from([(7, 360)]), 360) > 100.0);
+    }
+
+    #[test]
+    fn seeded_pairs_are_close_to_theory() {
+        let n = 36_000;
+        let mut roller = RandomRoller::from_seed(5);
+        let mut observed = BTreeMap::new();
+        for _ in 0..n {
+            let (a, b) = roll_two(&mut roller);
+            *observed.entry(a + b).or_insert(0) += 1;
+        }
+        let chi_square = compare_to_theory(&observed, n);
+        assert!(chi_square < 23.2, "{chi_square}");
+    }
+
+    #[test]
+    fn d20_rolls_every_face_and_nothing_else() {
+        let die = D20::new();
+        let mut rng = SeededRng::new(20);
+        let mut seen = [false; 21];
+        for _ in 0..10_000 {
+            let face = die.roll(&mut rng);
+            assert!((1..=20).contains(&face), "{face}");
+            seen[usize::from(face)] = true;
+        }
+        assert!(!seen[0] && seen[1..].iter().all(|&s| s));
+    }
+
+    #[test]
+    fn d6_roller_rolls_as_the_fixed_die_did() {
+        for seed in [0, 1, 7, 12] {
+            let mut roller = RandomRoller::from_seed(seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..1_000 {
+                assert_eq!(roller.roll(), rng.gen_range(1..=6));
+            }
+        }
+    }
+
+    #[test]
+    fn two_sides_is_enough() {
+        let mut roller = RandomRoller::with_die(Die::<2>::new(), 2);
+        assert!((0..100).all(|_| matches!(roller.roll(), 1 | 2)));
+    }
+
+    fn history(rolls: &[u8]) -> RollHistory {
+        let mut history = RollHistory::new();
+        for &roll in rolls {
+            history.record(roll);
+        }
+        history
+    }
+
+    #[test]
+    fn streaks() {
+        let history = history(&[7, 7, 3, 7, 7, 7, 2, 3, 3]);
+        assert_eq!(history.longest_streak(7), 3);
+        assert_eq!(history.longest_streak(3), 2);
+        assert_eq!(history.longest_streak(2), 1);
+        assert_eq!(history.longest_streak(12), 0);
+        assert_eq!(history.best_streak(), Some((7, 3)));
+    }
+
+    #[test]
+    fn ties_go_to_the_lowest_value() {
+        assert_eq!(history(&[9, 9, 4, 4]).best_streak(), Some((4, 2)));
+        assert_eq!(RollHistory::new().best_streak(), None);
+    }
+
+    #[test]
+    fn droughts() {
+        let history = history(&[7, 7, 3, 7, 7, 7, 2, 3, 3]);
+        assert_eq!(history.drought(3), 0);
+        assert_eq!(history.drought(2), 2);
+        assert_eq!(history.drought(7), 3);
+        // Never rolled: the drought is as long as the history
+        assert_eq!(history.drought(12), 9);
+        assert_eq!(RollHistory::new().drought(7), 0);
+    }
+
+    #[test]
+    fn last_n_rolls() {
+        let history = history(&[1, 2, 3, 4]);
+        assert_eq!(history.last_n(2), [3, 4]);
+        assert_eq!(history.last_n(4), [1, 2, 3, 4]);
+        assert_eq!(history.last_n(10), [1, 2, 3, 4]);
+        assert!(history.last_n(0).is_empty());
+        assert!(RollHistory::new().last_n(3).is_empty());
+    }
+
+    #[test]
+    fn loaded_die_rejects_bad_weights() {
+        assert_eq!(
+            LoadedDie::new([1.0, 1.0, -0.5, 1.0, 1.0, 1.0], SeededRng::new(0)).unwrap_err(),
+            LoadedDieError::InvalidWeight { face: 3 }
+        );
+        assert_eq!(
+            LoadedDie::new([f64::NAN, 1.0, 1.0, 1.0, 1.0, 1.0], SeededRng::new(0)).unwrap_err(),
+            LoadedDieError::InvalidWeight { face: 1 }
+        );
+        assert_eq!(
+            LoadedDie::new([0.0; 6], SeededRng::new(0)).unwrap_err(),
+            LoadedDieError::AllZero
+        );
+        assert_eq!(
+            LoadedDie::favoring(7, 2.0, SeededRng::new(0)).unwrap_err(),
+            LoadedDieError::NoSuchFace(7)
+        );
+        assert_eq!(
+            LoadedDie::favoring(6, -1.0, SeededRng::new(0))
+                .unwrap_err()
+                .to_string(),
+            "weight for face 6 must be finite and not negative"
+        );
+    }
+
+    #[test]
+    fn loaded_die_rolls_as_a_weighted_index_would() {
+        let weights = [1.0, 2.0, 0.5, 0.0, 3.0, 1.0];
+        let mut die = LoadedDie::new(weights, SeededRng::new(8)).unwrap();
+        let faces = WeightedIndex::new(weights).unwrap();
+        let mut rng = StdRng::seed_from_u64(8);
+        for _ in 0..1_000 {
+            assert_eq!(usize::from(die.roll()), faces.sample(&mut rng) + 1);
+        }
+    }
+
+    #[test]
+    fn loaded_die_replays_its_rolls() {
+        let weights = [1.0, 1.0, 4.0, 1.0, 1.0, 1.0];
+        let mut die = LoadedDie::new(weights, RecordingRng::new(SeededRng::new(6))).unwrap();
+        let rolls: Vec<u8> = (0..50).map(|_| die.roll()).collect();
+        let log = die.rng.into_log();
+        let mut again = LoadedDie::new(weights, ReplayRng::new(log)).unwrap();
+        assert!(rolls.iter().all(|&roll| again.roll() == roll));
+        assert!(again.rng.is_done());
+    }
+
+    #[test]
+    fn fully_loaded_die_always_rolls_its_face() {
+        let mut die = LoadedDie::new([0.0, 0.0, 0.0, 0.0, 1.0, 0.0], SeededRng::new(3)).unwrap();
+        assert!((0..1_000).all(|_| die.roll() == 5));
+    }
+
+    #[test]
+    fn favored_face_comes_up_as_often_as_its_weight_says() {
+        let n = 60_000;
+        let mut die = LoadedDie::favoring(3, 2.0, SeededRng::new(4)).unwrap();
+        let stats = roll_stats(&mut die, n);
+        // Weights of 2 against five 1s: a 3 two times in seven
+        let share = stats.count(3) as f64 / n as f64;
+        assert!((share - 2.0 / 7.0).abs() < 0.01, "{share}");
+        assert!(share > 1.0 / 6.0 + 0.1);
+        assert_eq!(stats.count(0) + stats.count(7), 0);
+    }
+}
@@ -0,0 +1,765 @@
+//! # The fancy-hat dice game
+//!
+//! The catch-all examples in `main` describe a game: roll a 3 and your
+//! player gets a fancy hat, roll a 7 and they lose one, roll anything else
+//! and they move that many spaces. `resolve_roll` says which of those a roll
+//! calls for, as a `GameEvent`. A `DiceGame` plays it for real, and each turn
+//! reports what happened as a `TurnOutcome`.
+//!
+//! The examples go on to change what "anything else" means, so that is up to
+//! the game's `GameRules`.
+//!
+//! Each roll is the sum of two dice from a `DieRoller`.
+//!
+//! A `Tournament` plays the same game with several players taking turns,
+//! and ranks them as it goes. `simulate_games` plays it many times over to
+//! show what each set of rules does on average.
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::dice::{DieRoller, RandomRoller, RollHistory};
+
+/// # Where the player is, and what they're wearing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Player {
+    position: u8,
+    hats: u8,
+}
+
+impl Player {
+    /// The space the player is on, counting from 0
+    pub fn position(&self) -> u8 {
+        self.position
+    }
+
+    /// How many fancy hats the player has
+    pub fn hats(&self) -> u8 {
+        self.hats
+    }
+
+    /// Give the player another hat, returning how many they have now
+    ///
+    /// A player with `u8::MAX` hats has no room for more.
+    pub fn add_fancy_hat(&mut self) -> u8 {
+        self.hats = self.hats.saturating_add(1);
+        self.hats
+    }
+
+    /// Take a hat away, returning how many are left, or `None` if the
+    /// player had none
+    pub fn remove_fancy_hat(&mut self) -> Option<u8> {
+        let hats = self.hats.checked_sub(1)?;
+        self.hats = hats;
+        Some(hats)
+    }
+
+    /// Move `spaces` forward on a circular board of `board_size` spaces,
+    /// returning the new position
+    ///
+    /// # Panics
+    ///
+    /// If `board_size` is 0.
+    pub fn move_player(&mut self, spaces: u8, board_size: u8) -> u8 {
+        // Less than `board_size`, so back in a `u8`
+        let to = (u16::from(self.position) + u16::from(spaces)) % u16::from(board_size);
+        self.position = to as u8;
+        self.position
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} hat(s), on space {}", self.hats, self.position)
+    }
+}
+
+/// # What happens on a roll other than 3 or 7
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[allow(clippy::enum_variant_names)] // each names what happens "on other" rolls
+pub enum GameRules {
+    /// Move that many spaces
+    #[default]
+    MoveOnOther,
+    /// Roll again, up to `MAX_REROLLS` times in one turn
+    RerollOnOther,
+    /// Nothing; the turn is over
+    NothingOnOther,
+}
+
+/// Most rerolls `DiceGame::play_turn` allows in one turn under
+/// `GameRules::RerollOnOther`, so an unlucky run of rolls can't go on forever
+pub const MAX_REROLLS: usize = 3;
+
+/// # What a roll calls for, before it is applied to a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A 3
+    GainedHat,
+    /// A 7
+    LostHat,
+    /// Move this many spaces
+    Moved(u8),
+    /// Roll again
+    Rerolled,
+    /// The turn is over
+    Nothing,
+}
+
+/// What `roll` calls for under `rules`
+///
+/// This is the `match` from the catch-all examples, one arm per rule for
+/// the rolls that aren't a 3 or a 7.
+pub fn resolve_roll(roll: u8, rules: GameRules) -> GameEvent {
+    match (roll, rules) {
+        (3, _) => GameEvent::GainedHat,
+        (7, _) => GameEvent::LostHat,
+        (other, GameRules::MoveOnOther) => GameEvent::Moved(other),
+        (_, GameRules::RerollOnOther) => GameEvent::Rerolled,
+        (_, GameRules::NothingOnOther) => GameEvent::Nothing,
+    }
+}
+
+/// # What one roll did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnOutcome {
+    /// A 3: the player now has `hats` hats
+    GainedHat { hats: u8 },
+    /// A 7: the player now has `hats` hats
+    LostHat { hats: u8 },
+    /// A 7, but the player had no hat to lose
+    NoHatToLose,
+    /// Any other roll: the player moved from `from` to `to`
+    Moved { from: u8, to: u8 },
+    /// Any other roll, under `GameRules::RerollOnOther`
+    MustReroll,
+    /// Any other roll, under `GameRules::NothingOnOther`
+    NoEffect,
+}
+
+/// # A player on a circular board of `board_size` spaces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceGame {
+    player: Player,
+    board_size: u8,
+    rules: GameRules,
+    history: RollHistory,
+}
+
+impl DiceGame {
+    /// A new player at space 0 with no hats, playing by the default
+    /// `GameRules`, or `None` if `board_size` is 0
+    pub fn new(board_size: u8) -> Option<DiceGame> {
+        DiceGame::with_rules(board_size, GameRules::default())
+    }
+
+    /// `new`, but playing by `rules`
+    pub fn with_rules(board_size: u8, rules: GameRules) -> Option<DiceGame> {
+        (board_size > 0).then_some(DiceGame {
+            player: Player::default(),
+            board_size,
+            rules,
+            history: RollHistory::new(),
+        })
+    }
+
+    /// The rules this game is played by
+    pub fn rules(&self) -> GameRules {
+        self.rules
+    }
+
+    /// The player's current state
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// Every roll played so far, rerolls included
+    pub fn history(&self) -> &RollHistory {
+        &self.history
+    }
+
+    /// Play `turns` full turns, rolling two dice from `roller` for each roll
+    ///
+    /// Returns the outcome of every roll, rerolls included, in order.
+    pub fn play(&mut self, roller: &mut dyn DieRoller, turns: usize) -> Vec<TurnOutcome> {
+        let mut transcript = Vec::new();
+        for _ in 0..turns {
+            transcript.extend(self.play_turn(|| roller.roll() + roller.roll()));
+        }
+        transcript
+    }
+
+    /// Play a turn at a time with `roller`, asking on `input` after each one
+    /// whether to go on
+    ///
+    /// Every outcome and the player afterwards are written to `output`,
+    /// followed by the question. An empty answer or `y` plays another turn,
+    /// and `q` or the end of `input` stops. Returns how many turns were
+    /// played.
+    pub fn play_interactive(
+        &mut self,
+        roller: &mut dyn DieRoller,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> io::Result<usize> {
+        let mut line = String::new();
+        for turn in 1.. {
+            for outcome in self.play(roller, 1) {
+                writeln!(output, "turn {turn}: {outcome:?}")?;
+            }
+            writeln!(output, "{}", self.player)?;
+            loop {
+                write!(output, "roll again? [Y/q] ")?;
+                output.flush()?;
+                line.clear();
+                if input.read_line(&mut line)? == 0 {
+                    writeln!(output)?;
+                    return Ok(turn);
+                }
+                match line.trim() {
+                    "" | "y" | "Y" => break,
+                    "q" | "Q" => return Ok(turn),
+                    other => writeln!(output, "{other:?}: answer y or q")?,
+                }
+            }
+        }
+        unreachable!("ran out of turn numbers")
+    }
+
+    /// Play one full turn, taking rolls from `next_roll`
+    ///
+    /// While `take_turn` says `MustReroll`, rolls again, but no more than
+    /// `MAX_REROLLS` times; if the last reroll still says `MustReroll`, that
+    /// is the turn's outcome. Returns the outcome of every roll, in order.
+    pub fn play_turn(&mut self, mut next_roll: impl FnMut() -> u8) -> Vec<TurnOutcome> {
+        let mut outcomes = vec![self.take_turn(next_roll())];
+        while outcomes.last() == Some(&TurnOutcome::MustReroll) && outcomes.len() <= MAX_REROLLS {
+            outcomes.push(self.take_turn(next_roll()));
+        }
+        outcomes
+    }
+
+    /// Play one `roll`
+    ///
+    /// Moving past the last space carries on from space 0.
+    pub fn take_turn(&mut self, roll: u8) -> TurnOutcome {
+        self.history.record(roll);
+        let player = &mut self.player;
+        match resolve_roll(roll, self.rules) {
+            GameEvent::GainedHat => TurnOutcome::GainedHat {
+                hats: player.add_fancy_hat(),
+            },
+            GameEvent::LostHat => match player.remove_fancy_hat() {
+                Some(hats) => TurnOutcome::LostHat { hats },
+                None => TurnOutcome::NoHatToLose,
+            },
+            GameEvent::Rerolled => TurnOutcome::MustReroll,
+            GameEvent::Nothing => TurnOutcome::NoEffect,
+            GameEvent::Moved(spaces) => TurnOutcome::Moved {
+                from: player.position,
+                to: player.move_player(spaces, self.board_size),
+            },
+        }
+    }
+}
+
+/// # Several named players taking turns on one board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tournament {
+    players: Vec<(String, Player)>,
+    board_size: u8,
+    rules: GameRules,
+}
+
+/// # Where one player of a `Tournament` stands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    /// 1 for the leader
+    pub place: usize,
+    pub name: String,
+    pub player: Player,
+}
+
+impl Tournament {
+    /// A tournament for `names`, each starting at space 0 with no hats,
+    /// playing by the default `GameRules`, or `None` if `board_size` is 0
+    pub fn new<S: Into<String>>(
+        board_size: u8,
+        names: impl IntoIterator<Item = S>,
+    ) -> Option<Tournament> {
+        (board_size > 0).then(|| Tournament {
+            players: names
+                .into_iter()
+                .map(|name| (name.into(), Player::default()))
+                .collect(),
+            board_size,
+            rules: GameRules::default(),
+        })
+    }
+
+    /// Give every player one turn, in the order they were named
+    pub fn play_round(&mut self, roller: &mut dyn DieRoller) {
+        for (_, player) in &mut self.players {
+            let mut game = DiceGame {
+                player: *player,
+                board_size: self.board_size,
+                rules: self.rules,
+                history: RollHistory::new(),
+            };
+            game.play(roller, 1);
+            *player = game.player;
+        }
+    }
+
+    /// Every player, furthest along the board first, then most hats, then
+    /// by name
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut players = self.players.clone();
+        players.sort_by(|(a_name, a), (b_name, b)| {
+            (b.position, b.hats)
+                .cmp(&(a.position, a.hats))
+                .then_with(|| a_name.cmp(b_name))
+        });
+        players
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, player))| Standing {
+                place: i + 1,
+                name,
+                player,
+            })
+            .collect()
+    }
+
+    /// Play `rounds` rounds, returning the leader at the end, or `None` if
+    /// there are no players
+    pub fn winner_after(&mut self, roller: &mut dyn DieRoller, rounds: usize) -> Option<Standing> {
+        for _ in 0..rounds {
+            self.play_round(roller);
+        }
+        self.standings().into_iter().next()
+    }
+}
+
+/// Spaces on the board for every game `simulate_games` plays
+pub const SIMULATION_BOARD_SIZE: u8 = 20;
+
+/// # How a batch of simulated games ended
+///
+/// With no games at all, every figure is 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationSummary {
+    pub games: usize,
+    pub mean_position: f64,
+    pub mean_hats: f64,
+    /// Share of games, from 0 to 1, that ended with the player hatless
+    pub zero_hat_fraction: f64,
+    pub min_position: u8,
+    pub max_position: u8,
+    pub min_hats: u8,
+    pub max_hats: u8,
+}
+
+impl fmt::Display for SimulationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} game(s): position {:.2} on average ({}..={}), hats {:.2} on average ({}..={}), \
+             {:.1}% without a hat",
+            self.games,
+            self.mean_position,
+            self.min_position,
+            self.max_position,
+            self.mean_hats,
+            self.min_hats,
+            self.max_hats,
+            self.zero_hat_fraction * 100.0
+        )
+    }
+}
+
+/// Play `games` games of `turns_per_game` turns each under `rules`, one
+/// after another with rolls from a single die seeded with `seed`, and sum
+/// up where the players ended
+pub fn simulate_games(
+    games: usize,
+    turns_per_game: usize,
+    rules: GameRules,
+    seed: u64,
+) -> SimulationSummary {
+    let mut roller = RandomRoller::from_seed(seed);
+    let players: Vec<Player> = (0..games)
+        .map(|_| {
+            let mut game =
+                DiceGame::with_rules(SIMULATION_BOARD_SIZE, rules).expect("board has spaces");
+            game.play(&mut roller, turns_per_game);
+            game.player
+        })
+        .collect();
+    let mean = |total: usize| match games {
+        0 => 0.0,
+        n => total as f64 / n as f64,
+    };
+    let positions = players.iter().map(|p| p.position);
+    let hats = players.iter().map(|p| p.hats);
+    SimulationSummary {
+        games,
+        mean_position: mean(positions.clone().map(usize::from).sum()),
+        mean_hats: mean(hats.clone().map(usize::from).sum()),
+        zero_hat_fraction: mean(players.iter().filter(|p| p.hats == 0).count()),
+        min_position: positions.clone().min().unwrap_or(0),
+        max_position: positions.max().unwrap_or(0),
+        min_hats: hats.clone().min().unwrap_or(0),
+        max_hats: hats.max().unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> DiceGame {
+        DiceGame::new(10).unwrap()
+    }
+
+    #[test]
+    fn three_gains_a_hat() {
+        let mut game = game();
+        assert_eq!(game.take_turn(3), TurnOutcome::GainedHat { hats: 1 });
+        assert_eq!(game.take_turn(3), TurnOutcome::GainedHat { hats: 2 });
+        assert_eq!(game.player().hats(), 2);
+        assert_eq!(game.player().position(), 0);
+    }
+
+    #[test]
+    fn seven_loses_a_hat_but_not_below_zero() {
+        let mut game = game();
+        assert_eq!(game.take_turn(7), TurnOutcome::NoHatToLose);
+        assert_eq!(game.player().hats(), 0);
+        game.take_turn(3);
+        assert_eq!(game.take_turn(7), TurnOutcome::LostHat { hats: 0 });
+    }
+
+    #[test]
+    fn moves_wrap_around_the_board() {
+        let mut game = game();
+        assert_eq!(game.take_turn(8), TurnOutcome::Moved { from: 0, to: 8 });
+        assert_eq!(game.take_turn(5), TurnOutcome::Moved { from: 8, to: 3 });
+        // A roll bigger than the board goes round more than once
+        assert_eq!(game.take_turn(255), TurnOutcome::Moved { from: 3, to: 8 });
+    }
+
+    #[test]
+    fn fixed_rolls_reach_a_known_state() {
+        let mut game = DiceGame::new(6).unwrap();
+        for roll in [3, 4, 3, 7, 5, 12, 3, 2] {
+            game.take_turn(roll);
+        }
+        // Hats: +1 +1 -1 +1; moves: 4, 5, 12, 2 spaces on a 6-space board
+        assert_eq!(game.player().hats(), 2);
+        assert_eq!(game.player().position(), (4 + 5 + 12 + 2) % 6);
+    }
+
+    #[test]
+    fn empty_board_is_refused() {
+        assert_eq!(DiceGame::new(0), None);
+    }
+
+    #[test]
+    fn rules_decide_what_a_nine_does() {
+        let outcome = |rules| DiceGame::with_rules(10, rules).unwrap().take_turn(9);
+        assert_eq!(
+            outcome(GameRules::MoveOnOther),
+            TurnOutcome::Moved { from: 0, to: 9 }
+        );
+        assert_eq!(outcome(GameRules::RerollOnOther), TurnOutcome::MustReroll);
+        assert_eq!(outcome(GameRules::NothingOnOther), TurnOutcome::NoEffect);
+        // 3 and 7 mean the same whatever the rules
+        for rules in [GameRules::RerollOnOther, GameRules::NothingOnOther] {
+            let mut game = DiceGame::with_rules(10, rules).unwrap();
+            assert_eq!(game.take_turn(3), TurnOutcome::GainedHat { hats: 1 });
+            assert_eq!(game.take_turn(9), outcome(rules));
+            assert_eq!(game.player().position(), 0);
+        }
+    }
+
+    #[test]
+    fn rerolls_until_a_three_or_seven() {
+        let mut game = DiceGame::with_rules(10, GameRules::RerollOnOther).unwrap();
+        let mut rolls = [9, 4, 3].into_iter();
+        assert_eq!(
+            game.play_turn(|| rolls.next().unwrap()),
+            [
+                TurnOutcome::MustReroll,
+                TurnOutcome::MustReroll,
+                TurnOutcome::GainedHat { hats: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn rerolls_are_bounded() {
+        let mut game = DiceGame::with_rules(10, GameRules::RerollOnOther).unwrap();
+        let mut rolled = 0;
+        let outcomes = game.play_turn(|| {
+            rolled += 1;
+            9
+        });
+        assert_eq!(rolled, 1 + MAX_REROLLS);
+        assert_eq!(outcomes, [TurnOutcome::MustReroll; 1 + MAX_REROLLS]);
+    }
+
+    #[test]
+    fn play_turn_without_rerolls_takes_one_roll() {
+        let mut game = game();
+        assert_eq!(
+            game.play_turn(|| 9),
+            [TurnOutcome::Moved { from: 0, to: 9 }]
+        );
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        use crate::dice::RandomRoller;
+
+        let play = || {
+            let mut game = DiceGame::with_rules(12, GameRules::RerollOnOther).unwrap();
+            let transcript = game.play(&mut RandomRoller::from_seed(11), 50);
+            (transcript, game)
+        };
+        let (transcript, game) = play();
+        assert!(transcript.len() >= 50);
+        assert_eq!(play(), (transcript, game));
+    }
+
+    #[test]
+    fn scripted_game_has_a_known_transcript() {
+        use crate::dice::ScriptedRoller;
+
+        let mut game = game();
+        // Rolls of 3, 9, 7, 12 and then 3 again as the script wraps around
+        let mut roller = ScriptedRoller::new([1, 2, 4, 5, 3, 4, 6, 6]);
+        assert_eq!(
+            game.play(&mut roller, 5),
+            [
+                TurnOutcome::GainedHat { hats: 1 },
+                TurnOutcome::Moved { from: 0, to: 9 },
+                TurnOutcome::LostHat { hats: 0 },
+                TurnOutcome::Moved { from: 9, to: 1 },
+                TurnOutcome::GainedHat { hats: 1 },
+            ]
+        );
+        assert_eq!(game.player().position(), 1);
+        assert_eq!(game.player().hats(), 1);
+    }
+
+    #[test]
+    fn three_and_seven_mean_the_same_under_every_rule() {
+        for rules in [
+            GameRules::MoveOnOther,
+            GameRules::RerollOnOther,
+            GameRules::NothingOnOther,
+        ] {
+            assert_eq!(resolve_roll(3, rules), GameEvent::GainedHat);
+            assert_eq!(resolve_roll(7, rules), GameEvent::LostHat);
+        }
+    }
+
+    #[test]
+    fn other_rolls_depend_on_the_rules() {
+        for roll in [0, 2, 4, 6, 8, 9, 12, 255] {
+            assert_eq!(
+                resolve_roll(roll, GameRules::MoveOnOther),
+                GameEvent::Moved(roll)
+            );
+            assert_eq!(
+                resolve_roll(roll, GameRules::RerollOnOther),
+                GameEvent::Rerolled
+            );
+            assert_eq!(
+                resolve_roll(roll, GameRules::NothingOnOther),
+                GameEvent::Nothing
+            );
+        }
+    }
+
+    #[test]
+    fn interactive_game_follows_the_answers() {
+        use crate::dice::ScriptedRoller;
+
+        let mut game = game();
+        let mut roller = ScriptedRoller::new([1, 2, 4, 5, 3, 4]);
+        let mut output = Vec::new();
+        let turns = game
+            .play_interactive(&mut roller, &b"\nmaybe\ny\nq\n"[..], &mut output)
+            .unwrap();
+        assert_eq!(turns, 3);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "turn 1: GainedHat { hats: 1 }\n\
+             1 hat(s), on space 0\n\
+             roll again? [Y/q] \
+             turn 2: Moved { from: 0, to: 9 }\n\
+             1 hat(s), on space 9\n\
+             roll again? [Y/q] \"maybe\": answer y or q\n\
+             roll again? [Y/q] \
+             turn 3: LostHat { hats: 0 }\n\
+             0 hat(s), on space 9\n\
+             roll again? [Y/q] "
+        );
+    }
+
+    #[test]
+    fn interactive_game_stops_at_the_end_of_input() {
+        use crate::dice::ScriptedRoller;
+
+        let mut output = Vec::new();
+        let turns = game()
+            .play_interactive(&mut ScriptedRoller::new([1, 2]), &b""[..], &mut output)
+            .unwrap();
+        assert_eq!(turns, 1);
+        assert!(String::from_utf8(output).unwrap().ends_with("[Y/q] \n"));
+    }
+
+    #[test]
+    fn add_fancy_hat_saturates() {
+        let mut player = Player::default();
+        assert_eq!(player.add_fancy_hat(), 1);
+        assert_eq!(player.hats(), 1);
+        player.hats = u8::MAX;
+        assert_eq!(player.add_fancy_hat(), u8::MAX);
+    }
+
+    #[test]
+    fn remove_fancy_hat_does_not_underflow() {
+        let mut player = Player::default();
+        assert_eq!(player.remove_fancy_hat(), None);
+        assert_eq!(player.hats(), 0);
+        player.add_fancy_hat();
+        player.add_fancy_hat();
+        assert_eq!(player.remove_fancy_hat(), Some(1));
+        assert_eq!(player.hats(), 1);
+    }
+
+    #[test]
+    fn move_player_wraps_and_keeps_hats() {
+        let mut player = Player::default();
+        player.add_fancy_hat();
+        assert_eq!(player.move_player(7, 10), 7);
+        assert_eq!(player.move_player(7, 10), 4);
+        assert_eq!(player.move_player(255, 255), 4);
+        assert_eq!(
+            player,
+            Player {
+                position: 4,
+                hats: 1
+            }
+        );
+    }
+
+    #[test]
+    fn every_roll_goes_into_the_history() {
+        let mut game = DiceGame::with_rules(10, GameRules::RerollOnOther).unwrap();
+        let mut rolls = [5, 5, 5, 3, 7].into_iter();
+        game.play_turn(|| rolls.next().unwrap());
+        game.take_turn(7);
+        let history = game.history();
+        assert_eq!(history.last_n(10), [5, 5, 5, 3, 7]);
+        assert_eq!(history.best_streak(), Some((5, 3)));
+        assert_eq!(history.drought(3), 1);
+    }
+
+    #[test]
+    fn scripted_tournament_standings() {
+        use crate::dice::ScriptedRoller;
+
+        let mut tournament = Tournament::new(20, ["dee", "bea", "cal", "abe"]).unwrap();
+        // Round 1: dee 4, bea 5, cal 4, abe 4
+        // Round 2: dee 3 (a hat), bea 7 (no hat to lose), cal 7, abe 3
+        let mut roller = ScriptedRoller::new([2, 2, 2, 3, 2, 2, 2, 2, 1, 2, 6, 1, 3, 4, 1, 2]);
+        tournament.play_round(&mut roller);
+        tournament.play_round(&mut roller);
+        let standings: Vec<_> = tournament
+            .standings()
+            .into_iter()
+            .map(|s| (s.place, s.name, s.player.position(), s.player.hats()))
+            .collect();
+        assert_eq!(
+            standings,
+            [
+                (1, String::from("bea"), 5, 0),
+                // Level with dee on space and hats, so it comes down to names
+                (2, String::from("abe"), 4, 1),
+                (3, String::from("dee"), 4, 1),
+                // Level with abe and dee on space, but with fewer hats
+                (4, String::from("cal"), 4, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn winner_after_some_rounds() {
+        use crate::dice::ScriptedRoller;
+
+        let mut tournament = Tournament::new(10, ["a", "b"]).unwrap();
+        // a always rolls 4, b always rolls 5
+        let mut roller = ScriptedRoller::new([2, 2, 2, 3]);
+        let winner = tournament.winner_after(&mut roller, 1).unwrap();
+        assert_eq!((winner.place, winner.name.as_str()), (1, "b"));
+        // Three more rounds: a on 16 % 10 = 6, b on 20 % 10 = 0
+        let winner = tournament.winner_after(&mut roller, 3).unwrap();
+        assert_eq!(winner.name, "a");
+        assert_eq!(winner.player.position(), 6);
+    }
+
+    #[test]
+    fn empty_tournament_has_no_winner() {
+        let mut tournament = Tournament::new(10, Vec::<String>::new()).unwrap();
+        assert_eq!(
+            tournament.winner_after(&mut crate::dice::RandomRoller::from_seed(0), 5),
+            None
+        );
+        assert!(Tournament::new(0, ["a"]).is_none());
+    }
+
+    #[test]
+    fn small_simulation_pinned() {
+        let summary = simulate_games(5, 10, GameRules::MoveOnOther, 1);
+        assert_eq!(
+            summary,
+            SimulationSummary {
+                games: 5,
+                mean_position: 11.0,
+                mean_hats: 0.8,
+                zero_hat_fraction: 0.4,
+                min_position: 8,
+                max_position: 15,
+                min_hats: 0,
+                max_hats: 2,
+            }
+        );
+        assert_eq!(
+            summary.to_string(),
+            "5 game(s): position 11.00 on average (8..=15), hats 0.80 on average (0..=2), \
+             40.0% without a hat"
+        );
+    }
+
+    #[test]
+    fn moving_gets_further_than_standing_still() {
+        for seed in 0..5 {
+            let moving = simulate_games(50, 10, GameRules::MoveOnOther, seed);
+            let still = simulate_games(50, 10, GameRules::NothingOnOther, seed);
+            assert!(moving.mean_position >= still.mean_position, "seed {seed}");
+            assert_eq!(still.max_position, 0);
+        }
+    }
+
+    #[test]
+    fn no_games_no_figures() {
+        let summary = simulate_games(0, 10, GameRules::MoveOnOther, 0);
+        assert_eq!(summary.games, 0);
+        assert_eq!(summary.mean_position, 0.0);
+        assert_eq!(summary.zero_hat_fraction, 0.0);
+        assert_eq!(summary.max_hats, 0);
+    }
+}
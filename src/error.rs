@@ -0,0 +1,248 @@
+//! # One error type for the whole crate
+//!
+//! Each module has an error type of its own, saying exactly what went wrong
+//! there. `EnumsError` has a variant wrapping each of them, and a `From`
+//! impl for each, so code that calls into several modules can use `?` on
+//! all of them and still hand back a single type.
+//!
+//! An `EnumsError` displays as the error it wraps, and gives that error as
+//! its `source()`, so the cause is kept whichever way it's read:
+//!
+//! ```
+//! use rust_enums::error::EnumsError;
+//! use rust_enums::Coin2;
+//!
+//! fn cents(name: &str) -> Result<u32, EnumsError> {
+//!     Ok(name.parse::<Coin2>()?.cents())
+//! }
+//!
+//! let e = cents("button").unwrap_err();
+//! assert_eq!(e.to_string(), r#""button" is not a coin"#);
+//! assert!(matches!(e, EnumsError::Coin(_)));
+//! ```
+use core::error::Error;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "std")]
+use crate::canvas::ApplyError;
+use crate::codec::{DecodeError, EncodeError};
+use crate::coin::{UnknownCoin, UnknownState};
+use crate::color::ColorError;
+use crate::execute::MessageError;
+#[cfg(feature = "std")]
+use crate::history::UndoError;
+use crate::ip::InvalidAddress;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, JsonlError};
+use crate::message::MessageParseError;
+#[cfg(feature = "std")]
+use crate::script::ScriptError;
+#[cfg(feature = "std")]
+use crate::stream::StreamError;
+
+/// # Any error from this crate
+#[derive(Debug)]
+pub enum EnumsError {
+    /// A name that isn't a coin
+    Coin(UnknownCoin),
+    /// A name that isn't a state
+    State(UnknownState),
+    /// Text that isn't an IP address
+    Address(InvalidAddress),
+    /// A color that couldn't be made
+    Color(ColorError),
+    /// A command that didn't parse as a `Message`
+    Parse(MessageParseError),
+    /// Bytes that didn't decode as a `Message`
+    Decode(DecodeError),
+    /// A `Message` too long to encode
+    Encode(EncodeError),
+    /// A `Message` that couldn't be executed
+    Message(MessageError),
+    /// A `Message` a `Canvas` wouldn't apply
+    #[cfg(feature = "std")]
+    Apply(ApplyError),
+    /// An undo with nothing to undo
+    #[cfg(feature = "std")]
+    Undo(UndoError),
+    /// A script that stopped early
+    #[cfg(feature = "std")]
+    Script(ScriptError),
+    /// A stream of framed `Message`s that couldn't be read
+    #[cfg(feature = "std")]
+    Stream(StreamError),
+    /// JSON that isn't a `Message`
+    #[cfg(feature = "json")]
+    Json(JsonError),
+    /// JSON lines that couldn't be read as `Message`s
+    #[cfg(feature = "json")]
+    Jsonl(JsonlError),
+    /// Reading or writing failed
+    #[cfg(feature = "std")]
+    Io(io::Error),
+}
+
+impl EnumsError {
+    /// The error this one wraps
+    fn inner(&self) -> &(dyn Error + 'static) {
+        match self {
+            EnumsError::Coin(e) => e,
+            EnumsError::State(e) => e,
+            EnumsError::Address(e) => e,
+            EnumsError::Color(e) => e,
+            EnumsError::Parse(e) => e,
+            EnumsError::Decode(e) => e,
+            EnumsError::Encode(e) => e,
+            EnumsError::Message(e) => e,
+            #[cfg(feature = "std")]
+            EnumsError::Apply(e) => e,
+            #[cfg(feature = "std")]
+            EnumsError::Undo(e) => e,
+            #[cfg(feature = "std")]
+            EnumsError::Script(e) => e,
+            #[cfg(feature = "std")]
+            EnumsError::Stream(e) => e,
+            #[cfg(feature = "json")]
+            EnumsError::Json(e) => e,
+            #[cfg(feature = "json")]
+            EnumsError::Jsonl(e) => e,
+            #[cfg(feature = "std")]
+            EnumsError::Io(e) => e,
+        }
+    }
+}
+
+impl fmt::Display for EnumsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.inner(), f)
+    }
+}
+
+impl Error for EnumsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.inner())
+    }
+}
+
+macro_rules! from_error {
+    ($($(#[$cfg:meta])? $variant:ident($error:ty)),* $(,)?) => {
+        $(
+            $(#[$cfg])?
+            impl From<$error> for EnumsError {
+                fn from(e: $error) -> Self {
+                    EnumsError::$variant(e)
+                }
+            }
+        )*
+    };
+}
+
+from_error!(
+    Coin(UnknownCoin),
+    State(UnknownState),
+    Address(InvalidAddress),
+    Color(ColorError),
+    Parse(MessageParseError),
+    Decode(DecodeError),
+    Encode(EncodeError),
+    Message(MessageError),
+    #[cfg(feature = "std")]
+    Apply(ApplyError),
+    #[cfg(feature = "std")]
+    Undo(UndoError),
+    #[cfg(feature = "std")]
+    Script(ScriptError),
+    #[cfg(feature = "std")]
+    Stream(StreamError),
+    #[cfg(feature = "json")]
+    Json(JsonError),
+    #[cfg(feature = "json")]
+    Jsonl(JsonlError),
+    #[cfg(feature = "std")]
+    Io(io::Error),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wrap `inner`, and check the wrapper shows it and gives it as its
+    /// source
+    fn assert_wraps<E: Error + Into<EnumsError> + 'static>(inner: E) -> EnumsError {
+        let text = inner.to_string();
+        let e: EnumsError = inner.into();
+        assert!(e.to_string().contains(&text), "{e} doesn't mention {text}");
+        let source = e.source().expect("a source");
+        assert!(source.is::<E>(), "{source:?}");
+        assert_eq!(source.to_string(), text);
+        e
+    }
+
+    #[test]
+    fn every_variant_wraps_its_error() {
+        let e = assert_wraps(UnknownCoin(String::from("button")));
+        assert!(matches!(e, EnumsError::Coin(_)));
+        let e = assert_wraps(UnknownState(String::from("Atlantis")));
+        assert!(matches!(e, EnumsError::State(_)));
+        let e = assert_wraps(InvalidAddress(String::from("localhost")));
+        assert!(matches!(e, EnumsError::Address(_)));
+        let e = assert_wraps(ColorError::InvalidHex(String::from("#nope")));
+        assert!(matches!(e, EnumsError::Color(_)));
+        let e = assert_wraps(MessageParseError::Empty);
+        assert!(matches!(e, EnumsError::Parse(_)));
+        let e = assert_wraps(DecodeError::UnknownTag(9));
+        assert!(matches!(e, EnumsError::Decode(_)));
+        let e = assert_wraps(MessageError::EmptyWrite);
+        assert!(matches!(e, EnumsError::Message(_)));
+        let e = assert_wraps(ApplyError::NotRunning);
+        assert!(matches!(e, EnumsError::Apply(_)));
+        let e = assert_wraps(UndoError::NothingToUndo);
+        assert!(matches!(e, EnumsError::Undo(_)));
+        let e = assert_wraps(ScriptError::Parse {
+            line: 3,
+            error: MessageParseError::Empty,
+        });
+        assert!(matches!(e, EnumsError::Script(_)));
+        let e = assert_wraps(StreamError::TrailingBytes(2));
+        assert!(matches!(e, EnumsError::Stream(_)));
+        let e = assert_wraps(io::Error::other("disk on fire"));
+        assert!(matches!(e, EnumsError::Io(_)));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_errors_are_wrapped_too() {
+        let e = assert_wraps(JsonError::MissingField("code"));
+        assert!(matches!(e, EnumsError::Json(_)));
+        let e = assert_wraps(JsonlError::Io(io::Error::other("gone")));
+        assert!(matches!(e, EnumsError::Jsonl(_)));
+    }
+
+    #[test]
+    fn the_chain_reaches_the_root_cause() {
+        let e = EnumsError::from(ScriptError::Parse {
+            line: 2,
+            error: MessageParseError::InvalidColor(ColorError::InvalidHex(String::from("#zz"))),
+        });
+        let mut chain = Vec::new();
+        let mut next: Option<&dyn Error> = Some(&e);
+        while let Some(error) = next {
+            chain.push(error.to_string());
+            next = error.source();
+        }
+        assert_eq!(chain.len(), 4, "{chain:?}");
+        assert_eq!(chain.last().unwrap(), "`#zz` is not a hex color");
+        assert!(chain[0].contains("line 2"));
+    }
+
+    #[test]
+    fn question_mark_converts() {
+        fn parse(text: &str) -> Result<crate::Message, EnumsError> {
+            Ok(text.parse()?)
+        }
+        let e: Box<dyn Error> = Box::new(parse("jump").unwrap_err());
+        assert!(e.to_string().contains("jump"));
+    }
+}
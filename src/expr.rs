@@ -0,0 +1,323 @@
+//! # A recursive enum for arithmetic
+//!
+//! An `Expr` is a tree: a number, or an operator over smaller `Expr`s, each
+//! in its own `Box`. Evaluating one is a single `match` that recurses into
+//! the operands, and so is printing one.
+//!
+//! `Expr::parse` reads the usual notation, with `*` and `/` binding tighter
+//! than `+` and `-`, and unary minus tighter still.
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::report::Reporter;
+
+/// # An arithmetic expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+}
+
+/// # Errors from evaluating an `Expr`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    /// A `Div` whose right-hand side came to zero
+    DivisionByZero,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// # Errors from parsing an `Expr`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input ended where a number or `(` was expected
+    UnexpectedEnd,
+    /// `found`, at byte `at`, can't go there
+    Unexpected { found: char, at: usize },
+    /// The `(` at byte `at` is never closed
+    Unclosed { at: usize },
+    /// `text`, at byte `at`, looks like a number but isn't one
+    BadNumber { text: String, at: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ParseError::Unexpected { found, at } => write!(f, "unexpected {found:?} at {at}"),
+            ParseError::Unclosed { at } => write!(f, "unclosed '(' at {at}"),
+            ParseError::BadNumber { text, at } => write!(f, "bad number {text:?} at {at}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Expr {
+    /// The value of the expression
+    ///
+    /// Dividing by zero is an error rather than an infinity or a NaN.
+    pub fn eval(&self) -> Result<f64, EvalError> {
+        Ok(match self {
+            Expr::Num(n) => *n,
+            Expr::Add(a, b) => a.eval()? + b.eval()?,
+            Expr::Sub(a, b) => a.eval()? - b.eval()?,
+            Expr::Mul(a, b) => a.eval()? * b.eval()?,
+            Expr::Div(a, b) => match b.eval()? {
+                0.0 => return Err(EvalError::DivisionByZero),
+                divisor => a.eval()? / divisor,
+            },
+            Expr::Neg(a) => -a.eval()?,
+        })
+    }
+
+    /// Parse `input`, such as `1 + 2 * (3 - 4)`
+    pub fn parse(input: &str) -> Result<Expr, ParseError> {
+        let mut parser = Parser { input, pos: 0 };
+        let expr = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(found) => Err(ParseError::Unexpected {
+                found,
+                at: parser.pos,
+            }),
+        }
+    }
+}
+
+/// Every operation in parentheses, so the output parses back to the same
+/// tree
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{n}"),
+            Expr::Add(a, b) => write!(f, "({a} + {b})"),
+            Expr::Sub(a, b) => write!(f, "({a} - {b})"),
+            Expr::Mul(a, b) => write!(f, "({a} * {b})"),
+            Expr::Div(a, b) => write!(f, "({a} / {b})"),
+            Expr::Neg(a) => write!(f, "(-{a})"),
+        }
+    }
+}
+
+/// Recursive descent over `input`, one function per precedence level
+struct Parser<'a> {
+    input: &'a str,
+    /// Byte offset of the next unread character
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// The next character that isn't whitespace, without consuming it
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.pos..];
+        let trimmed = rest.trim_start();
+        self.pos += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    /// Consume the next character if it is `c`
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += c.len_utf8();
+        }
+        found
+    }
+
+    /// `product (('+' | '-') product)*`
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.product()?;
+        loop {
+            if self.eat('+') {
+                expr = Expr::Add(Box::new(expr), Box::new(self.product()?));
+            } else if self.eat('-') {
+                expr = Expr::Sub(Box::new(expr), Box::new(self.product()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// `unary (('*' | '/') unary)*`
+    fn product(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+        loop {
+            if self.eat('*') {
+                expr = Expr::Mul(Box::new(expr), Box::new(self.unary()?));
+            } else if self.eat('/') {
+                expr = Expr::Div(Box::new(expr), Box::new(self.unary()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// `'-' unary | atom`
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat('-') {
+            Ok(Expr::Neg(Box::new(self.unary()?)))
+        } else {
+            self.atom()
+        }
+    }
+
+    /// `number | '(' sum ')'`
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        let next = self.peek();
+        let at = self.pos;
+        match next {
+            None => Err(ParseError::UnexpectedEnd),
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.sum()?;
+                if self.eat(')') {
+                    Ok(expr)
+                } else {
+                    Err(ParseError::Unclosed { at })
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let text = &self.input[at..];
+                let len = text
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(text.len());
+                let text = &text[..len];
+                self.pos += len;
+                text.parse()
+                    .map(Expr::Num)
+                    .map_err(|_| ParseError::BadNumber {
+                        text: text.to_string(),
+                        at,
+                    })
+            }
+            Some(found) => Err(ParseError::Unexpected { found, at }),
+        }
+    }
+}
+
+/// # Parsing, printing and evaluating a few expressions
+pub fn expr_examples(r: &mut Reporter) -> io::Result<()> {
+    let inputs = ["1 + 2 * (3 - 4)", "-(2 - 5) / 0.5", "1 / (2 - 2)", "(1 + 2"];
+    let mut evaluated = 0;
+    for input in inputs {
+        match Expr::parse(input) {
+            Ok(expr) => match expr.eval() {
+                Ok(value) => {
+                    evaluated += 1;
+                    writeln!(r, "{input} parses as {expr} = {value}")?
+                }
+                Err(e) => writeln!(r, "{input} parses as {expr}, but: {e}")?,
+            },
+            Err(e) => writeln!(r, "{input} doesn't parse: {e}")?,
+        }
+    }
+    r.result(format_args!(
+        "{evaluated} of {} expression(s) evaluated",
+        inputs.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<f64, EvalError> {
+        Expr::parse(input).unwrap().eval()
+    }
+
+    #[test]
+    fn multiplication_binds_tighter() {
+        assert_eq!(eval("1 + 2 * 3"), Ok(7.0));
+        assert_eq!(eval("(1 + 2) * 3"), Ok(9.0));
+        assert_eq!(eval("1 + 2 * (3 - 4)"), Ok(-1.0));
+        assert_eq!(eval("8 / 4 / 2"), Ok(1.0));
+        assert_eq!(eval("8 - 4 - 2"), Ok(2.0));
+    }
+
+    #[test]
+    fn builds_the_expected_tree() {
+        use Expr::*;
+        assert_eq!(
+            Expr::parse("1 - 2 * 3").unwrap(),
+            Sub(
+                Box::new(Num(1.0)),
+                Box::new(Mul(Box::new(Num(2.0)), Box::new(Num(3.0))))
+            )
+        );
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-3"), Ok(-3.0));
+        assert_eq!(eval("--3"), Ok(3.0));
+        assert_eq!(eval("2 * -3"), Ok(-6.0));
+        assert_eq!(eval("-2 * 3"), Ok(-6.0));
+        assert_eq!(eval("-(1 + 2)"), Ok(-3.0));
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        assert_eq!(eval("1 / 0"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("1 / (2 - 2)"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("0 / 2"), Ok(0.0));
+    }
+
+    #[test]
+    fn unbalanced_parentheses() {
+        assert_eq!(
+            Expr::parse("(1 + 2").unwrap_err(),
+            ParseError::Unclosed { at: 0 }
+        );
+        assert_eq!(
+            Expr::parse("1 + 2)").unwrap_err(),
+            ParseError::Unexpected { found: ')', at: 5 }
+        );
+        assert_eq!(
+            Expr::parse("((1)").unwrap_err().to_string(),
+            "unclosed '(' at 0"
+        );
+    }
+
+    #[test]
+    fn other_parse_errors() {
+        assert_eq!(Expr::parse("").unwrap_err(), ParseError::UnexpectedEnd);
+        assert_eq!(Expr::parse("1 +").unwrap_err(), ParseError::UnexpectedEnd);
+        assert_eq!(
+            Expr::parse("1 + x").unwrap_err(),
+            ParseError::Unexpected { found: 'x', at: 4 }
+        );
+        assert_eq!(
+            Expr::parse("1.2.3").unwrap_err(),
+            ParseError::BadNumber {
+                text: String::from("1.2.3"),
+                at: 0
+            }
+        );
+    }
+
+    #[test]
+    fn display_round_trips_through_the_parser() {
+        for input in ["1 + 2 * (3 - 4)", "-(2 - 5) / 0.5", "--1.25", "1 - (2 - 3)"] {
+            let expr = Expr::parse(input).unwrap();
+            let printed = expr.to_string();
+            assert_eq!(Expr::parse(&printed).unwrap(), expr, "{printed}");
+        }
+        assert_eq!(
+            Expr::parse("1 + 2 * -3").unwrap().to_string(),
+            "(1 + (2 * (-3)))"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! # Coins and states from C
+//!
+//! `extern "C"` functions, with unmangled names, for calling the coin
+//! examples from C or anything else that speaks its ABI:
+//!
+//! ```c
+//! typedef enum { ENUMS_PENNY, ENUMS_NICKEL, ENUMS_DIME, ENUMS_QUARTER } CCoin;
+//!
+//! uint8_t enums_coin_value(CCoin coin);
+//! size_t enums_make_change(uint32_t cents, CCoin *out, size_t cap);
+//! int32_t enums_parse_state(const char *name);
+//! ```
+//!
+//! Every pointer is checked for null before it is used. Nothing here
+//! allocates, so nothing has to be freed on the other side.
+use core::ffi::{c_char, CStr};
+
+use crate::{Coin, UsState};
+
+/// # A `Coin` as C sees it
+///
+/// The same variants as `Coin`, numbered from zero in the same order.
+/// Passing any other number as a `CCoin` is undefined behavior, as it is
+/// for any Rust enum.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CCoin {
+    /// One cent
+    Penny,
+    /// Five cents
+    Nickel,
+    /// Ten cents
+    Dime,
+    /// Twenty-five cents
+    Quarter,
+}
+
+impl From<Coin> for CCoin {
+    fn from(coin: Coin) -> Self {
+        match coin {
+            Coin::Penny => CCoin::Penny,
+            Coin::Nickel => CCoin::Nickel,
+            Coin::Dime => CCoin::Dime,
+            Coin::Quarter => CCoin::Quarter,
+        }
+    }
+}
+
+impl From<CCoin> for Coin {
+    fn from(coin: CCoin) -> Self {
+        match coin {
+            CCoin::Penny => Coin::Penny,
+            CCoin::Nickel => Coin::Nickel,
+            CCoin::Dime => Coin::Dime,
+            CCoin::Quarter => Coin::Quarter,
+        }
+    }
+}
+
+/// What `coin` is worth, in cents
+#[no_mangle]
+pub extern "C" fn enums_coin_value(coin: CCoin) -> u8 {
+    match coin {
+        CCoin::Penny => 1,
+        CCoin::Nickel => 5,
+        CCoin::Dime => 10,
+        CCoin::Quarter => 25,
+    }
+}
+
+/// Write the fewest coins that make `cents` to `out`, biggest first, and
+/// return how many coins that takes
+///
+/// At most `cap` coins are written. A return value over `cap` means the
+/// buffer was too small, and holds only the first `cap` coins; calling with
+/// a null `out` just asks how big it needs to be.
+///
+/// # Safety
+///
+/// Unless it is null, `out` must be valid for writing `cap` `CCoin`s.
+#[no_mangle]
+pub unsafe extern "C" fn enums_make_change(cents: u32, out: *mut CCoin, cap: usize) -> usize {
+    let cap = if out.is_null() { 0 } else { cap };
+    let mut remaining = cents;
+    let mut count = 0;
+    for coin in [CCoin::Quarter, CCoin::Dime, CCoin::Nickel, CCoin::Penny] {
+        let value = u32::from(enums_coin_value(coin));
+        let coins = (remaining / value) as usize;
+        for i in count..cap.min(count + coins) {
+            // SAFETY: `out` isn't null, and the caller promises room for
+            // `cap` coins
+            unsafe { out.add(i).write(coin) };
+        }
+        count += coins;
+        remaining %= value;
+    }
+    count
+}
+
+/// The index of the state called `name` among `UsState::VARIANTS`, which
+/// are in alphabetical order, or `-1` if there's no such state
+///
+/// `name` is matched as `UsState::from_name` matches, ignoring case and
+/// spaces. A null `name`, or one that isn't UTF-8, gives `-1`.
+///
+/// # Safety
+///
+/// Unless it is null, `name` must point to a NUL-terminated string that
+/// stays unchanged for the length of the call.
+#[no_mangle]
+pub unsafe extern "C" fn enums_parse_state(name: *const c_char) -> i32 {
+    if name.is_null() {
+        return -1;
+    }
+    // SAFETY: `name` isn't null, and the caller promises it's terminated
+    let name = unsafe { CStr::from_ptr(name) };
+    name.to_str()
+        .ok()
+        .and_then(UsState::from_name)
+        .and_then(|state| UsState::VARIANTS.iter().position(|s| *s == state))
+        .map_or(-1, |index| index as i32)
+}
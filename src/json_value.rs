@@ -0,0 +1,278 @@
+//! # A recursive enum for JSON documents
+//!
+//! A JSON value is a JSON scalar, or an array or object of more JSON values.
+//! `Json` says just that, and `pretty` prints one with a `match` that
+//! recurses into arrays and objects, indenting a level each time.
+//!
+//! Parsing is left to the `json` feature of the library; this is only about
+//! the shape of the data.
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::report::Reporter;
+
+/// # Any JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Array(Vec<Json>),
+    /// Members in the order they were given, keys not deduplicated
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// An array of `items`
+    pub fn array(items: impl IntoIterator<Item = Json>) -> Json {
+        Json::Array(items.into_iter().collect())
+    }
+
+    /// An object with `members`, in order
+    pub fn object<K: Into<String>>(members: impl IntoIterator<Item = (K, Json)>) -> Json {
+        Json::Object(members.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Json::Bool(b)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Self {
+        Json::Number(n)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(n: u32) -> Self {
+        Json::Number(f64::from(n))
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::Str(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::Str(s)
+    }
+}
+
+/// `value` as JSON text, with each array element and object member on a
+/// line of its own, indented `indent` spaces per level
+///
+/// Empty arrays and objects stay on one line, as `[]` and `{}`. JSON has no
+/// infinities or NaN, so those numbers are written as `null`.
+pub fn pretty(value: &Json, indent: usize) -> String {
+    let mut out = String::new();
+    write_pretty(&mut out, value, indent, 0);
+    out
+}
+
+/// `value` as JSON text on a single line, with no spaces
+pub fn compact(value: &Json) -> String {
+    let mut out = String::new();
+    write_compact(&mut out, value);
+    out
+}
+
+fn write_compact(out: &mut String, value: &Json) {
+    match value {
+        Json::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_compact(out, item);
+            }
+            out.push(']');
+        }
+        Json::Object(members) => {
+            out.push('{');
+            for (i, (key, member)) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_compact(out, member);
+            }
+            out.push('}');
+        }
+        scalar => write_pretty(out, scalar, 0, 0),
+    }
+}
+
+fn write_pretty(out: &mut String, value: &Json, indent: usize, depth: usize) {
+    let pad = |out: &mut String, depth: usize| out.push_str(&" ".repeat(indent * depth));
+    match value {
+        Json::Null => out.push_str("null"),
+        Json::Bool(b) => write!(out, "{b}").unwrap(),
+        Json::Number(n) if n.is_finite() => write!(out, "{n}").unwrap(),
+        Json::Number(_) => out.push_str("null"),
+        Json::Str(s) => write_string(out, s),
+        Json::Array(items) if items.is_empty() => out.push_str("[]"),
+        Json::Array(items) => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(",\n");
+                }
+                pad(out, depth + 1);
+                write_pretty(out, item, indent, depth + 1);
+            }
+            out.push('\n');
+            pad(out, depth);
+            out.push(']');
+        }
+        Json::Object(members) if members.is_empty() => out.push_str("{}"),
+        Json::Object(members) => {
+            out.push_str("{\n");
+            for (i, (key, member)) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(",\n");
+                }
+                pad(out, depth + 1);
+                write_string(out, key);
+                out.push_str(": ");
+                write_pretty(out, member, indent, depth + 1);
+            }
+            out.push('\n');
+            pad(out, depth);
+            out.push('}');
+        }
+    }
+}
+
+/// Write `text` as a JSON string literal
+fn write_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// # A wallet, written out as JSON
+pub fn json_examples(r: &mut Reporter) -> io::Result<()> {
+    let wallet = Json::object([
+        ("owner", Json::from("Ferris")),
+        (
+            "note",
+            Json::from(String::from("keep \"safe\"\nno, really")),
+        ),
+        (
+            "coins",
+            Json::array([
+                Json::object([("kind", Json::from("Penny")), ("cents", Json::from(1.0))]),
+                Json::object([
+                    ("kind", Json::from("Quarter")),
+                    ("cents", Json::from(25.0)),
+                    ("state", Json::from("Alaska")),
+                ]),
+            ]),
+        ),
+        ("bills", Json::array([])),
+        ("lost", Json::from(false)),
+        ("found", Json::Null),
+    ]);
+    let text = pretty(&wallet, 2);
+    writeln!(r, "{text}")?;
+    r.result(format_args!(
+        "{} byte(s) pretty, {} compact",
+        text.len(),
+        compact(&wallet).len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(pretty(&Json::Null, 2), "null");
+        assert_eq!(pretty(&Json::from(true), 2), "true");
+        assert_eq!(pretty(&Json::from(1.0), 2), "1");
+        assert_eq!(pretty(&Json::from(-2.5), 2), "-2.5");
+        assert_eq!(pretty(&Json::from(f64::NAN), 2), "null");
+        assert_eq!(pretty(&Json::from("hi"), 2), r#""hi""#);
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let s = Json::from("say \"hi\"\n\tback\\slash\u{1}");
+        assert_eq!(pretty(&s, 2), r#""say \"hi\"\n\tback\\slash\u0001""#);
+    }
+
+    #[test]
+    fn empty_containers_stay_on_one_line() {
+        assert_eq!(pretty(&Json::array([]), 2), "[]");
+        assert_eq!(pretty(&Json::object::<&str>([]), 2), "{}");
+        assert_eq!(
+            pretty(&Json::object([("empty", Json::object::<&str>([]))]), 2),
+            "{\n  \"empty\": {}\n}"
+        );
+    }
+
+    #[test]
+    fn nested_arrays_indent_a_level_each() {
+        let value = Json::array([
+            Json::from(1.0),
+            Json::array([Json::from(2.0), Json::array([Json::from(3.0)])]),
+        ]);
+        assert_eq!(
+            pretty(&value, 2),
+            "[\n  1,\n  [\n    2,\n    [\n      3\n    ]\n  ]\n]"
+        );
+    }
+
+    #[test]
+    fn object_members_keep_their_order() {
+        let value = Json::object([
+            ("z", Json::from(1.0)),
+            ("a", Json::array([Json::Null])),
+            ("line\nbreak", Json::from("\"quoted\"")),
+        ]);
+        assert_eq!(
+            pretty(&value, 4),
+            "{\n    \"z\": 1,\n    \"a\": [\n        null\n    ],\n    \"line\\nbreak\": \"\\\"quoted\\\"\"\n}"
+        );
+    }
+
+    #[test]
+    fn zero_indent_still_breaks_lines() {
+        let value = Json::object([("a", Json::array([Json::from(true)]))]);
+        assert_eq!(pretty(&value, 0), "{\n\"a\": [\ntrue\n]\n}");
+    }
+
+    #[test]
+    fn compact_is_one_line() {
+        let value = Json::object([
+            ("kind", Json::from("penny")),
+            ("cents", Json::from(1)),
+            ("tags", Json::array([Json::Null, Json::from(true)])),
+            ("empty", Json::object(Vec::<(String, Json)>::new())),
+        ]);
+        assert_eq!(
+            compact(&value),
+            r#"{"kind":"penny","cents":1,"tags":[null,true],"empty":{}}"#
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! # Early returns with `let .. else`
+//!
+//! `if let` runs a block when a pattern matches. Often the interesting code
+//! is what comes *after* the match, and nesting all of it inside an `if let`
+//! pushes it ever further to the right. `let .. else` turns that around: the
+//! pattern's bindings land in the enclosing scope, and the `else` block,
+//! which must diverge with `return`, `break` or a panic, handles the case
+//! where the pattern does not match.
+use std::fmt;
+use std::io::{self, Write};
+
+use rust_enums::{Coin2, UsState};
+
+use crate::report::Reporter;
+
+/// # The coin passed to `describe_quarter` wasn't a quarter
+#[derive(Debug, PartialEq, Eq)]
+pub struct NotAQuarter;
+
+impl fmt::Display for NotAQuarter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not a quarter")
+    }
+}
+
+impl std::error::Error for NotAQuarter {}
+
+/// Describe a state quarter, or return `NotAQuarter` for any other coin
+pub fn describe_quarter(coin: &Coin2) -> Result<String, NotAQuarter> {
+    let Coin2::Quarter(state) = coin else {
+        return Err(NotAQuarter);
+    };
+    Ok(format!("State quarter from {:?}!", state))
+}
+
+/// Parse an optional piece of text as a number, or `None` if there is no
+/// text or it isn't a number
+///
+/// Each `let .. else` peels off one layer, so the happy path reads straight
+/// down with no nesting.
+#[allow(clippy::question_mark)] // `input?` would hide the `let .. else`
+pub fn parse_number(input: Option<&str>) -> Option<u32> {
+    let Some(text) = input else {
+        return None;
+    };
+    let Ok(number) = text.trim().parse() else {
+        return None;
+    };
+    Some(number)
+}
+
+/// # `let .. else` taking the happy path and returning early
+pub fn let_else_examples(r: &mut Reporter) -> io::Result<()> {
+    for coin in [Coin2::Quarter(UsState::Colorado), Coin2::Dime] {
+        match describe_quarter(&coin) {
+            Ok(description) => writeln!(r, "{:?}: {description}", coin)?,
+            Err(e) => writeln!(r, "{:?}: {e}", coin)?,
+        }
+    }
+    let inputs = [Some(" 42 "), Some("forty-two"), None];
+    for input in inputs {
+        writeln!(r, "parse_number({:?}) = {:?}", input, parse_number(input))?;
+    }
+    let parsed = inputs.into_iter().filter_map(parse_number).count();
+    r.result(format_args!("{parsed} of {} input(s) parsed", inputs.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_a_state_quarter() {
+        assert_eq!(
+            describe_quarter(&Coin2::Quarter(UsState::Colorado)),
+            Ok(String::from("State quarter from Colorado!"))
+        );
+    }
+
+    #[test]
+    fn other_coins_are_not_quarters() {
+        assert_eq!(describe_quarter(&Coin2::Dime), Err(NotAQuarter));
+        assert_eq!(NotAQuarter.to_string(), "not a quarter");
+    }
+
+    #[test]
+    fn parse_number_returns_early() {
+        assert_eq!(parse_number(Some("42")), Some(42));
+        assert_eq!(parse_number(Some(" 7\n")), Some(7));
+        assert_eq!(parse_number(Some("forty-two")), None);
+        assert_eq!(parse_number(Some("-1")), None);
+        assert_eq!(parse_number(Some("")), None);
+        assert_eq!(parse_number(None), None);
+    }
+}
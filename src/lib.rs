@@ -0,0 +1,103 @@
+//! # Enums and Pattern Matching: the `Message` library
+//!
+//! The `Message` enum from the examples binary, along with the modules that
+//! build, encode, apply and schedule `Message`s. Keeping these in a library
+//! lets benchmarks and other crates use them, not just `main`.
+//!
+//! Alongside `Message` are the coin, IP address and wallet types from the
+//! book's chapter, so the examples binary uses them through this library's
+//! public API like any other crate would. The types most code needs are
+//! re-exported from the crate root:
+//!
+//! ```
+//! use rust_enums::{Coin2, Message, UsState, Wallet};
+//!
+//! let wallet = Wallet::new([Coin2::Quarter(UsState::Ohio), Coin2::Dime]);
+//! assert_eq!(wallet.total(), 35);
+//! assert_eq!(Message::Quit { code: 0 }.describe(), "Quit: shutting down");
+//! ```
+//!
+//! `option_ref` and `type_names` are the odd ones out: helpers for `Option`s
+//! of references and for naming the type of a value, kept here so their doc
+//! examples are compiled and run as tests, as are `maybe`, `Option` written
+//! out by hand, and `patterns_advanced`, sorting dice rolls by pattern.
+//! With `rand`, `rng` has the `CrateRng` trait every random pick goes
+//! through, and the RNGs that record a run's picks and replay them.
+//!
+//! The types themselves live in the workspace's member crates, each of
+//! which can be depended on alone: `enums-core` (`Cents`, `noop!`, the
+//! `Option` and type name helpers, `Maybe`, the roll patterns and
+//! `CrateRng`), `enums-coins` (coins, states and wallets), `enums-net` (IP
+//! addresses) and `enums-msg` (`Message`, `Canvas` and the rest). Every
+//! module is re-exported here at the path it had before the split, so code
+//! using this crate doesn't change.
+//!
+//! With the default `std` feature off and `alloc` on, the crate is
+//! `#![no_std]`: the coin, color, IP address and `Message` types, the codec
+//! and the other modules that only need `alloc` are still there, while
+//! printing, I/O, threads, clocks and `HashMap`-based modules are left out.
+//! The `rand` and `strum` features, also on by default, bring in
+//! `Wallet::random` and `iter()` over the fieldless enums; each of those
+//! enums has a `VARIANTS` slice for when `strum` is off.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::todo,
+    clippy::unimplemented
+)]
+#![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(all(feature = "std", feature = "rand"))]
+#[doc(hidden)]
+pub mod bench_support;
+#[cfg(feature = "alloc")]
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "playground")]
+pub mod playground;
+#[cfg(feature = "testing")]
+pub mod proptest_support;
+
+pub use enums_core::noop;
+#[cfg(feature = "rand")]
+pub use enums_core::rng;
+pub use enums_core::{maybe, patterns_advanced};
+#[cfg(feature = "alloc")]
+pub use enums_core::{option_ref, type_names};
+
+#[cfg(feature = "alloc")]
+pub use enums_coins::{coin, wallet};
+
+#[cfg(feature = "alloc")]
+pub use enums_net::ip;
+
+#[cfg(feature = "async")]
+pub use enums_msg::async_worker;
+#[cfg(feature = "json")]
+pub use enums_msg::json;
+#[cfg(feature = "alloc")]
+pub use enums_msg::{builder, codec, color, envelope, execute, expiry, handler, message};
+#[cfg(feature = "std")]
+pub use enums_msg::{
+    canvas, history, priority, repl, replay, router, script, stats, stream, worker,
+};
+
+#[cfg(feature = "std")]
+pub use canvas::Canvas;
+#[cfg(feature = "alloc")]
+pub use coin::{Cents, Coin, Coin2, UsState};
+#[cfg(feature = "alloc")]
+pub use ip::IpAddrTypes;
+pub use maybe::Maybe;
+#[cfg(feature = "alloc")]
+pub use message::Message;
+#[cfg(feature = "alloc")]
+pub use wallet::Wallet;
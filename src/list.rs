@@ -0,0 +1,176 @@
+//! # A recursive enum: the cons list
+//!
+//! An enum's variants can hold the enum itself, as long as something puts a
+//! fixed size on the recursion. Here a `Box` does: a `List` is either `Nil`,
+//! or a value `Cons`ed onto the front of another, boxed `List`.
+use std::fmt;
+use std::io::{self, Write};
+use std::mem;
+
+use strum::IntoEnumIterator;
+
+use rust_enums::Coin;
+
+use crate::report::Reporter;
+use crate::value_in_cents;
+
+/// # A singly linked list, built from the front
+#[derive(Debug, PartialEq, Eq)]
+pub enum List<T> {
+    /// A value, and the rest of the list after it
+    Cons(T, Box<List<T>>),
+    /// The end of the list
+    Nil,
+}
+
+impl<T> List<T> {
+    /// Put `value` in front of the list
+    pub fn push_front(&mut self, value: T) {
+        let rest = mem::replace(self, List::Nil);
+        *self = List::Cons(value, Box::new(rest));
+    }
+
+    /// How many values are in the list
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Whether the list is `Nil`
+    pub fn is_empty(&self) -> bool {
+        matches!(self, List::Nil)
+    }
+
+    /// The values, front to back
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rest: self }
+    }
+}
+
+/// The list holding `values`, in the same order
+impl<T> From<Vec<T>> for List<T> {
+    fn from(values: Vec<T>) -> Self {
+        let mut list = List::Nil;
+        for value in values.into_iter().rev() {
+            list.push_front(value);
+        }
+        list
+    }
+}
+
+/// Drops one `Cons` at a time
+///
+/// The compiler's drop would recurse once per element, so a long enough
+/// list would overflow the stack.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let List::Cons(_, rest) = self else {
+            return;
+        };
+        let mut rest = mem::replace(&mut **rest, List::Nil);
+        while let List::Cons(_, next) = &mut rest {
+            // Cut the tail off first, so dropping `rest` doesn't recurse
+            let next = mem::replace(&mut **next, List::Nil);
+            rest = next;
+        }
+    }
+}
+
+/// `1 -> 2 -> 3 -> nil`
+impl<T: fmt::Display> fmt::Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for value in self {
+            write!(f, "{value} -> ")?;
+        }
+        write!(f, "nil")
+    }
+}
+
+/// # Borrows each value of a `List` in turn
+///
+/// Returned by `List::iter`.
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    rest: &'a List<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        match self.rest {
+            List::Cons(value, rest) => {
+                self.rest = rest;
+                Some(value)
+            }
+            List::Nil => None,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// # A cons list of coin values, summed by walking it
+pub fn list_examples(r: &mut Reporter) -> io::Result<()> {
+    let mut cents = List::from(
+        Coin::iter()
+            .map(|coin| value_in_cents(&coin))
+            .collect::<Vec<_>>(),
+    );
+    cents.push_front(25);
+    let total: u32 = cents.iter().map(|&c| u32::from(c)).sum();
+    r.result(format_args!(
+        "{cents}: {} coin(s), {total} cents",
+        cents.len()
+    ))?;
+    let empty: List<u8> = List::Nil;
+    writeln!(r, "{empty} is empty: {}", empty.is_empty())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_list() {
+        let list: List<i32> = List::from(Vec::new());
+        assert_eq!(list, List::Nil);
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.iter().next(), None);
+        assert_eq!(list.to_string(), "nil");
+    }
+
+    #[test]
+    fn single_element() {
+        let mut list = List::Nil;
+        list.push_front("only");
+        assert_eq!(list, List::Cons("only", Box::new(List::Nil)));
+        assert!(!list.is_empty());
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.to_string(), "only -> nil");
+    }
+
+    #[test]
+    fn iterates_front_to_back() {
+        let mut list = List::from(vec![2, 3]);
+        list.push_front(1);
+        assert_eq!(list.iter().collect::<Vec<_>>(), [&1, &2, &3]);
+        assert_eq!(list.to_string(), "1 -> 2 -> 3 -> nil");
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn long_list_drops_without_overflowing_the_stack() {
+        let list = List::from((0..100_000).collect::<Vec<u32>>());
+        assert_eq!(list.len(), 100_000);
+        drop(list);
+    }
+}
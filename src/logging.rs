@@ -0,0 +1,232 @@
+//! # Diagnostics, kept apart from what the examples show
+//!
+//! The examples write what they show through a [`Reporter`]. Asides about
+//! how they got there, like which coin matched or what type a value has,
+//! are logged instead, and only appear on stderr when asked for.
+//!
+//! The module has the shape of the `log` crate: a `Log` trait, one global
+//! logger, a maximum level, and `debug!` and `info!` macros that check the
+//! level before formatting anything. Unlike with `env_logger`, `RUST_LOG`
+//! is just a level name, such as `debug`, with no per-module filters.
+//!
+//! [`Reporter`]: crate::report::Reporter
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// # How much a log record matters, from most to least
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    /// The level called `name` in `RUST_LOG`, in any case
+    pub fn from_name(name: &str) -> Option<Level> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(name))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+/// The most verbose level shown when `RUST_LOG` is `filter`, raised by one
+/// for each of `verbosity`, or `None` if nothing is shown
+///
+/// Without a filter only errors are shown, as with `env_logger`; `off`
+/// shows nothing until raised.
+pub fn max_level(filter: Option<&str>, verbosity: usize) -> Option<Level> {
+    let base = match filter.map(str::trim) {
+        None | Some("") => Level::Error as usize,
+        Some(name) if name.eq_ignore_ascii_case("off") => 0,
+        Some(name) => Level::from_name(name).map_or(Level::Error as usize, |l| l as usize),
+    };
+    let raised = (base + verbosity).min(Level::Trace as usize);
+    Level::ALL
+        .into_iter()
+        .find(|&level| level as usize == raised)
+}
+
+/// # One message logged by `debug!` or `info!`
+pub struct Record<'a> {
+    pub level: Level,
+    /// The module it was logged from
+    pub target: &'static str,
+    pub args: fmt::Arguments<'a>,
+}
+
+/// # Where log records go
+pub trait Log: Send + Sync {
+    fn log(&self, record: &Record);
+}
+
+static LOGGER: OnceLock<Box<dyn Log>> = OnceLock::new();
+
+/// The most verbose `Level` logged, as a number, `0` for none at all
+static MAX_LEVEL: AtomicUsize = AtomicUsize::new(0);
+
+/// Install `logger`, logging records up to `max_level`
+///
+/// Only the first logger installed is kept, as with `log::set_boxed_logger`;
+/// returns whether it was this one.
+pub fn set_logger(logger: Box<dyn Log>, max_level: Option<Level>) -> bool {
+    let installed = LOGGER.set(logger).is_ok();
+    if installed {
+        MAX_LEVEL.store(max_level.map_or(0, |l| l as usize), Ordering::Relaxed);
+    }
+    installed
+}
+
+/// # Writes each record to stderr, as `[LEVEL target] message`
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn log(&self, record: &Record) {
+        eprintln!(
+            "[{} {}] {}",
+            record.level.name(),
+            record.target,
+            record.args
+        );
+    }
+}
+
+/// Log to stderr at the level `RUST_LOG` asks for, raised by `verbosity`
+pub fn init(verbosity: usize) {
+    let filter = std::env::var("RUST_LOG").ok();
+    set_logger(
+        Box::new(StderrLogger),
+        max_level(filter.as_deref(), verbosity),
+    );
+}
+
+/// Whether a record at `level` would be logged
+pub fn enabled(level: Level) -> bool {
+    level as usize <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Hand a record to the logger, if `level` is enabled; called by the macros
+pub fn log(level: Level, target: &'static str, args: fmt::Arguments) {
+    if let Some(logger) = LOGGER.get().filter(|_| enabled(level)) {
+        logger.log(&Record {
+            level,
+            target,
+            args,
+        });
+    }
+}
+
+/// Log a `format!`-style message at `Level::Debug`
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::logging::log(
+            $crate::logging::Level::Debug,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+/// Log a `format!`-style message at `Level::Info`
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::logging::log(
+            $crate::logging::Level::Info,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+pub(crate) use {debug, info};
+
+/// Run `f`, returning the records it logged on this thread up to `level`
+///
+/// Installs a logger for the whole test binary that hands each record to
+/// the thread that logged it, so tests running side by side don't see each
+/// other's records.
+#[cfg(test)]
+pub fn capture(level: Level, f: impl FnOnce()) -> Vec<(Level, String)> {
+    use std::cell::RefCell;
+
+    /// The level being captured on this thread, and what was captured
+    type Capture = Option<(Level, Vec<(Level, String)>)>;
+
+    thread_local! {
+        static CAPTURED: RefCell<Capture> = const { RefCell::new(None) };
+    }
+
+    struct Captured;
+
+    impl Log for Captured {
+        fn log(&self, record: &Record) {
+            CAPTURED.with_borrow_mut(|captured| {
+                if let Some((max, records)) = captured {
+                    if record.level <= *max {
+                        records.push((record.level, record.args.to_string()));
+                    }
+                }
+            });
+        }
+    }
+
+    set_logger(Box::new(Captured), Some(Level::Trace));
+    CAPTURED.set(Some((level, Vec::new())));
+    f();
+    CAPTURED
+        .take()
+        .map(|(_, records)| records)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_names() {
+        assert_eq!(Level::from_name("debug"), Some(Level::Debug));
+        assert_eq!(Level::from_name("WARN"), Some(Level::Warn));
+        assert_eq!(Level::from_name("loud"), None);
+    }
+
+    #[test]
+    fn verbosity_raises_the_level() {
+        assert_eq!(max_level(None, 0), Some(Level::Error));
+        assert_eq!(max_level(None, 2), Some(Level::Info));
+        assert_eq!(max_level(Some("info"), 1), Some(Level::Debug));
+        assert_eq!(max_level(Some("debug"), 5), Some(Level::Trace));
+        assert_eq!(max_level(Some("off"), 0), None);
+        assert_eq!(max_level(Some("off"), 1), Some(Level::Error));
+    }
+
+    #[test]
+    fn capture_keeps_records_up_to_its_level() {
+        let records = capture(Level::Info, || {
+            info!("shown {}", 1);
+            debug!("hidden");
+        });
+        assert_eq!(records, [(Level::Info, String::from("shown 1"))]);
+    }
+}
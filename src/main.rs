@@ -10,57 +10,87 @@
  * `if let` construct is another convenient and concise idiom available to handle
  * enums in your code.
  */
-use std::any::type_name;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::panic;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
 extern crate rand;
-use rand::{seq::SliceRandom, thread_rng};
+use rust_enums::rng::CrateRng;
 use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
 
-/// Utility function to print type of a variable
-fn print_type_of<T>(_: &T) {
-    println!("{}", type_name::<T>())
+use dice::{RandomRoller, ScriptedRoller, D20, D6};
+use dice_game::{resolve_roll, DiceGame, GameRules, Tournament};
+use json_value::Json;
+use report::Reporter;
+use rust_enums::builder::MessageScript;
+use rust_enums::coin::{quarter_year_for, quarter_year_or_default, quarter_year_or_err};
+use rust_enums::color::Color;
+use rust_enums::envelope::{Envelope, MessageV1};
+use rust_enums::expiry::{self, Timed};
+use rust_enums::handler::MessageLogger;
+#[cfg(feature = "json")]
+use rust_enums::json;
+use rust_enums::message::{coalesce_moves, count_by_kind, dedup_consecutive, MessageKind};
+use rust_enums::option_ref::{first_positive, greet, plus_one_ref};
+use rust_enums::router::Router;
+use rust_enums::stats::MessageStats;
+use rust_enums::stream::{MessageReader, MessageWriter, OneByteAtATime};
+use rust_enums::type_names::short_type_name_of;
+use rust_enums::wallet::{compare_value, pick_richer, RicherOutcome};
+use rust_enums::{canvas, codec, history, priority, replay, worker, Message};
+use rust_enums::{noop, Coin, Coin2, IpAddrTypes, UsState, Wallet};
+
+mod cli;
+mod completions;
+mod config;
+mod dice;
+mod dice_game;
+mod expr;
+mod json_value;
+mod let_else;
+mod list;
+mod logging;
+mod math;
+mod maybe;
+mod nested;
+mod patterns_advanced;
+mod predicates;
+mod report;
+mod section;
+mod shape;
+mod slices;
+#[cfg(test)]
+mod snapshot;
+mod trade;
+mod traffic_light;
+#[cfg(feature = "tui")]
+mod tui;
+mod while_let_demo;
+
+/// Utility function to write the type of a variable, called `name`, as a
+/// line of detail
+fn print_type_of<T>(r: &mut Reporter, name: &str, value: &T) -> io::Result<()> {
+    r.detail(format_args!(
+        "type of `{name}` is {}",
+        short_type_name_of(value)
+    ))
 }
 
 /// # Enums and Pattern Matching Examples
 ///
 /// Examples from Rust Book Ch. 6: Enums and Pattern Matching
-fn main() {
-    // Defining an Enum
-    defining_an_enum();
-
-    // Enum with associated `String` values
-    enum_string_values();
-
-    // Enum with different associated types
-    enum_different_types();
-
-    // Enum with methods
-    enum_methods();
-
-    // The `Option` Enum and Its Advantages Over Null Values
-    option_type();
-
-    // The `match` Control Flow Construct
-    match_control_flow();
-
-    // Patterns That Bind to Values
-    match_control_flow_patterns();
-
-    // Matching with `Option<T>`
-    matching_with_option_t();
-
-    // Matches are Exhaustive
-    plus_one_broken(Some(1));
-
-    // Catch-all Patterns and the `_` Placeholder
-    catch_all_patterns();
-    catch_all_patterns_underscore_placeholder();
-    catch_all_patterns_noop_catchall();
-
-    // Concise Control Flow with `if let`
-    if_let_match_example();
-    if_let_example();
-    if_let_else_example();
+///
+/// Command-line arguments can pick one section of examples, or one of the
+/// [`cli`] tools instead; see there.
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // A panic is a broken invariant, not bad input, and the panic hook has
+    // already printed what it was to stderr
+    let code = panic::catch_unwind(|| cli::run(&args)).unwrap_or(cli::EXIT_SOFTWARE);
+    ExitCode::from(code)
 }
 
 /// # Defining an Enum
@@ -126,12 +156,23 @@ fn route_enum_kind(_ip_kind: IpAddrKind) {}
 /// have associated `String` values
 /// We attach data to each variant of the enum directly, so there is no need
 /// for an extra struct.
-fn enum_string_values() {
+fn enum_string_values(r: &mut Reporter) -> io::Result<()> {
     let home = IpAddr::V4(String::from("127.0.0.1"));
 
     let loopback = IpAddr::V6(String::from("::1"));
-    println!("`home` is: {:#?}", home);
-    println!("`loopback` is: {:#?}", loopback);
+    r.detail(format_args!("`home` is: {:#?}", home))?;
+    r.detail(format_args!("`loopback` is: {:#?}", loopback))?;
+    r.result(format_args!(
+        "`home` is {home:?}, `loopback` is {loopback:?}"
+    ))?;
+    let addresses = [home, loopback].map(|ip| {
+        let (kind, address) = match ip {
+            IpAddr::V4(address) => ("V4", address),
+            IpAddr::V6(address) => ("V6", address),
+        };
+        Json::object([("kind", Json::from(kind)), ("address", Json::from(address))])
+    });
+    r.record([("addresses", Json::array(addresses))])
 }
 /// # `IpAddr` enum representing a specific address with a kind
 #[derive(Debug)]
@@ -147,27 +188,35 @@ enum IpAddr {
 /// values between `0` and `255`. If we wanted to store `V4` addresses as four
 /// `u8` values but still express `V6` addresses as one `String` value, we
 /// wouldn’t be able to with a struct. Enums handle this case with ease
-fn enum_different_types() {
+fn enum_different_types(r: &mut Reporter) -> io::Result<()> {
     let home = IpAddrTypes::V4(127, 0, 0, 1);
 
     let loopback = IpAddrTypes::V6(String::from("::1"));
-    println!("`home` is: {:#?}", home);
-    println!("`loopback` is: {:#?}", loopback);
-}
-/// # Implementation of IpAddr with different associated types
-///
-/// An example alternative implementation of `IpAddr` using different
-/// associated types for `V4` and `V6` variants.
-#[derive(Debug)]
-enum IpAddrTypes {
-    V4(u8, u8, u8, u8),
-    V6(String),
+    r.detail(format_args!("`home` is: {:#?}", home))?;
+    r.detail(format_args!("`loopback` is: {:#?}", loopback))?;
+    r.result(format_args!(
+        "`home` is {home:?}, `loopback` is {loopback:?}"
+    ))?;
+    let addresses = [home, loopback].map(|ip| match ip {
+        IpAddrTypes::V4(a, b, c, d) => Json::object([
+            ("kind", Json::from("V4")),
+            (
+                "octets",
+                Json::array([a, b, c, d].map(|n| Json::from(u32::from(n)))),
+            ),
+        ]),
+        IpAddrTypes::V6(address) => {
+            Json::object([("kind", Json::from("V6")), ("address", Json::from(address))])
+        }
+    });
+    r.record([("addresses", Json::array(addresses))])
 }
 
 /// # Rust standard library IpAddr example
 ///
 /// The following example demonstrates how the Rust standard library implements
 /// IpAddr: An enum containing two associated stuct variants
+#[allow(dead_code)] // illustration only
 struct Ipv4Addr {
     // --snip--
 }
@@ -175,6 +224,7 @@ struct Ipv4Addr {
 ///
 /// The following example demonstrates how the Rust standard library implements
 /// IpAddr: An enum containing two associated stuct variants
+#[allow(dead_code)] // illustration only
 struct Ipv6Addr {
     // --snip--
 }
@@ -197,40 +247,24 @@ struct Ipv6Addr {
 /// scope. We’ll talk more about bringing types into scope in [Chapter 7][1].
 ///
 /// [1]: https://doc.rust-lang.org/book/ch07-00-managing-growing-projects-with-packages-crates-and-modules.html
+#[allow(dead_code)] // illustration only
 enum IpAddrStdLibExample {
     V4(Ipv4Addr),
     V6(Ipv6Addr),
 }
 
-/// # Enum with wide variety of types embedded in its variants.
-///
-/// `Message` enum whose variants each store different amounts and types of
-/// values
-/// This enum has four variants with different types:
-///
-/// - `Quit` has no data associated with it at all.
-/// - `Move` has named fields, like a struct does.
-/// - `Write` includes a single `String`.
-/// - `ChangeColor` includes three `i32` values.
-///
-/// If we used the different structs, each of which has its own type, we
-/// couldn’t as easily define a function to take any of these kinds of messages
-/// as we could with the `Message` enum defined below, which is a single type.
-enum Message {
-    Quit,
-    Move { x: i32, y: i32 },
-    Write(String),
-    ChangeColor(i32, i32, i32),
-}
-
 // The following structs could hold the same data that the preceding enum variants hold:
 /// Equivalent to `Message::Quit` enum variant
 ///
 /// Struct that could hold the same data that the `Message::Quit` enum variant holds
-struct QuitMessage; // unit struct
+#[derive(Debug)]
+struct QuitMessage {
+    code: i32,
+}
 /// Equivalent to `Message::Move` enum variant
 ///
 /// Struct that could hold the same data that the `Message:Move` enum variant holds
+#[derive(Debug)]
 struct MoveMessage {
     x: i32,
     y: i32,
@@ -238,38 +272,876 @@ struct MoveMessage {
 /// Equivalent to `Message::Write` enum variant
 ///
 /// Struct that could hold the same data that the `Message::Write` enum variant holds
+#[derive(Debug)]
 struct WriteMessage(String); // tuple struct
 /// Equivalent to `Message::ChangeColor` enum variant
 ///
 /// Struct that could hold the same data that the `Message::ChangeColor` enum variant holds
-struct ChangeColorMessage(i32, i32, i32); // tuple struct
+#[derive(Debug)]
+struct ChangeColorMessage(Color); // tuple struct
+
+/// # Converting between `Message` and the equivalent structs
+///
+/// Each struct above holds exactly the data of one `Message` variant, so a
+/// struct can always become a `Message` with `From`. Going the other way can
+/// fail, because a `Message` may be any of the four variants: `TryFrom` hands
+/// the original `Message` back inside a `WrongVariant` error when it does not
+/// match.
+fn struct_conversions(r: &mut Reporter) -> io::Result<()> {
+    let m: Message = MoveMessage { x: 3, y: 7 }.into();
+    writeln!(r, "{}", m.describe())?;
+
+    match MoveMessage::try_from(m) {
+        Ok(mv) => r.result(format_args!("Converted back to {:?}", mv))?,
+        Err(e) => r.warn(format_args!(
+            "Could not convert {:?} into {}",
+            e.message, e.expected
+        ))?,
+    }
+    match WriteMessage::try_from(Message::Quit { code: 0 }) {
+        Ok(w) => writeln!(r, "Converted back to {:?}", w)?,
+        Err(e) => r.warn(format_args!(
+            "Could not convert {:?} into {}",
+            e.message, e.expected
+        ))?,
+    }
+    Ok(())
+}
+
+/// # Error returned when a `Message` is not the requested variant
+///
+/// Holds the name of the variant that was `expected` along with the original
+/// `Message`, so that a failed conversion doesn't lose the value.
+#[derive(Debug)]
+struct WrongVariant {
+    expected: &'static str,
+    message: Message,
+}
+
+impl From<QuitMessage> for Message {
+    fn from(m: QuitMessage) -> Self {
+        Message::Quit { code: m.code }
+    }
+}
+
+impl From<MoveMessage> for Message {
+    fn from(m: MoveMessage) -> Self {
+        Message::Move { x: m.x, y: m.y }
+    }
+}
+
+impl From<WriteMessage> for Message {
+    fn from(m: WriteMessage) -> Self {
+        Message::Write(m.0)
+    }
+}
+
+impl From<ChangeColorMessage> for Message {
+    fn from(m: ChangeColorMessage) -> Self {
+        Message::ChangeColor(m.0)
+    }
+}
+
+impl TryFrom<Message> for QuitMessage {
+    type Error = WrongVariant;
+
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        match message {
+            Message::Quit { code } => Ok(QuitMessage { code }),
+            _ => Err(WrongVariant {
+                expected: "Quit",
+                message,
+            }),
+        }
+    }
+}
+
+impl TryFrom<Message> for MoveMessage {
+    type Error = WrongVariant;
+
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        match message {
+            Message::Move { x, y } => Ok(MoveMessage { x, y }),
+            message => Err(WrongVariant {
+                expected: "Move",
+                message,
+            }),
+        }
+    }
+}
+
+impl TryFrom<Message> for WriteMessage {
+    type Error = WrongVariant;
+
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        match message {
+            Message::Write(text) => Ok(WriteMessage(text)),
+            message => Err(WrongVariant {
+                expected: "Write",
+                message,
+            }),
+        }
+    }
+}
+
+impl TryFrom<Message> for ChangeColorMessage {
+    type Error = WrongVariant;
+
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        match message {
+            Message::ChangeColor(color) => Ok(ChangeColorMessage(color)),
+            message => Err(WrongVariant {
+                expected: "ChangeColor",
+                message,
+            }),
+        }
+    }
+}
 
 /// # Enum with methods
 ///
 /// There is one more similarity between enums and structs: just as we’re able
 /// to define methods on structs using `impl`, we’re also able to define
 /// methods on enums. Here’s a method named `call` that we could define on our
-/// `Message` enum
-fn enum_methods() {
-    let m = Message::Write(String::from("hello"));
-    m.call();
+/// `Message` enum. `call` prints the text from `describe`, which is what the
+/// examples write to their `Reporter` instead.
+///
+/// `execute` is the fallible version of `call`: it returns a `Response`, or a
+/// `MessageError` for a message that would do nothing, which we `match` on.
+fn enum_methods(r: &mut Reporter) -> io::Result<()> {
+    let messages = [
+        Message::Write(String::from("hello")),
+        Message::Move { x: 3, y: 7 },
+        Message::ChangeColor(Color::rgb(255, 136, 0)),
+        Message::Resize {
+            width: 40,
+            height: 12,
+        },
+        Message::Draw(vec![(1, 1), (2, 2)]),
+        Message::Quit { code: 0 },
+    ];
+    for m in &messages {
+        writeln!(r, "{}", m.describe())?;
+    }
+
+    let more = [Message::Write(String::new()), Message::Move { x: 0, y: 0 }];
+    let mut executed = 0;
+    for m in messages.iter().chain(&more) {
+        match m.execute() {
+            Ok(response) => {
+                executed += 1;
+                writeln!(r, "`{m}` executed: {response}")?
+            }
+            Err(e) => writeln!(r, "`{m}` rejected: {e}")?,
+        }
+    }
+    r.result(format_args!(
+        "{executed} of {} message(s) executed",
+        messages.len() + more.len()
+    ))
 }
 
-/// # Enum with methods example
-///
-/// An `impl` for `call` method on `Message` enum
-impl Message {
-    fn call(&self) {
-        // method body would be defined here
-        match self {
-            Self::Write(string) => {
-                println!("Message: {string}");
+/// # Picking `Message`s apart with predicates
+///
+/// Uses the `is_*` predicates and `as_*` accessors instead of a `match` to
+/// pull the parts we care about out of a mixed list.
+fn message_predicates(r: &mut Reporter) -> io::Result<()> {
+    let messages = [
+        Message::Write(String::from("hello")),
+        Message::Move { x: 2, y: -1 },
+        Message::ChangeColor(Color::BLUE),
+        Message::Write(String::from("world")),
+        Message::Quit { code: 0 },
+    ];
+    let texts: Vec<&str> = messages.iter().filter_map(Message::as_write).collect();
+    r.result(format_args!(
+        "{} writes: {:?}",
+        messages.iter().filter(|m| m.is_write()).count(),
+        texts
+    ))?;
+    for m in &messages {
+        if let Some((x, y)) = m.as_move() {
+            writeln!(r, "`{m}`: is_move() = {}, moves by ({x}, {y})", m.is_move())?;
+        }
+        if let Some(color) = m.as_color() {
+            writeln!(
+                r,
+                "`{m}`: is_change_color() = {}, changes to {color}",
+                m.is_change_color()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// # Visiting `Message`s with a handler
+///
+/// `visit` calls back into a `MessageHandler` instead of making us `match`:
+/// the same messages drive a `MessageLogger` and a `Canvas`.
+fn visit_messages(r: &mut Reporter) -> io::Result<()> {
+    let messages = [
+        Message::Move { x: 2, y: 1 },
+        Message::Write(String::from("hello")),
+        Message::ChangeColor(Color::GREEN),
+        Message::Quit { code: 0 },
+    ];
+    let mut logger = MessageLogger::new();
+    let mut canvas = canvas::Canvas::new();
+    for m in &messages {
+        m.visit(&mut logger);
+        m.visit(&mut canvas);
+    }
+    writeln!(r, "Handler calls: {}", logger.calls().join(", "))?;
+    r.result(format_args!("Canvas after visiting: {canvas}"))
+}
+
+/// # Routing `Message`s to handlers by kind
+///
+/// A `Router` with a handler for `Write` only: the `Move` has no handler of
+/// its own, so the fallback gets it. Handlers must be `Send + 'static`, so
+/// they note what they got in a shared log rather than writing to `r`.
+fn route_messages(r: &mut Reporter) -> io::Result<()> {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut router = Router::new();
+    let write_log = Arc::clone(&log);
+    router.register(MessageKind::Write, move |m| {
+        write_log
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(format!("Write handler got {m}"))
+    });
+    let fallback_log = Arc::clone(&log);
+    router.set_fallback(move |m| {
+        fallback_log
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(format!("Fallback got {m}"))
+    });
+    for m in [
+        Message::Write(String::from("routed")),
+        Message::Move { x: 1, y: 2 },
+    ] {
+        router.route(&m);
+    }
+    // A poisoned log still holds every line pushed before the panic
+    let log = log.lock().unwrap_or_else(PoisonError::into_inner);
+    for line in log.iter() {
+        writeln!(r, "{line}")?;
+    }
+    r.result(format_args!("{} message(s) routed", log.len()))
+}
+
+/// # Dropping repeated `Message`s
+///
+/// A noisy stream that keeps repeating itself shrinks with
+/// `dedup_consecutive`, while a repeat that isn't back to back survives.
+fn dedup_messages(r: &mut Reporter) -> io::Result<()> {
+    let noisy = vec![
+        Message::ChangeColor(Color::RED),
+        Message::ChangeColor(Color::RED),
+        Message::ChangeColor(Color::RED),
+        Message::Move { x: 1, y: 0 },
+        Message::Move { x: 1, y: 0 },
+        Message::ChangeColor(Color::RED),
+    ];
+    let before = noisy.len();
+    let deduped = dedup_consecutive(noisy);
+    r.result(format_args!(
+        "{before} messages deduplicated to {}:",
+        deduped.len()
+    ))?;
+    for m in &deduped {
+        writeln!(r, "  {m}")?;
+    }
+    Ok(())
+}
+
+/// # Coalescing runs of `Move`s
+///
+/// A burst of small moves becomes one `Move` per run with
+/// `coalesce_moves`, and a run that ends up back where it started vanishes.
+fn coalesce_messages(r: &mut Reporter) -> io::Result<()> {
+    let bursty = vec![
+        Message::Move { x: 1, y: 0 },
+        Message::Move { x: 1, y: 1 },
+        Message::Move { x: 0, y: 1 },
+        Message::Write(String::from("here")),
+        Message::Move { x: 2, y: 0 },
+        Message::Move { x: -2, y: 0 },
+    ];
+    let before = bursty.len();
+    let coalesced = coalesce_moves(bursty);
+    r.result(format_args!(
+        "{before} messages coalesced to {}:",
+        coalesced.len()
+    ))?;
+    for m in &coalesced {
+        writeln!(r, "  {m}")?;
+    }
+    Ok(())
+}
+
+/// # Statistics over a random stream of `Message`s
+///
+/// Generates a script of 1,000 random messages picked by `rng`, so the same
+/// seed prints the same thing, and feeds it through `MessageStats`.
+fn message_stats(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let script: Vec<Message> = (0..1_000).map(|_| random_message(rng)).collect();
+    let stats = MessageStats::from_messages(&script);
+    r.result(stats.summary())?;
+    #[cfg(feature = "json")]
+    writeln!(r, "As JSON: {}", json::stats_to_json(&stats))?;
+    Ok(())
+}
+
+/// A random `Message` of any kind, with small payloads
+fn random_message(rng: &mut impl CrateRng) -> Message {
+    match rng.roll_range(0, 5) {
+        0 => Message::Quit { code: 0 },
+        // From -10 to 10 each way, drawn from 0 to 20
+        1 => Message::Move {
+            x: rng.roll_range(0, 20) as i32 - 10,
+            y: rng.roll_range(0, 20) as i32 - 10,
+        },
+        2 => {
+            let words = ["hello", "world", "enums", "match"];
+            Message::Write(rng.pick(&words).expect("there are words").to_string())
+        }
+        3 => {
+            let colors = [Color::RED, Color::GREEN, Color::BLUE];
+            Message::ChangeColor(rng.pick(&colors).expect("there are colors"))
+        }
+        4 => Message::Resize {
+            width: rng.roll_range(1, 80) as u32,
+            height: rng.roll_range(1, 24) as u32,
+        },
+        _ => Message::Draw(vec![(
+            rng.roll_range(0, 79) as i32,
+            rng.roll_range(0, 23) as i32,
+        )]),
+    }
+}
+
+/// # Counting `Message`s by kind
+///
+/// Uses `MessageKind` to tally a mixed list of messages, reporting a count
+/// for every kind, including those that never appear.
+fn message_kinds(r: &mut Reporter) -> io::Result<()> {
+    let messages = [
+        Message::Move { x: 1, y: 0 },
+        Message::Write(String::from("a")),
+        Message::Move { x: 0, y: 1 },
+        Message::Quit { code: 0 },
+    ];
+    let counts = count_by_kind(&messages);
+    for kind in MessageKind::iter() {
+        writeln!(r, "{:?}: {}", kind, counts.get(&kind).unwrap_or(&0))?;
+    }
+    r.result(format_args!(
+        "{} message(s) of {} kind(s)",
+        messages.len(),
+        counts.len()
+    ))
+}
+
+/// # Parsing a list of `Message`s
+///
+/// Parses a few lines of command text and prints each resulting `Message`, or
+/// the reason it could not be parsed
+fn parse_messages(r: &mut Reporter) -> io::Result<()> {
+    let lines = [
+        "move -2 5",
+        "WRITE hello there",
+        "color 0 128 255",
+        "resize 40 12",
+        "draw 1,1 2,2 3,3",
+        "draw 1;1",
+        "jump 1",
+        "move 1",
+        "quit",
+    ];
+    let mut parsed = 0;
+    for line in lines {
+        match line.parse::<Message>() {
+            Ok(m) => {
+                parsed += 1;
+                writeln!(r, "`{line}` parsed as {:?}", m)?
+            }
+            Err(e) => writeln!(r, "`{line}` failed to parse: {e}")?,
+        }
+    }
+    r.result(format_args!("{parsed} of {} line(s) parsed", lines.len()))
+}
+
+/// # Displaying a list of `Message`s
+///
+/// Prints a mixed `Vec` of `Message`s using their `Display` form, one per line
+fn display_messages(r: &mut Reporter) -> io::Result<()> {
+    let messages = vec![
+        Message::Move { x: 3, y: 7 },
+        Message::Write(String::from("say \"hi\"\nthen leave")),
+        Message::ChangeColor(Color::RED),
+        Message::Resize {
+            width: 80,
+            height: 24,
+        },
+        Message::Draw(vec![(1, 2), (3, 4)]),
+        Message::Quit { code: 0 },
+    ];
+    for m in &messages {
+        writeln!(r, "{m}")?;
+    }
+    r.result(format_args!("{} message(s) displayed", messages.len()))
+}
+
+/// # JSON round trip for `Message`
+///
+/// Serializes a `Vec` holding every `Message` variant to JSON and reads it
+/// back, see the [`json`] module for the representation used.
+#[cfg(feature = "json")]
+fn json_round_trip(r: &mut Reporter) -> io::Result<()> {
+    let messages = vec![
+        Message::Quit { code: 0 },
+        Message::Move { x: 3, y: 7 },
+        Message::Write(String::from("hello")),
+        Message::ChangeColor(Color::RED),
+        Message::Resize {
+            width: 80,
+            height: 24,
+        },
+        Message::Draw(vec![(1, 2), (3, 4)]),
+    ];
+    let encoded = json::messages_to_json(&messages);
+    writeln!(r, "Messages as JSON: {encoded}")?;
+    match json::messages_from_json(&encoded) {
+        Ok(decoded) => r.result(format_args!("Read back from JSON: {:?}", decoded))?,
+        Err(e) => writeln!(r, "Could not read back JSON: {e}")?,
+    }
+    for m in &messages {
+        let single = json::message_to_json(m);
+        match json::message_from_json(&single) {
+            Ok(decoded) => writeln!(r, "`{single}` -> {decoded}")?,
+            Err(e) => writeln!(r, "`{single}` failed: {e}")?,
+        }
+    }
+    Ok(())
+}
+
+/// # Binary encoding for `Message`
+///
+/// Packs one of each `Message` variant back to back into a single buffer
+/// using the tagged encoding from the [`codec`] module, then decodes them
+/// again one at a time. Finally an old version 1 [`Envelope`] is decoded
+/// and upgraded to a current `Message`.
+fn binary_round_trip(r: &mut Reporter) -> io::Result<()> {
+    let messages = vec![
+        Message::Quit { code: 0 },
+        Message::Move { x: 3, y: 7 },
+        Message::Write(String::from("hello")),
+        Message::ChangeColor(Color::RED),
+        Message::Resize {
+            width: 80,
+            height: 24,
+        },
+        Message::Draw(vec![(1, 2), (3, 4)]),
+    ];
+    let buf = codec::encode_batch(&messages).map_err(io::Error::other)?;
+    r.result(format_args!(
+        "{} messages encoded into {} bytes: {:?}",
+        messages.len(),
+        buf.len(),
+        buf
+    ))?;
+
+    let mut rest = buf.as_slice();
+    while !rest.is_empty() {
+        match Message::decode(rest) {
+            Ok((m, used)) => {
+                writeln!(r, "Decoded {m} from {used} bytes")?;
+                rest = &rest[used..];
+            }
+            Err(e) => {
+                writeln!(r, "Could not decode: {e}")?;
+                break;
+            }
+        }
+    }
+
+    let mut old = Vec::new();
+    Envelope::V1(MessageV1::ChangeColor(-5, 300, 7))
+        .encode(&mut old)
+        .map_err(io::Error::other)?;
+    match Envelope::decode(&old) {
+        Ok((envelope, _)) => writeln!(
+            r,
+            "Version 1 {:?} upgrades to {}",
+            envelope,
+            envelope.clone().upgrade()
+        )?,
+        Err(e) => writeln!(r, "Could not decode envelope: {e}")?,
+    }
+    Ok(())
+}
+
+/// # Streaming framed `Message`s
+///
+/// Three messages go through a `MessageWriter` into a `Vec`, then come back
+/// out of a `MessageReader` that only ever gets one byte per `read`.
+fn framed_stream(r: &mut Reporter) -> io::Result<()> {
+    let mut writer = MessageWriter::new(Vec::new());
+    for m in [
+        Message::Write(String::from("framed")),
+        Message::Move { x: -1, y: 1 },
+        Message::Quit { code: 0 },
+    ] {
+        if let Err(e) = writer.write_message(&m) {
+            writeln!(r, "Could not write frame: {e}")?;
+        }
+    }
+    let bytes = writer.into_inner();
+    let mut reader = MessageReader::new(OneByteAtATime(&bytes));
+    let mut read = 0;
+    loop {
+        match reader.read_message() {
+            Ok(Some(m)) => {
+                read += 1;
+                writeln!(r, "Read framed {m}")?
+            }
+            Ok(None) => break,
+            Err(e) => {
+                writeln!(r, "Could not read frame: {e}")?;
+                break;
+            }
+        }
+    }
+    r.result(format_args!(
+        "Read {read} framed message(s) from {} bytes",
+        bytes.len()
+    ))
+}
+
+/// # Composing `Message`s with a builder
+///
+/// [`MessageScript`] builds a `Vec<Message>` one call at a time, with
+/// `repeat` for loops. Only the first `quit()` adds a `Quit`.
+fn message_script(r: &mut Reporter) -> io::Result<()> {
+    let script = MessageScript::new()
+        .color(0, 0, 255)
+        .repeat(3, |b| b.move_by(2, 1).write("step"))
+        .quit()
+        .quit();
+    if script.is_empty() {
+        return r.result("Built an empty script");
+    }
+    r.result(format_args!("Built a script of {} messages:", script.len()))?;
+    for m in script.build() {
+        writeln!(r, "  {m}")?;
+    }
+    Ok(())
+}
+
+/// # Applying a queue of `Message`s to a `Canvas`
+///
+/// Runs a scripted queue against a fresh [`canvas::Canvas`]. The `Quit` in
+/// the middle stops processing, so the messages after it are never applied.
+fn message_queue(r: &mut Reporter) -> io::Result<()> {
+    let script = MessageScript::new()
+        .move_by(3, 4)
+        .write("hello")
+        .color(255, 136, 0)
+        .move_by(-1, 2)
+        .quit()
+        .write("never applied");
+    let queue = script.build();
+    let mut canvas = canvas::Canvas::new();
+    let applied = canvas::run_queue(&mut canvas, &queue);
+    r.result(format_args!(
+        "Applied {applied} of {} messages",
+        queue.len()
+    ))?;
+    r.detail(format_args!(
+        "Final canvas: cursor = {:?}, color = {:?}, log = {:?}, running = {}",
+        canvas.cursor(),
+        canvas.color(),
+        canvas.log(),
+        canvas.is_running()
+    ))
+}
+
+/// # Validated colors for `ChangeColor`
+///
+/// `Message::ChangeColor` holds a [`Color`], which can only be built from
+/// components in `0..=255`. Out-of-range components and malformed hex are
+/// rejected with a `ColorError` instead of being clamped.
+fn validated_colors(r: &mut Reporter) -> io::Result<()> {
+    let mut rejected = 0;
+    for (red, green, blue) in [(255, 136, 0), (-5, 9999, 0)] {
+        match Color::new(red, green, blue) {
+            Ok(color) => writeln!(r, "Color::new({red}, {green}, {blue}) = {color}")?,
+            Err(e) => {
+                rejected += 1;
+                writeln!(r, "Color::new({red}, {green}, {blue}) failed: {e}")?
+            }
+        }
+    }
+    for hex in ["#00ff00", "00f", "#nope"] {
+        match Color::from_hex(hex) {
+            Ok(color) => writeln!(r, "Color::from_hex({hex:?}) = {color}")?,
+            Err(e) => {
+                rejected += 1;
+                writeln!(r, "Color::from_hex({hex:?}) failed: {e}")?
+            }
+        }
+    }
+    for (name, color) in [
+        ("RED", Color::RED),
+        ("GREEN", Color::GREEN),
+        ("BLUE", Color::BLUE),
+        ("BLACK", Color::BLACK),
+        ("WHITE", Color::WHITE),
+    ] {
+        writeln!(r, "{}", Message::ChangeColor(color).describe())?;
+        writeln!(r, "  (that was Color::{name})")?;
+    }
+    r.result(format_args!("{rejected} of 5 color(s) rejected"))
+}
+
+/// # Moving around a bounded `Board`
+///
+/// Walks the cursor off every edge of a small board, once with
+/// `MovePolicy::Clamp` and once with `MovePolicy::Reject`, printing the
+/// `ApplyOutcome` of each move.
+fn bounded_moves(r: &mut Reporter) -> io::Result<()> {
+    let walk = [
+        Message::Move { x: 7, y: 0 },
+        Message::Move { x: 0, y: 9 },
+        Message::Move { x: -20, y: -20 },
+        Message::Move { x: 2, y: 1 },
+    ];
+    let mut ends = Vec::new();
+    for policy in [canvas::MovePolicy::Clamp, canvas::MovePolicy::Reject] {
+        let Some(board) = canvas::Board::new(5, 4) else {
+            return Ok(());
+        };
+        let mut canvas = canvas::Canvas::with_board(board, policy);
+        writeln!(
+            r,
+            "{:?} on a {}x{} board:",
+            canvas.policy(),
+            canvas.board().width(),
+            canvas.board().height()
+        )?;
+        for m in &walk {
+            let outcome = canvas::apply(&mut canvas, m);
+            writeln!(r, "  {m}: {:?}, cursor now {:?}", outcome, canvas.cursor())?;
+        }
+        ends.push(format!("{:?} under {:?}", canvas.cursor(), canvas.policy()));
+    }
+    r.result(format_args!("The cursor ends at {}", ends.join(", ")))
+}
+
+/// # Applying a batch of `Message`s
+///
+/// Runs the same batch through `canvas::apply_all` twice: once carrying on
+/// past failures, and once with `fail_fast` stopping at the first one.
+fn batch_apply(r: &mut Reporter) -> io::Result<()> {
+    let batch = [
+        Message::Move { x: 2, y: 2 },
+        Message::Move { x: 100, y: 0 },
+        Message::Write(String::from("hello")),
+        Message::Quit { code: 0 },
+        Message::Move { x: 1, y: 0 },
+    ];
+    let mut applied = Vec::new();
+    for fail_fast in [false, true] {
+        let Some(board) = canvas::Board::new(10, 10) else {
+            return Ok(());
+        };
+        let mut canvas = canvas::Canvas::with_board(board, canvas::MovePolicy::Reject);
+        let result = canvas::apply_all(&mut canvas, &batch, fail_fast);
+        writeln!(r, "fail_fast = {fail_fast}: applied {}", result.applied)?;
+        for (index, error) in &result.errors {
+            writeln!(r, "  message {index} ({}) failed: {error}", batch[*index])?;
+        }
+        applied.push(result.applied);
+    }
+    r.result(format_args!(
+        "Applied {} of {} carrying on, {} with fail_fast",
+        applied[0],
+        batch.len(),
+        applied[1]
+    ))
+}
+
+/// # Scheduling `Message`s by priority
+///
+/// Pushes a shuffled batch into a [`priority::PriorityQueue`]. However the
+/// batch is shuffled, the `Quit` comes out first, so draining the queue into
+/// a canvas applies nothing else.
+fn priority_scheduling(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let mut batch = || {
+        let mut batch = vec![
+            Message::Write(String::from("low")),
+            Message::Move { x: 1, y: 0 },
+            Message::Move { x: 2, y: 0 },
+            Message::ChangeColor(Color::RED),
+            Message::Quit { code: 0 },
+        ];
+        shuffle(&mut batch, rng);
+        batch
+    };
+
+    let mut queue = priority::PriorityQueue::new();
+    let shuffled = batch();
+    let order: Vec<String> = shuffled.iter().map(Message::to_string).collect();
+    writeln!(r, "Pushed in order: {}", order.join(", "))?;
+    for m in shuffled {
+        queue.push(m);
+    }
+    let mut popped = Vec::new();
+    while let Some(m) = queue.pop() {
+        popped.push(format!("{m} ({:?})", m.priority()));
+    }
+    r.result(format_args!("Popped in order: {}", popped.join(", ")))?;
+
+    let mut queue = priority::PriorityQueue::new();
+    for m in batch() {
+        queue.push(m);
+    }
+    let queued = queue.len();
+    let mut canvas = canvas::Canvas::new();
+    let applied = queue.drain_to(&mut canvas);
+    writeln!(
+        r,
+        "Drained {queued} messages, applied {applied}; queue empty: {}",
+        queue.is_empty()
+    )?;
+    Ok(())
+}
+
+/// Put `items` in an order picked by `rng`, swapping as `SliceRandom::shuffle`
+/// does, so a `SeededRng` shuffles as a `StdRng` with the same seed would
+fn shuffle<T>(items: &mut [T], rng: &mut impl CrateRng) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.roll_range(0, i as u64) as usize);
+    }
+}
+
+/// # Dropping expired `Message`s
+///
+/// A queue of [`expiry::Timed`] messages drained at 100ms keeps the ones
+/// that expire later, drops the rest, and keeps the overdue `Quit` anyway.
+fn expiring_messages(r: &mut Reporter) -> io::Result<()> {
+    let mut queue = VecDeque::from([
+        Timed::new(Message::Write(String::from("too late")), 40),
+        Timed::new(Message::Move { x: 2, y: 2 }, 250),
+        Timed::new(Message::Quit { code: 0 }, 10),
+    ]);
+    let (live, expired) = expiry::drain_live(&mut queue, 100);
+    let live: Vec<String> = live.iter().map(Message::to_string).collect();
+    r.result(format_args!(
+        "At 100ms: {expired} expired, still live: {}",
+        live.join(", ")
+    ))
+}
+
+/// # Undoing `Message`s
+///
+/// Applies a few messages through a [`history::History`], then undoes them
+/// one at a time until there is nothing left to undo. The `Write` can't be
+/// undone, so `undo` steps over it.
+fn undo_messages(r: &mut Reporter) -> io::Result<()> {
+    let mut canvas = canvas::Canvas::new();
+    let mut history = history::History::new();
+    for m in [
+        Message::Move { x: 3, y: 4 },
+        Message::ChangeColor(Color::RED),
+        Message::Write(String::from("stays in the log")),
+        Message::Move { x: 1, y: 1 },
+    ] {
+        history.apply(&mut canvas, m);
+    }
+    writeln!(
+        r,
+        "After {} undoable changes: cursor = {:?}, color = {}",
+        history.len(),
+        canvas.cursor(),
+        canvas.color()
+    )?;
+    loop {
+        match history.undo(&mut canvas) {
+            Ok(()) => writeln!(
+                r,
+                "Undo: cursor = {:?}, color = {}",
+                canvas.cursor(),
+                canvas.color()
+            )?,
+            Err(e) => {
+                r.result(format_args!(
+                    "Undo stopped: {e} (history empty: {}); log = {:?}",
+                    history.is_empty(),
+                    canvas.log()
+                ))?;
+                break;
             }
-            Message::Quit => unimplemented!(),
-            Message::Move { x, y } => unimplemented!(),
-            Message::ChangeColor(_, _, _) => unimplemented!(),
         }
     }
+    Ok(())
+}
+
+/// # Sending `Message`s to a worker thread
+///
+/// Sends a scripted sequence to a [`worker::spawn_worker`] thread from the
+/// main thread, then joins it to get back the canvas it built.
+fn message_worker(r: &mut Reporter) -> io::Result<()> {
+    let (sender, handle) = worker::spawn_worker();
+    for m in [
+        Message::Write(String::from("from the main thread")),
+        Message::Move { x: 5, y: 5 },
+        Message::ChangeColor(Color::GREEN),
+        Message::Quit { code: 0 },
+    ] {
+        if sender.send(m).is_err() {
+            break;
+        }
+    }
+    match handle.join() {
+        Ok(canvas) => r.result(format_args!(
+            "Worker finished: cursor = {:?}, color = {}, log = {:?}",
+            canvas.cursor(),
+            canvas.color(),
+            canvas.log()
+        )),
+        Err(_) => r.warn("Worker thread panicked"),
+    }
+}
+
+/// # Replaying a recorded session
+///
+/// Replays a session of a few messages sent 20ms apart ten times faster than
+/// it was recorded, really sleeping between them. The demo reports the time
+/// it was asked to sleep rather than the time it took, which would be
+/// different on every run.
+fn replay_session(r: &mut Reporter) -> io::Result<()> {
+    let recording: Vec<_> = [
+        Message::Write(String::from("recorded")),
+        Message::Move { x: 1, y: 1 },
+        Message::Quit { code: 0 },
+    ]
+    .into_iter()
+    .zip((0..).step_by(20))
+    .map(|(msg, at_ms)| replay::RecordedMessage { at_ms, msg })
+    .collect();
+    let mut canvas = canvas::Canvas::new();
+    let mut slept = Duration::ZERO;
+    let applied = replay::replay(&recording, &mut canvas, 10.0, &mut |pause| {
+        slept += pause;
+        thread::sleep(pause);
+    });
+    r.result(format_args!(
+        "Replayed {applied} messages recorded over {}ms, sleeping {slept:?}: {canvas}",
+        recording.last().map_or(0, |r| r.at_ms),
+    ))
 }
 
 /// # The `Option` Enum and Its Advantages Over Null Values
@@ -304,7 +1176,7 @@ impl Message {
 /// that we mean for `absent_number` to be of `type Option<i32>`.
 ///
 /// [1]: https://doc.rust-lang.org/book/ch10-00-generics.html
-fn option_type() {
+fn option_type(r: &mut Reporter) -> io::Result<()> {
     // Rust compiler can infer the Option<T> types for these variables
     let some_number = Some(5);
     let some_char = Some('e');
@@ -314,15 +1186,12 @@ fn option_type() {
     // in the following example:
     let absent_number: Option<i32> = None;
 
-    println!("`some_number` is: {:?}", some_number);
-    print!("Type of variable `some_number` is: ");
-    print_type_of(&some_number);
-    println!("`some_char` is: {:?}", some_char);
-    print!("Type of variable `some_char` is: ");
-    print_type_of(&some_char);
-    println!("`absent_number` is: {:?}", absent_number);
-    print!("Type of variable `absent_number` is: ");
-    print_type_of(&absent_number);
+    writeln!(r, "`some_number` is: {:?}", some_number)?;
+    print_type_of(r, "some_number", &some_number)?;
+    writeln!(r, "`some_char` is: {:?}", some_char)?;
+    print_type_of(r, "some_char", &some_char)?;
+    r.result(format_args!("`absent_number` is: {:?}", absent_number))?;
+    print_type_of(r, "absent_number", &absent_number)?;
 
     // When we have a `Some` value, we know that a value is present and the
     // value is held within the `Some`. When we have a `None` value, in some
@@ -336,6 +1205,7 @@ fn option_type() {
     let _y: Option<i8> = Option::Some(5);
 
     // let sum = _x + _y; // Compile Error: cannot add `Option<i8>` to `i8`
+    Ok(())
 }
 
 /// # The `match` Control Flow Construct
@@ -361,23 +1231,37 @@ fn option_type() {
 /// cents, as shown in `value_in_cents()`.
 ///
 /// [1]: https://doc.rust-lang.org/book/ch18-00-patterns.html
-fn match_control_flow() {
+fn match_control_flow(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
     let penny = Coin::Penny;
     let nickel = Coin::Nickel;
     let dime = Coin::Dime;
     let quarter = Coin::Quarter;
-    let mut rng = thread_rng();
     let vec_coins = Coin::iter().collect::<Vec<_>>();
-    let random_coin = vec_coins.choose(&mut rng).unwrap();
+    let random_coin = &rng.pick(&vec_coins).expect("there are coins");
+    print_type_of(r, "random_coin", random_coin)?;
 
-    println!("`penny` value_in_cents = {:?}", value_in_cents(&penny));
-    println!("`nickel` value_in_cents = {:?}", value_in_cents(&nickel));
-    println!("`dime` value_in_cents = {:?}", value_in_cents(&dime));
-    println!("`quarter` value_in_cents = {:?}", value_in_cents(&quarter));
-    println!(
-        "`random_coin` value_in_cents = {:?}",
-        value_in_cents(random_coin)
-    );
+    let mut coins = Vec::new();
+    for (name, coin) in [
+        ("penny", &penny),
+        ("nickel", &nickel),
+        ("dime", &dime),
+        ("quarter", &quarter),
+        ("random_coin", random_coin),
+    ] {
+        let cents = value_in_cents(coin);
+        let line = format!("`{name}` value_in_cents = {}", r.value(cents));
+        // The fixed coins show the `match`; the random one is what it found
+        if name == "random_coin" {
+            r.result(line)?;
+        } else {
+            r.note(line)?;
+        }
+        coins.push(Json::object([
+            ("kind", Json::from(format!("{coin:?}").to_lowercase())),
+            ("cents", Json::from(u32::from(cents))),
+        ]));
+    }
+    r.record([("coins", Json::Array(coins))])
 }
 
 /// # value_in_cents(): Example of the `match` Control Flow Construct
@@ -393,7 +1277,7 @@ fn value_in_cents(coin: &Coin) -> u8 {
             // If you want to run multiple lines of code in a match arm, you
             // must use curly brackets, and the comma following the arm is then
             // optional.
-            println!("Lucky penny!");
+            logging::debug!("Lucky penny!");
             1
         }
         Coin::Nickel => 5,
@@ -402,28 +1286,6 @@ fn value_in_cents(coin: &Coin) -> u8 {
     }
 }
 
-/// # Enum to represent `Coin`s
-///
-/// An enum that has all variants of `Coin` types
-#[derive(Debug, EnumIter)]
-enum Coin {
-    Penny,
-    Nickel,
-    Dime,
-    Quarter,
-}
-
-/// # A `noop` macro to perform no operation
-///
-/// Source: [Reddit: best no op macro? assert!(true)? What do you think of this
-/// error handling pattern?][1]
-///
-/// [1]:
-///     https://www.reddit.com/r/rust/comments/8powj8/comment/e0czkeu/?utm_source=reddit&utm_medium=web2x&context=3
-macro_rules! noop {
-    () => {};
-}
-
 /// # Patterns That Bind to Values
 ///
 /// Another useful feature of match arms is that they can bind to the parts of
@@ -436,134 +1298,84 @@ macro_rules! noop {
 /// designs, so only quarters have this extra value. We can add this information
 /// to our `enum` by changing the `Quarter` variant to include a `UsState` value
 /// stored inside it, which we’ve done below.
-fn match_control_flow_patterns() {
+fn match_control_flow_patterns(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
     let penny = Coin2::Penny;
     let nickel = Coin2::Nickel;
     let dime = Coin2::Dime;
     let quarter = Coin2::Quarter(UsState::default());
-    let mut rng = thread_rng();
     let vec_coins = Coin2::iter().collect::<Vec<_>>();
-    let mut random_coin = *vec_coins.choose(&mut rng).unwrap();
+    let mut random_coin = rng.pick(&vec_coins).expect("there are coins");
     let vec_states = UsState::iter().collect::<Vec<_>>();
-    let random_state: &UsState;
+    let random_state: UsState;
 
     match random_coin {
-        Coin2::Penny => {
-            noop!();
-        }
-        Coin2::Nickel => {
-            noop!();
-        }
-        Coin2::Dime => {
-            noop!();
-        }
+        Coin2::Penny | Coin2::Nickel | Coin2::Dime => noop!(only quarters have a state),
         Coin2::Quarter(_) => {
-            random_state = vec_states.choose(&mut rng).unwrap();
-            random_coin = Coin2::Quarter(*random_state);
+            random_state = rng.pick(&vec_states).expect("there are states");
+            random_coin = Coin2::Quarter(random_state);
         }
     }
+    print_type_of(r, "random_coin", &random_coin)?;
 
-    println!(
-        "`penny` value_in_cents_state_quarters = {:?}",
-        value_in_cents_state_quarters(&penny)
-    );
-    println!(
-        "`nickel` value_in_cents_state_quarters = {:?}",
-        value_in_cents_state_quarters(&nickel)
-    );
-    println!(
-        "`dime` value_in_cents_state_quarters = {:?}",
-        value_in_cents_state_quarters(&dime)
-    );
-    println!(
-        "`quarter` value_in_cents_state_quarters = {:?}",
-        value_in_cents_state_quarters(&quarter)
-    );
-    println!(
-        "`random_coin` value_in_cents_state_quarters = {:?}",
-        value_in_cents_state_quarters(&random_coin)
-    );
-}
-
-/// #  Enum to represent `UsState`s for all 50 US State Quarters
-///
-/// `Quarter` variant to include a `UsState` value stored inside it
-#[derive(Debug, EnumIter, Clone, Copy)]
-enum UsState {
-    Alabama,
-    Alaska,
-    Arizona,
-    Arkansas,
-    California,
-    Colorado,
-    Connecticut,
-    Delaware,
-    Florida,
-    Georgia,
-    Hawaii,
-    Idaho,
-    Illinois,
-    Indiana,
-    Iowa,
-    Kansas,
-    Kentucky,
-    Louisiana,
-    Maine,
-    Maryland,
-    Massachusetts,
-    Michigan,
-    Minnesota,
-    Mississippi,
-    Missouri,
-    Montana,
-    Nebraska,
-    Nevada,
-    NewHampshire,
-    NewJersey,
-    NewMexico,
-    NewYork,
-    NorthCarolina,
-    NorthDakota,
-    Ohio,
-    Oklahoma,
-    Oregon,
-    Pennsylvania,
-    RhodeIsland,
-    SouthCarolina,
-    SouthDakota,
-    Tennessee,
-    Texas,
-    Utah,
-    Vermont,
-    Virginia,
-    Washington,
-    WestVirginia,
-    Wisconsin,
-    Wyoming,
-}
-
-/// # `Default` trait implementation for `UsState`
-///
-/// You have a better chance of finding `Virginia` quarters than any others.
-/// Mint records show nearly 1.6 billion were put into circulation.
-///
-/// Therefore, the `Coin2::Quarter(UsState::Virginia)` variant is the most
-/// probable to find and is set to be the `Default` variant
-impl Default for UsState {
-    fn default() -> Self {
-        UsState::Virginia
-    }
-}
-
-/// # Enum to represent `Coin`s and US State Quarters
-///
-/// A `Coin` enum in which the `Quarter` variant also holds a `UsState` value
-#[derive(Debug, EnumIter, Clone, Copy)]
-enum Coin2 {
-    Penny,
-    Nickel,
-    Dime,
-    Quarter(UsState),
+    let mut coins = Vec::new();
+    for (name, coin) in [
+        ("penny", &penny),
+        ("nickel", &nickel),
+        ("dime", &dime),
+        ("quarter", &quarter),
+        ("random_coin", &random_coin),
+    ] {
+        let cents = value_in_cents_state_quarters(coin);
+        let line = format!(
+            "`{name}` value_in_cents_state_quarters = {}",
+            r.value(cents)
+        );
+        if name == "random_coin" {
+            r.result(line)?;
+        } else {
+            r.note(line)?;
+        }
+        let state = match coin {
+            Coin2::Quarter(state) => Json::from(format!("{state:?}")),
+            _ => Json::Null,
+        };
+        coins.push(Json::object([
+            (
+                "kind",
+                Json::from(format!("{:?}", coin.kind()).to_lowercase()),
+            ),
+            ("cents", Json::from(u32::from(cents))),
+            ("state", state),
+        ]));
+    }
+    r.record([("coins", Json::Array(coins))])
+}
+
+/// # Chaining `Option` combinators
+///
+/// Looking up a state and then its quarter's year is two steps that can
+/// each come up empty, or in this case only the first can. `map` chains the
+/// second onto the first without a `match`, and `unwrap_or` and `ok_or_else`
+/// say what to do when there is nothing at the end.
+fn quarter_years(r: &mut Reporter) -> io::Result<()> {
+    let names = ["Ohio", "Atlantis"];
+    for name in names {
+        writeln!(
+            r,
+            "{name}: {:?}, or else {}, or as a Result {:?}",
+            quarter_year_for(name),
+            quarter_year_or_default(name),
+            quarter_year_or_err(name).map_err(|e| e.to_string())
+        )?;
+    }
+    let minted = names
+        .iter()
+        .filter_map(|name| quarter_year_for(name))
+        .count();
+    r.result(format_args!(
+        "{minted} of {} state(s) have a quarter year",
+        names.len()
+    ))
 }
 
 /// # Example of the `match` Control Flow Construct with Patterns That Bind to Values
@@ -579,12 +1391,39 @@ fn value_in_cents_state_quarters(coin: &Coin2) -> u8 {
         Coin2::Nickel => 5,
         Coin2::Dime => 10,
         Coin2::Quarter(state) => {
-            println!("State quarter from {:?}!", state);
+            logging::info!("State quarter from {:?}!", state);
             25
         }
     }
 }
 
+/// # Comparing coins and wallets with `Ordering`
+///
+/// Two coins compared by value, then two wallets of coins picked by `rng`.
+fn wallets(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let penny = Coin2::Penny;
+    let quarter = Coin2::Quarter(UsState::Texas);
+    writeln!(
+        r,
+        "{penny:?} against {quarter:?}: {:?}",
+        compare_value(&penny, &quarter)
+    )?;
+
+    let (a, b) = (Wallet::random_with(5, rng), Wallet::random_with(5, rng));
+    writeln!(r, "first wallet: {:?} = {}¢", a.coins(), r.value(a.total()))?;
+    writeln!(
+        r,
+        "second wallet: {:?} = {}¢",
+        b.coins(),
+        r.value(b.total())
+    )?;
+    match pick_richer(&a, &b) {
+        RicherOutcome::First(_) => r.result("the first wallet is richer"),
+        RicherOutcome::Second(_) => r.result("the second wallet is richer"),
+        RicherOutcome::Tie(cents) => r.result(format_args!("both wallets hold {cents}¢")),
+    }
+}
+
 /// # Matching with `Option<T>`
 ///
 /// In the previous section, we wanted to get the inner `T` value out of the
@@ -592,14 +1431,46 @@ fn value_in_cents_state_quarters(coin: &Coin2) -> u8 {
 /// `match`, as we did with the `Coin` enum! Instead of comparing coins, we’ll
 /// compare the variants of `Option<T>`, but the way the `match` expression
 /// works remains the same.
-fn matching_with_option_t() {
+fn matching_with_option_t(r: &mut Reporter) -> io::Result<()> {
     let five = Some(5);
     let six = plus_one(five);
     let none = plus_one(None);
 
-    println!("`five` is {:?}", five);
-    println!("`six` is {:?}", six);
-    println!("`none` is {:?}", none);
+    writeln!(r, "`five` is {:?}", five)?;
+    r.result(format_args!("`six` is {:?}", six))?;
+    writeln!(r, "`none` is {:?}", none)?;
+
+    // The same `match` works for any type that can add one to itself
+    let byte: Option<u8> = plus_one_generic(Some(254));
+    let big: Option<i64> = plus_one_generic(Some(-1));
+    let float: Option<f64> = plus_one_generic(Some(0.5));
+    let nothing: Option<f64> = plus_one_generic(None);
+    writeln!(
+        r,
+        "`u8` {:?}, `i64` {:?}, `f64` {:?}, `None` {:?}",
+        byte, big, float, nothing
+    )?;
+
+    // `plus_one(Some(i32::MAX))` would overflow, so say what to do instead
+    match checked_plus_one(Some(i32::MAX)) {
+        Ok(n) => writeln!(r, "checked `i32::MAX + 1` is {:?}", n)?,
+        Err(e) => writeln!(r, "checked `i32::MAX + 1` failed: {e}")?,
+    }
+    writeln!(
+        r,
+        "saturating `i32::MAX + 1` is {:?}",
+        saturating_plus_one(Some(i32::MAX))
+    )?;
+
+    // Adding any amount, one `Option` at a time with `match` or a whole batch
+    // with `map`
+    writeln!(r, "`plus_n(Some(5), -7)` is {:?}", plus_n(Some(5), -7))?;
+    writeln!(
+        r,
+        "`plus_n_all` by 10: {:?}",
+        plus_n_all([Some(1), None, Some(-3)], 10)
+    )?;
+    Ok(())
 }
 
 /// # Function to match an `Option<T>`
@@ -613,6 +1484,7 @@ fn matching_with_option_t() {
 ///
 /// This function is very easy to write, thanks to `match`, and will look like
 /// `plus_one()`.
+#[allow(clippy::manual_map)] // the explicit `match` is the point of the example
 fn plus_one(x: Option<i32>) -> Option<i32> {
     match x {
         None => None,
@@ -620,43 +1492,84 @@ fn plus_one(x: Option<i32>) -> Option<i32> {
     }
 }
 
-/// # Matches Are Exhaustive
-///
-/// There’s one other aspect of `match` we need to discuss: the arms’ patterns
-/// must cover all possibilities. Consider this version of our `plus_one`
-/// function, which has a bug and won’t compile: We didn’t handle the `None`
-/// case, so this code will cause a bug. Luckily, it’s a bug Rust knows how to
-/// catch. If we try to compile this code, we’ll get this error:
-///
-/// ```bash
-/// $ cargo run
-///   Compiling enums v0.1.0 (file:///projects/enums)
-///   error[E0004]: non-exhaustive patterns: `None` not covered
-///    --> src/main.rs:3:15
-///     |
-///   3 |         match x {
-///     |               ^ pattern `None` not covered
-///     |
-///   note: `Option<i32>` defined here
-///    --> /rustc/d5a82bbd26e1ad8b7401f6a718a9c57c96905483/library/core/src/option.rs:518:1
-///     |
-///     = note:
-///   /rustc/d5a82bbd26e1ad8b7401f6a718a9c57c96905483/library/core/src/option.rs:522:5: not covered
-///     = note: the matched value is of type `Option<i32>`
-///   help: ensure that all possible cases are being handled by adding a match arm with a wildcard pattern or an explicit pattern as shown
-///     |
-///   4 ~             Some(i) => Some(i + 1),
-///   5 ~             None => todo!(),
-///     |
-///   
-///   For more information about this error, try `rustc --explain E0004`.
-///   error: could not compile `enums` due to previous error
-/// ```
-///
-fn plus_one_broken(x: Option<i32>) -> Option<i32> {
+/// # Adding one would overflow
+#[derive(Debug, PartialEq, Eq)]
+pub struct OverflowError {
+    /// The value that couldn't have one added to it
+    pub value: i32,
+}
+
+impl std::fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} + 1 overflows `i32`", self.value)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+/// # `plus_one()` that reports overflow
+///
+/// `plus_one(Some(i32::MAX))` panics in a debug build and wraps around to
+/// `i32::MIN` in a release build. Here `checked_add` turns that case into an
+/// `Err`, so the caller has to decide what it means.
+pub fn checked_plus_one(x: Option<i32>) -> Result<Option<i32>, OverflowError> {
+    match x {
+        None => Ok(None),
+        Some(i) => match i.checked_add(1) {
+            Some(n) => Ok(Some(n)),
+            None => Err(OverflowError { value: i }),
+        },
+    }
+}
+
+/// # `plus_one()` that stops at `i32::MAX`
+///
+/// When overflow should just be ignored, `saturating_add` leaves `i32::MAX`
+/// where it is.
+#[allow(clippy::manual_map)] // the explicit `match` is the point of the example
+pub fn saturating_plus_one(x: Option<i32>) -> Option<i32> {
     match x {
-        Some(i) => Some(i + 1),
-        None => todo!(), // If this is commented out, we get Compile Error!
+        None => None,
+        Some(i) => Some(i.saturating_add(1)),
+    }
+}
+
+/// # Function to add any amount to an `Option<i32>`
+///
+/// `plus_one()` with the `1` replaced by `n`, which may be negative. Like
+/// `plus_one()`, it overflows if the sum doesn't fit in an `i32`.
+#[allow(clippy::manual_map)] // the explicit `match` is the point of the example
+pub fn plus_n(x: Option<i32>, n: i32) -> Option<i32> {
+    match x {
+        None => None,
+        Some(i) => Some(i + n),
+    }
+}
+
+/// # `plus_n()` over a whole batch
+///
+/// The same logic as `plus_n()` without writing out the `match`:
+/// `Option::map` applies the closure to a `Some` and passes a `None`
+/// through, and the iterator's own `map` does that for every element. Each
+/// `None` stays in its place in the output.
+pub fn plus_n_all(xs: impl IntoIterator<Item = Option<i32>>, n: i32) -> Vec<Option<i32>> {
+    xs.into_iter().map(|x| x.map(|i| i + n)).collect()
+}
+
+/// # Function to match an `Option<T>` for any number type
+///
+/// `plus_one()` with the `i32` replaced by a type parameter. The body is the
+/// same `match`; the bounds just say that `T` can be added to and that a `1`
+/// of type `T` can be made from a `u8`, which holds for `u8`, `i64`, `f64`
+/// and most other number types.
+#[allow(clippy::manual_map)] // the explicit `match` is the point of the example
+pub fn plus_one_generic<T>(x: Option<T>) -> Option<T>
+where
+    T: std::ops::Add<Output = T> + From<u8>,
+{
+    match x {
+        None => None,
+        Some(i) => Some(i + T::from(1)),
     }
 }
 
@@ -668,20 +1581,12 @@ fn plus_one_broken(x: Option<i32>) -> Option<i32> {
 /// instead gets a new fancy hat. If you roll a 7, your player loses a fancy
 /// hat. For all other values, your player moves that number of spaces on the
 /// game board. Here’s a `match` that implements that logic, with the result of
-/// the dice roll hardcoded rather than a random value, and all other logic
-/// represented by functions without bodies because actually implementing them
-/// is out of scope for this example.
-fn catch_all_patterns() {
-    let dice_roll = 9;
-    match dice_roll {
-        3 => add_fancy_hat(),
-        7 => remove_fancy_hat(),
-        other => move_player(other),
-    }
-
-    fn add_fancy_hat() {}
-    fn remove_fancy_hat() {}
-    fn move_player(_num_spaces: u8) {}
+/// the dice roll hardcoded rather than a random value. The `match` is in
+/// [`dice_game::resolve_roll`], whose `other` arm binds the roll to move by,
+/// and which returns what the roll calls for as a [`dice_game::GameEvent`].
+fn catch_all_patterns(r: &mut Reporter) -> io::Result<()> {
+    show_rolls(r, GameRules::MoveOnOther, &[3, 7, 9])?;
+    Ok(())
 }
 
 /// # Catch-all Patterns and the `_` Placeholder
@@ -696,17 +1601,9 @@ fn catch_all_patterns() {
 /// so we can change our code to use `_` instead of the variable named `other`.
 ///
 /// This example also meets the exhaustiveness requirement because we’re explicitly ignoring all other values in the last arm; we haven’t forgotten anything.
-fn catch_all_patterns_underscore_placeholder() {
-    let dice_roll = 9;
-    match dice_roll {
-        3 => add_fancy_hat(),
-        7 => remove_fancy_hat(),
-        _ => reroll(),
-    }
-
-    fn add_fancy_hat() {}
-    fn remove_fancy_hat() {}
-    fn reroll() {}
+fn catch_all_patterns_underscore_placeholder(r: &mut Reporter) -> io::Result<()> {
+    show_rolls(r, GameRules::RerollOnOther, &[3, 7, 9])?;
+    Ok(())
 }
 
 /// # Catch-all Patterns and the `_` Placeholder
@@ -726,16 +1623,137 @@ fn catch_all_patterns_underscore_placeholder() {
 ///
 /// [1]: https://doc.rust-lang.org/book/ch03-02-data-types.html#the-tuple-type
 /// [2]: https://doc.rust-lang.org/book/ch18-00-patterns.html
-fn catch_all_patterns_noop_catchall() {
-    let dice_roll = 9;
-    match dice_roll {
-        3 => add_fancy_hat(),
-        7 => remove_fancy_hat(),
-        _ => (),
+fn catch_all_patterns_noop_catchall(r: &mut Reporter) -> io::Result<()> {
+    show_rolls(r, GameRules::NothingOnOther, &[3, 7, 9])?;
+    Ok(())
+}
+
+/// Print what each of `rolls` calls for under `rules`, and where they leave
+/// a player who started with no hats on space 0
+fn show_rolls(r: &mut Reporter, rules: GameRules, rolls: &[u8]) -> io::Result<()> {
+    let mut game = DiceGame::with_rules(10, rules).expect("board has spaces");
+    for &dice_roll in rolls {
+        let event = resolve_roll(dice_roll, rules);
+        game.take_turn(dice_roll);
+        writeln!(r, "Rolled {dice_roll}: {event:?}, now {}", game.player())?;
     }
+    r.result(format_args!(
+        "After {} roll(s) under {rules:?}: {}",
+        rolls.len(),
+        game.player()
+    ))
+}
 
-    fn add_fancy_hat() {}
-    fn remove_fancy_hat() {}
+/// # Four players, one board
+///
+/// A [`dice_game::Tournament`] of twenty rounds, rolled with `rng`, and the
+/// final table.
+fn tournament(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let mut tournament =
+        Tournament::new(20, ["Ferris", "Corro", "Rusty", "Crabby"]).expect("board has spaces");
+    let winner = tournament
+        .winner_after(&mut RandomRoller::with_rng(D6::new(), rng), 20)
+        .expect("there are players");
+    r.result(format_args!("{} wins after 20 rounds", winner.name))?;
+    for standing in tournament.standings() {
+        writeln!(
+            r,
+            "{}. {:<6} {}",
+            standing.place, standing.name, standing.player
+        )?;
+    }
+    Ok(())
+}
+
+/// # A thousand games under each set of rules
+///
+/// The same seed for each, drawn from `rng`, so the differences come from
+/// the rules alone.
+fn simulations(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let seed = rng.roll_range(0, u64::MAX);
+    let all_rules = [
+        GameRules::MoveOnOther,
+        GameRules::RerollOnOther,
+        GameRules::NothingOnOther,
+    ];
+    for rules in all_rules {
+        writeln!(
+            r,
+            "{rules:?}: {}",
+            dice_game::simulate_games(1_000, 20, rules, seed)
+        )?;
+    }
+    r.result(format_args!(
+        "Simulated 1000 game(s) under each of {} sets of rules",
+        all_rules.len()
+    ))
+}
+
+/// # `Option`s of references
+///
+/// Looking something up usually borrows it, giving an `Option<&T>` rather
+/// than an `Option<T>`. `as_ref` and `as_deref` borrow the inside of an
+/// owned `Option`, and `copied` goes the other way for `Copy` types.
+fn option_refs(r: &mut Reporter) -> io::Result<()> {
+    let xs = vec![-4, 0, 3, 8];
+    let first = first_positive(&xs);
+    r.result(format_args!(
+        "First positive in {xs:?}: {first:?}, plus one: {:?}",
+        plus_one_ref(first)
+    ))?;
+    let owned: Option<i32> = first.copied();
+    writeln!(r, "Copied out of the slice: {owned:?}")?;
+
+    let name = Some(String::from("Ferris"));
+    // `as_ref` lends `Option<&String>`, `as_deref` goes on to `Option<&str>`
+    let len = name.as_ref().map(String::len);
+    writeln!(r, "{} ({len:?} letters)", greet(name.as_deref()))?;
+    writeln!(r, "{} and `name` is still {name:?}", greet(None))?;
+    Ok(())
+}
+
+/// # One game, three sets of rules
+///
+/// The three catch-all examples above are the same game under different
+/// [`dice_game::GameRules`]. Ten turns of each, with the same rolls from a
+/// seed drawn from `rng`, show where they part ways.
+fn game_rules(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let seed = rng.roll_range(0, u64::MAX);
+    for rules in [
+        GameRules::MoveOnOther,
+        GameRules::RerollOnOther,
+        GameRules::NothingOnOther,
+    ] {
+        let mut game = DiceGame::with_rules(20, rules).expect("board has spaces");
+        let transcript = game.play(&mut RandomRoller::from_seed(seed), 10);
+        let rerolls = transcript.len() - 10;
+        let player = game.player();
+        writeln!(
+            r,
+            "{:?}: {} hat(s), on space {}, {rerolls} reroll(s)",
+            game.rules(),
+            player.hats(),
+            player.position()
+        )?;
+    }
+
+    // Two d20s still only win a hat on a 3 and lose one on a 7, which are
+    // much rarer than with two d6s
+    let mut game = DiceGame::new(20).expect("board has spaces");
+    game.play(&mut RandomRoller::with_die(D20::new(), seed), 10);
+    let history = game.history();
+    writeln!(
+        r,
+        "With d20s: {}, last rolls {:?}, {} roll(s) since the last 3",
+        game.player(),
+        history.last_n(5),
+        history.drought(3)
+    )?;
+
+    // A scripted die plays back exactly the rolls it is given: 3, 9, 7
+    let mut game = DiceGame::new(20).expect("board has spaces");
+    let transcript = game.play(&mut ScriptedRoller::new([1, 2, 4, 5, 3, 4]), 3);
+    r.result(format_args!("Scripted game: {transcript:?}"))
 }
 
 /// # Concise Control Flow with `if let` - Equivalent `match` Example
@@ -751,12 +1769,14 @@ fn catch_all_patterns_noop_catchall() {
 /// anything with the `None` value. To satisfy the `match` expression, we have
 /// to add `_ => ()` after processing just one variant, which is annoying
 /// boilerplate code to add.
-fn if_let_match_example() {
+#[allow(clippy::single_match)] // the `match` is the point of the example
+fn if_let_match_example(r: &mut Reporter) -> io::Result<()> {
     let config_max = Some(3u8);
     match config_max {
-        Some(max) => println!("The maximum is configured to be {}", max),
+        Some(max) => r.result(format_args!("The maximum is configured to be {}", max))?,
         _ => (),
     }
+    Ok(())
 }
 
 /// # Concise Control Flow with `if let` - Example
@@ -781,10 +1801,88 @@ fn if_let_match_example() {
 /// In other words, you can think of `if let` as syntax sugar for a `match` that
 /// runs code when the value matches one pattern and then ignores _all_ other
 /// values.
-fn if_let_example() {
+fn if_let_example(r: &mut Reporter) -> io::Result<()> {
     let config_max = Some(3u8);
     if let Some(max) = config_max {
-        println!("The maximum is configured to be {}", max);
+        r.result(format_args!("The maximum is configured to be {}", max))?;
+    }
+    Ok(())
+}
+
+/// # Concise Control Flow with `if let` - Functions to Test
+///
+/// The examples above only print. Here the same ideas return their results,
+/// so tests can check that each `if let` behaves just like its `match`:
+/// `count_non_quarters()` against `count_non_quarters_match()`, and
+/// `config_max_message()` against `config_max_message_match()`.
+fn concise_control_flow(r: &mut Reporter) -> io::Result<()> {
+    let coins = [
+        Coin2::Penny,
+        Coin2::Quarter(UsState::Alaska),
+        Coin2::Dime,
+        Coin2::Quarter(UsState::Texas),
+        Coin2::Nickel,
+    ];
+    let (count, states) = count_non_quarters(&coins);
+    r.result(format_args!(
+        "{count} non-quarter coin(s), quarters from {states:?}"
+    ))?;
+    for config_max in [Some(3u8), None] {
+        writeln!(
+            r,
+            "`config_max` {:?}: {:?}",
+            config_max,
+            config_max_message(config_max)
+        )?;
+    }
+    Ok(())
+}
+
+/// # Count the coins that aren't quarters, with `if let .. else`
+///
+/// Returns the count along with the state of each quarter, in order.
+pub fn count_non_quarters(coins: &[Coin2]) -> (usize, Vec<UsState>) {
+    let mut count = 0;
+    let mut states = Vec::new();
+    for coin in coins {
+        if let Coin2::Quarter(state) = coin {
+            states.push(*state);
+        } else {
+            count += 1;
+        }
+    }
+    (count, states)
+}
+
+/// # `count_non_quarters()` written with `match`
+pub fn count_non_quarters_match(coins: &[Coin2]) -> (usize, Vec<UsState>) {
+    let mut count = 0;
+    let mut states = Vec::new();
+    for coin in coins {
+        match coin {
+            Coin2::Quarter(state) => states.push(*state),
+            _ => count += 1,
+        }
+    }
+    (count, states)
+}
+
+/// # The message `if_let_example()` prints, if it prints one
+#[allow(clippy::manual_map)] // the explicit `if let` is the point of the example
+pub fn config_max_message(config_max: Option<u8>) -> Option<String> {
+    if let Some(max) = config_max {
+        Some(format!("The maximum is configured to be {}", max))
+    } else {
+        None
+    }
+}
+
+/// # `config_max_message()` written with `match`
+#[allow(clippy::manual_map)] // the explicit `match` is the point of the example
+pub fn config_max_message_match(config_max: Option<u8>) -> Option<String> {
+    match config_max {
+        Some(max) => Some(format!("The maximum is configured to be {}", max)),
+        None => None,
     }
 }
 
@@ -816,16 +1914,15 @@ fn if_let_example() {
 /// [1]: ./fn.match_control_flow_patterns.html
 /// [2]: https://doc.rust-lang.org/book/ch06-03-if-let.html#concise-control-flow-with-if-let
 /// [3]: https://manishearth.github.io/blog/2017/04/13/prolonging-temporaries-in-rust/
-fn if_let_else_example() {
-    let mut rng = thread_rng();
-    let vec_coins = Coin2::iter().collect::<Vec<_>>();
-    let vec_states = UsState::iter().collect::<Vec<_>>();
-    println!("if_let_else_example(): Counting coins...");
+fn if_let_else_example(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    // References to the `'static` variants, so that a pick is a `&Coin2`
+    let vec_coins = Coin2::VARIANTS.iter().collect::<Vec<_>>();
+    let vec_states = UsState::VARIANTS.iter().collect::<Vec<_>>();
+    writeln!(r, "if_let_else_example(): Counting coins...")?;
     let mut count = 0;
     for _i in 0..15 {
-        let mut coin = vec_coins.choose(&mut rng).unwrap();
+        let mut coin = rng.pick(&vec_coins).expect("there are coins");
 
-        let mut random_coin: &Coin2 = coin;
         // initialize type of `random_state`, but only set it later if needed
         let random_state: &UsState;
 
@@ -833,11 +1930,11 @@ fn if_let_else_example() {
         // Only gets set for `Quarter`s
         let mut _rnd_owned = None;
 
-        println!("Count is: {count}");
+        r.detail(format_args!("Count is: {count}"))?;
         let maybe_random_coin = {
             if let Coin2::Quarter(_default_state) = coin {
                 // Generate a random state when a `Quarter` was drawn
-                random_state = vec_states.choose(&mut rng).unwrap();
+                random_state = rng.pick(&vec_states).expect("there are states");
 
                 // This value only lives until the end of `if let` block
                 let _inner_scope_random_coin = Coin2::Quarter(*random_state);
@@ -845,7 +1942,7 @@ fn if_let_else_example() {
                 // we return its value
                 _rnd_owned = Some(Coin2::Quarter(*random_state));
                 if let &Coin2::Quarter(state) = &_inner_scope_random_coin {
-                    println!("State quarter from {:?}!", state);
+                    writeln!(r, "State quarter from {:?}!", state)?;
                 }
                 // Note: If we print state here, it will **always** be
                 // `Virginia` (the enum's `Default`)
@@ -871,7 +1968,323 @@ fn if_let_else_example() {
         };
         coin = maybe_random_coin.unwrap_or(coin);
         // Now we have access to the randomly generated State Quarter from the `if let` scope
-        println!("The `Coin` was: {:?}", coin);
+        writeln!(r, "The `Coin` was: {:?}", coin)?;
+    }
+    r.result(format_args!("Total count of coins: {count}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_enums::rng::SeededRng;
+
+    #[test]
+    fn quit_struct_round_trip() {
+        let m = Message::from(QuitMessage { code: 2 });
+        assert!(matches!(m, Message::Quit { code: 2 }));
+        assert_eq!(QuitMessage::try_from(m).unwrap().code, 2);
+    }
+
+    #[test]
+    fn move_struct_round_trip() {
+        let m = Message::from(MoveMessage { x: 3, y: 7 });
+        assert!(matches!(m, Message::Move { x: 3, y: 7 }));
+        let mv = MoveMessage::try_from(m).unwrap();
+        assert_eq!((mv.x, mv.y), (3, 7));
+    }
+
+    #[test]
+    fn write_struct_round_trip() {
+        let m = Message::from(WriteMessage(String::from("hello")));
+        assert!(matches!(&m, Message::Write(text) if text == "hello"));
+        assert_eq!(WriteMessage::try_from(m).unwrap().0, "hello");
+    }
+
+    #[test]
+    fn change_color_struct_round_trip() {
+        let m = Message::from(ChangeColorMessage(Color::rgb(1, 2, 3)));
+        assert!(matches!(m, Message::ChangeColor(c) if c == Color::rgb(1, 2, 3)));
+        let c = ChangeColorMessage::try_from(m).unwrap();
+        assert_eq!(c.0, Color::rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn try_from_wrong_variant_returns_message() {
+        let err = MoveMessage::try_from(Message::Write(String::from("hi"))).unwrap_err();
+        assert_eq!(err.expected, "Move");
+        assert!(matches!(&err.message, Message::Write(text) if text == "hi"));
+    }
+
+    #[test]
+    fn plus_one_generic_on_u8() {
+        assert_eq!(plus_one_generic(Some(4u8)), Some(5));
+        assert_eq!(plus_one_generic::<u8>(None), None);
+    }
+
+    #[test]
+    fn plus_one_generic_on_i64() {
+        assert_eq!(plus_one_generic(Some(i64::from(i32::MAX))), Some(1 << 31));
+        assert_eq!(plus_one_generic::<i64>(None), None);
+    }
+
+    #[test]
+    fn plus_one_generic_on_f64() {
+        assert_eq!(plus_one_generic(Some(0.5f64)), Some(1.5));
+        assert_eq!(plus_one_generic::<f64>(None), None);
+    }
+
+    #[test]
+    fn checked_plus_one_reports_overflow() {
+        assert_eq!(
+            checked_plus_one(Some(i32::MAX)),
+            Err(OverflowError { value: i32::MAX })
+        );
+        assert_eq!(
+            OverflowError { value: i32::MAX }.to_string(),
+            "2147483647 + 1 overflows `i32`"
+        );
+        assert_eq!(checked_plus_one(Some(5)), Ok(Some(6)));
+        assert_eq!(checked_plus_one(Some(i32::MIN)), Ok(Some(i32::MIN + 1)));
+        assert_eq!(checked_plus_one(None), Ok(None));
+    }
+
+    #[test]
+    fn saturating_plus_one_stops_at_max() {
+        assert_eq!(saturating_plus_one(Some(i32::MAX)), Some(i32::MAX));
+        assert_eq!(saturating_plus_one(Some(-1)), Some(0));
+        assert_eq!(saturating_plus_one(None), None);
+    }
+
+    #[test]
+    fn plus_n_adds_any_amount() {
+        assert_eq!(plus_n(Some(5), 3), Some(8));
+        assert_eq!(plus_n(Some(5), -7), Some(-2));
+        assert_eq!(plus_n(Some(5), 0), Some(5));
+        assert_eq!(plus_n(None, 3), None);
+        assert_eq!(plus_n(Some(1), 1), plus_one(Some(1)));
+    }
+
+    #[test]
+    fn plus_n_all_keeps_nones_in_place() {
+        let batch = [Some(1), None, Some(-3), None];
+        assert_eq!(plus_n_all(batch, 10), [Some(11), None, Some(7), None]);
+        assert_eq!(plus_n_all(batch, -1), [Some(0), None, Some(-4), None]);
+        assert_eq!(plus_n_all(batch, 0), batch);
+        assert!(plus_n_all([], 1).is_empty());
+    }
+
+    #[test]
+    fn plus_n_all_agrees_with_plus_n() {
+        let batch = [Some(i32::MIN), Some(0), None, Some(100)];
+        let one_at_a_time: Vec<_> = batch.iter().map(|&x| plus_n(x, 5)).collect();
+        assert_eq!(plus_n_all(batch, 5), one_at_a_time);
+    }
+
+    #[test]
+    fn count_non_quarters_collects_quarter_states() {
+        let coins = [
+            Coin2::Quarter(UsState::Ohio),
+            Coin2::Penny,
+            Coin2::Penny,
+            Coin2::Quarter(UsState::Maine),
+            Coin2::Dime,
+        ];
+        assert_eq!(
+            count_non_quarters(&coins),
+            (3, vec![UsState::Ohio, UsState::Maine])
+        );
+        assert_eq!(count_non_quarters(&coins), count_non_quarters_match(&coins));
+        assert_eq!(count_non_quarters(&[]), (0, Vec::new()));
+    }
+
+    #[test]
+    fn if_let_matches_the_equivalent_match() {
+        for coin in Coin2::iter() {
+            assert_eq!(
+                count_non_quarters(&[coin]),
+                count_non_quarters_match(&[coin])
+            );
+        }
+        assert_eq!(
+            config_max_message(Some(3)).as_deref(),
+            Some("The maximum is configured to be 3")
+        );
+        assert_eq!(config_max_message(None), None);
+        for config_max in [Some(0), Some(3), Some(u8::MAX), None] {
+            assert_eq!(
+                config_max_message(config_max),
+                config_max_message_match(config_max)
+            );
+        }
+    }
+
+    use report::OutputLevel;
+
+    /// Everything `demo` writes to its `Reporter`
+    fn capture(demo: impl FnOnce(&mut Reporter) -> io::Result<()>) -> String {
+        capture_at(OutputLevel::Normal, demo)
+    }
+
+    /// What `demo` writes to a `Reporter` at `level`
+    fn capture_at(
+        level: OutputLevel,
+        demo: impl FnOnce(&mut Reporter) -> io::Result<()>,
+    ) -> String {
+        let mut buf = Vec::new();
+        demo(&mut Reporter::new(&mut buf).with_level(level)).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn match_control_flow_output() {
+        let text = capture(|r| match_control_flow(r, &mut SeededRng::new(42)));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[..4],
+            [
+                "`penny` value_in_cents = 1",
+                "`nickel` value_in_cents = 5",
+                "`dime` value_in_cents = 10",
+                "`quarter` value_in_cents = 25",
+            ]
+        );
+        // The last coin is drawn at random
+        let random = lines[4].strip_prefix("`random_coin` value_in_cents = ");
+        assert!(matches!(random, Some("1" | "5" | "10" | "25")), "{text}");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn enum_different_types_output() {
+        let result = "`home` is V4(127, 0, 0, 1), `loopback` is V6(\"::1\")\n";
+        assert_eq!(capture(enum_different_types), result);
+        assert_eq!(
+            capture_at(OutputLevel::Verbose, enum_different_types),
+            format!(
+                "`home` is: V4(\n    127,\n    0,\n    0,\n    1,\n)\n\
+                 `loopback` is: V6(\n    \"::1\",\n)\n{result}"
+            )
+        );
+    }
+
+    #[test]
+    fn type_names_are_details() {
+        let normal = capture(option_type);
+        assert!(!normal.contains("type of"), "{normal}");
+        let verbose = capture_at(OutputLevel::Verbose, option_type);
+        assert!(
+            verbose.contains("type of `some_char` is Option<char>\n"),
+            "{verbose}"
+        );
+        assert_eq!(
+            capture_at(OutputLevel::Quiet, option_type),
+            "`absent_number` is: None\n"
+        );
+    }
+
+    #[test]
+    fn struct_conversions_output() {
+        assert_eq!(
+            capture(struct_conversions),
+            "Move: by x = 3, y = 7\n\
+             Converted back to MoveMessage { x: 3, y: 7 }\n\
+             Could not convert Quit { code: 0 } into Write\n"
+        );
+    }
+
+    #[test]
+    fn seeded_demo_snapshots() {
+        // Each demo draws from an RNG of its own, so one snapshot doesn't
+        // change when another demo draws more or fewer numbers
+        let seeded = || SeededRng::new(42);
+        snapshot::assert_snapshot(
+            "match_control_flow",
+            "match_control_flow, seed 42",
+            &capture(|r| match_control_flow(r, &mut seeded())),
+        );
+        snapshot::assert_snapshot(
+            "match_control_flow_patterns",
+            "match_control_flow_patterns, seed 42",
+            &capture(|r| match_control_flow_patterns(r, &mut seeded())),
+        );
+        snapshot::assert_snapshot(
+            "wallets",
+            "wallets, seed 42",
+            &capture(|r| wallets(r, &mut seeded())),
+        );
+        snapshot::assert_snapshot(
+            "game_rules",
+            "game_rules, seed 42",
+            &capture(|r| game_rules(r, &mut seeded())),
+        );
+        snapshot::assert_snapshot(
+            "tournament",
+            "tournament, seed 42",
+            &capture(|r| tournament(r, &mut seeded())),
+        );
+        snapshot::assert_snapshot(
+            "simulations",
+            "simulations, seed 42",
+            &capture(|r| simulations(r, &mut seeded())),
+        );
+        snapshot::assert_snapshot(
+            "dice_examples",
+            "dice_examples, seed 42",
+            &capture(|r| dice::dice_examples(r, &mut seeded())),
+        );
+    }
+
+    #[test]
+    fn a_recorded_run_replays_with_the_same_picks() {
+        use rust_enums::rng::{RecordingRng, ReplayRng, RngLog};
+
+        let mut recording = RecordingRng::new(SeededRng::new(9));
+        let first = capture(|r| if_let_else_example(r, &mut recording));
+        let log = recording.into_log();
+        assert!(log.decisions().len() >= 15, "{log}");
+
+        // Through the log's text, as a run's log would be kept
+        let log: RngLog = log.to_string().parse().unwrap();
+        let mut replay = ReplayRng::new(log.clone());
+        assert_eq!(capture(|r| if_let_else_example(r, &mut replay)), first);
+        assert!(replay.is_done());
+        assert_eq!(replay.diverged(), None);
+
+        let mut replayed = RecordingRng::new(ReplayRng::new(log.clone()));
+        capture(|r| if_let_else_example(r, &mut replayed));
+        assert_eq!(replayed.into_log(), log);
+    }
+
+    /// A demo that doesn't draw at random
+    type Demo = fn(&mut Reporter) -> io::Result<()>;
+
+    #[test]
+    fn demo_snapshots() {
+        let demos: [(&str, Demo); 1] = [("enum_different_types", enum_different_types)];
+        for (name, demo) in demos {
+            snapshot::assert_snapshot(name, name, &capture(demo));
+        }
+    }
+
+    #[test]
+    fn a_penny_is_logged_at_debug() {
+        use logging::Level;
+
+        let records = logging::capture(Level::Trace, || {
+            assert_eq!(value_in_cents(&Coin::Penny), 1);
+            assert_eq!(value_in_cents(&Coin::Dime), 10);
+        });
+        assert_eq!(records, [(Level::Debug, String::from("Lucky penny!"))]);
+    }
+
+    #[test]
+    fn nothing_is_logged_at_the_default_level() {
+        let default = logging::max_level(None, 0).unwrap();
+        let records = logging::capture(default, || {
+            value_in_cents(&Coin::Penny);
+            value_in_cents_state_quarters(&Coin2::Quarter(UsState::Ohio));
+            option_type(&mut Reporter::new(io::sink())).unwrap();
+        });
+        assert!(records.is_empty(), "{records:?}");
     }
-    println!("Total count of coins: {count}");
 }
@@ -0,0 +1,128 @@
+//! # Errors as an enum
+//!
+//! A function that can fail in more than one way returns a `Result` whose
+//! error is an enum with a variant per way. The caller can `match` on the
+//! variants, or pass any of them along with `?`.
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::report::Reporter;
+
+/// # Ways arithmetic can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    DivisionByZero,
+    /// The result doesn't fit in the type
+    Overflow,
+    /// The square root of a negative number isn't a real number
+    NegativeSqrt,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MathError::DivisionByZero => write!(f, "division by zero"),
+            MathError::Overflow => write!(f, "result out of range"),
+            MathError::NegativeSqrt => write!(f, "square root of a negative number"),
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+/// `a / b`, rounded toward zero
+///
+/// `i64::MIN / -1` would be one more than `i64::MAX`, so it is an
+/// `Overflow` rather than a panic.
+pub fn checked_div(a: i64, b: i64) -> Result<i64, MathError> {
+    if b == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    a.checked_div(b).ok_or(MathError::Overflow)
+}
+
+/// The square root of `x`
+///
+/// `-0.0` has a square root, `-0.0`, and NaN stays NaN.
+pub fn checked_sqrt(x: f64) -> Result<f64, MathError> {
+    if x < 0.0 {
+        return Err(MathError::NegativeSqrt);
+    }
+    Ok(x.sqrt())
+}
+
+/// The square root of `a / b`, with either step's error passed on by `?`
+pub fn sqrt_of_quotient(a: i64, b: i64) -> Result<f64, MathError> {
+    let quotient = checked_div(a, b)?;
+    checked_sqrt(quotient as f64)
+}
+
+/// # Matching on a `MathError`, and passing one on with `?`
+pub fn error_handling_with_enums(r: &mut Reporter) -> io::Result<()> {
+    let mut worked = 0;
+    for (a, b) in [(84, 2), (1, 0), (i64::MIN, -1)] {
+        match checked_div(a, b) {
+            Ok(q) => {
+                worked += 1;
+                writeln!(r, "{a} / {b} = {q}")?
+            }
+            Err(MathError::DivisionByZero) => {
+                r.warn(format_args!("{a} / {b}: can't divide by zero"))?
+            }
+            Err(e) => r.warn(format_args!("{a} / {b}: {e}"))?,
+        }
+    }
+    for (a, b) in [(50, 2), (-50, 2), (50, 0)] {
+        match sqrt_of_quotient(a, b) {
+            Ok(root) => {
+                worked += 1;
+                writeln!(r, "sqrt({a} / {b}) = {root}")?
+            }
+            Err(e) => r.warn(format_args!("sqrt({a} / {b}): {e}"))?,
+        }
+    }
+    r.result(format_args!("{worked} of 6 calculation(s) worked"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn division() {
+        assert_eq!(checked_div(7, 2), Ok(3));
+        assert_eq!(checked_div(-7, 2), Ok(-3));
+        assert_eq!(checked_div(i64::MIN, 1), Ok(i64::MIN));
+        assert_eq!(checked_div(1, 0), Err(MathError::DivisionByZero));
+        assert_eq!(checked_div(i64::MIN, 0), Err(MathError::DivisionByZero));
+    }
+
+    #[test]
+    fn min_over_minus_one_overflows() {
+        assert_eq!(checked_div(i64::MIN, -1), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn square_roots() {
+        assert_eq!(checked_sqrt(9.0), Ok(3.0));
+        assert_eq!(checked_sqrt(0.0), Ok(0.0));
+        assert_eq!(checked_sqrt(-0.0), Ok(-0.0));
+        assert_eq!(checked_sqrt(-1.0), Err(MathError::NegativeSqrt));
+        assert!(checked_sqrt(f64::NAN).unwrap().is_nan());
+    }
+
+    #[test]
+    fn question_mark_passes_each_error_on() {
+        assert_eq!(sqrt_of_quotient(50, 2), Ok(5.0));
+        assert_eq!(sqrt_of_quotient(1, 0), Err(MathError::DivisionByZero));
+        assert_eq!(sqrt_of_quotient(i64::MIN, -1), Err(MathError::Overflow));
+        assert_eq!(sqrt_of_quotient(-50, 2), Err(MathError::NegativeSqrt));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(MathError::Overflow.to_string(), "result out of range");
+        let boxed: Box<dyn std::error::Error> = Box::new(MathError::NegativeSqrt);
+        assert_eq!(boxed.to_string(), "square root of a negative number");
+    }
+}
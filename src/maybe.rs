@@ -0,0 +1,61 @@
+//! # `Maybe`, next to `Option`
+//!
+//! The examples of `rust_enums::maybe`, which writes out by hand the
+//! `Option` combinators they use.
+use std::io::{self, Write};
+
+use rust_enums::maybe::{plus_one_maybe, Maybe};
+
+use crate::report::Reporter;
+
+/// Half of `x`, if it is even
+fn half(x: i32) -> Maybe<i32> {
+    if x % 2 == 0 {
+        Maybe::Just(x / 2)
+    } else {
+        Maybe::Nothing
+    }
+}
+
+/// # `Maybe` doing what `Option` does
+pub fn maybe_examples(r: &mut Reporter) -> io::Result<()> {
+    let five = Maybe::Just(5);
+    let six = plus_one_maybe(five);
+    let none = plus_one_maybe(Maybe::Nothing);
+    writeln!(
+        r,
+        "plus_one: {:?} -> {:?}, {:?} -> {:?}",
+        Some(5),
+        crate::plus_one(Some(5)),
+        None::<i32>,
+        crate::plus_one(None)
+    )?;
+    r.result(format_args!(
+        "plus_one_maybe: {five} -> {six}, nothing -> {none}"
+    ))?;
+    writeln!(
+        r,
+        "{six} is just: {}, {none} is just: {}",
+        six.is_just(),
+        none.is_just()
+    )?;
+    writeln!(
+        r,
+        "{six} doubled: {}, halved twice: {}",
+        six.map(|x| x * 2),
+        six.and_then(half).and_then(half)
+    )?;
+    writeln!(
+        r,
+        "{none} unwrapped or 0: {}, as a Result: {:?}",
+        none.unwrap_or(0),
+        none.ok_or("no number")
+    )?;
+    let option: Option<i32> = six.into();
+    writeln!(
+        r,
+        "{six} as an Option: {option:?}, and back: {}",
+        Maybe::from(option)
+    )?;
+    Ok(())
+}
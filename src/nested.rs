@@ -0,0 +1,83 @@
+//! # Patterns inside patterns
+//!
+//! A pattern can reach through more than one layer at once:
+//! `Some(Coin2::Quarter(state))` matches an `Option` holding a coin that is
+//! a quarter, and binds the quarter's state, with no `match` inside a
+//! `match`. Matching on a reference binds by reference too, so nothing is
+//! moved or cloned out of the `Option`.
+use std::io::{self, Write};
+
+use rust_enums::{Cents, Coin2, UsState};
+
+use crate::report::Reporter;
+
+/// The state of `maybe_coin`, if it is a state quarter
+pub fn state_of(maybe_coin: &Option<Coin2>) -> Option<UsState> {
+    match maybe_coin {
+        Some(Coin2::Quarter(state)) => Some(*state),
+        Some(_) | None => None,
+    }
+}
+
+/// What `maybe_coin` is worth, or 0 if there is no coin
+pub fn value_or_zero(maybe_coin: &Option<Coin2>) -> Cents {
+    match maybe_coin {
+        Some(coin) => coin.cents(),
+        None => 0,
+    }
+}
+
+/// # A nested `match` next to the `if let` that does the same
+pub fn nested_examples(r: &mut Reporter) -> io::Result<()> {
+    let mut total = 0;
+    for maybe_coin in [
+        Some(Coin2::Quarter(UsState::Texas)),
+        Some(Coin2::Dime),
+        None,
+    ] {
+        let described = if let Some(Coin2::Quarter(state)) = &maybe_coin {
+            format!("a quarter from {state:?}")
+        } else {
+            String::from("not a state quarter")
+        };
+        writeln!(
+            r,
+            "{maybe_coin:?}: `match` says {:?}, `if let` says {described}, worth {} cents",
+            state_of(&maybe_coin),
+            value_or_zero(&maybe_coin)
+        )?;
+        total += value_or_zero(&maybe_coin);
+    }
+    r.result(format_args!("3 pocket(s) worth {total} cents in all"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_of_each_kind_of_option() {
+        assert_eq!(
+            state_of(&Some(Coin2::Quarter(UsState::Alaska))),
+            Some(UsState::Alaska)
+        );
+        assert_eq!(state_of(&Some(Coin2::Dime)), None);
+        assert_eq!(state_of(&None), None);
+    }
+
+    #[test]
+    fn value_or_zero_of_each_kind_of_option() {
+        assert_eq!(value_or_zero(&Some(Coin2::Quarter(UsState::Alaska))), 25);
+        assert_eq!(value_or_zero(&Some(Coin2::Dime)), 10);
+        assert_eq!(value_or_zero(&None), 0);
+    }
+
+    #[test]
+    fn the_option_is_only_borrowed() {
+        let maybe_coin = Some(Coin2::Quarter(UsState::Utah));
+        state_of(&maybe_coin);
+        value_or_zero(&maybe_coin);
+        // Still ours, unchanged
+        assert_eq!(maybe_coin, Some(Coin2::Quarter(UsState::Utah)));
+    }
+}
@@ -0,0 +1,22 @@
+//! # Dice rolls, sorted by pattern
+//!
+//! The examples of `rust_enums::patterns_advanced`, whose `classify_roll`
+//! sorts sums of two dice with ranges, guards and `@` bindings.
+use std::io::{self, Write};
+
+use rust_enums::patterns_advanced::{classify_roll, RollClass};
+use rust_enums::rng::CrateRng;
+
+use crate::report::Reporter;
+
+/// # Classifying rolls of dice, with faces picked by `rng`
+pub fn roll_examples(r: &mut Reporter, rng: &mut impl CrateRng) -> io::Result<()> {
+    let mut sevens = 0;
+    for _ in 0..5 {
+        let (a, b): (u8, u8) = (rng.roll_face(6), rng.roll_face(6));
+        let class = classify_roll(a + b);
+        sevens += usize::from(class == RollClass::Seven);
+        writeln!(r, "Rolled {a} + {b}: {class:?}")?;
+    }
+    r.result(format_args!("{sevens} of 5 roll(s) came up Seven"))
+}
@@ -0,0 +1,93 @@
+//! # The coin and `Option` examples, with string-shaped results
+//!
+//! Small functions a playground page could call: what a coin is worth by
+//! its name, `plus_one` on an optional number, and the change for an
+//! amount. Errors are `String`s and coins are named by lowercase strings,
+//! so nothing outside the module's signatures needs to be known to use
+//! them.
+//!
+//! These are not wasm bindings. There is no `#[wasm_bindgen]` on the
+//! functions, errors aren't `JsValue`s, `make_change` returns a
+//! `Vec<String>` rather than a JavaScript array, and there are no
+//! `wasm-bindgen-test` tests; the tests here run natively.
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{Cents, Coin2, Wallet};
+
+/// What the coin called `name` is worth, or why `name` isn't a coin
+///
+/// ```
+/// use rust_enums::playground::value_in_cents;
+///
+/// assert_eq!(value_in_cents("quarter from Ohio"), Ok(25));
+/// assert_eq!(value_in_cents("button"), Err(String::from(r#""button" is not a coin"#)));
+/// ```
+pub fn value_in_cents(name: &str) -> Result<Cents, String> {
+    name.parse::<Coin2>()
+        .map(|coin| coin.cents())
+        .map_err(|e| e.to_string())
+}
+
+/// One more than `x`, if there is an `x`
+///
+/// `None` for `i32::MAX` too, rather than an overflow panic.
+pub fn plus_one(x: Option<i32>) -> Option<i32> {
+    match x {
+        None => None,
+        Some(i) => i.checked_add(1),
+    }
+}
+
+/// The names of the fewest coins that make `cents`, biggest first
+///
+/// ```
+/// use rust_enums::playground::make_change;
+///
+/// assert_eq!(make_change(30), ["quarter", "nickel"]);
+/// ```
+pub fn make_change(cents: Cents) -> Vec<String> {
+    Wallet::change_for(cents)
+        .coins()
+        .iter()
+        .map(|coin| format!("{:?}", coin.kind()).to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coins_are_valued_by_name() {
+        assert_eq!(value_in_cents("penny"), Ok(1));
+        assert_eq!(value_in_cents("Dime"), Ok(10));
+        assert_eq!(value_in_cents("quarter:alaska"), Ok(25));
+    }
+
+    #[test]
+    fn a_name_that_isnt_a_coin_is_an_error() {
+        assert_eq!(
+            value_in_cents("quarter from Atlantis"),
+            Err(String::from(r#""quarter from Atlantis" is not a coin"#))
+        );
+        assert!(value_in_cents("").is_err());
+    }
+
+    #[test]
+    fn plus_one_of_some_and_none() {
+        assert_eq!(plus_one(Some(5)), Some(6));
+        assert_eq!(plus_one(None), None);
+        assert_eq!(plus_one(Some(i32::MAX)), None);
+    }
+
+    #[test]
+    fn change_is_named_coin_by_coin() {
+        assert_eq!(
+            make_change(66),
+            ["quarter", "quarter", "dime", "nickel", "penny"]
+        );
+        assert!(make_change(0).is_empty());
+    }
+}
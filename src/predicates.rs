@@ -0,0 +1,109 @@
+//! # Yes-or-no questions with `matches!`
+//!
+//! When all we want to know is *whether* a value fits a pattern, a full
+//! `match` with a `true` arm and a `_ => false` arm says it the long way.
+//! `matches!(value, pattern)` is exactly that `match`, and takes an
+//! `if` guard after the pattern just as a match arm does.
+use std::io::{self, Write};
+
+use rust_enums::{Coin2, IpAddrTypes, Message, UsState};
+
+use crate::report::Reporter;
+
+/// Whether `c` is a quarter, from whichever state
+pub fn is_state_quarter(c: &Coin2) -> bool {
+    matches!(c, Coin2::Quarter(_))
+}
+
+/// `is_state_quarter`, written out as the `match` that `matches!` stands for
+#[allow(clippy::match_like_matches_macro)] // the long way is the point
+pub fn is_state_quarter_match(c: &Coin2) -> bool {
+    match c {
+        Coin2::Quarter(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether `addr` is a version four address
+pub fn is_v4(addr: &IpAddrTypes) -> bool {
+    matches!(addr, IpAddrTypes::V4(..))
+}
+
+/// Whether `msg` is a `Write` of more than `n` bytes
+///
+/// The guard can use what the pattern bound: `text` only exists once the
+/// message is known to be a `Write`.
+pub fn is_write_longer_than(msg: &Message, n: usize) -> bool {
+    matches!(msg, Message::Write(text) if text.len() > n)
+}
+
+/// Whether any of `coins` is a quarter
+pub fn any_quarters(coins: &[Coin2]) -> bool {
+    coins.iter().any(|c| matches!(c, Coin2::Quarter(_)))
+}
+
+/// # `matches!` next to the `match` it abbreviates
+pub fn predicate_examples(r: &mut Reporter) -> io::Result<()> {
+    let coins = [Coin2::Dime, Coin2::Quarter(UsState::Ohio)];
+    for coin in &coins {
+        writeln!(
+            r,
+            "{:?}: `matches!` says {}, `match` says {}",
+            coin,
+            is_state_quarter(coin),
+            is_state_quarter_match(coin)
+        )?;
+    }
+    r.result(format_args!(
+        "Any quarters in {:?}? {}",
+        coins,
+        any_quarters(&coins)
+    ))?;
+    let home = IpAddrTypes::V4(127, 0, 0, 1);
+    writeln!(r, "{:?} is V4: {}", home, is_v4(&home))?;
+    let msg = Message::Write(String::from("hello"));
+    writeln!(
+        r,
+        "{msg} is a Write longer than 3: {}",
+        is_write_longer_than(&msg, 3)
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn state_quarters() {
+        assert!(is_state_quarter(&Coin2::Quarter(UsState::Alabama)));
+        assert!(!is_state_quarter(&Coin2::Penny));
+        for coin in Coin2::iter() {
+            assert_eq!(is_state_quarter(&coin), is_state_quarter_match(&coin));
+        }
+    }
+
+    #[test]
+    fn v4_addresses() {
+        assert!(is_v4(&IpAddrTypes::V4(10, 0, 0, 1)));
+        assert!(!is_v4(&IpAddrTypes::V6(String::from("::1"))));
+    }
+
+    #[test]
+    fn write_length_guard() {
+        let hello = Message::Write(String::from("hello"));
+        assert!(is_write_longer_than(&hello, 4));
+        // Exactly `n` bytes is not longer than `n`
+        assert!(!is_write_longer_than(&hello, 5));
+        assert!(!is_write_longer_than(&hello, 6));
+        assert!(!is_write_longer_than(&Message::Move { x: 9, y: 9 }, 0));
+    }
+
+    #[test]
+    fn any_quarters_in_a_purse() {
+        assert!(any_quarters(&[Coin2::Penny, Coin2::Quarter(UsState::Utah)]));
+        assert!(!any_quarters(&[Coin2::Penny, Coin2::Nickel, Coin2::Dime]));
+        assert!(!any_quarters(&[]));
+    }
+}
@@ -0,0 +1,201 @@
+//! # Random values for property tests
+//!
+//! A `Strategy` makes a random value of some type from an RNG, and `check`
+//! runs a property against many of them. There is a strategy here for each
+//! of the crate's main enums, covering every variant, so property tests in
+//! this crate and downstream can share them.
+//!
+//! `Strategy` and `check` are shaped like `proptest`'s, but a failing value
+//! isn't shrunk. Instead every case has its own seed, which the failure
+//! report gives, so a failure can be replayed with `check_one`.
+//!
+//! ```
+//! use rust_enums::proptest_support::{check, coins};
+//!
+//! check(coins(), 100, |coin| assert!(coin.cents() <= 25));
+//! ```
+use std::fmt::Debug;
+use std::net::Ipv6Addr;
+use std::panic::{self, AssertUnwindSafe};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::color::Color;
+use crate::{Coin2, IpAddrTypes, Message, UsState};
+
+/// How many cases a property test checks unless it has reason to differ
+pub const CASES: usize = 256;
+
+/// # Makes random values of one type
+///
+/// Any closure from an RNG to a value is a strategy.
+pub trait Strategy {
+    /// The type of value made
+    type Value;
+
+    /// A new random value
+    fn generate(&self, rng: &mut StdRng) -> Self::Value;
+}
+
+impl<T, F: Fn(&mut StdRng) -> T> Strategy for F {
+    type Value = T;
+
+    fn generate(&self, rng: &mut StdRng) -> T {
+        self(rng)
+    }
+}
+
+/// Any `UsState`
+pub fn states() -> impl Strategy<Value = UsState> {
+    |rng: &mut StdRng| UsState::VARIANTS.choose(rng).copied().unwrap_or_default()
+}
+
+/// Any `Coin2`, with quarters from any state
+pub fn coins() -> impl Strategy<Value = Coin2> {
+    let states = states();
+    move |rng: &mut StdRng| match rng.gen_range(0..4) {
+        0 => Coin2::Penny,
+        1 => Coin2::Nickel,
+        2 => Coin2::Dime,
+        _ => Coin2::Quarter(states.generate(rng)),
+    }
+}
+
+/// Any `IpAddrTypes`, each `V6` holding a well-formed address
+///
+/// `V6` text is what `std::net::Ipv6Addr` displays, so it is sometimes
+/// compressed with `::` and sometimes an IPv4-mapped `::ffff:a.b.c.d`.
+pub fn ip_addrs() -> impl Strategy<Value = IpAddrTypes> {
+    |rng: &mut StdRng| {
+        if rng.gen() {
+            IpAddrTypes::V4(rng.gen(), rng.gen(), rng.gen(), rng.gen())
+        } else {
+            // Mostly zero groups, so `::` compression comes up often
+            let groups: [u16; 8] = std::array::from_fn(|_| match rng.gen_range(0..3) {
+                0 => rng.gen(),
+                _ => 0,
+            });
+            let mut address = Ipv6Addr::from(groups);
+            if rng.gen_ratio(1, 8) {
+                address = std::net::Ipv4Addr::from(rng.gen::<u32>()).to_ipv6_mapped();
+            }
+            IpAddrTypes::V6(address.to_string())
+        }
+    }
+}
+
+/// Characters worth trying in text: the ones `Message`'s `Display` escapes,
+/// spaces, and some beyond ASCII
+const AWKWARD: &[char] = &[
+    '"', '\\', '\n', '\r', '\t', '\0', '\u{7f}', ' ', ',', 'é', '\u{2028}', '😀',
+];
+
+/// A string of up to `max` characters, mixing letters, awkward characters
+/// and any Unicode scalar value
+pub fn text(max: usize) -> impl Strategy<Value = String> {
+    move |rng: &mut StdRng| {
+        let len = rng.gen_range(0..=max);
+        (0..len)
+            .map(|_| match rng.gen_range(0..4) {
+                0 => AWKWARD.choose(rng).copied().unwrap_or(' '),
+                1 => rng.gen(),
+                _ => rng.gen_range('a'..='z'),
+            })
+            .collect()
+    }
+}
+
+/// Any `Message`, with `Write` text from `text(32)` and up to 8 `Draw` points
+pub fn messages() -> impl Strategy<Value = Message> {
+    let text = text(32);
+    move |rng: &mut StdRng| match rng.gen_range(0..6) {
+        0 => Message::Quit { code: rng.gen() },
+        1 => Message::Move {
+            x: rng.gen(),
+            y: rng.gen(),
+        },
+        2 => Message::Write(text.generate(rng)),
+        3 => Message::ChangeColor(Color::rgb(rng.gen(), rng.gen(), rng.gen())),
+        4 => Message::Resize {
+            width: rng.gen(),
+            height: rng.gen(),
+        },
+        _ => {
+            let points = rng.gen_range(0..=8);
+            Message::Draw((0..points).map(|_| (rng.gen(), rng.gen())).collect())
+        }
+    }
+}
+
+/// The seed of case `case`
+fn seed_of(case: usize) -> u64 {
+    // Spread the cases out, rather than seeding with 0, 1, 2...
+    (case as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
+
+/// Check `property` against `cases` values from `strategy`
+///
+/// # Panics
+///
+/// If `property` panics for any value, with the value and the seed that
+/// made it.
+pub fn check<S>(strategy: S, cases: usize, property: impl Fn(&S::Value))
+where
+    S: Strategy,
+    S::Value: Debug,
+{
+    for case in 0..cases {
+        check_one(&strategy, seed_of(case), &property);
+    }
+}
+
+/// Check `property` against the one value `strategy` makes from `seed`
+///
+/// # Panics
+///
+/// If `property` panics, with the value and `seed`.
+pub fn check_one<S>(strategy: &S, seed: u64, property: impl Fn(&S::Value))
+where
+    S: Strategy,
+    S::Value: Debug,
+{
+    let value = strategy.generate(&mut StdRng::seed_from_u64(seed));
+    if let Err(cause) = panic::catch_unwind(AssertUnwindSafe(|| property(&value))) {
+        let cause = cause
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| cause.downcast_ref::<&str>().copied())
+            .unwrap_or("(no message)");
+        panic!("property failed for {value:?} (seed {seed}): {cause}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_cover_every_variant() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let coins: Vec<Coin2> = (0..100).map(|_| coins().generate(&mut rng)).collect();
+        assert!(coins.contains(&Coin2::Penny));
+        assert!(coins.iter().any(|c| matches!(c, Coin2::Quarter(_))));
+        let kinds: std::collections::HashSet<_> = (0..100)
+            .map(|_| messages().generate(&mut rng).kind())
+            .collect();
+        assert_eq!(kinds.len(), 6);
+    }
+
+    #[test]
+    fn a_failure_names_its_seed() {
+        let failure = panic::catch_unwind(|| check(coins(), CASES, |c| assert_ne!(c.cents(), 10)));
+        let cause = failure.unwrap_err();
+        let message = cause.downcast_ref::<String>().unwrap();
+        assert!(
+            message.starts_with("property failed for Dime (seed "),
+            "{message}"
+        );
+    }
+}
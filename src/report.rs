@@ -0,0 +1,446 @@
+//! # Where the examples write their output
+//!
+//! Every example writes through a `Reporter` instead of printing, so `main`
+//! can send the output to stdout and a test can collect it in a `Vec<u8>`
+//! and compare it with the text it expects.
+//!
+//! A `Reporter` writes prose, or with `OutputFormat::Json` one JSON object
+//! per line, a record for each demo run through `Reporter::demo`. A demo
+//! with data worth keeping hands it over with `Reporter::record`; any other
+//! demo's record holds the lines of prose it wrote.
+//!
+//! Each line of prose is one of three kinds. `result` writes what a demo
+//! computed, `note` (or `write!`) the narrative around it, and `detail`
+//! the type names and intermediate states behind it; the reporter's
+//! `OutputLevel` says which of them get through.
+//!
+//! Prose can be colored: `header`, `value` and `warn` style a line or a
+//! value by what it is, and a reporter built `with_color(true)` writes the
+//! styles as ANSI escapes. JSON is never colored. `ColorChoice` holds the
+//! `--color` values, `auto`, `always` and `never`, and decides whether a
+//! run gets `with_color(true)`.
+use std::env;
+use std::fmt::{self, Display};
+use std::io::{self, IsTerminal, Write};
+use std::mem;
+
+use crate::json_value::{compact, Json};
+
+/// # How a `Reporter` formats what the demos give it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// The prose each demo writes
+    #[default]
+    Text,
+    /// A JSON record per demo, one per line
+    Json,
+}
+
+impl OutputFormat {
+    /// The format called `name`, as given to `--format`
+    pub fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// The name `from_name` takes for the format
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+        }
+    }
+}
+
+/// # Whether to color prose, as given to `--color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color a terminal, unless `NO_COLOR` is set to anything but `""`
+    #[default]
+    Auto,
+    /// Always color, even with `NO_COLOR` set
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    /// The choice called `name`, as given to `--color`
+    pub fn from_name(name: &str) -> Option<ColorChoice> {
+        match name {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// The name `from_name` takes for the choice
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        }
+    }
+
+    /// Whether to color what is written to stdout
+    pub fn for_stdout(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                io::stdout().is_terminal() && !no_color
+            }
+        }
+    }
+}
+
+/// # Which lines of prose a `Reporter` writes, as picked by `--quiet` and
+/// `--verbose`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OutputLevel {
+    /// Only the result lines, one per demo
+    Quiet,
+    /// The results and the narrative around them
+    #[default]
+    Normal,
+    /// Everything, with the details as well
+    Verbose,
+}
+
+/// # What a piece of prose is, and so how it is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// A heading over the lines after it, in bold
+    Header,
+    /// An amount worth picking out, such as a coin's value, in yellow
+    Value,
+    /// Something that went wrong, in red
+    Warn,
+}
+
+impl Style {
+    /// The SGR parameter that turns the style on
+    fn sgr(self) -> &'static str {
+        match self {
+            Style::Header => "1",
+            Style::Value => "33",
+            Style::Warn => "31",
+        }
+    }
+}
+
+/// # A value to format in a `Style`, if coloring at all
+pub struct Styled<T> {
+    value: T,
+    style: Option<Style>,
+}
+
+impl<T: Display> Display for Styled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.style {
+            Some(style) => write!(f, "\x1b[{}m{}\x1b[0m", style.sgr(), self.value),
+            None => self.value.fmt(f),
+        }
+    }
+}
+
+/// # The destination of an example's output
+pub struct Reporter<'a> {
+    out: Box<dyn Write + 'a>,
+    format: OutputFormat,
+    /// Which lines of prose get through
+    level: OutputLevel,
+    /// Whether to color prose
+    color: bool,
+    /// The prose of the demo being run, held back in JSON mode
+    prose: Vec<u8>,
+    /// Whether the demo being run has written its own record
+    recorded: bool,
+    /// The name of the demo being run
+    demo: Option<&'static str>,
+}
+
+impl<'a> Reporter<'a> {
+    /// A reporter writing prose to `out`
+    pub fn new(out: impl Write + 'a) -> Self {
+        Reporter {
+            out: Box::new(out),
+            format: OutputFormat::Text,
+            level: OutputLevel::Normal,
+            color: false,
+            prose: Vec::new(),
+            recorded: false,
+            demo: None,
+        }
+    }
+
+    /// The same reporter, writing in `format`
+    pub fn with_format(self, format: OutputFormat) -> Self {
+        Reporter { format, ..self }
+    }
+
+    /// The same reporter, writing the lines of prose `level` lets through
+    pub fn with_level(self, level: OutputLevel) -> Self {
+        Reporter { level, ..self }
+    }
+
+    /// The same reporter, coloring prose if `color` is true and the format
+    /// is text
+    pub fn with_color(self, color: bool) -> Self {
+        Reporter { color, ..self }
+    }
+
+    /// `value` in `style`, if this reporter colors
+    fn styled<T>(&self, style: Style, value: T) -> Styled<T> {
+        let colored = self.color && self.format == OutputFormat::Text;
+        Styled {
+            value,
+            style: colored.then_some(style),
+        }
+    }
+
+    /// Write `text` as the line giving what the demo computed, at every
+    /// level
+    pub fn result(&mut self, text: impl Display) -> io::Result<()> {
+        self.write_line(text)
+    }
+
+    /// Write `text` as a line of narrative, unless quiet; the same as
+    /// `writeln!`
+    pub fn note(&mut self, text: impl Display) -> io::Result<()> {
+        writeln!(self, "{text}")
+    }
+
+    /// Write `text` as a line of detail, such as a type name or a state on
+    /// the way to the result, only when verbose
+    pub fn detail(&mut self, text: impl Display) -> io::Result<()> {
+        if self.level < OutputLevel::Verbose {
+            return Ok(());
+        }
+        self.write_line(text)
+    }
+
+    /// Write `text` as a line of prose, whatever the level
+    fn write_line(&mut self, text: impl Display) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Text => writeln!(self.out, "{text}"),
+            OutputFormat::Json => writeln!(self.prose, "{text}"),
+        }
+    }
+
+    /// Write `text` as a line heading the ones after it
+    pub fn header(&mut self, text: impl Display) -> io::Result<()> {
+        let line = self.styled(Style::Header, text);
+        writeln!(self, "{line}")
+    }
+
+    /// `value`, to format into a line, picked out as an amount
+    pub fn value<T: Display>(&self, value: T) -> Styled<T> {
+        self.styled(Style::Value, value)
+    }
+
+    /// Write `text` as a line saying something went wrong
+    pub fn warn(&mut self, text: impl Display) -> io::Result<()> {
+        let line = self.styled(Style::Warn, text);
+        writeln!(self, "{line}")
+    }
+
+    /// Run `demo`, the demo called `name`
+    ///
+    /// In JSON mode, once `demo` is done, its record is written: the one it
+    /// gave `record`, or else `{"section": name, "text": [...]}` with the
+    /// lines of prose it wrote.
+    pub fn demo(
+        &mut self,
+        name: &'static str,
+        demo: impl FnOnce(&mut Reporter) -> io::Result<()>,
+    ) -> io::Result<()> {
+        self.demo = Some(name);
+        self.recorded = false;
+        let result = demo(self);
+        self.demo = None;
+        let prose = mem::take(&mut self.prose);
+        result?;
+        if self.format == OutputFormat::Json && !self.recorded {
+            let text = String::from_utf8_lossy(&prose);
+            let lines = Json::array(text.lines().map(Json::from));
+            self.write_record(name, vec![(String::from("text"), lines)])?;
+        }
+        Ok(())
+    }
+
+    /// In JSON mode, write `fields` as the record of the demo being run,
+    /// after a `"section"` member naming it; in text mode, do nothing
+    pub fn record<K: Into<String>>(
+        &mut self,
+        fields: impl IntoIterator<Item = (K, Json)>,
+    ) -> io::Result<()> {
+        if self.format != OutputFormat::Json {
+            return Ok(());
+        }
+        self.recorded = true;
+        let name = self.demo.unwrap_or("main");
+        let fields = fields.into_iter().map(|(k, v)| (k.into(), v)).collect();
+        self.write_record(name, fields)
+    }
+
+    fn write_record(&mut self, name: &str, fields: Vec<(String, Json)>) -> io::Result<()> {
+        let mut members = vec![(String::from("section"), Json::from(name))];
+        members.extend(fields);
+        writeln!(self.out, "{}", compact(&Json::Object(members)))
+    }
+}
+
+impl Reporter<'static> {
+    /// A reporter writing prose to stdout
+    pub fn stdout() -> Self {
+        Reporter::new(io::stdout())
+    }
+}
+
+/// Narrative prose, which in JSON mode is held back until the demo is done,
+/// and when quiet is dropped
+impl Write for Reporter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.level == OutputLevel::Quiet {
+            return Ok(buf.len());
+        }
+        match self.format {
+            OutputFormat::Text => self.out.write(buf),
+            OutputFormat::Json => self.prose.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_record(r: &mut Reporter) -> io::Result<()> {
+        writeln!(r, "prose")?;
+        r.record([("cents", Json::from(25))])
+    }
+
+    fn prose_only(r: &mut Reporter) -> io::Result<()> {
+        writeln!(r, "one")?;
+        writeln!(r, "two")
+    }
+
+    fn run(format: OutputFormat) -> String {
+        let mut buf = Vec::new();
+        {
+            let mut r = Reporter::new(&mut buf).with_format(format);
+            r.demo("with_record", with_record).unwrap();
+            r.demo("prose_only", prose_only).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn text_mode_writes_prose_and_skips_records() {
+        assert_eq!(run(OutputFormat::Text), "prose\none\ntwo\n");
+    }
+
+    #[test]
+    fn json_mode_writes_a_record_per_demo() {
+        assert_eq!(
+            run(OutputFormat::Json),
+            "{\"section\":\"with_record\",\"cents\":25}\n\
+             {\"section\":\"prose_only\",\"text\":[\"one\",\"two\"]}\n"
+        );
+    }
+
+    fn styled(r: &mut Reporter) -> io::Result<()> {
+        r.header("Coins")?;
+        writeln!(r, "a dime is {} cents", r.value(10))?;
+        r.warn("a button is not a coin")
+    }
+
+    /// What `styled` writes, colored or not
+    fn run_styled(format: OutputFormat, color: bool) -> String {
+        let mut buf = Vec::new();
+        {
+            let mut r = Reporter::new(&mut buf)
+                .with_format(format)
+                .with_color(color);
+            r.demo("styled", styled).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn color_wraps_styled_prose_in_escapes() {
+        assert_eq!(
+            run_styled(OutputFormat::Text, true),
+            "\x1b[1mCoins\x1b[0m\n\
+             a dime is \x1b[33m10\x1b[0m cents\n\
+             \x1b[31ma button is not a coin\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn without_color_styled_prose_is_plain() {
+        let plain = run_styled(OutputFormat::Text, false);
+        assert_eq!(plain, "Coins\na dime is 10 cents\na button is not a coin\n");
+        assert!(!run_styled(OutputFormat::Json, true).contains('\x1b'));
+    }
+
+    fn leveled(r: &mut Reporter) -> io::Result<()> {
+        r.header("Coins")?;
+        r.detail("type of `dime` is Coin")?;
+        r.note("a dime is 10 cents")?;
+        r.result("2 dimes make 20 cents")
+    }
+
+    /// What `leveled` writes at `level`
+    fn run_leveled(format: OutputFormat, level: OutputLevel) -> String {
+        let mut buf = Vec::new();
+        {
+            let mut r = Reporter::new(&mut buf)
+                .with_format(format)
+                .with_level(level);
+            r.demo("leveled", leveled).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn levels_pick_the_lines_written() {
+        let text = |level| run_leveled(OutputFormat::Text, level);
+        assert_eq!(text(OutputLevel::Quiet), "2 dimes make 20 cents\n");
+        assert_eq!(
+            text(OutputLevel::Normal),
+            "Coins\na dime is 10 cents\n2 dimes make 20 cents\n"
+        );
+        assert_eq!(
+            text(OutputLevel::Verbose),
+            "Coins\ntype of `dime` is Coin\na dime is 10 cents\n2 dimes make 20 cents\n"
+        );
+        assert_eq!(
+            run_leveled(OutputFormat::Json, OutputLevel::Quiet),
+            "{\"section\":\"leveled\",\"text\":[\"2 dimes make 20 cents\"]}\n"
+        );
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!(OutputFormat::from_name("json"), Some(OutputFormat::Json));
+        assert_eq!(OutputFormat::from_name("text"), Some(OutputFormat::Text));
+        assert_eq!(OutputFormat::from_name("yaml"), None);
+        assert_eq!(ColorChoice::from_name("never"), Some(ColorChoice::Never));
+        assert_eq!(ColorChoice::from_name("auto"), Some(ColorChoice::Auto));
+        assert_eq!(ColorChoice::from_name("sometimes"), None);
+        assert!(ColorChoice::Always.for_stdout());
+        assert!(!ColorChoice::Never.for_stdout());
+    }
+}
@@ -0,0 +1,496 @@
+//! # Every demo in `main`, by name
+//!
+//! A `Section` is one demo: the name its output goes under, a line saying
+//! what it shows, and the function that shows it. `Section::iter()` walks
+//! them in the order `main` runs them, which is the order the book covers
+//! the ideas in, so running every section is a loop rather than a list of
+//! calls that has to be kept in step with this one.
+//!
+//! Each section belongs to one [`Part`], the broader group a
+//! `<part>` argument picks; `--only` picks sections one by one.
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use rust_enums::rng::SeededRng;
+use strum_macros::{Display, EnumIter, EnumString, IntoStaticStr};
+
+use crate::cli::Part;
+use crate::json_value::Json;
+use crate::report::{OutputLevel, Reporter};
+use crate::*;
+
+/// # What a section runs with
+pub struct RunContext<'a> {
+    /// Where the section writes what it shows
+    pub reporter: Reporter<'a>,
+    /// The one RNG every section that draws at random draws from, so the
+    /// same seed gives the same output
+    pub rng: SeededRng,
+    /// How long each section run so far took, in the order they ran
+    timings: Vec<(Section, Duration)>,
+}
+
+impl<'a> RunContext<'a> {
+    /// A context writing to `reporter`, drawing from an RNG seeded with
+    /// `seed`
+    pub fn new(reporter: Reporter<'a>, seed: u64) -> Self {
+        RunContext {
+            reporter,
+            rng: SeededRng::new(seed),
+            timings: Vec::new(),
+        }
+    }
+
+    /// The same context, writing the lines of prose `level` lets through
+    pub fn with_level(self, level: OutputLevel) -> Self {
+        RunContext {
+            reporter: self.reporter.with_level(level),
+            ..self
+        }
+    }
+
+    /// How long each section run with this context took, wall-clock, in
+    /// the order they ran
+    pub fn timings(&self) -> &[(Section, Duration)] {
+        &self.timings
+    }
+
+    /// Write `timings` as a table under a `timings` header, the times
+    /// right-aligned in milliseconds, or in JSON mode as a `timings` record
+    pub fn write_timings(&mut self) -> io::Result<()> {
+        let RunContext {
+            reporter, timings, ..
+        } = self;
+        let width = timings
+            .iter()
+            .map(|(section, _)| section.to_string().len())
+            .max()
+            .unwrap_or(0);
+        reporter.demo("timings", |r| {
+            r.header("timings")?;
+            for (section, duration) in timings.iter() {
+                let ms = format!("{:.3}", millis(*duration));
+                writeln!(
+                    r,
+                    "  {:<width$}  {:>10} ms",
+                    section.to_string(),
+                    r.value(ms)
+                )?;
+            }
+            let sections = timings.iter().map(|(section, duration)| {
+                Json::object([
+                    ("section", Json::from(section.to_string())),
+                    ("ms", Json::from(millis(*duration))),
+                ])
+            });
+            r.record([("timings", Json::array(sections))])
+        })
+    }
+}
+
+/// `duration` in milliseconds
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// # One demo in `main`
+///
+/// Named in `snake_case`, as `--only` takes it and as a JSON record's
+/// `"section"` gives it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Section {
+    DefiningAnEnum,
+    ShapeExamples,
+    EnumStringValues,
+    EnumDifferentTypes,
+    EnumMethods,
+    StructConversions,
+    ValidatedColors,
+    MessageKinds,
+    MessagePredicates,
+    VisitMessages,
+    RouteMessages,
+    DedupMessages,
+    CoalesceMessages,
+    MessageStats,
+    DisplayMessages,
+    ParseMessages,
+    #[cfg(feature = "json")]
+    JsonRoundTrip,
+    BinaryRoundTrip,
+    FramedStream,
+    MessageScript,
+    MessageQueue,
+    BoundedMoves,
+    BatchApply,
+    PriorityScheduling,
+    ExpiringMessages,
+    UndoMessages,
+    MessageWorker,
+    ReplaySession,
+    MatchControlFlow,
+    MatchControlFlowPatterns,
+    TradeExamples,
+    NestedExamples,
+    SliceExamples,
+    Wallets,
+    WhileLetExamples,
+    OptionType,
+    MatchingWithOptionT,
+    MaybeExamples,
+    OptionRefs,
+    QuarterYears,
+    ErrorHandlingWithEnums,
+    CatchAllPatterns,
+    CatchAllPatternsUnderscorePlaceholder,
+    CatchAllPatternsNoopCatchall,
+    RollExamples,
+    IfLetMatchExample,
+    IfLetExample,
+    IfLetElseExample,
+    ConciseControlFlow,
+    ListExamples,
+    ExprExamples,
+    JsonExamples,
+    TrafficLightExamples,
+    LetElseExamples,
+    PredicateExamples,
+    GameRules,
+    Tournament,
+    Simulations,
+    DiceExamples,
+}
+
+impl Section {
+    /// The part of the examples the section is in
+    pub fn part(&self) -> Part {
+        use Section::*;
+
+        match self {
+            DefiningAnEnum | ShapeExamples | EnumStringValues | EnumDifferentTypes => Part::Ip,
+            MatchControlFlow
+            | MatchControlFlowPatterns
+            | TradeExamples
+            | NestedExamples
+            | SliceExamples
+            | Wallets
+            | WhileLetExamples => Part::Coins,
+            OptionType
+            | MatchingWithOptionT
+            | MaybeExamples
+            | OptionRefs
+            | QuarterYears
+            | ErrorHandlingWithEnums
+            | CatchAllPatterns
+            | CatchAllPatternsUnderscorePlaceholder
+            | CatchAllPatternsNoopCatchall
+            | RollExamples
+            | IfLetMatchExample
+            | IfLetExample
+            | IfLetElseExample
+            | ConciseControlFlow
+            | ListExamples
+            | ExprExamples
+            | JsonExamples
+            | TrafficLightExamples
+            | LetElseExamples
+            | PredicateExamples => Part::Patterns,
+            GameRules | Tournament | Simulations | DiceExamples => Part::Dice,
+            EnumMethods | StructConversions | ValidatedColors | MessageKinds
+            | MessagePredicates | VisitMessages | RouteMessages | DedupMessages
+            | CoalesceMessages | MessageStats | DisplayMessages | ParseMessages
+            | BinaryRoundTrip | FramedStream | MessageScript | MessageQueue | BoundedMoves
+            | BatchApply | PriorityScheduling | ExpiringMessages | UndoMessages | MessageWorker
+            | ReplaySession => Part::Messages,
+            #[cfg(feature = "json")]
+            JsonRoundTrip => Part::Messages,
+        }
+    }
+
+    /// What the section shows, for `--list`
+    pub fn description(&self) -> &'static str {
+        match self {
+            Section::DefiningAnEnum => "an enum of the kinds of IP address",
+            Section::ShapeExamples => "the area and perimeter of one of each shape",
+            Section::EnumStringValues => "an enum with associated `String` values",
+            Section::EnumDifferentTypes => "an enum with different associated types",
+            Section::EnumMethods => "an enum with methods",
+            Section::StructConversions => "converting between `Message` and the equivalent structs",
+            Section::ValidatedColors => "validated colors for `ChangeColor`",
+            Section::MessageKinds => "counting `Message`s by kind",
+            Section::MessagePredicates => "picking `Message`s apart with predicates",
+            Section::VisitMessages => "visiting `Message`s with a handler",
+            Section::RouteMessages => "routing `Message`s to handlers by kind",
+            Section::DedupMessages => "dropping repeated `Message`s",
+            Section::CoalesceMessages => "coalescing runs of `Move`s",
+            Section::MessageStats => "statistics over a random stream of `Message`s",
+            Section::DisplayMessages => "`Display` for `Message`",
+            Section::ParseMessages => "parsing `Message`s from command text",
+            #[cfg(feature = "json")]
+            Section::JsonRoundTrip => "JSON round trip for `Message`",
+            Section::BinaryRoundTrip => "binary encoding for `Message`",
+            Section::FramedStream => "streaming framed `Message`s",
+            Section::MessageScript => "composing `Message`s with a builder",
+            Section::MessageQueue => "applying a queue of `Message`s to a `Canvas`",
+            Section::BoundedMoves => "moving around a bounded `Board`",
+            Section::BatchApply => "applying a batch of `Message`s",
+            Section::PriorityScheduling => "scheduling `Message`s by priority",
+            Section::ExpiringMessages => "dropping expired `Message`s",
+            Section::UndoMessages => "undoing `Message`s",
+            Section::MessageWorker => "sending `Message`s to a worker thread",
+            Section::ReplaySession => "replaying a recorded session",
+            Section::MatchControlFlow => "the `match` control flow construct",
+            Section::MatchControlFlowPatterns => "patterns that bind to values",
+            Section::TradeExamples => "matching on a tuple of two coins",
+            Section::NestedExamples => "patterns inside patterns",
+            Section::SliceExamples => "matching on the shape of a slice",
+            Section::Wallets => "comparing coins and wallets with `Ordering`",
+            Section::WhileLetExamples => "looping with `while let`",
+            Section::OptionType => "the `Option` enum and its advantages over null values",
+            Section::MatchingWithOptionT => "matching with `Option<T>`",
+            Section::MaybeExamples => "`Maybe` doing what `Option` does",
+            Section::OptionRefs => "`Option`s of references",
+            Section::QuarterYears => "chaining `Option` combinators",
+            Section::ErrorHandlingWithEnums => "errors as enums",
+            Section::CatchAllPatterns => "a catch-all pattern that binds its value",
+            Section::CatchAllPatternsUnderscorePlaceholder => "the `_` placeholder",
+            Section::CatchAllPatternsNoopCatchall => "a catch-all that does nothing",
+            Section::RollExamples => "ranges, guards and `@` bindings",
+            Section::IfLetMatchExample => "a `match` that `if let` is concise for",
+            Section::IfLetExample => "concise control flow with `if let`",
+            Section::IfLetElseExample => "`if let .. else`",
+            Section::ConciseControlFlow => "`if let` next to the `match` it replaces",
+            Section::ListExamples => "recursive enums: a cons list",
+            Section::ExprExamples => "recursive enums: arithmetic expressions",
+            Section::JsonExamples => "recursive enums: JSON documents",
+            Section::TrafficLightExamples => "enums as state machines",
+            Section::LetElseExamples => "early returns with `let .. else`",
+            Section::PredicateExamples => "predicates with `matches!`",
+            Section::GameRules => "one game, three sets of rules",
+            Section::Tournament => "four players, one board",
+            Section::Simulations => "a thousand games under each set of rules",
+            Section::DiceExamples => "rolling a die many times",
+        }
+    }
+
+    /// Run the section's demo, writing to `ctx.reporter` under the
+    /// section's name, and adding how long it took to `ctx.timings()`
+    pub fn run(&self, ctx: &mut RunContext) -> io::Result<()> {
+        let RunContext {
+            reporter,
+            rng,
+            timings,
+        } = ctx;
+        let start = Instant::now();
+        let result = reporter.demo(self.into(), |r| match self {
+            Section::DefiningAnEnum => {
+                defining_an_enum();
+                Ok(())
+            }
+            Section::ShapeExamples => shape::shape_examples(r),
+            Section::EnumStringValues => enum_string_values(r),
+            Section::EnumDifferentTypes => enum_different_types(r),
+            Section::EnumMethods => enum_methods(r),
+            Section::StructConversions => struct_conversions(r),
+            Section::ValidatedColors => validated_colors(r),
+            Section::MessageKinds => message_kinds(r),
+            Section::MessagePredicates => message_predicates(r),
+            Section::VisitMessages => visit_messages(r),
+            Section::RouteMessages => route_messages(r),
+            Section::DedupMessages => dedup_messages(r),
+            Section::CoalesceMessages => coalesce_messages(r),
+            Section::MessageStats => message_stats(r, rng),
+            Section::DisplayMessages => display_messages(r),
+            Section::ParseMessages => parse_messages(r),
+            #[cfg(feature = "json")]
+            Section::JsonRoundTrip => json_round_trip(r),
+            Section::BinaryRoundTrip => binary_round_trip(r),
+            Section::FramedStream => framed_stream(r),
+            Section::MessageScript => message_script(r),
+            Section::MessageQueue => message_queue(r),
+            Section::BoundedMoves => bounded_moves(r),
+            Section::BatchApply => batch_apply(r),
+            Section::PriorityScheduling => priority_scheduling(r, rng),
+            Section::ExpiringMessages => expiring_messages(r),
+            Section::UndoMessages => undo_messages(r),
+            Section::MessageWorker => message_worker(r),
+            Section::ReplaySession => replay_session(r),
+            Section::MatchControlFlow => match_control_flow(r, rng),
+            Section::MatchControlFlowPatterns => match_control_flow_patterns(r, rng),
+            Section::TradeExamples => trade::trade_examples(r),
+            Section::NestedExamples => nested::nested_examples(r),
+            Section::SliceExamples => slices::slice_examples(r),
+            Section::Wallets => wallets(r, rng),
+            Section::WhileLetExamples => while_let_demo::while_let_examples(r),
+            Section::OptionType => option_type(r),
+            Section::MatchingWithOptionT => matching_with_option_t(r),
+            Section::MaybeExamples => maybe::maybe_examples(r),
+            Section::OptionRefs => option_refs(r),
+            Section::QuarterYears => quarter_years(r),
+            Section::ErrorHandlingWithEnums => math::error_handling_with_enums(r),
+            Section::CatchAllPatterns => catch_all_patterns(r),
+            Section::CatchAllPatternsUnderscorePlaceholder => {
+                catch_all_patterns_underscore_placeholder(r)
+            }
+            Section::CatchAllPatternsNoopCatchall => catch_all_patterns_noop_catchall(r),
+            Section::RollExamples => patterns_advanced::roll_examples(r, rng),
+            Section::IfLetMatchExample => if_let_match_example(r),
+            Section::IfLetExample => if_let_example(r),
+            Section::IfLetElseExample => if_let_else_example(r, rng),
+            Section::ConciseControlFlow => concise_control_flow(r),
+            Section::ListExamples => list::list_examples(r),
+            Section::ExprExamples => expr::expr_examples(r),
+            Section::JsonExamples => json_value::json_examples(r),
+            Section::TrafficLightExamples => traffic_light::traffic_light_examples(r),
+            Section::LetElseExamples => let_else::let_else_examples(r),
+            Section::PredicateExamples => predicates::predicate_examples(r),
+            Section::GameRules => game_rules(r, rng),
+            Section::Tournament => tournament(r, rng),
+            Section::Simulations => simulations(r, rng),
+            Section::DiceExamples => dice::dice_examples(r, rng),
+        });
+        timings.push((*self, start.elapsed()));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn every_demo_has_a_section() {
+        // One per `Reporter::demo` call `main` used to make by hand, plus
+        // `defining_an_enum`, which writes nothing
+        let expected = if cfg!(feature = "json") { 59 } else { 58 };
+        assert_eq!(Section::iter().count(), expected);
+    }
+
+    #[test]
+    fn names_parse_back() {
+        for section in Section::iter() {
+            assert_eq!(Section::from_str(&section.to_string()), Ok(section));
+        }
+        assert_eq!(
+            Section::from_str("match_control_flow_patterns"),
+            Ok(Section::MatchControlFlowPatterns)
+        );
+        assert!(Section::from_str("MatchControlFlow").is_err());
+    }
+
+    #[test]
+    fn sections_of_a_part_are_together() {
+        let mut seen: Vec<Part> = Section::iter().map(|s| s.part()).collect();
+        seen.dedup();
+        assert_eq!(
+            seen,
+            [
+                Part::Ip,
+                Part::Messages,
+                Part::Coins,
+                Part::Patterns,
+                Part::Dice
+            ]
+        );
+    }
+
+    #[test]
+    fn every_section_runs_without_panicking() {
+        let mut ctx = RunContext::new(Reporter::new(io::sink()), 42);
+        Section::iter().for_each(|section| section.run(&mut ctx).unwrap());
+        assert_eq!(ctx.timings().len(), Section::iter().count());
+    }
+
+    #[test]
+    fn timings_list_the_sections_run_in_order() {
+        let mut buf = Vec::new();
+        let mut ctx = RunContext::new(Reporter::new(&mut buf), 1);
+        for section in [Section::Wallets, Section::EnumDifferentTypes] {
+            section.run(&mut ctx).unwrap();
+        }
+        let timed: Vec<Section> = ctx.timings().iter().map(|&(s, _)| s).collect();
+        assert_eq!(timed, [Section::Wallets, Section::EnumDifferentTypes]);
+        assert!(ctx.timings().iter().all(|(_, d)| *d > Duration::ZERO));
+
+        ctx.write_timings().unwrap();
+        drop(ctx);
+        let text = String::from_utf8(buf).unwrap();
+        let summary: Vec<&str> = text
+            .lines()
+            .skip_while(|&line| line != "timings")
+            .skip(1)
+            .collect();
+        assert_eq!(summary.len(), 2, "{text}");
+        assert!(summary[0].starts_with("  wallets               "));
+        assert!(summary[1].starts_with("  enum_different_types  "));
+        assert!(summary.iter().all(|line| line.ends_with(" ms")));
+    }
+
+    /// What the coin sections write at `level`, with seed 42
+    fn coins_at(level: OutputLevel) -> String {
+        let mut buf = Vec::new();
+        let mut ctx = RunContext::new(Reporter::new(&mut buf), 42).with_level(level);
+        for section in Part::Coins.sections() {
+            section.run(&mut ctx).unwrap();
+        }
+        drop(ctx);
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn coin_sections_at_each_level() {
+        let quiet = coins_at(OutputLevel::Quiet);
+        let normal = coins_at(OutputLevel::Normal);
+        let verbose = coins_at(OutputLevel::Verbose);
+        assert_eq!(
+            quiet.lines().count(),
+            Part::Coins.sections().len(),
+            "{quiet}"
+        );
+        assert_eq!(normal.lines().count(), 29, "{normal}");
+        // The type of each random coin
+        assert_eq!(verbose.lines().count(), 31, "{verbose}");
+        for text in [&quiet, &normal, &verbose] {
+            assert!(text.contains("the second wallet is richer\n"), "{text}");
+        }
+    }
+
+    #[test]
+    fn quiet_sections_write_one_line_at_most() {
+        for section in Section::iter() {
+            let mut buf = Vec::new();
+            let mut ctx =
+                RunContext::new(Reporter::new(&mut buf), 42).with_level(OutputLevel::Quiet);
+            section.run(&mut ctx).unwrap();
+            drop(ctx);
+            let text = String::from_utf8(buf).unwrap();
+            let expected = usize::from(section != Section::DefiningAnEnum);
+            assert_eq!(text.lines().count(), expected, "{section}: {text}");
+        }
+    }
+
+    #[test]
+    fn each_section_writes_a_record_under_its_name() {
+        use crate::report::OutputFormat;
+
+        let mut buf = Vec::new();
+        let mut ctx = RunContext::new(Reporter::new(&mut buf).with_format(OutputFormat::Json), 1);
+        for section in Section::iter() {
+            section.run(&mut ctx).unwrap();
+        }
+        drop(ctx);
+        let text = String::from_utf8(buf).unwrap();
+        let names: Vec<String> = text
+            .lines()
+            .map(|line| {
+                let rest = line.strip_prefix("{\"section\":\"").unwrap();
+                rest[..rest.find('"').unwrap()].to_string()
+            })
+            .collect();
+        let expected: Vec<String> = Section::iter().map(|s| s.to_string()).collect();
+        assert_eq!(names, expected);
+    }
+}
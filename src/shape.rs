@@ -0,0 +1,220 @@
+//! # Shapes, from the opening of the chapter
+//!
+//! "Defining an Enum" starts by saying a `Rectangle` is one of a set of
+//! shapes that also includes `Circle` and `Triangle`. `Shape` is that set,
+//! each variant with the fields its own shape needs, and `area` and
+//! `perimeter` are `match`es with one formula per variant.
+use std::f64::consts::PI;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::report::Reporter;
+
+/// # A rectangle, circle or triangle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Rectangle {
+        width: f64,
+        height: f64,
+    },
+    Circle {
+        radius: f64,
+    },
+    /// A triangle with sides of length `a`, `b` and `c`
+    Triangle {
+        a: f64,
+        b: f64,
+        c: f64,
+    },
+}
+
+/// # A `Shape` that can't exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeError {
+    /// A length is negative, infinite or NaN
+    InvalidDimension,
+    /// One side of a triangle is longer than the other two put together
+    ImpossibleTriangle,
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShapeError::InvalidDimension => write!(f, "lengths must be finite and not negative"),
+            ShapeError::ImpossibleTriangle => {
+                write!(f, "one side is longer than the other two together")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+impl Shape {
+    /// The shape's area
+    ///
+    /// A triangle's comes from Heron's formula. A triangle whose sides
+    /// exactly add up, or a circle of radius 0, is flat, with an area of 0.
+    pub fn area(&self) -> Result<f64, ShapeError> {
+        self.check()?;
+        Ok(match *self {
+            Shape::Rectangle { width, height } => width * height,
+            Shape::Circle { radius } => PI * radius * radius,
+            Shape::Triangle { a, b, c } => {
+                let s = (a + b + c) / 2.0;
+                // Rounding can take a flat triangle just below zero
+                (s * (s - a) * (s - b) * (s - c)).max(0.0).sqrt()
+            }
+        })
+    }
+
+    /// The length of the shape's outline
+    pub fn perimeter(&self) -> Result<f64, ShapeError> {
+        self.check()?;
+        Ok(match *self {
+            Shape::Rectangle { width, height } => 2.0 * (width + height),
+            Shape::Circle { radius } => 2.0 * PI * radius,
+            Shape::Triangle { a, b, c } => a + b + c,
+        })
+    }
+
+    /// Whether the shape can exist at all
+    fn check(&self) -> Result<(), ShapeError> {
+        let lengths: &[f64] = match self {
+            Shape::Rectangle { width, height } => &[*width, *height],
+            Shape::Circle { radius } => &[*radius],
+            Shape::Triangle { a, b, c } => &[*a, *b, *c],
+        };
+        if !lengths.iter().all(|l| l.is_finite() && *l >= 0.0) {
+            return Err(ShapeError::InvalidDimension);
+        }
+        match *self {
+            Shape::Triangle { a, b, c } if a > b + c || b > a + c || c > a + b => {
+                Err(ShapeError::ImpossibleTriangle)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Shape::Rectangle { width, height } => write!(f, "{width}x{height} rectangle"),
+            Shape::Circle { radius } => write!(f, "circle of radius {radius}"),
+            Shape::Triangle { a, b, c } => write!(f, "triangle with sides {a}, {b} and {c}"),
+        }
+    }
+}
+
+/// # The area and perimeter of one of each shape
+pub fn shape_examples(r: &mut Reporter) -> io::Result<()> {
+    let (mut shapes, mut total) = (0, 0.0);
+    for shape in [
+        Shape::Rectangle {
+            width: 3.0,
+            height: 4.0,
+        },
+        Shape::Circle { radius: 1.0 },
+        Shape::Triangle {
+            a: 3.0,
+            b: 4.0,
+            c: 5.0,
+        },
+        Shape::Triangle {
+            a: 1.0,
+            b: 1.0,
+            c: 3.0,
+        },
+    ] {
+        match (shape.area(), shape.perimeter()) {
+            (Ok(area), Ok(perimeter)) => {
+                shapes += 1;
+                total += area;
+                writeln!(
+                    r,
+                    "A {shape} has area {area:.2} and perimeter {perimeter:.2}"
+                )?
+            }
+            (Err(e), _) | (_, Err(e)) => writeln!(r, "A {shape} can't exist: {e}")?,
+        }
+    }
+    r.result(format_args!(
+        "{shapes} shape(s) with {total:.2} of area in all"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(a: f64, b: f64, c: f64) -> Shape {
+        Shape::Triangle { a, b, c }
+    }
+
+    #[test]
+    fn known_areas() {
+        let rectangle = Shape::Rectangle {
+            width: 3.0,
+            height: 4.0,
+        };
+        assert_eq!(rectangle.area(), Ok(12.0));
+        assert_eq!(rectangle.perimeter(), Ok(14.0));
+        assert_eq!(Shape::Circle { radius: 2.0 }.area(), Ok(4.0 * PI));
+        assert_eq!(Shape::Circle { radius: 2.0 }.perimeter(), Ok(4.0 * PI));
+        assert_eq!(triangle(3.0, 4.0, 5.0).area(), Ok(6.0));
+        assert_eq!(triangle(3.0, 4.0, 5.0).perimeter(), Ok(12.0));
+        let equilateral = triangle(2.0, 2.0, 2.0).area().unwrap();
+        assert!((equilateral - 3f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn flat_shapes_have_no_area() {
+        assert_eq!(triangle(1.0, 2.0, 3.0).area(), Ok(0.0));
+        // Flat up to rounding
+        assert!(triangle(0.1, 0.2, 0.3).area().unwrap() < 1e-9);
+        assert_eq!(Shape::Circle { radius: 0.0 }.area(), Ok(0.0));
+        assert_eq!(Shape::Circle { radius: 0.0 }.perimeter(), Ok(0.0));
+    }
+
+    #[test]
+    fn impossible_triangles() {
+        for t in [triangle(1.0, 1.0, 3.0), triangle(5.0, 1.0, 1.0)] {
+            assert_eq!(t.area(), Err(ShapeError::ImpossibleTriangle));
+            assert_eq!(t.perimeter(), Err(ShapeError::ImpossibleTriangle));
+        }
+    }
+
+    #[test]
+    fn bad_dimensions() {
+        for shape in [
+            Shape::Rectangle {
+                width: -1.0,
+                height: 2.0,
+            },
+            Shape::Circle { radius: f64::NAN },
+            Shape::Circle {
+                radius: f64::INFINITY,
+            },
+            triangle(-3.0, 4.0, 5.0),
+        ] {
+            assert_eq!(shape.area(), Err(ShapeError::InvalidDimension), "{shape}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Shape::Rectangle {
+                width: 3.0,
+                height: 4.5
+            }
+            .to_string(),
+            "3x4.5 rectangle"
+        );
+        assert_eq!(
+            triangle(3.0, 4.0, 5.0).to_string(),
+            "triangle with sides 3, 4 and 5"
+        );
+    }
+}
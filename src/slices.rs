@@ -0,0 +1,128 @@
+//! # Matching on the shape of a slice
+//!
+//! Slice patterns match on how many elements there are and bind the ones
+//! that matter: `[]` for none, `[only]` for exactly one, and
+//! `[first, .., last]` for two or more, with `..` standing for whatever is
+//! in between.
+use std::io::{self, Write};
+
+use rust_enums::{Coin, Coin2};
+
+use crate::report::Reporter;
+
+/// # What a handful of coins looks like
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandfulDescription {
+    Empty,
+    Single(Coin),
+    /// `count` coins, at least two, every one a quarter
+    AllQuarters {
+        count: usize,
+    },
+    /// `count` coins, at least two, from a `first_kind` to a `last_kind`
+    Ends {
+        first_kind: Coin,
+        last_kind: Coin,
+        count: usize,
+    },
+}
+
+/// Describe `coins` by its shape
+pub fn describe_handful(coins: &[Coin2]) -> HandfulDescription {
+    match coins {
+        [] => HandfulDescription::Empty,
+        [only] => HandfulDescription::Single(only.kind()),
+        all if all.iter().all(|c| matches!(c, Coin2::Quarter(_))) => {
+            HandfulDescription::AllQuarters { count: all.len() }
+        }
+        [first, .., last] => HandfulDescription::Ends {
+            first_kind: first.kind(),
+            last_kind: last.kind(),
+            count: coins.len(),
+        },
+    }
+}
+
+/// # Three handfuls, described
+pub fn slice_examples(r: &mut Reporter) -> io::Result<()> {
+    use rust_enums::UsState::*;
+
+    let mut coins = 0;
+    for handful in [
+        vec![],
+        vec![Coin2::Dime, Coin2::Penny, Coin2::Quarter(Ohio)],
+        vec![Coin2::Quarter(Iowa), Coin2::Quarter(Utah)],
+    ] {
+        writeln!(r, "{handful:?}: {:?}", describe_handful(&handful))?;
+        coins += handful.len();
+    }
+    r.result(format_args!("3 handful(s) of {coins} coin(s) described"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_enums::UsState;
+
+    #[test]
+    fn empty() {
+        assert_eq!(describe_handful(&[]), HandfulDescription::Empty);
+    }
+
+    #[test]
+    fn one_coin() {
+        assert_eq!(
+            describe_handful(&[Coin2::Nickel]),
+            HandfulDescription::Single(Coin::Nickel)
+        );
+        // One quarter is a single coin before it is all quarters
+        assert_eq!(
+            describe_handful(&[Coin2::Quarter(UsState::Ohio)]),
+            HandfulDescription::Single(Coin::Quarter)
+        );
+    }
+
+    #[test]
+    fn two_coins_are_both_ends() {
+        assert_eq!(
+            describe_handful(&[Coin2::Dime, Coin2::Dime]),
+            HandfulDescription::Ends {
+                first_kind: Coin::Dime,
+                last_kind: Coin::Dime,
+                count: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn long_mixed_handful() {
+        let handful = [
+            Coin2::Penny,
+            Coin2::Quarter(UsState::Utah),
+            Coin2::Dime,
+            Coin2::Nickel,
+            Coin2::Quarter(UsState::Iowa),
+        ];
+        assert_eq!(
+            describe_handful(&handful),
+            HandfulDescription::Ends {
+                first_kind: Coin::Penny,
+                last_kind: Coin::Quarter,
+                count: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn all_quarters() {
+        let handful = [
+            Coin2::Quarter(UsState::Utah),
+            Coin2::Quarter(UsState::Utah),
+            Coin2::Quarter(UsState::Iowa),
+        ];
+        assert_eq!(
+            describe_handful(&handful),
+            HandfulDescription::AllQuarters { count: 3 }
+        );
+    }
+}
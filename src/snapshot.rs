@@ -0,0 +1,121 @@
+//! # Snapshot tests of what the demos write
+//!
+//! `assert_snapshot` compares a demo's output with the copy saved under
+//! `tests/snapshots/`. When they differ, or there is no saved copy yet, the
+//! new output is written next to it as `<name>.snap.new` and the test
+//! fails. To accept an intentional change, look over the `.snap.new` files
+//! and rename them over the `.snap` ones, or run the tests again with
+//! `INSTA_UPDATE=always` to overwrite every snapshot that differs.
+//!
+//! The file layout and the update switch are `insta`'s, so the snapshots
+//! read the same; there is no `cargo insta review` to do the renaming.
+//!
+//! Output is normalized before it is compared: line endings become `\n`
+//! and trailing whitespace is dropped, so a snapshot saved on one platform
+//! matches on another. The demos snapshotted here draw from seeded RNGs and
+//! keep counts in `BTreeMap`s, so nothing else in them varies between runs.
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The directory snapshots are saved in
+fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+/// `text` with `\n` line endings and no trailing whitespace on any line,
+/// ending in a single newline
+pub fn normalize(text: &str) -> String {
+    let mut normalized: String = text
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    normalized.truncate(normalized.trim_end().len());
+    normalized.push('\n');
+    normalized
+}
+
+/// The contents of a snapshot file: a header saying what was
+/// snapshotted, then the normalized `text`
+fn snapshot_file(expression: &str, text: &str) -> String {
+    format!(
+        "---\nsource: src/main.rs\nexpression: {expression}\n---\n{}",
+        normalize(text)
+    )
+}
+
+/// Check `text`, the output of `expression`, against the snapshot `name`
+///
+/// # Panics
+///
+/// If there is no snapshot `name`, or it differs from `text`, unless
+/// `INSTA_UPDATE` is `always`, in which case the snapshot is overwritten.
+pub fn assert_snapshot(name: &str, expression: &str, text: &str) {
+    let dir = snapshot_dir();
+    let path = dir.join(format!("{name}.snap"));
+    let new_path = dir.join(format!("{name}.snap.new"));
+    let actual = snapshot_file(expression, text);
+    let saved = fs::read_to_string(&path).ok();
+    if saved.as_deref().map(|saved| saved.replace("\r\n", "\n")) == Some(actual.clone()) {
+        // An earlier failure's new copy is stale now
+        let _ = fs::remove_file(&new_path);
+        return;
+    }
+    fs::create_dir_all(&dir).unwrap();
+    if env::var("INSTA_UPDATE").as_deref() == Ok("always") {
+        fs::write(&path, actual).unwrap();
+        let _ = fs::remove_file(&new_path);
+        return;
+    }
+    fs::write(&new_path, &actual).unwrap();
+    match saved {
+        None => panic!(
+            "no snapshot {name} yet; review {} and rename it to {name}.snap",
+            new_path.display()
+        ),
+        Some(saved) => panic!(
+            "snapshot {name} differs; review {} against {name}.snap\n{}",
+            new_path.display(),
+            line_diff(&saved, &actual)
+        ),
+    }
+}
+
+/// The lines of `old` and `new` that differ, marked `-` and `+`, paired up
+/// by line number
+fn line_diff(old: &str, new: &str) -> String {
+    let (old, new): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+    let mut diff = String::new();
+    for i in 0..old.len().max(new.len()) {
+        let (a, b) = (old.get(i), new.get(i));
+        if a != b {
+            if let Some(a) = a {
+                diff.push_str(&format!("{:>4} -{a}\n", i + 1));
+            }
+            if let Some(b) = b {
+                diff.push_str(&format!("{:>4} +{b}\n", i + 1));
+            }
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizing_hides_platform_differences() {
+        assert_eq!(normalize("a  \r\nb\t\r\n\r\n"), "a\nb\n");
+        assert_eq!(normalize("a\nb"), normalize("a\r\nb\n"));
+    }
+
+    #[test]
+    fn diffs_show_changed_lines() {
+        assert_eq!(
+            line_diff("a\nb\nc\n", "a\nx\n"),
+            "   2 -b\n   2 +x\n   3 -c\n"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! # Matching on two values at once
+//!
+//! A `match` on a tuple looks at both values together, so one arm can ask
+//! for "a quarter for a quarter" or "the same coin twice" directly, where
+//! matching each value on its own would need nested `match`es.
+use std::io::{self, Write};
+
+use rust_enums::{Cents, Coin2, UsState};
+
+use crate::report::Reporter;
+
+/// # What to make of a trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeVerdict {
+    /// The same coin for the same coin
+    Fair,
+    /// A quarter for a quarter from another state: same money, different
+    /// collection
+    CollectorSwap(UsState, UsState),
+    /// The offer is worth `surplus` more than the ask
+    Generous { surplus: Cents },
+    /// The offer is worth `shortfall` less than the ask
+    BadDeal { shortfall: Cents },
+}
+
+/// Whether handing over `offer` for `ask` is a good trade
+pub fn evaluate_trade(offer: &Coin2, ask: &Coin2) -> TradeVerdict {
+    match (offer, ask) {
+        (Coin2::Quarter(given), Coin2::Quarter(got)) if given != got => {
+            TradeVerdict::CollectorSwap(*given, *got)
+        }
+        (Coin2::Penny, Coin2::Penny)
+        | (Coin2::Nickel, Coin2::Nickel)
+        | (Coin2::Dime, Coin2::Dime)
+        | (Coin2::Quarter(_), Coin2::Quarter(_)) => TradeVerdict::Fair,
+        (offer, ask) if offer.cents() > ask.cents() => TradeVerdict::Generous {
+            surplus: offer.cents() - ask.cents(),
+        },
+        (offer, ask) => TradeVerdict::BadDeal {
+            shortfall: ask.cents() - offer.cents(),
+        },
+    }
+}
+
+/// # A few trades, and what to make of them
+pub fn trade_examples(r: &mut Reporter) -> io::Result<()> {
+    let mut fair = 0;
+    for (offer, ask) in [
+        (Coin2::Dime, Coin2::Dime),
+        (Coin2::Quarter(UsState::Ohio), Coin2::Quarter(UsState::Utah)),
+        (Coin2::Quarter(UsState::Ohio), Coin2::Nickel),
+        (Coin2::Penny, Coin2::Dime),
+    ] {
+        let verdict = evaluate_trade(&offer, &ask);
+        fair += usize::from(verdict == TradeVerdict::Fair);
+        writeln!(r, "{offer:?} for {ask:?}: {verdict:?}")?;
+    }
+    r.result(format_args!("{fair} of 4 trade(s) fair"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn same_coin_is_fair() {
+        for coin in Coin2::iter() {
+            assert_eq!(evaluate_trade(&coin, &coin), TradeVerdict::Fair);
+        }
+        let ohio = Coin2::Quarter(UsState::Ohio);
+        assert_eq!(evaluate_trade(&ohio, &ohio), TradeVerdict::Fair);
+    }
+
+    #[test]
+    fn quarters_from_different_states_are_swaps_in_either_order() {
+        let ohio = Coin2::Quarter(UsState::Ohio);
+        let utah = Coin2::Quarter(UsState::Utah);
+        assert_eq!(
+            evaluate_trade(&ohio, &utah),
+            TradeVerdict::CollectorSwap(UsState::Ohio, UsState::Utah)
+        );
+        assert_eq!(
+            evaluate_trade(&utah, &ohio),
+            TradeVerdict::CollectorSwap(UsState::Utah, UsState::Ohio)
+        );
+    }
+
+    #[test]
+    fn more_for_less_is_generous() {
+        assert_eq!(
+            evaluate_trade(&Coin2::Quarter(UsState::Iowa), &Coin2::Penny),
+            TradeVerdict::Generous { surplus: 24 }
+        );
+        assert_eq!(
+            evaluate_trade(&Coin2::Dime, &Coin2::Nickel),
+            TradeVerdict::Generous { surplus: 5 }
+        );
+    }
+
+    #[test]
+    fn less_for_more_is_a_bad_deal() {
+        assert_eq!(
+            evaluate_trade(&Coin2::Penny, &Coin2::Quarter(UsState::Iowa)),
+            TradeVerdict::BadDeal { shortfall: 24 }
+        );
+        assert_eq!(
+            evaluate_trade(&Coin2::Nickel, &Coin2::Dime),
+            TradeVerdict::BadDeal { shortfall: 5 }
+        );
+    }
+}
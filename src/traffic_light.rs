@@ -0,0 +1,95 @@
+//! # An enum as a state machine
+//!
+//! Each variant of `TrafficLight` is a state, and `next` is the transition
+//! out of it. Because `next` is a `match` with no catch-all arm, adding a
+//! light, say a flashing amber, won't compile until its transition is
+//! written too.
+use std::io::{self, Write};
+use std::time::Duration;
+
+use strum_macros::EnumIter;
+
+use crate::report::Reporter;
+
+/// # The phases of a traffic light
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum TrafficLight {
+    Red,
+    Yellow,
+    Green,
+}
+
+impl TrafficLight {
+    /// The phase after this one: red, then green, then yellow, then red
+    /// again
+    pub fn next(&self) -> TrafficLight {
+        match self {
+            TrafficLight::Red => TrafficLight::Green,
+            TrafficLight::Green => TrafficLight::Yellow,
+            TrafficLight::Yellow => TrafficLight::Red,
+        }
+    }
+
+    /// How long the light stays in this phase
+    pub fn duration(&self) -> Duration {
+        match self {
+            TrafficLight::Red => Duration::from_secs(30),
+            TrafficLight::Yellow => Duration::from_secs(5),
+            TrafficLight::Green => Duration::from_secs(25),
+        }
+    }
+}
+
+/// The phases a light goes through in `steps` transitions from `start`,
+/// `start` included
+pub fn simulate(start: TrafficLight, steps: usize) -> Vec<TrafficLight> {
+    let mut phases = Vec::with_capacity(steps + 1);
+    let mut light = start;
+    phases.push(light);
+    for _ in 0..steps {
+        light = light.next();
+        phases.push(light);
+    }
+    phases
+}
+
+/// # One full cycle of a traffic light
+pub fn traffic_light_examples(r: &mut Reporter) -> io::Result<()> {
+    let cycle = simulate(TrafficLight::Red, 2);
+    for light in &cycle {
+        writeln!(r, "{light:?} for {:?}", light.duration())?;
+    }
+    let total: Duration = cycle.iter().map(TrafficLight::duration).sum();
+    r.result(format_args!("One cycle takes {total:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn three_steps_come_back_to_the_start() {
+        for light in TrafficLight::iter() {
+            assert_eq!(light.next().next().next(), light);
+            assert_ne!(light.next(), light);
+        }
+    }
+
+    #[test]
+    fn simulate_seven_steps() {
+        use TrafficLight::*;
+        assert_eq!(
+            simulate(Red, 7),
+            [Red, Green, Yellow, Red, Green, Yellow, Red, Green]
+        );
+        assert_eq!(simulate(Yellow, 0), [Yellow]);
+    }
+
+    #[test]
+    fn every_phase_lasts_a_while() {
+        for light in TrafficLight::iter() {
+            assert!(light.duration() > Duration::ZERO, "{light:?}");
+        }
+    }
+}
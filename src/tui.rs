@@ -0,0 +1,328 @@
+//! # A menu to browse the sections and run them one at a time
+//!
+//! `enums tui` lists every [`Section`]. The up and down arrows (or `k` and
+//! `j`) move through the list, Enter runs the highlighted section into the
+//! scrollback pane under it, and `q` quits. The status bar shows the seed
+//! every section runs with, so a run can be repeated with `--seed`.
+//!
+//! The terminal handling is only what the menu needs: `stty` for raw mode,
+//! ANSI escapes to draw, and decoding the arrow keys' escape sequences from
+//! stdin. Unlike a terminal library, it doesn't follow resizes or read the
+//! mouse. The event loop only sees `Event`s from an `EventSource`, so
+//! `ScriptedEvents` can drive it in tests without a terminal.
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use strum::IntoEnumIterator;
+
+use crate::report::Reporter;
+use crate::section::{RunContext, Section};
+
+/// Sections shown at once; the list scrolls to keep the highlight in view
+const MENU_ROWS: usize = 12;
+
+/// Lines of the pane shown at once, the most recent ones
+const PANE_ROWS: usize = 16;
+
+/// # Something the user did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// Move the highlight up one section
+    Up,
+    /// Move the highlight down one section
+    Down,
+    /// Run the highlighted section
+    Enter,
+    /// Leave the menu
+    Quit,
+}
+
+/// # Where the menu's events come from
+pub trait EventSource {
+    /// The next event, waiting for one if need be, or `None` once there
+    /// will be no more
+    fn next_event(&mut self) -> io::Result<Option<Event>>;
+}
+
+/// # A fixed list of events, for tests
+#[cfg(test)]
+pub struct ScriptedEvents(std::collections::VecDeque<Event>);
+
+#[cfg(test)]
+impl ScriptedEvents {
+    /// A source giving `events` in order, then `None`
+    pub fn new(events: impl IntoIterator<Item = Event>) -> Self {
+        ScriptedEvents(events.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+impl EventSource for ScriptedEvents {
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        Ok(self.0.pop_front())
+    }
+}
+
+/// # Key presses read from a terminal in raw mode
+pub struct KeyEvents<R: BufRead> {
+    input: io::Bytes<R>,
+}
+
+impl<R: BufRead> KeyEvents<R> {
+    /// A source decoding the keys pressed from `input`
+    pub fn new(input: R) -> Self {
+        KeyEvents {
+            input: input.bytes(),
+        }
+    }
+
+    fn byte(&mut self) -> io::Result<Option<u8>> {
+        self.input.next().transpose()
+    }
+}
+
+impl<R: BufRead> EventSource for KeyEvents<R> {
+    /// Keys the menu has no use for are skipped
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        while let Some(byte) = self.byte()? {
+            let event = match byte {
+                b'q' | b'Q' | 0x03 => Event::Quit,
+                b'\r' | b'\n' => Event::Enter,
+                b'k' => Event::Up,
+                b'j' => Event::Down,
+                // An arrow key is `ESC [ A` to `ESC [ D`
+                0x1b => match (self.byte()?, self.byte()?) {
+                    (Some(b'['), Some(b'A')) => Event::Up,
+                    (Some(b'['), Some(b'B')) => Event::Down,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            return Ok(Some(event));
+        }
+        Ok(None)
+    }
+}
+
+/// # The menu's state: the highlight, the list's scroll and the pane
+pub struct Menu {
+    sections: Vec<Section>,
+    selected: usize,
+    /// The index of the first section shown
+    top: usize,
+    pane: Vec<String>,
+    seed: u64,
+    ran: Vec<Section>,
+}
+
+impl Menu {
+    /// A menu of every section, the first highlighted, each running with an
+    /// RNG seeded with `seed`
+    pub fn new(seed: u64) -> Self {
+        Menu {
+            sections: Section::iter().collect(),
+            selected: 0,
+            top: 0,
+            pane: Vec::new(),
+            seed,
+            ran: Vec::new(),
+        }
+    }
+
+    /// The highlighted section
+    pub fn selected(&self) -> Section {
+        self.sections[self.selected]
+    }
+
+    /// Every section run so far, in order
+    #[cfg(test)]
+    pub fn ran(&self) -> &[Section] {
+        &self.ran
+    }
+
+    /// Every line written to the pane so far
+    #[cfg(test)]
+    pub fn pane(&self) -> &[String] {
+        &self.pane
+    }
+
+    /// Act on `event`, other than `Quit`
+    fn handle(&mut self, event: Event) -> io::Result<()> {
+        match event {
+            Event::Up => self.selected = self.selected.saturating_sub(1),
+            Event::Down => self.selected = (self.selected + 1).min(self.sections.len() - 1),
+            Event::Enter => self.run_selected()?,
+            Event::Quit => {}
+        }
+        // Scroll just far enough to show the highlight
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + MENU_ROWS {
+            self.top = self.selected + 1 - MENU_ROWS;
+        }
+        Ok(())
+    }
+
+    /// Run the highlighted section, adding what it wrote to the pane under
+    /// a line naming it
+    fn run_selected(&mut self) -> io::Result<()> {
+        let section = self.selected();
+        let mut buf = Vec::new();
+        section.run(&mut RunContext::new(Reporter::new(&mut buf), self.seed))?;
+        self.pane.push(format!("── {section} ──"));
+        self.pane
+            .extend(String::from_utf8_lossy(&buf).lines().map(String::from));
+        self.ran.push(section);
+        Ok(())
+    }
+
+    /// Draw the whole screen: the visible part of the list, the end of the
+    /// pane, and the status bar
+    ///
+    /// Lines end in `\r\n`, as a terminal in raw mode doesn't return the
+    /// cursor to the start of the line on `\n`.
+    pub fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1b[H\x1b[2J\x1b[1menums: sections\x1b[0m\r\n")?;
+        let shown = self.sections.iter().enumerate().skip(self.top);
+        for (i, section) in shown.take(MENU_ROWS) {
+            let line = format!("{:<44}{}", section.to_string(), section.description());
+            match i == self.selected {
+                true => write!(out, "\x1b[7m> {line}\x1b[0m\r\n")?,
+                false => write!(out, "  {line}\r\n")?,
+            }
+        }
+        write!(out, "{}\r\n", "─".repeat(72))?;
+        let start = self.pane.len().saturating_sub(PANE_ROWS);
+        for line in &self.pane[start..] {
+            write!(out, "{line}\r\n")?;
+        }
+        write!(
+            out,
+            "\x1b[7m seed {} │ ↑↓ move │ Enter run │ q quit \x1b[0m",
+            self.seed
+        )
+    }
+}
+
+/// Draw `menu` to `out` and act on each event from `events`, until a
+/// `Quit` or the events run out
+pub fn run_menu(
+    menu: &mut Menu,
+    events: &mut impl EventSource,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    loop {
+        menu.draw(out)?;
+        out.flush()?;
+        match events.next_event()? {
+            None | Some(Event::Quit) => return Ok(()),
+            Some(event) => menu.handle(event)?,
+        }
+    }
+}
+
+/// # The terminal in raw mode on the alternate screen, until dropped
+pub struct RawMode {
+    /// The settings `stty -g` gave before, to put back
+    saved: String,
+}
+
+impl RawMode {
+    /// Put the terminal on stdin into raw mode, and switch stdout to the
+    /// alternate screen with the cursor hidden
+    pub fn enter() -> io::Result<RawMode> {
+        if !io::stdin().is_terminal() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the menu needs a terminal",
+            ));
+        }
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// Run `stty` with `args` on the terminal on stdin, returning its output
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!("stty {}: failed", args.join(" "))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn down_down_enter_runs_the_third_section() {
+        let mut menu = Menu::new(42);
+        let mut events = ScriptedEvents::new([Event::Down, Event::Down, Event::Enter, Event::Quit]);
+        run_menu(&mut menu, &mut events, &mut io::sink()).unwrap();
+        assert_eq!(menu.ran(), [Section::EnumStringValues]);
+        assert_eq!(
+            menu.pane(),
+            [
+                "── enum_string_values ──",
+                "`home` is V4(\"127.0.0.1\"), `loopback` is V6(\"::1\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_highlight_stays_in_the_list() {
+        let mut menu = Menu::new(1);
+        let mut events = ScriptedEvents::new([Event::Up, Event::Up]);
+        run_menu(&mut menu, &mut events, &mut io::sink()).unwrap();
+        assert_eq!(menu.selected(), Section::DefiningAnEnum);
+
+        let downs = vec![Event::Down; Section::iter().count() + 5];
+        run_menu(&mut menu, &mut ScriptedEvents::new(downs), &mut io::sink()).unwrap();
+        assert_eq!(menu.selected(), Section::iter().next_back().unwrap());
+        assert!(menu.ran().is_empty());
+    }
+
+    #[test]
+    fn the_screen_shows_the_highlight_and_the_seed() {
+        let mut menu = Menu::new(7);
+        let mut screen = Vec::new();
+        // Far enough down that the list has scrolled
+        let downs = vec![Event::Down; MENU_ROWS + 2];
+        run_menu(&mut menu, &mut ScriptedEvents::new(downs), &mut screen).unwrap();
+        let mut last = Vec::new();
+        menu.draw(&mut last).unwrap();
+        let last = String::from_utf8(last).unwrap();
+        assert!(last.contains(&format!("\x1b[7m> {}", menu.selected())));
+        assert!(!last.contains("defining_an_enum"));
+        assert!(last.ends_with(" seed 7 │ ↑↓ move │ Enter run │ q quit \x1b[0m"));
+    }
+
+    #[test]
+    fn keys_decode_to_events() {
+        let keys: &[u8] = b"j\x1b[Bk\x1b[Ax\r\x1b[Cq";
+        let mut events = KeyEvents::new(keys);
+        let mut decoded = Vec::new();
+        while let Some(event) = events.next_event().unwrap() {
+            decoded.push(event);
+        }
+        use Event::*;
+        assert_eq!(decoded, [Down, Down, Up, Up, Enter, Quit]);
+    }
+}
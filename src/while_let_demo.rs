@@ -0,0 +1,101 @@
+//! # Looping while a pattern matches
+//!
+//! `while let` is `if let` on repeat: the loop goes on for as long as the
+//! pattern matches, which makes it the natural way to empty a collection
+//! whose `pop` returns `Some` until there is nothing left.
+use std::collections::VecDeque;
+use std::io::{self, Write};
+
+use rust_enums::{Cents, Coin2, Message, UsState};
+
+use crate::report::Reporter;
+
+/// Pop every coin off `stack`, returning what they were worth altogether
+pub fn drain_coins(stack: &mut Vec<Coin2>) -> Cents {
+    let mut total = 0;
+    while let Some(coin) = stack.pop() {
+        total += coin.cents();
+    }
+    total
+}
+
+/// Take messages off the front of `queue` up to and including the first
+/// `Quit`, leaving whatever comes after it queued
+///
+/// Without a `Quit`, the whole queue is drained.
+pub fn drain_until_quit(queue: &mut VecDeque<Message>) -> Vec<Message> {
+    let mut drained = Vec::new();
+    while let Some(msg) = queue.pop_front() {
+        let quit = msg.is_quit();
+        drained.push(msg);
+        if quit {
+            break;
+        }
+    }
+    drained
+}
+
+/// # Emptying a stack of coins and a queue of messages
+pub fn while_let_examples(r: &mut Reporter) -> io::Result<()> {
+    let mut stack = vec![Coin2::Penny, Coin2::Quarter(UsState::Maine), Coin2::Dime];
+    r.result(format_args!(
+        "{:?} is worth {} cents",
+        stack.clone(),
+        drain_coins(&mut stack)
+    ))?;
+
+    let mut queue = VecDeque::from([
+        Message::Write(String::from("first")),
+        Message::Quit { code: 0 },
+        Message::Write(String::from("never read")),
+    ]);
+    let drained = drain_until_quit(&mut queue);
+    writeln!(r, "Drained {drained:?}, left {queue:?}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drain_coins_totals_and_empties_the_stack() {
+        let mut stack = vec![
+            Coin2::Quarter(UsState::Ohio),
+            Coin2::Quarter(UsState::Utah),
+            Coin2::Dime,
+            Coin2::Nickel,
+            Coin2::Penny,
+            Coin2::Penny,
+        ];
+        assert_eq!(drain_coins(&mut stack), 67);
+        assert!(stack.is_empty());
+        assert_eq!(drain_coins(&mut stack), 0);
+    }
+
+    #[test]
+    fn many_coins_dont_overflow() {
+        let mut stack = vec![Coin2::Quarter(UsState::Iowa); 100];
+        assert_eq!(drain_coins(&mut stack), 2_500);
+    }
+
+    #[test]
+    fn drain_stops_at_quit() {
+        let after = [Message::Move { x: 1, y: 1 }, Message::Quit { code: 2 }];
+        let mut queue =
+            VecDeque::from([Message::Write(String::from("a")), Message::Quit { code: 1 }]);
+        queue.extend(after.clone());
+        assert_eq!(
+            drain_until_quit(&mut queue),
+            [Message::Write(String::from("a")), Message::Quit { code: 1 }]
+        );
+        assert_eq!(queue, after);
+    }
+
+    #[test]
+    fn drain_without_quit_takes_everything() {
+        let mut queue = VecDeque::from([Message::Move { x: 1, y: 1 }]);
+        assert_eq!(drain_until_quit(&mut queue).len(), 1);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+//! # `Arbitrary` `Message`s through the binary codec
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use rust_enums::arbitrary::Unstructured;
+use rust_enums::Message;
+
+#[test]
+fn a_thousand_arbitrary_messages_round_trip() {
+    let mut data = vec![0; 1 << 20];
+    StdRng::seed_from_u64(445).fill_bytes(&mut data);
+    let mut u = Unstructured::new(&data);
+
+    let messages: Vec<Message> = (0..1000).map(|_| u.arbitrary()).collect();
+    assert!(!u.is_empty(), "ran out of bytes");
+    let kinds: std::collections::HashSet<_> = messages.iter().map(Message::kind).collect();
+    assert_eq!(kinds.len(), 6);
+
+    for message in messages {
+        let mut buf = Vec::new();
+        message.encode(&mut buf).unwrap();
+        assert_eq!(buf.len(), message.encoded_len());
+        assert_eq!(Message::decode(&buf), Ok((message, buf.len())));
+    }
+}
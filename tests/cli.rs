@@ -0,0 +1,521 @@
+//! # End-to-end tests of the `enums` binary
+//!
+//! Each test runs the binary, usually with a `--seed` so its output is the
+//! same every time, and checks its exit code, stdout and stderr. Inputs and
+//! expected outputs too long to write inline are in `tests/fixtures`.
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// The path of the fixture called `name`
+fn fixture(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", name]
+        .iter()
+        .collect()
+}
+
+/// Run the binary with `args`
+fn enums<S: AsRef<std::ffi::OsStr>>(args: impl IntoIterator<Item = S>) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_enums"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Run the binary with `args`, writing `stdin` to it
+fn enums_with_stdin(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_enums"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn message_run_exits_with_the_quit_code() {
+    let output = enums(["message".into(), "run".into(), fixture("quit-3.txt")]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stdout(&output).lines().collect::<Vec<_>>(),
+        [
+            "3 command(s) executed",
+            "cursor (1, 2) on 80x24, color #000000, 1 line(s), 0 point(s), stopped",
+        ]
+    );
+}
+
+#[test]
+fn message_run_without_quit_exits_cleanly() {
+    let output = enums(["message".into(), "run".into(), fixture("no-quit.txt")]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn message_repl_refuses_a_board_too_big_to_render() {
+    let output = enums_with_stdin(
+        &["message", "repl", "--render"],
+        b"resize 100000 100000\nquit\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stderr(&output),
+        "line 1: 100000x100000 is not a valid board size\n"
+    );
+    assert!(stdout(&output).contains("on 80x24"));
+}
+
+#[test]
+fn message_run_of_a_missing_script_is_an_io_error() {
+    let output = enums(["message", "run", "no/such/script.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    assert!(stderr(&output).starts_with("no/such/script.txt: "));
+}
+
+#[test]
+fn message_run_of_an_unreadable_script_is_an_io_error() {
+    // A directory opens, but can't be read as a script
+    let dir = fixture("");
+    let output = enums(["message".into(), "run".into(), dir.clone()]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    let expected = format!("{}: could not read script: ", dir.display());
+    assert!(
+        stderr(&output).starts_with(&expected),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn message_run_of_a_bad_script_is_a_parse_error() {
+    let output = enums(["message".into(), "run".into(), fixture("bad-line.txt")]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(
+        stderr(&output).contains(": line 2: "),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn dice_autoplay_with_a_seed_is_reproducible() {
+    let output = enums(["dice", "play", "--seed", "2", "--turns", "10"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "after 10 turn(s): 1 hat(s), on space 12, longest streak 1 x 3\n"
+    );
+}
+
+#[test]
+fn dice_play_reads_answers_from_stdin() {
+    let output = enums_with_stdin(&["dice", "play", "--seed", "7"], b"y\nq\n");
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = stdout(&output);
+    assert_eq!(stdout.matches("roll again?").count(), 2);
+    assert!(stdout.ends_with("after 2 turn(s): 1 hat(s), on space 4, longest streak 1 x 3\n"));
+}
+
+#[test]
+fn dice_play_rejects_a_bad_flag() {
+    let output = enums(["dice", "play", "--turns"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("dice play: --turns needs a value\n"));
+}
+
+#[test]
+fn coins_section_runs_alone() {
+    let output = enums(["coins"]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("`penny` value_in_cents = 1\n"));
+    // Nothing from the IP address examples
+    assert!(!stdout.contains("`home` is"));
+    assert!(!stdout.contains("`loopback` is"));
+}
+
+#[test]
+fn seeded_coins_section_matches_its_fixture() {
+    let output = enums(["--seed", "42", "coins"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        fs::read_to_string(fixture("coins-seed-42.txt")).unwrap()
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn quiet_prints_a_line_per_section() {
+    let output = enums(["--quiet", "--seed", "42", "coins"]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert_eq!(stdout.lines().count(), 7, "{stdout}");
+    assert!(stdout.contains("the second wallet is richer\n"));
+    assert!(!stdout.contains("`penny` value_in_cents"));
+}
+
+#[test]
+fn quiet_and_verbose_are_refused_together() {
+    let output = enums(["-q", "-v", "coins"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("--quiet and --verbose don't go together\n"));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn seeded_run_has_known_lines_and_no_stderr() {
+    let output = enums(["--seed", "42"]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    for line in [
+        "A circle of radius 1 has area 3.14 and perimeter 6.28",
+        "`random_coin` value_in_cents = 1",
+        "`random_coin` value_in_cents_state_quarters = 10",
+        "first wallet: [Quarter(Colorado), Nickel, Nickel, Penny, Penny] = 37¢",
+    ] {
+        assert!(stdout.lines().any(|l| l == line), "no {line:?} in output");
+    }
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn seeded_sections_follow_the_seed() {
+    for section in [
+        "message_stats",
+        "wallets",
+        "roll_examples",
+        "game_rules",
+        "tournament",
+        "simulations",
+        "dice_examples",
+    ] {
+        let [one, two] = ["1", "2"].map(|seed| enums(["--seed", seed, "--only", section]));
+        assert!(one.status.success() && two.status.success());
+        assert_ne!(stdout(&one), stdout(&two), "{section}");
+    }
+}
+
+#[test]
+fn list_names_every_part_and_its_sections() {
+    let output = enums(["--list"]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    let parts: Vec<_> = stdout
+        .lines()
+        .filter(|line| !line.starts_with(' '))
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    assert_eq!(
+        parts,
+        ["all", "ip", "messages", "coins", "patterns", "dice"]
+    );
+    let coins = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("coins "))
+        .nth(1)
+        .unwrap();
+    assert!(
+        coins.starts_with("  match_control_flow ") && coins.ends_with("control flow construct"),
+        "{coins:?}"
+    );
+}
+
+#[test]
+fn only_runs_the_sections_named_in_order() {
+    let output = enums(["--seed", "42", "--only", "wallets,enum_different_types"]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    let wallets = stdout.find("first wallet:").unwrap();
+    let home = stdout.find("`home` is").unwrap();
+    assert!(wallets < home, "{stdout}");
+    assert!(!stdout.contains("value_in_cents"));
+}
+
+#[test]
+fn only_rejects_an_unknown_section() {
+    let output = enums(["--only", "wallets,atlantis"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(stderr(&output).starts_with("--only: unknown section \"atlantis\"\n"));
+}
+
+#[test]
+fn unknown_section_suggests_a_close_one() {
+    let output = enums(["coin"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(stderr(&output).starts_with(
+        "error: unrecognized subcommand 'coin'\n\n  tip: a similar subcommand exists: 'coins'\n"
+    ));
+}
+
+#[test]
+fn invalid_subcommand_prints_the_usage() {
+    for args in [
+        &["frobnicate"][..],
+        &["message", "paint"],
+        &["coins", "count", "x"],
+    ] {
+        let output = enums(args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(output.stdout.is_empty(), "{args:?}");
+        assert!(stderr(&output).contains("usage: enums"), "{args:?}");
+    }
+}
+
+#[test]
+fn coins_count_totals_stdin() {
+    let pocket = fs::read(fixture("pocket.txt")).unwrap();
+    let output = enums_with_stdin(&["coins", "count"], &pocket);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "5 coin(s), 66 cent(s)\n");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn coins_count_rejects_a_malformed_token() {
+    let pocket = fs::read(fixture("pocket-with-a-button.txt")).unwrap();
+    let output = enums_with_stdin(&["coins", "count"], &pocket);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert_eq!(stderr(&output), "coins count: \"button\" is not a coin\n");
+}
+
+/// Run `enums --format json <section>`, parsing each line of its output
+#[cfg(feature = "json")]
+fn json_records(section: &str) -> Vec<rust_enums::json::Value> {
+    let output = enums(["--format", "json", section]);
+    assert!(output.status.success());
+    stdout(&output)
+        .lines()
+        .map(|line| rust_enums::json::parse_value(line).unwrap())
+        .collect()
+}
+
+/// The record for the demo called `name`
+#[cfg(feature = "json")]
+fn record<'a>(records: &'a [rust_enums::json::Value], name: &str) -> &'a rust_enums::json::Value {
+    use rust_enums::json::Value;
+
+    let name = Value::String(String::from(name));
+    records
+        .iter()
+        .find(|record| record.get("section") == Some(&name))
+        .unwrap()
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn coins_as_json() {
+    use rust_enums::json::Value;
+
+    let records = json_records("coins");
+    let Some(Value::Array(coins)) = record(&records, "match_control_flow_patterns").get("coins")
+    else {
+        panic!("no coins in {records:?}");
+    };
+    let quarter = &coins[3];
+    assert_eq!(
+        quarter.get("kind"),
+        Some(&Value::String(String::from("quarter")))
+    );
+    assert_eq!(
+        quarter.get("cents"),
+        Some(&Value::Number(String::from("25")))
+    );
+    assert_eq!(
+        quarter.get("state"),
+        Some(&Value::String(String::from("Virginia")))
+    );
+    assert_eq!(coins[0].get("state"), Some(&Value::Null));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn ip_addresses_as_json() {
+    use rust_enums::json::Value;
+
+    let records = json_records("ip");
+    let Some(Value::Array(addresses)) = record(&records, "enum_different_types").get("addresses")
+    else {
+        panic!("no addresses in {records:?}");
+    };
+    let octets = ["127", "0", "0", "1"].map(|n| Value::Number(String::from(n)));
+    assert_eq!(
+        addresses[0].get("octets"),
+        Some(&Value::Array(octets.to_vec()))
+    );
+    assert_eq!(
+        addresses[1].get("address"),
+        Some(&Value::String(String::from("::1")))
+    );
+}
+
+#[test]
+fn text_is_the_default_format() {
+    let text = enums(["--seed", "1", "--format", "text", "coins"]).stdout;
+    assert_eq!(text, enums(["--seed", "1", "coins"]).stdout);
+    assert!(!text.starts_with(b"{"));
+}
+
+#[test]
+fn unknown_format_is_a_usage_error() {
+    let output = enums(["--format", "yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn color_is_only_written_when_asked_for() {
+    let colored = stdout(&enums(["--seed", "1", "--color", "always", "coins"]));
+    assert!(colored.contains("`penny` value_in_cents = \x1b[33m1\x1b[0m\n"));
+    // Not a terminal, so `auto` doesn't color
+    for choice in ["never", "auto"] {
+        let plain = enums(["--seed", "1", "--color", choice, "coins"]);
+        assert!(!stdout(&plain).contains('\x1b'), "{choice}");
+    }
+    let bad = enums(["--color", "sometimes"]);
+    assert_eq!(bad.status.code(), Some(2));
+}
+
+#[test]
+fn timings_follow_the_sections_run() {
+    let output = enums(["--timings", "--only", "tournament,wallets"]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    let table: Vec<&str> = stdout.lines().skip_while(|&l| l != "timings").collect();
+    assert_eq!(table.len(), 3, "{stdout}");
+    assert!(table[1].trim_start().starts_with("tournament "));
+    assert!(table[2].trim_start().starts_with("wallets "));
+    let untimed = enums(["--only", "wallets"]);
+    assert!(!String::from_utf8_lossy(&untimed.stdout).contains("timings"));
+}
+
+#[test]
+fn a_fixed_seed_gives_identical_output() {
+    let (first, second) = (enums(["--seed", "42"]), enums(["--seed", "42"]));
+    assert!(first.status.success());
+    assert!(!first.stdout.is_empty());
+    assert_eq!(first.stdout, second.stdout);
+    assert!(!stderr(&first).contains("seed:"));
+}
+
+#[test]
+fn a_random_seed_is_printed() {
+    let output = enums(["coins"]);
+    assert!(output.status.success());
+    let stderr = stderr(&output);
+    let seed = stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("seed: "))
+        .unwrap_or_else(|| panic!("no seed banner in {stderr:?}"));
+    assert!(seed.parse::<u64>().is_ok(), "{seed:?}");
+}
+
+#[test]
+fn tui_needs_a_terminal() {
+    let output = enums_with_stdin(&["tui", "--seed", "1"], b"q");
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), "tui: the menu needs a terminal\n");
+}
+
+#[test]
+fn config_show_prints_the_defaults() {
+    let output = enums(["config", "show"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "# seed = a random one, printed to stderr\n# sections = every one, in order\nformat = \"text\"\ncolor = \"auto\"\n"
+    );
+}
+
+#[test]
+fn config_flags_beat_the_file() {
+    let config = fixture("config.toml");
+    let output = enums([
+        "--config".as_ref(),
+        config.as_os_str(),
+        "--seed".as_ref(),
+        "7".as_ref(),
+        "config".as_ref(),
+        "show".as_ref(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "seed = 7\nsections = [\"match_control_flow\", \"defining_an_enum\"]\nformat = \"json\"\ncolor = \"auto\"\n"
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn enums_toml_in_the_working_directory_is_read() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("config");
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy(fixture("config-unknown-key.toml"), dir.join("enums.toml")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_enums"))
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let flags = enums(["--seed", "42", "--only", "match_control_flow"]);
+    assert_eq!(stdout(&output), stdout(&flags));
+    assert_eq!(
+        stderr(&output),
+        "enums.toml: warning: line 2: unknown key `sedd`, ignored\n"
+    );
+}
+
+#[test]
+fn a_malformed_config_names_the_line() {
+    let config = fixture("config-malformed.toml");
+    let output = enums(["--config".as_ref(), config.as_os_str()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        format!("{}: line 3: expected `=` after `seed`\n", config.display())
+    );
+
+    let output = enums(["--config", "no-such-config.toml"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn completions_are_written_for_each_shell() {
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let output = enums(["completions", shell]);
+        assert!(output.status.success(), "{shell}");
+        let script = stdout(&output);
+        assert!(script.contains("completions"), "{shell}");
+        assert!(script.contains("match_control_flow"), "{shell}");
+    }
+}
+
+#[test]
+fn completions_reject_an_unknown_shell() {
+    let output = enums(["completions", "tcsh"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "completions: unknown shell \"tcsh\", expected one of bash, zsh, fish, powershell\n"
+    );
+}
@@ -0,0 +1,55 @@
+//! # Examples that must not compile
+//!
+//! Each `tests/compile_fail/<name>.rs` is compiled on its own with `rustc`,
+//! as far as generating object code so errors from constants evaluated for
+//! a generic type show up too, and has to fail. Every line of the `<name>.stderr` next to it has to
+//! appear in the compiler's output. Only the headline lines go in the
+//! `.stderr` files, since notes and spans shift between compiler versions.
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn assert_compile_fails(name: &str) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/compile_fail");
+    let source = dir.join(format!("{name}.rs"));
+    let expected = fs::read_to_string(dir.join(format!("{name}.stderr"))).unwrap();
+    let out =
+        std::env::temp_dir().join(format!("enums-compile-fail-{}-{name}", std::process::id()));
+
+    // Cargo points `RUSTC` at the compiler it is using, when it sets it
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    let output = Command::new(rustc)
+        .args(["--edition", "2021", "--emit=obj", "--color=never", "-o"])
+        .arg(&out)
+        .arg(&source)
+        .output()
+        .unwrap();
+    let _ = fs::remove_file(&out);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "{name}.rs compiled, but shouldn't have"
+    );
+    for line in expected.lines() {
+        assert!(
+            stderr.contains(line),
+            "{name}.rs: expected `{line}` in:\n{stderr}"
+        );
+    }
+}
+
+#[test]
+fn match_on_option_without_none() {
+    assert_compile_fails("plus_one_missing_none");
+}
+
+#[test]
+fn match_on_coin_without_quarter() {
+    assert_compile_fails("coin_missing_quarter");
+}
+
+#[test]
+fn die_with_one_side() {
+    assert_compile_fails("die_one_side");
+}
@@ -0,0 +1,26 @@
+// Matching on a `Coin2` without a `Quarter` arm is just as non-exhaustive,
+// even though every other variant is covered.
+#[derive(Debug)]
+enum UsState {
+    Alabama,
+}
+
+#[derive(Debug)]
+enum Coin2 {
+    Penny,
+    Nickel,
+    Dime,
+    Quarter(UsState),
+}
+
+fn value_in_cents(coin: &Coin2) -> u8 {
+    match coin {
+        Coin2::Penny => 1,
+        Coin2::Nickel => 5,
+        Coin2::Dime => 10,
+    }
+}
+
+fn main() {
+    value_in_cents(&Coin2::Quarter(UsState::Alabama));
+}
@@ -0,0 +1,18 @@
+// A `Die` checks its number of sides when it is made, so a one-sided die is
+// a build error rather than a die that always rolls 1.
+pub struct Die<const SIDES: u8> {
+    _sides: (),
+}
+
+impl<const SIDES: u8> Die<SIDES> {
+    const AT_LEAST_TWO_SIDES: () = assert!(SIDES >= 2, "a die needs at least two sides");
+
+    pub const fn new() -> Self {
+        let () = Self::AT_LEAST_TWO_SIDES;
+        Die { _sides: () }
+    }
+}
+
+fn main() {
+    let _coin_with_one_face = Die::<1>::new();
+}
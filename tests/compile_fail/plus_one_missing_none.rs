@@ -0,0 +1,16 @@
+// # Matches Are Exhaustive
+//
+// There's one other aspect of `match` we need to discuss: the arms' patterns
+// must cover all possibilities. Consider this version of our `plus_one`
+// function, which has a bug and won't compile: we didn't handle the `None`
+// case. Luckily, it's a bug Rust knows how to catch, and it refuses the
+// match with E0004.
+fn plus_one(x: Option<i32>) -> Option<i32> {
+    match x {
+        Some(i) => Some(i + 1),
+    }
+}
+
+fn main() {
+    plus_one(Some(1));
+}
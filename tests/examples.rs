@@ -0,0 +1,47 @@
+//! # The examples run, and a seed makes them repeatable
+//!
+//! `cargo test` builds every example already; this runs the seeded ones
+//! twice with the same `--seed` and checks they print the same thing. The
+//! runs have a target directory of their own, so they don't wait on the
+//! one running the tests.
+use std::path::Path;
+use std::process::Command;
+
+/// What `cargo run --example <example> -- <args>` prints
+fn run_example(example: &str, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["run", "--quiet", "--color", "never", "--example", example])
+        .arg("--target-dir")
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("examples"))
+        .arg("--")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "example {example} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn seeded_examples_are_repeatable() {
+    for example in ["coins", "messages"] {
+        let first = run_example(example, &["--seed", "1"]);
+        assert!(!first.is_empty(), "{example} printed nothing");
+        assert_eq!(first, run_example(example, &["--seed", "1"]), "{example}");
+    }
+    assert!(run_example("coins", &["--seed", "1"]).contains("cents"));
+}
+
+#[test]
+fn ip_classifies_the_addresses_given() {
+    let output = run_example("ip", &["192.168.0.1", "::1"]);
+    assert_eq!(
+        output,
+        "192.168.0.1  is a private version four address\n\
+         ::1          is the version six loopback address\n"
+    );
+}
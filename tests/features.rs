@@ -0,0 +1,140 @@
+//! # Feature combinations
+//!
+//! `build_matrix` builds the library with combinations of its features the
+//! default build never sees, as a CI build matrix would, so code that
+//! leans on `rand` or `strum` without its feature fails here rather than
+//! downstream. The builds share a target directory of their own. It also
+//! checks that `enums-coins` depends on none of the other member crates.
+//!
+//! The other modules check the `VARIANTS` slices that stand in for
+//! `iter()` when `strum` is off.
+use rust_enums::message::MessageKind;
+use rust_enums::{Coin, Coin2, UsState};
+
+mod build_matrix {
+    use std::path::Path;
+    use std::process::Command;
+
+    fn assert_builds(features: &str) {
+        let output = Command::new(env!("CARGO"))
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .args(["build", "--lib", "--no-default-features", "--features"])
+            .args([features, "--color", "never", "--target-dir"])
+            .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("features"))
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "build with features `{features}` failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[test]
+    fn no_features() {
+        assert_builds("");
+    }
+
+    #[test]
+    fn alloc_with_rand() {
+        assert_builds("alloc,rand");
+    }
+
+    #[test]
+    fn alloc_with_playground_and_ffi() {
+        assert_builds("alloc,playground,ffi");
+    }
+
+    #[test]
+    fn alloc_with_strum() {
+        assert_builds("alloc,strum");
+    }
+
+    #[test]
+    fn std_without_rand_or_strum() {
+        assert_builds("std,json,async,arbitrary");
+    }
+
+    #[test]
+    fn coins_build_without_the_other_crates() {
+        let output = Command::new(env!("CARGO"))
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .args([
+                "tree",
+                "-p",
+                "enums-coins",
+                "-e",
+                "normal",
+                "--prefix",
+                "none",
+            ])
+            .args(["--features", "rand,strum", "--color", "never"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let tree = String::from_utf8_lossy(&output.stdout);
+        assert!(tree.contains("enums-core"), "{tree}");
+        assert!(
+            !tree.contains("enums-net") && !tree.contains("enums-msg"),
+            "{tree}"
+        );
+    }
+}
+
+/// The position of `coin` among `Coin`'s variants, written as a `match`
+/// so a new variant won't compile until it's counted here
+fn coin_index(coin: Coin) -> usize {
+    match coin {
+        Coin::Penny => 0,
+        Coin::Nickel => 1,
+        Coin::Dime => 2,
+        Coin::Quarter => 3,
+    }
+}
+
+fn kind_index(kind: MessageKind) -> usize {
+    match kind {
+        MessageKind::Quit => 0,
+        MessageKind::Move => 1,
+        MessageKind::Write => 2,
+        MessageKind::ChangeColor => 3,
+        MessageKind::Resize => 4,
+        MessageKind::Draw => 5,
+    }
+}
+
+#[test]
+fn variants_hold_every_variant_once_in_order() {
+    let coins: Vec<usize> = Coin::VARIANTS.iter().copied().map(coin_index).collect();
+    assert_eq!(coins, [0, 1, 2, 3]);
+    let kinds: Vec<usize> = MessageKind::VARIANTS
+        .iter()
+        .copied()
+        .map(kind_index)
+        .collect();
+    assert_eq!(kinds, [0, 1, 2, 3, 4, 5]);
+    let coin2s: Vec<Coin> = Coin2::VARIANTS.iter().map(Coin2::kind).collect();
+    assert_eq!(coin2s, Coin::VARIANTS);
+}
+
+#[test]
+fn every_state_is_listed_alphabetically() {
+    let names: Vec<String> = UsState::VARIANTS.iter().map(|s| format!("{s:?}")).collect();
+    assert_eq!(names.len(), 50);
+    assert!(names.windows(2).all(|pair| pair[0] < pair[1]), "{names:?}");
+}
+
+#[cfg(feature = "strum")]
+mod with_strum {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn variants_match_iter() {
+        assert!(Coin::iter().eq(Coin::VARIANTS.iter().copied()));
+        assert!(Coin2::iter().eq(Coin2::VARIANTS.iter().copied()));
+        assert!(UsState::iter().eq(UsState::VARIANTS.iter().copied()));
+        assert!(MessageKind::iter().eq(MessageKind::VARIANTS.iter().copied()));
+    }
+}
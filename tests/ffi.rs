@@ -0,0 +1,72 @@
+//! # The C functions, called through the C ABI
+//!
+//! The functions are declared here as C would see them, so the calls go
+//! through the unmangled symbols rather than Rust paths.
+use std::ffi::{c_char, CString};
+use std::ptr;
+
+use rust_enums::ffi::CCoin;
+
+extern "C" {
+    fn enums_coin_value(coin: CCoin) -> u8;
+    fn enums_make_change(cents: u32, out: *mut CCoin, cap: usize) -> usize;
+    fn enums_parse_state(name: *const c_char) -> i32;
+}
+
+fn parse_state(name: &str) -> i32 {
+    let name = CString::new(name).unwrap();
+    unsafe { enums_parse_state(name.as_ptr()) }
+}
+
+#[test]
+fn coin_values() {
+    let values = [CCoin::Penny, CCoin::Nickel, CCoin::Dime, CCoin::Quarter]
+        .map(|coin| unsafe { enums_coin_value(coin) });
+    assert_eq!(values, [1, 5, 10, 25]);
+}
+
+#[test]
+fn change_fills_the_buffer() {
+    let mut out = [CCoin::Penny; 8];
+    let count = unsafe { enums_make_change(41, out.as_mut_ptr(), out.len()) };
+    assert_eq!(count, 4);
+    assert_eq!(
+        out[..count],
+        [CCoin::Quarter, CCoin::Dime, CCoin::Nickel, CCoin::Penny]
+    );
+    assert_eq!(
+        unsafe { enums_make_change(0, out.as_mut_ptr(), out.len()) },
+        0
+    );
+}
+
+#[test]
+fn change_into_a_buffer_too_small_writes_only_what_fits() {
+    let mut out = [CCoin::Penny; 3];
+    let count = unsafe { enums_make_change(75, out.as_mut_ptr(), 2) };
+    assert_eq!(count, 3);
+    assert_eq!(out, [CCoin::Quarter, CCoin::Quarter, CCoin::Penny]);
+}
+
+#[test]
+fn change_into_a_null_buffer_only_counts() {
+    assert_eq!(unsafe { enums_make_change(99, ptr::null_mut(), 10) }, 9);
+    let huge = unsafe { enums_make_change(u32::MAX, ptr::null_mut(), 0) };
+    assert_eq!(huge, (u32::MAX / 25) as usize + 2);
+}
+
+#[test]
+fn states_by_name() {
+    assert_eq!(parse_state("Alabama"), 0);
+    assert_eq!(parse_state("new york"), 31);
+    assert_eq!(parse_state("Wyoming"), 49);
+    assert_eq!(parse_state("Atlantis"), -1);
+    assert_eq!(parse_state(""), -1);
+}
+
+#[test]
+fn a_null_or_non_utf8_state_name_is_not_found() {
+    assert_eq!(unsafe { enums_parse_state(ptr::null()) }, -1);
+    let latin1 = CString::new(vec![b'O', b'h', 0xed, b'o']).unwrap();
+    assert_eq!(unsafe { enums_parse_state(latin1.as_ptr()) }, -1);
+}
@@ -0,0 +1,20 @@
+//! # `noop!`, used from outside the crate that defines it
+use rust_enums::{noop, Coin};
+
+#[test]
+fn noop_is_exported() {
+    let () = noop!();
+    let () = noop!(any tokens at all);
+}
+
+#[test]
+fn noop_do_evaluates_once() {
+    let mut cents = Vec::new();
+    for coin in [Coin::Penny, Coin::Dime] {
+        match coin {
+            Coin::Penny => noop!(pennies are not counted),
+            _ => noop!(do cents.push(10)),
+        }
+    }
+    assert_eq!(cents, [10]);
+}
@@ -0,0 +1,26 @@
+//! # The core types build without `std`
+//!
+//! Builds the `#![no_std]` example, and the library under it, with only the
+//! `alloc` feature, as CI would with
+//! `cargo build --example no_std --no-default-features --features alloc`.
+//! Anything in the core modules that reaches for `std` fails the build.
+//! The build has a target directory of its own, so it doesn't wait on the
+//! one running the tests.
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn core_types_build_under_no_std() {
+    let output = Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["build", "--example", "no_std", "--no-default-features"])
+        .args(["--features", "alloc", "--color", "never", "--target-dir"])
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_std"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "no_std build failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,77 @@
+//! # The library's happy path, through `rust_enums::` paths only
+use std::cmp::Ordering;
+
+use rust_enums::canvas::{self, ApplyOutcome};
+use rust_enums::coin::{quarter_year_for, quarter_year_or_err, UnknownState};
+use rust_enums::message::MessageKind;
+use rust_enums::wallet::{compare_value, pick_richer, RicherOutcome};
+use rust_enums::{codec, Canvas, Cents, Coin, Coin2, IpAddrTypes, Message, UsState, Wallet};
+
+#[test]
+fn coins_and_state_quarters() {
+    let quarter = Coin2::Quarter(UsState::from_name("new mexico").unwrap());
+    assert_eq!(quarter, Coin2::Quarter(UsState::NewMexico));
+    assert_eq!(quarter.kind(), Coin::Quarter);
+    let cents: Cents = quarter.cents();
+    assert_eq!(cents, 25);
+
+    assert_eq!(quarter_year_for("New Mexico"), Some(2008));
+    assert_eq!(
+        quarter_year_or_err("Atlantis"),
+        Err(UnknownState(String::from("Atlantis")))
+    );
+    assert_eq!(UsState::default(), UsState::Virginia);
+}
+
+#[test]
+fn wallets_compare_by_value() {
+    assert_eq!(
+        compare_value(&Coin2::Dime, &Coin2::Penny),
+        Ordering::Greater
+    );
+
+    let rich = Wallet::new([Coin2::Quarter(UsState::Ohio), Coin2::Quarter(UsState::Iowa)]);
+    let poor = Wallet::new([Coin2::Penny; 3]);
+    assert_eq!(rich.total(), 50);
+    assert_eq!(pick_richer(&rich, &poor), RicherOutcome::First(&rich));
+    assert_eq!(pick_richer(&poor, &rich), RicherOutcome::Second(&rich));
+    assert_eq!(pick_richer(&poor, &poor), RicherOutcome::Tie(3));
+}
+
+#[test]
+fn ip_addresses() {
+    let home = IpAddrTypes::V4(127, 0, 0, 1);
+    let loopback = IpAddrTypes::V6(String::from("::1"));
+    assert_ne!(home, loopback);
+    assert!(matches!(home, IpAddrTypes::V4(127, _, _, 1)));
+}
+
+#[test]
+fn messages_on_a_canvas() {
+    let msgs = [
+        Message::Move { x: 2, y: 3 },
+        "write hello".parse().unwrap(),
+        Message::Quit { code: 4 },
+    ];
+    assert_eq!(msgs[1].kind(), MessageKind::Write);
+
+    // Through the wire format and back
+    let bytes = codec::encode_batch(&msgs).unwrap();
+    let mut decoded = Vec::new();
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let (msg, used) = Message::decode(rest).unwrap();
+        decoded.push(msg);
+        rest = &rest[used..];
+    }
+    assert_eq!(decoded, msgs);
+
+    let mut canvas = Canvas::new();
+    for msg in &decoded {
+        assert_eq!(canvas::apply(&mut canvas, msg), ApplyOutcome::Applied);
+    }
+    assert_eq!(canvas.cursor(), (2, 3));
+    assert_eq!(canvas.log(), ["hello"]);
+    assert!(!canvas.is_running());
+    assert_eq!(canvas.exit_code(), Some(4));
+}
@@ -0,0 +1,55 @@
+//! # Every `Display` parses back to the value it came from
+//!
+//! For each type with both `Display` and `FromStr`, a few hundred random
+//! values must survive `to_string` then `parse`, and neither `Display` nor
+//! `Debug` may ever write an empty string.
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+use rust_enums::proptest_support::{check, coins, ip_addrs, messages, states, Strategy, CASES};
+
+/// Check the round trip and non-empty output for `CASES` values of `strategy`
+fn round_trips<S>(strategy: S)
+where
+    S: Strategy,
+    S::Value: Display + Debug + FromStr + PartialEq,
+    <S::Value as FromStr>::Err: Debug,
+{
+    check(strategy, CASES, |value| {
+        let shown = value.to_string();
+        assert!(!shown.is_empty(), "empty Display");
+        assert!(!format!("{value:?}").is_empty(), "empty Debug");
+        let parsed = shown.parse::<S::Value>();
+        assert!(
+            parsed.as_ref().is_ok_and(|parsed| parsed == value),
+            "{shown:?} parsed as {parsed:?}"
+        );
+    });
+}
+
+#[test]
+fn states_round_trip() {
+    round_trips(states());
+}
+
+#[test]
+fn coins_round_trip() {
+    round_trips(coins());
+}
+
+#[test]
+fn ip_addresses_round_trip() {
+    round_trips(ip_addrs());
+}
+
+#[test]
+fn messages_round_trip() {
+    round_trips(messages());
+}
+
+#[test]
+fn message_display_is_one_line() {
+    check(messages(), CASES, |msg| {
+        assert!(!msg.to_string().contains(['\n', '\r']), "{msg}");
+    });
+}